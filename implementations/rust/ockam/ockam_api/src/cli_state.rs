@@ -74,6 +74,23 @@ pub enum CliStateError {
     InvalidVersion(String),
 }
 
+impl CliStateError {
+    /// Return a stable code identifying the kind of error, for programs consuming the errors
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            CliStateError::Io(_) => "io",
+            CliStateError::Serde(_) => "serialization",
+            CliStateError::Ockam(_) => "ockam",
+            CliStateError::AlreadyExists { .. } => "already_exists",
+            CliStateError::ResourceNotFound { .. } => "not_found",
+            CliStateError::InvalidPath(_) => "invalid_path",
+            CliStateError::EmptyPath => "empty_path",
+            CliStateError::InvalidOperation(_) => "invalid_operation",
+            CliStateError::InvalidVersion(_) => "invalid_version",
+        }
+    }
+}
+
 impl From<CliStateError> for ockam_core::Error {
     fn from(e: CliStateError) -> Self {
         match e {
@@ -367,6 +384,13 @@ mod tests {
         assert_eq!(identity1.path(), identity2.path());
     }
 
+    #[test]
+    fn test_list_items_when_state_dir_is_missing() {
+        let state = CliState::test().unwrap();
+        let _ = std::fs::remove_dir_all(state.vaults.dir());
+        assert!(state.vaults.list().unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_create_named_identity_state() {
         let state = CliState::test().unwrap();
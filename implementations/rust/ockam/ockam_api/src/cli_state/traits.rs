@@ -118,13 +118,32 @@ pub trait StateDirTrait: Sized + Send + Sync {
         Ok(items)
     }
 
+    /// Return an iterator over the items, each item being loaded when the iterator reaches it
+    /// so that the items are never all held in memory. As with `list`, the items which can't
+    /// be loaded are skipped. The items are returned in the order of the state directory
+    fn iter<'a>(&'a self) -> Result<Box<dyn Iterator<Item = Self::Item> + 'a>>
+    where
+        Self::Item: 'a,
+    {
+        let iter = match self.read_items_dir()? {
+            Some(iter) => iter,
+            None => return Ok(Box::new(std::iter::empty())),
+        };
+        Ok(Box::new(iter.filter_map(move |entry| {
+            let entry_path = entry.ok()?.path();
+            if !self.is_item_path(&entry_path).ok()? {
+                return None;
+            }
+            self.get(file_stem(&entry_path).ok()?).ok()
+        })))
+    }
+
     fn list_items_names(&self) -> Result<Vec<String>> {
         let mut items = Vec::default();
-        let iter = std::fs::read_dir(self.dir()).map_err(|e| {
-            let dir = self.dir().as_path().to_string_lossy();
-            error!(%dir, %e, "Unable to read state directory");
-            CliStateError::InvalidOperation(format!("Unable to read state from directory {dir}"))
-        })?;
+        let iter = match self.read_items_dir()? {
+            Some(iter) => iter,
+            None => return Ok(items),
+        };
         for entry in iter {
             let entry_path = entry?.path();
             if self.is_item_path(&entry_path)? {
@@ -134,6 +153,22 @@ pub trait StateDirTrait: Sized + Send + Sync {
         Ok(items)
     }
 
+    /// Return the entries of the state directory, or nothing if it has not been created yet
+    fn read_items_dir(&self) -> Result<Option<std::fs::ReadDir>> {
+        match std::fs::read_dir(self.dir()) {
+            Ok(iter) => Ok(Some(iter)),
+            // A state directory which has not been created yet contains no items
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => {
+                let dir = self.dir().as_path().to_string_lossy();
+                error!(%dir, %e, "Unable to read state directory");
+                Err(CliStateError::InvalidOperation(format!(
+                    "Unable to read state from directory {dir}: {e}"
+                )))
+            }
+        }
+    }
+
     // If a path has been created with the self.path function
     // then we know that the current name is an item name
     fn is_item_path(&self, path: &PathBuf) -> Result<bool> {
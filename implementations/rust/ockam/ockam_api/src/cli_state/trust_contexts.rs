@@ -24,6 +24,13 @@ impl TrustContextState {
 impl Display for TrustContextState {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Name: {}", self.name)?;
+        let trusted_identifiers = self.config.trusted_identifiers();
+        if !trusted_identifiers.is_empty() {
+            writeln!(f, "Trusted identifiers:")?;
+            for identifier in trusted_identifiers {
+                writeln!(f, "  {identifier}")?;
+            }
+        }
         Ok(())
     }
 }
@@ -5,6 +5,7 @@ use ockam_identity::IdentitiesVault;
 use ockam_vault::Vault;
 use ockam_vault_aws::{AwsKmsConfig, AwsSecurityModule};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -92,6 +93,21 @@ impl Display for VaultState {
                 false => "OCKAM",
             }
         )?;
+        if !self.config.tags.is_empty() {
+            writeln!(f, "Tags:")?;
+            for (key, value) in &self.config.tags {
+                writeln!(f, "  {key}={value}")?;
+            }
+        }
+        if let Some(policy) = &self.config.policy {
+            writeln!(f, "Policy:")?;
+            if !policy.allow.is_empty() {
+                writeln!(f, "  Allow: {}", policy.allow.join(", "))?;
+            }
+            if !policy.deny.is_empty() {
+                writeln!(f, "  Deny: {}", policy.deny.join(", "))?;
+            }
+        }
         Ok(())
     }
 }
@@ -100,16 +116,83 @@ impl Display for VaultState {
 pub struct VaultConfig {
     #[serde(default)]
     aws_kms: bool,
+    /// Organizational metadata attached to the vault. Tags have no effect on the vault keys
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    tags: BTreeMap<String, String>,
+    /// Identities allowed to sign with the keys of the vault through the identity service
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    policy: Option<VaultPolicy>,
 }
 
 impl VaultConfig {
     pub fn new(aws_kms: bool) -> Result<Self> {
-        Ok(Self { aws_kms })
+        Ok(Self {
+            aws_kms,
+            tags: BTreeMap::new(),
+            policy: None,
+        })
     }
 
     pub fn is_aws(&self) -> bool {
         self.aws_kms
     }
+
+    pub fn tags(&self) -> &BTreeMap<String, String> {
+        &self.tags
+    }
+
+    /// Set the value of a tag, or remove the tag if the value is empty
+    pub fn set_tag(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        let key = key.into();
+        let value = value.into();
+        if value.is_empty() {
+            self.tags.remove(&key);
+        } else {
+            self.tags.insert(key, value);
+        }
+    }
+
+    pub fn policy(&self) -> Option<&VaultPolicy> {
+        self.policy.as_ref()
+    }
+
+    /// Set the access policy of the vault, or remove it
+    pub fn set_policy(&mut self, policy: Option<VaultPolicy>) {
+        self.policy = policy;
+    }
+}
+
+/// Access policy of a vault, as lists of identifiers.
+/// An identity is allowed to use the vault if it is not denied and, when the allow list is
+/// not empty, if it is in the allow list
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Default)]
+pub struct VaultPolicy {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    allow: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    deny: Vec<String>,
+}
+
+impl VaultPolicy {
+    pub fn new(allow: Vec<String>, deny: Vec<String>) -> Self {
+        Self { allow, deny }
+    }
+
+    pub fn allow(&self) -> &[String] {
+        &self.allow
+    }
+
+    pub fn deny(&self) -> &[String] {
+        &self.deny
+    }
+
+    /// Return true if the identity with this identifier is allowed to use the vault
+    pub fn allows(&self, identifier: &str) -> bool {
+        if self.deny.iter().any(|denied| denied == identifier) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(|allowed| allowed == identifier)
+    }
 }
 
 mod traits {
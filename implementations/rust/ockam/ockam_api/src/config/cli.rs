@@ -81,6 +81,8 @@ pub struct TrustContextConfig {
     id: String,
     authority: Option<TrustAuthorityConfig>,
     path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    trusted_identifiers: Vec<IdentityIdentifier>,
 }
 
 impl TrustContextConfig {
@@ -89,6 +91,7 @@ impl TrustContextConfig {
             id,
             authority,
             path: None,
+            trusted_identifiers: vec![],
         }
     }
 
@@ -104,6 +107,20 @@ impl TrustContextConfig {
         self.path = Some(path);
     }
 
+    pub fn trusted_identifiers(&self) -> &[IdentityIdentifier] {
+        &self.trusted_identifiers
+    }
+
+    /// Add an identifier to the trusted identifiers of this trust context.
+    /// Return false if the identifier was already trusted
+    pub fn add_trusted_identifier(&mut self, identifier: IdentityIdentifier) -> bool {
+        if self.trusted_identifiers.contains(&identifier) {
+            return false;
+        }
+        self.trusted_identifiers.push(identifier);
+        true
+    }
+
     pub fn authority(&self) -> Result<&TrustAuthorityConfig> {
         self.authority
             .as_ref()
@@ -1,7 +1,44 @@
 pub mod models;
 
+mod audit_log;
+mod compression;
+mod derived_keys;
 mod enrollment_ticket;
+mod fingerprint;
 mod identity_service;
+mod json_selection;
+mod jwk;
+mod key_strength;
+mod merkle_tree;
+mod options;
+mod policy_expression;
+mod possession_proof;
+mod public_identity_uri;
+mod rate_limiter;
+mod remote_identities;
+mod route_binding;
+mod signature_schemes;
+mod signing_key_id;
+mod signing_session;
+mod state_store;
+mod threshold_signing;
+mod vault_group;
+mod verification_keys;
 
+pub use compression::*;
+pub use derived_keys::DERIVED_KEY_CONTEXT;
 pub use enrollment_ticket::*;
 pub use identity_service::*;
+pub use jwk::{JWK_MEDIA_TYPE, JWK_SET_MEDIA_TYPE};
+pub use key_strength::key_security_bits;
+pub use merkle_tree::*;
+pub use options::*;
+pub use possession_proof::*;
+pub use public_identity_uri::*;
+pub use rate_limiter::RateLimit;
+pub use route_binding::*;
+pub use signature_schemes::signature_candidates;
+pub use signing_key_id::signing_key_id;
+pub use state_store::{IdentityServiceStore, InMemoryStore};
+pub use vault_group::{VaultGroup, VaultSelectionStrategy};
+pub use verification_keys::{history_digest, HistoryDigest};
@@ -0,0 +1,134 @@
+//! Audit log of the requests processed by an IdentityService.
+//!
+//! Each entry records the metadata of a request: when it was processed, its method and path,
+//! the identifier of its sender when it was received over a secure channel, and the status of
+//! its response. Request and response bodies are never recorded.
+//!
+//! The log is best-effort. It keeps the most recent entries in memory, up to a configured
+//! number, and is lost when the node restarts unless it is persisted to a file. The file is a
+//! sequence of CBOR-encoded entries, rewritten to drop the old entries once it holds twice
+//! as many entries as the log. Entries which can't be written are only reported as warnings.
+
+use crate::identity::models::AuditEntry;
+use minicbor::Decoder;
+use ockam_core::compat::collections::VecDeque;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+pub(crate) struct AuditLog {
+    entries: VecDeque<AuditEntry<'static>>,
+    capacity: usize,
+    path: Option<PathBuf>,
+    /// Number of entries appended to the file since it was last rewritten
+    appended: usize,
+}
+
+impl AuditLog {
+    /// Create an audit log keeping up to `capacity` entries, loading the entries persisted
+    /// to a file, if any
+    pub(crate) fn new(capacity: usize, path: Option<PathBuf>) -> Self {
+        let mut log = Self {
+            entries: VecDeque::new(),
+            capacity,
+            path,
+            appended: 0,
+        };
+        if capacity > 0 {
+            if let Some(path) = log.path.clone() {
+                log.load(&path);
+                log.rewrite();
+            }
+        }
+        log
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    pub(crate) fn record(&mut self, entry: AuditEntry<'static>) {
+        if !self.is_enabled() {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        if let Some(path) = &self.path {
+            if self.appended >= self.capacity {
+                self.entries.push_back(entry);
+                self.rewrite();
+                return;
+            }
+            if let Err(e) = append(path, &entry) {
+                warn!(%e, path = %path.display(), "unable to persist an audit log entry");
+            }
+            self.appended += 1;
+        }
+        self.entries.push_back(entry);
+    }
+
+    /// Return the `limit` most recent entries, the oldest first
+    pub(crate) fn recent(&self, limit: usize) -> Vec<AuditEntry<'static>> {
+        let skipped = self.entries.len().saturating_sub(limit);
+        self.entries.iter().skip(skipped).cloned().collect()
+    }
+
+    /// Load the most recent entries of a file. The entries following an entry which can't be
+    /// decoded, for example because it was partially written, are ignored
+    fn load(&mut self, path: &Path) {
+        let data = match std::fs::read(path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return,
+            Err(e) => {
+                warn!(%e, path = %path.display(), "unable to read the audit log");
+                return;
+            }
+        };
+        let mut dec = Decoder::new(&data);
+        while dec.position() < data.len() {
+            match dec.decode::<AuditEntry>() {
+                Ok(entry) => {
+                    if self.entries.len() == self.capacity {
+                        self.entries.pop_front();
+                    }
+                    self.entries.push_back(entry.into_owned());
+                }
+                Err(e) => {
+                    warn!(%e, path = %path.display(), "the audit log is truncated");
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Replace the content of the file with the entries kept in memory
+    fn rewrite(&mut self) {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return,
+        };
+        let mut data = vec![];
+        for entry in self.entries.iter() {
+            match minicbor::to_vec(entry) {
+                Ok(encoded) => data.extend(encoded),
+                Err(e) => warn!(%e, "unable to encode an audit log entry"),
+            }
+        }
+        if let Err(e) = std::fs::write(path, data) {
+            warn!(%e, path = %path.display(), "unable to persist the audit log");
+        }
+        self.appended = 0;
+    }
+}
+
+fn append(path: &Path, entry: &AuditEntry) -> std::io::Result<()> {
+    let encoded = minicbor::to_vec(entry)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(&encoded)
+}
@@ -0,0 +1,49 @@
+use crate::error::ApiError;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use minicbor::bytes::ByteVec;
+use minicbor::Decoder;
+use ockam_core::api::Response;
+use ockam_core::Result;
+use std::io::{Read, Write};
+
+/// Replace the body of an encoded response with its compressed version if the body is
+/// larger than `threshold` bytes. The response is returned unchanged otherwise
+pub(crate) fn compress_response(response: Vec<u8>, threshold: usize) -> Result<Vec<u8>> {
+    let mut dec = Decoder::new(&response);
+    let header: Response = dec.decode()?;
+    let body = &response[dec.position()..];
+    let status = match header.status() {
+        Some(status) if header.has_body() && !header.is_compressed() && body.len() > threshold => {
+            status
+        }
+        _ => return Ok(response),
+    };
+
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(body).map_err(ApiError::wrap)?;
+    let compressed = encoder.finish().map_err(ApiError::wrap)?;
+
+    let response = Response::builder(header.re(), status)
+        .id(header.id())
+        .compressed()
+        .body(ByteVec::from(compressed))
+        .to_vec()?;
+    Ok(response)
+}
+
+/// Return the encoded body of a response. If the response is compressed, the body is
+/// decompressed first
+pub fn response_body(header: &Response, dec: &mut Decoder<'_>) -> Result<Vec<u8>> {
+    if header.is_compressed() {
+        let compressed: ByteVec = dec.decode()?;
+        let mut body = Vec::new();
+        DeflateDecoder::new(compressed.as_slice())
+            .read_to_end(&mut body)
+            .map_err(ApiError::wrap)?;
+        Ok(body)
+    } else {
+        Ok(dec.input()[dec.position()..].to_vec())
+    }
+}
@@ -0,0 +1,65 @@
+//! Signing keys derived from the root key of an identity.
+//!
+//! A derived key is computed with HKDF-SHA256 (RFC 5869) and the following parameters:
+//!
+//!  - `IKM`: the Ed25519 signature, by the root key of the identity, of the ASCII string
+//!    [`DERIVED_KEY_CONTEXT`]. Ed25519 signatures are deterministic, so this value is the same
+//!    for all the derivations of an identity and is only known to the holder of the root key.
+//!  - `salt`: the salt provided by the client, used as is. An empty salt is allowed.
+//!  - `info`: the info provided by the client, used as is.
+//!  - `L`: 32 bytes.
+//!
+//! The output is used as the secret key of an Ed25519 key pair. Deriving with the same salt and
+//! info always returns the same key pair.
+//!
+//! Only identities having an Ed25519 root key support derived keys: ECDSA signatures are not
+//! deterministic, and the root keys of hardware-backed vaults can't be used to derive keys
+//! without the derived secret leaving the hardware. The derived secret is only held as an
+//! ephemeral secret of the vault for the duration of the request.
+
+use ockam::identity::{IdentitiesKeys, IdentitiesVault, Identity};
+use ockam_core::compat::sync::Arc;
+use ockam_core::Result;
+use ockam_vault::{KeyId, Secret, SecretAttributes};
+
+/// Data signed by the root key of an identity to obtain the input key material of the derivation
+pub const DERIVED_KEY_CONTEXT: &str = "ockam/identity/derived_key/v1";
+
+/// Derive an ephemeral Ed25519 secret from the root key of an identity.
+/// The caller is responsible for deleting the returned secret
+pub(crate) async fn derive_signing_key(
+    vault: Arc<dyn IdentitiesVault>,
+    identities_keys: &IdentitiesKeys,
+    identity: &Identity,
+    salt: &[u8],
+    info: &[u8],
+) -> Result<KeyId> {
+    let ikm = identities_keys
+        .create_signature(identity, DERIVED_KEY_CONTEXT.as_bytes(), None)
+        .await?;
+    let ikm = vault
+        .import_ephemeral_secret(
+            Secret::new(ikm.as_ref().to_vec()),
+            SecretAttributes::Buffer(ikm.as_ref().len() as u32),
+        )
+        .await?;
+    let salt = vault
+        .import_ephemeral_secret(
+            Secret::new(salt.to_vec()),
+            SecretAttributes::Buffer(salt.len() as u32),
+        )
+        .await?;
+
+    let okm = vault
+        .hkdf_sha256(&salt, info, Some(&ikm), vec![SecretAttributes::Buffer(32)])
+        .await;
+    vault.delete_ephemeral_secret(ikm).await?;
+    vault.delete_ephemeral_secret(salt).await?;
+    let okm = okm?.remove(0);
+
+    let secret = vault.get_ephemeral_secret(&okm, "derived key").await;
+    vault.delete_ephemeral_secret(okm).await?;
+    vault
+        .import_ephemeral_secret(secret?.secret().clone(), SecretAttributes::Ed25519)
+        .await
+}
@@ -0,0 +1,53 @@
+//! Fingerprints of identities, read aloud or compared side by side to check out-of-band that
+//! two parties refer to the same identity.
+//!
+//! All the formats of a fingerprint are computed from the same digest: the SHA-256 digest of
+//! the exported change history of the identity. The fingerprint of an identity then changes
+//! when one of its keys is rotated, like the fingerprint of an SSH host key.
+//!
+//!  - `hex`: the whole digest, as colon-separated hex bytes, like the fingerprints of SSH.
+//!  - `words`: the 12 words of the BIP-39 English mnemonic of the first 16 bytes of the digest.
+//!  - `base32`: the first 10 bytes of the digest in base32, as 4 dash-separated groups of
+//!    4 characters.
+//!
+//! The shorter formats only cover a prefix of the digest, which is enough for a comparison
+//! made by people but must not be used to identify an identity in a protocol.
+
+use crate::error::ApiError;
+use crate::identity::verification_keys::HistoryDigest;
+use bip39::Mnemonic;
+use data_encoding::BASE32_NOPAD;
+use ockam_core::Result;
+
+/// Number of bytes of the digest encoded as words
+const WORDS_ENTROPY_LEN: usize = 16;
+
+/// Number of bytes of the digest encoded in base32
+const BASE32_LEN: usize = 10;
+
+/// Number of characters of a group of the base32 format
+const BASE32_GROUP_LEN: usize = 4;
+
+pub(crate) fn fingerprint_hex(digest: &HistoryDigest) -> String {
+    digest
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+pub(crate) fn fingerprint_words(digest: &HistoryDigest) -> Result<String> {
+    let mnemonic =
+        Mnemonic::from_entropy(&digest[..WORDS_ENTROPY_LEN]).map_err(ApiError::message)?;
+    Ok(mnemonic.to_string())
+}
+
+pub(crate) fn fingerprint_base32(digest: &HistoryDigest) -> String {
+    // base32 only uses ASCII characters, which can be sliced anywhere
+    let encoded = BASE32_NOPAD.encode(&digest[..BASE32_LEN]);
+    (0..encoded.len())
+        .step_by(BASE32_GROUP_LEN)
+        .map(|start| &encoded[start..encoded.len().min(start + BASE32_GROUP_LEN)])
+        .collect::<Vec<_>>()
+        .join("-")
+}
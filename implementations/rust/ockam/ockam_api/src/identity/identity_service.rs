@@ -1,27 +1,362 @@
+use crate::error::ApiError;
+use crate::identity::audit_log::AuditLog;
+use crate::identity::compress_response;
+use crate::identity::derived_keys::derive_signing_key;
+use crate::identity::fingerprint::{fingerprint_base32, fingerprint_hex, fingerprint_words};
+use crate::identity::json_selection::select_json_fields;
+use crate::identity::jwk::{current_public_keys, key_id, public_key_to_jwk};
+use crate::identity::key_strength::key_security_bits;
 use crate::identity::models::*;
+use crate::identity::policy_expression::PolicyExpression;
+use crate::identity::possession_proof::possession_proof_payload;
+use crate::identity::rate_limiter::SenderRateLimiter;
+use crate::identity::remote_identities::{RemoteIdentities, RemoteIdentity};
+use crate::identity::route_binding::{canonical_route, route_bound_payload};
+use crate::identity::signature_schemes::signature_candidates;
+use crate::identity::signing_key_id::signing_key_id;
+use crate::identity::signing_session::SigningSessions;
+use crate::identity::state_store::{CHALLENGES, DELEGATION_TOKENS};
+use crate::identity::threshold_signing::{ThresholdSigner, ThresholdSigningSessions};
+use crate::identity::vault_group::VaultSelection;
+use crate::identity::verification_keys::{history_digest, HistoryDigest, VerificationKeyCache};
+use crate::identity::{
+    IdentityServiceOptions, IdentityServiceStore, JWK_MEDIA_TYPE, JWK_SET_MEDIA_TYPE,
+};
+use crate::nodes::registry::ActiveSecureChannelListeners;
 use crate::nodes::service::NodeIdentities;
 use core::convert::Infallible;
+use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use core::time::Duration;
 use minicbor::encode::Write;
 use minicbor::{Decoder, Encode};
-use ockam::identity::IdentityHistoryComparison;
+use ockam::identity::{
+    AttributesEntry, ChangeIdentifier, CreateKeyChangeData, IdentitiesCreation, IdentitiesKeys,
+    IdentitiesStorage, Identity, IdentityChange, IdentityChangeConstants, IdentityChangeHistory,
+    IdentityHistoryComparison, IdentityIdentifier, IdentitySecureChannelLocalInfo,
+    IdentitySignedChange, KeyAttributes, OneTimeCode, Signature as ChangeSignature, SignatureType,
+    Timestamp,
+};
 use ockam_core::api::{Error, Id, Method, Request, Response, Status};
-use ockam_core::{Result, Routed, Worker};
-use ockam_node::Context;
-use ockam_vault::Signature;
-use tracing::trace;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::rand::random;
+use ockam_core::compat::sync::Arc;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{
+    Address, AllowAll, AllowSourceAddresses, CowStr, DenyAll, Encodable, Result, Route, Routed,
+    Worker,
+};
+use ockam_node::api::request_with_options;
+use ockam_node::tokio::time::timeout;
+use ockam_node::{Context, DelayedEvent, MessageSendReceiveOptions};
+use ockam_vault::{
+    EcdsaSignatureEncoding, PublicKey, SecretAttributes, SecretType, Signature, Vault,
+};
+use std::time::Instant;
+use subtle::ConstantTimeEq;
+use tracing::{debug, info, trace, warn};
 
-/// Vault Service Worker
+/// Name of the attribute under which the revocation record of a revoked identity is stored
+pub const REVOCATION_ATTRIBUTE: &str = "ockam_revocation";
+
+/// Name of the attribute under which the number of signatures created by the current root key
+/// of an identity is stored, when the key usage of that identity is limited
+pub const KEY_USAGE_ATTRIBUTE: &str = "ockam_key_usage";
+
+/// Name of the attribute under which the creation time of the current root key of an identity
+/// is stored, when that key was created by the service
+pub const KEY_CREATION_ATTRIBUTE: &str = "ockam_key_creation";
+
+/// Name of the attribute under which the label of an identity is stored, as UTF-8.
+/// A label is set by the clients of the service and is not attested
+pub const LABEL_ATTRIBUTE: &str = "ockam_label";
+
+/// Prefix of the names of the attributes which are stored by the service itself, like the
+/// revocation records or the labels. They are not attested
+const RESERVED_ATTRIBUTE_PREFIX: &str = "ockam_";
+
+/// Maximum length of the label of an identity, in bytes
+const MAX_LABEL_LEN: usize = 128;
+
+/// Semantic version of the API of the identity service, returned by `Get /version`.
+/// The minor version is increased when fields or actions are added, and the major version
+/// when the meaning of existing fields or actions changes
+pub const IDENTITY_SERVICE_API_VERSION: &str = "1.0.0";
+
+/// Oldest version of the API which clients can use with this service
+pub const IDENTITY_SERVICE_MIN_CLIENT_VERSION: &str = "1.0.0";
+
+/// Name of the digest algorithm of the signatures created over the SHA-256 digest of the data
+pub const SHA256_DIGEST_ALGORITHM: &str = "sha256";
+
+/// Maximum length of a digest which can be timestamped, which is the length of a SHA-512 digest
+const MAX_TIMESTAMPED_DIGEST_LEN: usize = 64;
+
+/// Identifier of the key looked up when a vault is probed. No key has this identifier
+const VAULT_PING_KEY_ID: &str = "ockam-vault-ping";
+
+/// Vault Service Worker.
+///
+/// The worker counts the requests it receives and forwards them to a handler which processes
+/// them one at a time. A request is rejected with a `ServiceUnavailable` status when too many
+/// requests are already queued or being processed
 pub struct IdentityService {
+    options: IdentityServiceOptions,
+    /// Requests forwarded to the handler which were not answered yet
+    in_flight_requests: Arc<AtomicUsize>,
+    /// Handler of the requests, until it is started with the worker
+    handler: Option<IdentityServiceHandler>,
+    handler_address: Option<Address>,
+}
+
+/// Handler of the requests of an IdentityService
+struct IdentityServiceHandler {
     node_identities: NodeIdentities,
+    options: IdentityServiceOptions,
+    in_flight_requests: Arc<AtomicUsize>,
+    metrics: IdentityServiceMetrics,
+    /// Used delegation tokens, issued challenges and threshold signature sessions
+    state_store: Arc<dyn IdentityServiceStore>,
+    signing_sessions: SigningSessions,
+    threshold_signing_sessions: ThresholdSigningSessions,
+    /// State of the strategies used to select the vaults of the vault groups
+    vault_selection: VaultSelection,
+    /// Signature schemes supported by the default vault, computed when the service starts
+    signing_capabilities: Vec<SigningScheme<'static>>,
+    /// Secure channel listeners of the node, when the service runs on a node
+    secure_channel_listeners: Option<ActiveSecureChannelListeners>,
+    /// Periodic event used to discard the expired signing sessions, once the worker is started
+    session_reaper: Option<DelayedEvent<Vec<u8>>>,
+    /// Identities fetched from directories
+    remote_identities: RemoteIdentities,
+    /// Context used to send requests to other services, once the worker is started
+    client_ctx: Option<Context>,
+    /// Token buckets of the sender identities, when the requests are rate limited
+    rate_limiter: Option<SenderRateLimiter>,
+    /// Identities of the signers of the verified signatures
+    verification_keys: VerificationKeyCache,
+    /// Metadata of the most recent requests
+    audit_log: AuditLog,
+}
+
+/// Signature created for a `create_signature` request
+struct CreatedSignature {
+    identity: Identity,
+    identities_keys: Arc<IdentitiesKeys>,
+    signature: Signature,
+    /// Vault which created the signature, when a vault group was requested
+    vault_name: Option<String>,
+}
+
+/// Counters maintained by the identity service since the worker was started
+struct IdentityServiceMetrics {
+    started_at: Instant,
+    signatures_created: AtomicU64,
+    verifications_passed: AtomicU64,
+    verifications_failed: AtomicU64,
+    identities_created: AtomicU64,
+    verification_key_cache_hits: AtomicU64,
+    verification_key_cache_misses: AtomicU64,
+}
+
+impl IdentityServiceMetrics {
+    fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            signatures_created: AtomicU64::new(0),
+            verifications_passed: AtomicU64::new(0),
+            verifications_failed: AtomicU64::new(0),
+            identities_created: AtomicU64::new(0),
+            verification_key_cache_hits: AtomicU64::new(0),
+            verification_key_cache_misses: AtomicU64::new(0),
+        }
+    }
+
+    fn increment(counter: &AtomicU64) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn to_response(&self, cached_signers: usize) -> MetricsResponse<'static> {
+        let passed = self.verifications_passed.load(Ordering::Relaxed);
+        let failed = self.verifications_failed.load(Ordering::Relaxed);
+        MetricsResponse::new(vec![
+            Metric::new(
+                "identity_service_signatures_created_total",
+                MetricKind::Counter,
+                self.signatures_created.load(Ordering::Relaxed),
+            ),
+            Metric::new(
+                "identity_service_verifications_total",
+                MetricKind::Counter,
+                passed + failed,
+            ),
+            Metric::new(
+                "identity_service_verifications_passed_total",
+                MetricKind::Counter,
+                passed,
+            ),
+            Metric::new(
+                "identity_service_verifications_failed_total",
+                MetricKind::Counter,
+                failed,
+            ),
+            Metric::new(
+                "identity_service_identities_created_total",
+                MetricKind::Counter,
+                self.identities_created.load(Ordering::Relaxed),
+            ),
+            Metric::new(
+                "identity_service_verification_key_cache_hits_total",
+                MetricKind::Counter,
+                self.verification_key_cache_hits.load(Ordering::Relaxed),
+            ),
+            Metric::new(
+                "identity_service_verification_key_cache_misses_total",
+                MetricKind::Counter,
+                self.verification_key_cache_misses.load(Ordering::Relaxed),
+            ),
+            Metric::new(
+                "identity_service_verification_key_cache_entries",
+                MetricKind::Gauge,
+                cached_signers as u64,
+            ),
+            Metric::new(
+                "identity_service_uptime_seconds",
+                MetricKind::Gauge,
+                self.started_at.elapsed().as_secs(),
+            ),
+        ])
+    }
 }
 
 impl IdentityService {
     pub async fn new(node_identities: NodeIdentities) -> Result<Self> {
-        Ok(Self { node_identities })
+        Self::new_with_options(node_identities, IdentityServiceOptions::default()).await
+    }
+
+    pub async fn new_with_options(
+        node_identities: NodeIdentities,
+        options: IdentityServiceOptions,
+    ) -> Result<Self> {
+        let signing_sessions = SigningSessions::new(
+            options.max_signing_sessions(),
+            options.signing_session_timeout(),
+        );
+        let threshold_signing_sessions = ThresholdSigningSessions::new(
+            options.state_store(),
+            options.max_signing_sessions(),
+            options.signing_session_timeout(),
+        );
+        let remote_identities = RemoteIdentities::new(options.remote_identity_ttl());
+        let rate_limiter = options.rate_limit().map(SenderRateLimiter::new);
+        let verification_keys = VerificationKeyCache::new(options.verification_key_cache_size());
+        let audit_log = AuditLog::new(options.audit_log_size(), options.audit_log_path().cloned());
+        let signing_capabilities = Self::signing_capabilities(&node_identities).await?;
+        Self::warm_up_keys(&node_identities, &options).await;
+        let in_flight_requests = Arc::new(AtomicUsize::new(0));
+        let handler = IdentityServiceHandler {
+            node_identities,
+            options: options.clone(),
+            in_flight_requests: in_flight_requests.clone(),
+            metrics: IdentityServiceMetrics::new(),
+            state_store: options.state_store(),
+            signing_sessions,
+            threshold_signing_sessions,
+            vault_selection: VaultSelection::default(),
+            signing_capabilities,
+            secure_channel_listeners: None,
+            session_reaper: None,
+            remote_identities,
+            client_ctx: None,
+            rate_limiter,
+            verification_keys,
+            audit_log,
+        };
+        Ok(Self {
+            options,
+            in_flight_requests,
+            handler: Some(handler),
+            handler_address: None,
+        })
+    }
+
+    /// Report the secure channel listeners of the identities, as tracked by a node manager
+    pub fn with_secure_channel_listeners(
+        mut self,
+        secure_channel_listeners: ActiveSecureChannelListeners,
+    ) -> Self {
+        if let Some(handler) = self.handler.as_mut() {
+            handler.secure_channel_listeners = Some(secure_channel_listeners);
+        }
+        self
+    }
+
+    /// Return the signature schemes for the key types which can be generated by the default vault
+    async fn signing_capabilities(
+        node_identities: &NodeIdentities,
+    ) -> Result<Vec<SigningScheme<'static>>> {
+        let vault = node_identities.get_identities_vault(None).await?;
+        let mut schemes = vec![];
+        for (key_type, secret_attributes, encodings) in [
+            ("ed25519", SecretAttributes::Ed25519, vec!["raw"]),
+            ("p256", SecretAttributes::NistP256, vec!["raw", "der"]),
+        ] {
+            if let Ok(key_id) = vault.create_ephemeral_secret(secret_attributes).await {
+                vault.delete_ephemeral_secret(key_id).await?;
+                schemes.push(SigningScheme::new(
+                    key_type,
+                    encodings.into_iter().map(|e| e.into()).collect(),
+                ));
+            }
+        }
+        Ok(schemes)
+    }
+
+    /// Load the keys of the configured identities from the default vault, so that the first
+    /// signatures don't pay for loading them. The warm-up is best-effort: the keys which can't
+    /// be loaded, or which are not loaded before the timeout, are only reported
+    async fn warm_up_keys(node_identities: &NodeIdentities, options: &IdentityServiceOptions) {
+        let names = options.warm_up_identities();
+        if names.is_empty() {
+            return;
+        }
+        let started_at = Instant::now();
+        let mut warmed_up = vec![];
+        let warm_up = async {
+            for name in names {
+                match Self::warm_up_key(node_identities, name).await {
+                    Ok(()) => warmed_up.push(name.as_str()),
+                    Err(e) => warn!(identity = %name, %e, "unable to warm up the identity key"),
+                }
+            }
+        };
+        let timed_out = timeout(options.warm_up_timeout(), warm_up).await.is_err();
+        let elapsed_ms = started_at.elapsed().as_millis() as u64;
+        if timed_out {
+            warn!(
+                warmed_up = ?warmed_up,
+                elapsed_ms,
+                "the warm-up of the identity keys timed out"
+            );
+        } else {
+            info!(warmed_up = ?warmed_up, elapsed_ms, "warmed up the identity keys");
+        }
+    }
+
+    /// Load the current root key of an identity, given by name, from the default vault
+    async fn warm_up_key(node_identities: &NodeIdentities, name: &str) -> Result<()> {
+        let identity = node_identities
+            .get_identity(name.to_string())
+            .await?
+            .ok_or_else(|| ApiError::message(format!("unknown identity {name}")))?;
+        let vault = node_identities.get_identities_vault(None).await?;
+        let key_id = vault.get_key_id(&identity.get_root_public_key()?).await?;
+        // reading the public key loads the secret from the storage of the vault
+        vault.get_public_key(&key_id).await?;
+        Ok(())
     }
 }
 
-impl IdentityService {
+impl IdentityServiceHandler {
     fn response_for_bad_request<W>(req: &Request, msg: &str, enc: W) -> Result<()>
     where
         W: Write<Error = Infallible>,
@@ -70,8 +405,97 @@ impl IdentityService {
         Ok(())
     }
 
+    /// Reject a request because too many requests are currently being processed.
+    /// The error message contains a hint for the delay after which the client can retry
+    fn response_for_overload<W>(req: &Request, retry_after: Duration, enc: W) -> Result<()>
+    where
+        W: Write<Error = Infallible>,
+    {
+        let error = Error::new(req.path()).with_message(format!(
+            "the identity service is overloaded, retry after {}ms",
+            retry_after.as_millis()
+        ));
+
+        let error = if let Some(m) = req.method() {
+            error.with_method(m)
+        } else {
+            error
+        };
+
+        Response::service_unavailable(req.id())
+            .body(error)
+            .encode(enc)?;
+
+        Ok(())
+    }
+
+    /// Reject a request which uses a vault whose policy doesn't allow its sender
+    fn response_for_forbidden_vault<W>(req: &Request, enc: W) -> Result<()>
+    where
+        W: Write<Error = Infallible>,
+    {
+        Self::response_with_error(
+            Some(req),
+            Status::Forbidden,
+            "the policy of the vault does not allow the sender to use it",
+            enc,
+        )
+    }
+
+    /// Reject a request because its sender exceeded its rate limit
+    fn response_for_rate_limit<W>(
+        req: &Request,
+        sender: &IdentityIdentifier,
+        retry_after: Duration,
+        enc: W,
+    ) -> Result<()>
+    where
+        W: Write<Error = Infallible>,
+    {
+        let error = Error::new(req.path()).with_message(format!(
+            "too many requests from {sender}, retry after {}ms",
+            retry_after.as_millis().max(1)
+        ));
+
+        let error = if let Some(m) = req.method() {
+            error.with_method(m)
+        } else {
+            error
+        };
+
+        Response::too_many_requests(req.id())
+            .body(error)
+            .encode(enc)?;
+
+        Ok(())
+    }
+
+    /// Reject a request because it could not be processed before the request timeout
+    fn response_for_timeout<W>(req: &Request, request_timeout: Duration, enc: W) -> Result<()>
+    where
+        W: Write<Error = Infallible>,
+    {
+        let error = Error::new(req.path()).with_message(format!(
+            "the request could not be processed within {}ms",
+            request_timeout.as_millis()
+        ));
+
+        let error = if let Some(m) = req.method() {
+            error.with_method(m)
+        } else {
+            error
+        };
+
+        Response::gateway_timeout(req.id())
+            .body(error)
+            .encode(enc)?;
+
+        Ok(())
+    }
+
     async fn handle_request<W>(
         &mut self,
+        sender: Option<&IdentityIdentifier>,
         req: &Request<'_>,
         dec: &mut Decoder<'_>,
         enc: W,
@@ -97,6 +521,365 @@ impl IdentityService {
 
         match method {
             Get => match req.path_segments::<2>().as_slice() {
+                ["health"] => {
+                    let body = HealthResponse::new(
+                        self.in_flight_requests.load(Ordering::Relaxed) as u64,
+                        self.options.max_in_flight_requests() as u64,
+                    );
+                    Self::ok_response(req, Some(body), enc)
+                }
+                // Clients discover the service with this endpoint first, to check that they
+                // can use its API, then with `capabilities/signing` for the signing schemes
+                // supported by its vault
+                ["version"] => {
+                    let body = VersionResponse::new(
+                        IDENTITY_SERVICE_API_VERSION,
+                        IDENTITY_SERVICE_MIN_CLIENT_VERSION,
+                        option_env!("GIT_HASH"),
+                    );
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["capabilities", "signing"] => {
+                    let schemes = self.signing_capabilities.clone();
+                    let body = SigningCapabilitiesResponse::new(schemes);
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["metrics"] => {
+                    let body = self.metrics.to_response(self.verification_keys.len());
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["service", "identity"] => {
+                    let name = match self.options.service_identity() {
+                        Some(name) => name.to_string(),
+                        None => {
+                            return Self::response_with_error(
+                                Some(req),
+                                Status::NotFound,
+                                "the identity service has no signing identity. \
+                                 The operator must set one with `IdentityServiceOptions::with_service_identity`",
+                                enc,
+                            )
+                        }
+                    };
+                    match self.node_identities.get_identity(name.clone()).await? {
+                        Some(identity) => {
+                            let body = ServiceIdentityResponse::new(
+                                name,
+                                identity.export()?,
+                                identity.identifier().to_string(),
+                            );
+                            Self::ok_response(req, Some(body), enc)
+                        }
+                        None => {
+                            let msg = format!(
+                                "the signing identity of the identity service is not known to the node: {name}"
+                            );
+                            Self::response_with_error(Some(req), Status::NotFound, &msg, enc)
+                        }
+                    }
+                }
+                // The entries are returned oldest first. `audit?limit=N` only returns
+                // the N most recent entries
+                [audit] if split_query(audit).0 == "audit" => {
+                    let limit = match query_parameter(req.path(), "limit") {
+                        None => usize::MAX,
+                        Some(limit) => match limit.parse::<usize>() {
+                            Ok(limit) => limit,
+                            Err(_) => {
+                                let msg = format!("invalid limit: {limit}");
+                                return Self::response_for_bad_request(req, &msg, enc);
+                            }
+                        },
+                    };
+                    let body = AuditLogResponse::new(self.audit_log.recent(limit));
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["challenge"] => {
+                    let ttl = self.options.challenge_ttl();
+                    let challenge = random::<[u8; 32]>().to_vec();
+                    self.state_store.put(
+                        CHALLENGES,
+                        &hex::encode(&challenge),
+                        vec![],
+                        Some(ttl),
+                    )?;
+                    let body = ChallengeResponse::new(challenge, ttl.as_secs());
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["vault", vault_path] => match vault_path.strip_suffix("/ping") {
+                    Some(vault_name) if self.node_identities.vault_exists(vault_name) => {
+                        let body = self.ping_vault(vault_name).await;
+                        Self::ok_response(req, Some(body), enc)
+                    }
+                    Some(vault_name) => Self::response_with_error(
+                        Some(req),
+                        Status::NotFound,
+                        &format!("unknown vault: {vault_name}"),
+                        enc,
+                    ),
+                    None => Self::response_for_bad_request(req, "unknown path", enc),
+                },
+                ["store", "export"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<StoreExportRequest>()?;
+                    if !self.vault_allows(args.vault_name(), sender)? {
+                        return Self::response_for_forbidden_vault(req, enc);
+                    }
+                    let admin = match self
+                        .node_identities
+                        .get_identity(args.admin().to_string())
+                        .await?
+                    {
+                        Some(admin) => admin,
+                        None => {
+                            return Self::response_for_bad_request(req, "unknown identity", enc)
+                        }
+                    };
+                    let repository = self.node_identities.identities_repository();
+                    let mut identities = vec![];
+                    for (name, identifier) in
+                        self.node_identities.find_identities_by_name_prefix("")?
+                    {
+                        match repository.retrieve_identity(&identifier).await? {
+                            Some(identity) => {
+                                identities.push(BundledIdentity::new(name, identity.export()?))
+                            }
+                            None => warn!(
+                                name = %name,
+                                identifier = %identifier,
+                                "the identity is not stored by this node and is not exported"
+                            ),
+                        }
+                    }
+                    let exported_at = match Timestamp::now() {
+                        Some(now) => now,
+                        None => return Err(ApiError::generic("unable to get the current time")),
+                    };
+                    let data = minicbor::to_vec(IdentitiesBundleData::new(
+                        admin.identifier().to_string(),
+                        exported_at,
+                        identities,
+                    ))?;
+                    let signature = self
+                        .node_identities
+                        .get_identities_keys(args.vault_name())
+                        .await?
+                        .create_signature(&admin, &data, None)
+                        .await?;
+                    IdentityServiceMetrics::increment(&self.metrics.signatures_created);
+
+                    let body = IdentitiesBundle::new(data, signature.as_ref().to_vec());
+                    Self::ok_response(req, Some(body), enc)
+                }
+                // Names which were given to the same identity, for example when it was
+                // imported twice. Each identifier is derived from the stored change history
+                // of the identity when it is available
+                ["store", "duplicates"] => {
+                    let repository = self.node_identities.identities_repository();
+                    let mut names_by_identifier: BTreeMap<String, Vec<String>> = BTreeMap::new();
+                    for (name, identifier) in
+                        self.node_identities.find_identities_by_name_prefix("")?
+                    {
+                        let identifier = match repository.retrieve_identity(&identifier).await? {
+                            Some(identity) => identity.identifier(),
+                            None => identifier,
+                        };
+                        names_by_identifier
+                            .entry(identifier.to_string())
+                            .or_default()
+                            .push(name);
+                    }
+                    let duplicates = names_by_identifier
+                        .into_iter()
+                        .filter(|(_, names)| names.len() > 1)
+                        .map(|(identifier, mut names)| {
+                            names.sort();
+                            DuplicateIdentities::new(
+                                identifier,
+                                names.into_iter().map(|n| n.into()).collect(),
+                            )
+                        })
+                        .collect();
+                    let body = DuplicateIdentitiesResponse::new(duplicates);
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["listeners"] => {
+                    let listeners = match &self.secure_channel_listeners {
+                        Some(listeners) => listeners,
+                        None => {
+                            return Self::response_with_error(
+                                Some(req),
+                                Status::NotImplemented,
+                                "secure channel listeners are not tracked by this service",
+                                enc,
+                            )
+                        }
+                    };
+                    let identities = self
+                        .node_identities
+                        .find_identities_by_name_prefix("")?
+                        .into_iter()
+                        .map(|(name, identifier)| {
+                            let addresses = listeners
+                                .addresses_of(&identifier)
+                                .iter()
+                                .map(|a| a.to_string().into())
+                                .collect();
+                            IdentityListeners::new(name, identifier.to_string(), addresses)
+                        })
+                        .collect();
+                    let body = ListIdentityListenersResponse::new(identities);
+                    Self::ok_response(req, Some(body), enc)
+                }
+                [""] => {
+                    let args = if req.has_body() {
+                        dec.decode::<ListIdentitiesRequest>()?
+                    } else {
+                        ListIdentitiesRequest::new()
+                    };
+                    let identities = self.list_identities(args.attributes()).await?;
+                    let mut labels = BTreeMap::new();
+                    let mut identity_ids = vec![];
+                    for (identity_id, label) in identities {
+                        if let Some(label) = label {
+                            labels.insert(identity_id.clone(), label);
+                        }
+                        identity_ids.push(identity_id.into());
+                    }
+                    let body = ListIdentitiesResponse::new(identity_ids).with_labels(labels);
+                    Self::ok_response(req, Some(body), enc)
+                }
+                [identity_name, "current_key"] => {
+                    match self
+                        .node_identities
+                        .get_identity(identity_name.to_string())
+                        .await?
+                    {
+                        Some(identity) => {
+                            let history = identity.change_history();
+                            match history.as_ref().last() {
+                                Some(change) => {
+                                    let public_key = change.change().public_key()?;
+                                    let mut body = CurrentKeyResponse::new(
+                                        (history.as_ref().len() - 1) as u64,
+                                        public_key.data(),
+                                    );
+                                    if let Some(created_at) = self.key_created_at(&identity).await?
+                                    {
+                                        body = body.with_created_at(created_at);
+                                    }
+                                    Self::ok_response(req, Some(body), enc)
+                                }
+                                None => {
+                                    Self::response_for_bad_request(req, "empty change history", enc)
+                                }
+                            }
+                        }
+                        None => Self::response_for_bad_request(req, "unknown identity", enc),
+                    }
+                }
+                // The key types are listed in the order in which they first appear in the history
+                [identity_name, "key_types"] => {
+                    match self
+                        .node_identities
+                        .get_identity(identity_name.to_string())
+                        .await?
+                    {
+                        Some(identity) => {
+                            let mut key_types: Vec<SecretType> = vec![];
+                            for change in identity.change_history().as_ref() {
+                                let key_type = change.change().public_key()?.stype();
+                                if !key_types.contains(&key_type) {
+                                    key_types.push(key_type);
+                                }
+                            }
+                            let body = KeyTypesResponse::new(key_types);
+                            Self::ok_response(req, Some(body), enc)
+                        }
+                        None => Self::response_for_bad_request(req, "unknown identity", enc),
+                    }
+                }
+                // The root key is returned as a JWK by default, and the current key of each
+                // label is returned as a JWK Set when the client accepts JWK_SET_MEDIA_TYPE
+                [identity_name, "jwk"] => {
+                    let identity = match self
+                        .node_identities
+                        .get_identity(identity_name.to_string())
+                        .await?
+                    {
+                        Some(identity) => identity,
+                        None => {
+                            return Self::response_for_bad_request(req, "unknown identity", enc)
+                        }
+                    };
+                    let media_type = req.accept().unwrap_or(JWK_MEDIA_TYPE);
+                    let keys = match media_type {
+                        JWK_MEDIA_TYPE => vec![(
+                            IdentityChangeConstants::ROOT_LABEL.to_string(),
+                            identity.get_root_public_key()?,
+                        )],
+                        JWK_SET_MEDIA_TYPE => current_public_keys(&identity)?.into_iter().collect(),
+                        _ => {
+                            let msg = format!("unsupported media type: {media_type}");
+                            return Self::response_for_bad_request(req, &msg, enc);
+                        }
+                    };
+                    let mut jwks = vec![];
+                    for (label, public_key) in keys {
+                        match public_key_to_jwk(&public_key, &key_id(&identity, &label)) {
+                            Some(jwk) => jwks.push(jwk),
+                            None => {
+                                let msg = format!(
+                                    "the {} key '{label}' can't be represented as a JWK",
+                                    public_key.stype()
+                                );
+                                return Self::response_for_bad_request(req, &msg, enc);
+                            }
+                        }
+                    }
+                    let jwk = if media_type == JWK_SET_MEDIA_TYPE {
+                        serde_json::json!({ "keys": jwks })
+                    } else {
+                        jwks.remove(0)
+                    };
+                    let body = PublicKeyJwkResponse::new(media_type, jwk.to_string());
+                    Self::ok_response(req, Some(body), enc)
+                }
+                [identity_name, "public"] => {
+                    match self
+                        .node_identities
+                        .get_identity(identity_name.to_string())
+                        .await?
+                    {
+                        Some(identity) => {
+                            let public_key = identity.get_root_public_key()?;
+                            let body = PublicIdentityResponse::new(
+                                identity.identifier().to_string(),
+                                public_key.stype(),
+                                public_key.data(),
+                            );
+                            Self::ok_response(req, Some(body), enc)
+                        }
+                        None => Self::response_for_bad_request(req, "unknown identity", enc),
+                    }
+                }
+                // A trailing `*` returns the identities whose name starts with the preceding
+                // prefix, `*` alone returning all the identities. The match is case-sensitive
+                // and no other character is interpreted as a wildcard
+                [pattern] if pattern.ends_with('*') => {
+                    let prefix = &pattern[..pattern.len() - 1];
+                    let identities = self
+                        .node_identities
+                        .find_identities_by_name_prefix(prefix)?
+                        .into_iter()
+                        .map(|(name, identifier)| NamedIdentity::new(name, identifier.to_string()))
+                        .collect();
+                    let body = FindIdentitiesResponse::new(identities);
+                    Self::ok_response(req, Some(body), enc)
+                }
                 [identity_name] => {
                     match self
                         .node_identities
@@ -104,10 +887,12 @@ impl IdentityService {
                         .await?
                     {
                         Some(identity) => {
+                            let label = self.identity_label(&identity.identifier()).await?;
                             let body = CreateResponse::new(
                                 identity.export()?,
                                 identity.identifier().to_string(),
-                            );
+                            )
+                            .with_label(label);
                             Self::ok_response(req, Some(body), enc)
                         }
                         None => Self::response_for_bad_request(req, "unknown identity", enc),
@@ -117,121 +902,3017 @@ impl IdentityService {
             },
             Post => match req.path_segments::<2>().as_slice() {
                 [""] => {
-                    let identity = self
-                        .node_identities
-                        .get_default_identities_creation()
-                        .await?
-                        .create_identity()
+                    let args = if req.has_body() {
+                        dec.decode::<CreateIdentityRequest>()?
+                    } else {
+                        CreateIdentityRequest::new()
+                    };
+                    let secret_attributes = match args.key_type() {
+                        None => SecretAttributes::Ed25519,
+                        Some(key_type) => match parse_key_type(key_type) {
+                            Some(secret_attributes) => secret_attributes,
+                            None => {
+                                let msg = format!("unsupported key type: {key_type}");
+                                return Self::response_for_bad_request(req, &msg, enc);
+                            }
+                        },
+                    };
+                    if let Some(msg) = args.label().and_then(invalid_label) {
+                        return Self::response_for_bad_request(req, &msg, enc);
+                    }
+                    let identities_creation = if args.persist() {
+                        self.node_identities
+                            .get_identities_creation(args.vault_name())
+                            .await?
+                    } else if args.vault_name().is_some() {
+                        let msg =
+                            "a vault can't be selected for an identity which is not persisted";
+                        return Self::response_for_bad_request(req, msg, enc);
+                    } else if args.label().is_some() {
+                        let msg = "a label can't be given to an identity which is not persisted";
+                        return Self::response_for_bad_request(req, msg, enc);
+                    } else {
+                        // The key and the identity are only stored in this vault and this
+                        // repository, which are dropped once the response is built
+                        IdentitiesCreation::new(IdentitiesStorage::create(), Vault::create())
+                    };
+                    let identity = match identities_creation
+                        .create_identity_with_secret_attributes(secret_attributes)
+                        .await
+                    {
+                        Ok(identity) => identity,
+                        // the vault rejects key types it doesn't support
+                        Err(e)
+                            if e.code().origin == Origin::Vault
+                                && e.code().kind == Kind::Misuse =>
+                        {
+                            let msg = format!(
+                                "the vault does not support the key type: {}",
+                                secret_attributes.secret_type()
+                            );
+                            return Self::response_for_bad_request(req, &msg, enc);
+                        }
+                        Err(e) => return Err(e),
+                    };
+                    if args.persist() {
+                        self.record_key_creation(&identity).await?;
+                    }
+                    if let Some(label) = args.label() {
+                        self.store_attribute(
+                            &identity.identifier(),
+                            LABEL_ATTRIBUTE,
+                            label.as_bytes().to_vec(),
+                        )
                         .await?;
+                    }
+                    IdentityServiceMetrics::increment(&self.metrics.identities_created);
                     let body =
-                        CreateResponse::new(identity.export()?, identity.identifier().to_string());
+                        CreateResponse::new(identity.export()?, identity.identifier().to_string())
+                            .with_label(args.label().map(|label| label.to_string()));
 
                     Self::ok_response(req, Some(body), enc)
                 }
-                ["actions", "validate_identity_change_history"] => {
+                // The label is replaced, or removed when the request has no label. The keys of
+                // the identity are not changed
+                ["actions", "set_label"] => {
                     if !req.has_body() {
                         return Self::response_for_bad_request(req, "empty body", enc);
                     }
 
-                    let args = dec.decode::<ValidateIdentityChangeHistoryRequest>()?;
-                    let identities_creation = self
+                    let args = dec.decode::<SetLabelRequest>()?;
+                    if let Some(msg) = args.label().and_then(invalid_label) {
+                        return Self::response_for_bad_request(req, &msg, enc);
+                    }
+                    let identifier = match IdentityIdentifier::try_from(args.identity_id()) {
+                        Ok(identifier) => identifier,
+                        Err(_) => {
+                            return Self::response_for_bad_request(req, "invalid identifier", enc)
+                        }
+                    };
+                    let identity = match self
                         .node_identities
-                        .get_default_identities_creation()
-                        .await?;
-                    let identity = identities_creation.decode_identity(args.identity()).await?;
-
-                    let body = ValidateIdentityChangeHistoryResponse::new(String::from(
-                        identity.identifier(),
-                    ));
+                        .identities_repository()
+                        .retrieve_identity(&identifier)
+                        .await?
+                    {
+                        Some(identity) => identity,
+                        None => {
+                            return Self::response_for_bad_request(req, "unknown identity", enc)
+                        }
+                    };
+                    match args.label() {
+                        Some(label) => {
+                            self.store_attribute(
+                                &identifier,
+                                LABEL_ATTRIBUTE,
+                                label.as_bytes().to_vec(),
+                            )
+                            .await?
+                        }
+                        None => self.remove_attribute(&identifier, LABEL_ATTRIBUTE).await?,
+                    }
 
+                    let body = CreateResponse::new(identity.export()?, identifier.to_string())
+                        .with_label(args.label().map(|label| label.to_string()));
                     Self::ok_response(req, Some(body), enc)
                 }
-                ["actions", "create_signature"] => {
+                ["actions", "create_from_public_key"] => {
                     if !req.has_body() {
                         return Self::response_for_bad_request(req, "empty body", enc);
                     }
 
-                    let args = dec.decode::<CreateSignatureRequest>()?;
-                    let identities_creation = self
-                        .node_identities
-                        .get_identities_creation(args.vault_name())
-                        .await?;
-                    let identity = identities_creation.decode_identity(args.identity()).await?;
-                    let identities_keys = self
-                        .node_identities
+                    let args = dec.decode::<CreateFromPublicKeyRequest>()?;
+                    let secret_attributes = match parse_key_type(args.key_type()) {
+                        Some(secret_attributes) => secret_attributes,
+                        None => {
+                            let msg = format!("unsupported key type: {}", args.key_type());
+                            return Self::response_for_bad_request(req, &msg, enc);
+                        }
+                    };
+                    let public_key =
+                        PublicKey::new(args.public_key().to_vec(), secret_attributes.secret_type());
+                    let (change_id, change) = initial_key_change(secret_attributes, public_key)?;
+
+                    // The change must be signed with the private key, which the node doesn't hold
+                    let self_signature = match args.self_signature() {
+                        Some(self_signature) => self_signature,
+                        None => {
+                            let body = CreateFromPublicKeyResponse::unsigned(change_id.as_ref());
+                            return Self::ok_response(req, Some(body), enc);
+                        }
+                    };
+                    let self_signature = match normalize_signature(
+                        secret_attributes.secret_type(),
+                        self_signature,
+                    ) {
+                        Some(self_signature) => self_signature,
+                        None => {
+                            return Self::response_for_bad_request(
+                                req,
+                                "malformed self-signature",
+                                enc,
+                            )
+                        }
+                    };
+                    let signed_change = IdentitySignedChange::new(
+                        change_id.clone(),
+                        change,
+                        vec![ChangeSignature::new(
+                            SignatureType::SelfSign,
+                            self_signature,
+                        )],
+                    );
+                    let identity = match self
+                        .node_identities
+                        .get_default_identities_creation()
+                        .await?
+                        .decode_identity(&vec![signed_change].encode()?)
+                        .await
+                    {
+                        Ok(identity) => identity,
+                        Err(_) => {
+                            return Self::response_for_bad_request(
+                                req,
+                                "the self-signature doesn't match the public key",
+                                enc,
+                            )
+                        }
+                    };
+
+                    if let Some(name) = args.name() {
+                        if !is_valid_identity_name(name) {
+                            let msg = format!("invalid identity name: {name}");
+                            return Self::response_for_bad_request(req, &msg, enc);
+                        }
+                        let collides = self
+                            .node_identities
+                            .find_identities_by_name_prefix(name)?
+                            .into_iter()
+                            .any(|(existing, identifier)| {
+                                existing == name && identifier != identity.identifier()
+                            });
+                        if collides {
+                            let msg =
+                                format!("the name is already used by another identity: {name}");
+                            return Self::response_with_error(
+                                Some(req),
+                                Status::Conflict,
+                                &msg,
+                                enc,
+                            );
+                        }
+                    }
+                    self.node_identities
+                        .identities_repository()
+                        .update_identity(&identity)
+                        .await?;
+                    if let Some(name) = args.name() {
+                        self.node_identities
+                            .set_identity_name(name, &identity.identifier())
+                            .await?;
+                    }
+                    IdentityServiceMetrics::increment(&self.metrics.identities_created);
+                    info!(identity = %identity.identifier(), "created an identity from a public key");
+
+                    let body = CreateFromPublicKeyResponse::new(
+                        change_id.as_ref(),
+                        identity.export()?,
+                        identity.identifier().to_string(),
+                    );
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "validate_identity_change_history"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<ValidateIdentityChangeHistoryRequest>()?;
+                    let identities_creation = self
+                        .node_identities
+                        .get_default_identities_creation()
+                        .await?;
+                    let identity = identities_creation.decode_identity(args.identity()).await?;
+
+                    let body = ValidateIdentityChangeHistoryResponse::new(String::from(
+                        identity.identifier(),
+                    ));
+
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "fingerprint"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<FingerprintRequest>()?;
+                    let identity = self
+                        .node_identities
+                        .get_default_identities_creation()
+                        .await?
+                        .decode_identity(args.identity())
+                        .await?;
+                    let digest = history_digest(&identity.export()?);
+
+                    let body = FingerprintResponse::new(
+                        identity.identifier().to_string(),
+                        fingerprint_hex(&digest),
+                        fingerprint_words(&digest)?,
+                        fingerprint_base32(&digest),
+                    );
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "canonicalize"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<CreateSignatureRequest>()?;
+                    let payload = match signing_payload(&args) {
+                        Ok(payload) => payload,
+                        Err(msg) => return Self::response_for_bad_request(req, &msg, enc),
+                    };
+                    let body = CanonicalizeResponse::new(payload);
+
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "create_signature"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<CreateSignatureRequest>()?;
+                    if !self.vault_allows(args.vault_name(), sender)? {
+                        return Self::response_for_forbidden_vault(req, enc);
+                    }
+                    let payload = match signing_payload(&args) {
+                        Ok(payload) => payload,
+                        Err(msg) => return Self::response_for_bad_request(req, &msg, enc),
+                    };
+                    let rotated_identity = match self.rotate_overused_key(&args).await? {
+                        Ok(rotated_identity) => rotated_identity,
+                        Err(msg) => return Self::response_for_bad_request(req, &msg, enc),
+                    };
+                    let signer = match &rotated_identity {
+                        Some(rotated_identity) => rotated_identity.export()?,
+                        None => args.identity().to_vec(),
+                    };
+                    let CreatedSignature {
+                        identity,
+                        identities_keys,
+                        mut signature,
+                        vault_name: group_vault,
+                    } = match self.sign_payload(&args, sender, &signer, &payload).await? {
+                        Ok(created) => created,
+                        Err(msg) => return Self::response_for_bad_request(req, &msg, enc),
+                    };
+                    IdentityServiceMetrics::increment(&self.metrics.signatures_created);
+                    let mut key_uses = 1;
+
+                    let key_type = identity.get_root_public_key()?.stype();
+                    // Ed25519 signatures are always deterministic. The software vault uses
+                    // RFC 6979 nonces for P-256 signatures but other vaults, like AWS KMS, use
+                    // random nonces, so the determinism is checked by signing a second time
+                    let deterministic = match key_type {
+                        SecretType::Ed25519 => true,
+                        SecretType::NistP256 if args.deterministic() => {
+                            let other_signature = identities_keys
+                                .create_signature(&identity, &payload, None)
+                                .await?;
+                            key_uses += 1;
+                            if other_signature.as_ref() != signature.as_ref() {
+                                return Self::response_for_bad_request(
+                                    req,
+                                    "the vault does not support deterministic P-256 signatures",
+                                    enc,
+                                );
+                            }
+                            true
+                        }
+                        _ if args.deterministic() => {
+                            let msg = format!(
+                                "deterministic signatures are not supported for the key type: {key_type}"
+                            );
+                            return Self::response_for_bad_request(req, &msg, enc);
+                        }
+                        _ => false,
+                    };
+
+                    if let Some(encoding) = args.signature_encoding() {
+                        match key_type {
+                            SecretType::NistP256 => {
+                                signature = signature.to_ecdsa_encoding(encoding)?;
+                            }
+                            // Other key types have a single signature encoding
+                            _ if encoding == EcdsaSignatureEncoding::Der => {
+                                return Self::response_for_bad_request(
+                                    req,
+                                    "the DER encoding is only supported for ECDSA signatures",
+                                    enc,
+                                );
+                            }
+                            _ => {}
+                        }
+                    }
+
+                    self.record_key_usage(&identity, key_uses).await?;
+
+                    let mut body = CreateSignatureResponse::new(signature.as_ref())
+                        .with_deterministic(deterministic);
+                    if let Some(vault_name) = group_vault {
+                        body = body.with_vault_name(vault_name);
+                    }
+                    if rotated_identity.is_some() {
+                        body = body.with_rotated_identity(signer);
+                    }
+                    if let Some(json_pointers) = args.json_pointers() {
+                        body = body.with_json_pointers(json_pointers.to_vec());
+                    }
+                    if args.include_kid() {
+                        body = body.with_kid(signing_key_id(&identity.get_root_public_key()?));
+                    }
+                    if let Some(route) = args.bound_route() {
+                        match canonical_route(route) {
+                            Ok(route) => body = body.with_bound_route(route),
+                            Err(msg) => return Self::response_for_bad_request(req, &msg, enc),
+                        }
+                    }
+
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "derived_key_signature"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<DerivedKeySignatureRequest>()?;
+                    if !self.vault_allows(args.vault_name(), sender)? {
+                        return Self::response_for_forbidden_vault(req, enc);
+                    }
+                    let identities_creation = self
+                        .node_identities
+                        .get_identities_creation(args.vault_name())
+                        .await?;
+                    let identity = identities_creation.decode_identity(args.identity()).await?;
+                    if identity.get_root_public_key()?.stype() != SecretType::Ed25519 {
+                        return Self::response_for_bad_request(
+                            req,
+                            "derived keys are only supported for Ed25519 identity keys",
+                            enc,
+                        );
+                    }
+
+                    let vault = self
+                        .node_identities
+                        .get_identities_vault(args.vault_name())
+                        .await?;
+                    let identities_keys = self
+                        .node_identities
+                        .get_identities_keys(args.vault_name())
+                        .await?;
+                    let key_id = derive_signing_key(
+                        vault.clone(),
+                        &identities_keys,
+                        &identity,
+                        args.salt(),
+                        args.info(),
+                    )
+                    .await?;
+
+                    let result = async {
+                        let public_key = vault.get_public_key(&key_id).await?;
+                        let signature = match args.data() {
+                            Some(data) => Some(vault.sign(&key_id, data).await?),
+                            None => None,
+                        };
+                        Ok::<_, ockam_core::Error>((public_key, signature))
+                    }
+                    .await;
+                    vault.delete_ephemeral_secret(key_id).await?;
+                    let (public_key, signature) = result?;
+                    if signature.is_some() {
+                        IdentityServiceMetrics::increment(&self.metrics.signatures_created);
+                    }
+
+                    let body = DerivedKeySignatureResponse::new(
+                        public_key.data(),
+                        signature.as_ref().map(|s| s.as_ref().into()),
+                    );
+
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "prove_possession"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<ProvePossessionRequest>()?;
+                    if !self.vault_allows(args.vault_name(), sender)? {
+                        return Self::response_for_forbidden_vault(req, enc);
+                    }
+                    // An empty challenge would produce a proof which can be replayed for any verifier
+                    if args.challenge().is_empty() {
+                        return Self::response_for_bad_request(req, "empty challenge", enc);
+                    }
+
+                    let identities_creation = self
+                        .node_identities
+                        .get_identities_creation(args.vault_name())
+                        .await?;
+                    let identity = identities_creation.decode_identity(args.identity()).await?;
+                    let identities_keys = self
+                        .node_identities
+                        .get_identities_keys(args.vault_name())
+                        .await?;
+                    // The challenge is prefixed, so that the proof can't be used as a
+                    // signature of data chosen by the verifier
+                    let payload = possession_proof_payload(
+                        &identity.identifier().to_string(),
+                        args.challenge(),
+                    );
+                    let signature = identities_keys
+                        .create_signature(&identity, &payload, None)
+                        .await?;
+                    IdentityServiceMetrics::increment(&self.metrics.signatures_created);
+
+                    let body = ProvePossessionResponse::new(
+                        identity.identifier().to_string(),
+                        signature.as_ref(),
+                    );
+
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "attest_challenge"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<AttestChallengeRequest>()?;
+                    let body = self.attest_challenge(&args).await?;
+
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "begin_sign"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<BeginSignRequest>()?;
+                    if !self.vault_allows(args.vault_name(), sender)? {
+                        return Self::response_for_forbidden_vault(req, enc);
+                    }
+                    let identity = self
+                        .node_identities
+                        .get_identities_creation(args.vault_name())
+                        .await?
+                        .decode_identity(args.identity())
+                        .await?;
+                    match self
+                        .signing_sessions
+                        .begin(identity, args.vault_name(), sender)
+                    {
+                        Some(session_id) => {
+                            let body = BeginSignResponse::new(session_id);
+                            Self::ok_response(req, Some(body), enc)
+                        }
+                        None => Self::response_for_overload(req, self.options.retry_after(), enc),
+                    }
+                }
+                ["actions", "sign_chunk"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<SignChunkRequest>()?;
+                    match self.signing_sessions.get_mut(args.session_id(), sender) {
+                        Some(session) => {
+                            session.update(args.chunk());
+                            Response::ok(req.id()).encode(enc)?;
+                            Ok(())
+                        }
+                        None => Self::response_for_bad_request(req, "unknown signing session", enc),
+                    }
+                }
+                ["actions", "finish_sign"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<FinishSignRequest>()?;
+                    let vault_name = match self.signing_sessions.get_mut(args.session_id(), sender)
+                    {
+                        Some(session) => session.vault_name(),
+                        None => {
+                            return Self::response_for_bad_request(
+                                req,
+                                "unknown signing session",
+                                enc,
+                            )
+                        }
+                    };
+                    // The session is kept when the sender is not allowed to use the vault
+                    if !self.vault_allows(vault_name, sender)? {
+                        return Self::response_for_forbidden_vault(req, enc);
+                    }
+                    let session = match self.signing_sessions.finish(args.session_id(), sender) {
+                        Some(session) => session,
+                        None => {
+                            return Self::response_for_bad_request(
+                                req,
+                                "unknown signing session",
+                                enc,
+                            )
+                        }
+                    };
+                    let identities_keys = self
+                        .node_identities
+                        .get_identities_keys(session.vault_name())
+                        .await?;
+                    let identity = session.identity().clone();
+                    let digest = session.finalize();
+                    let signature = identities_keys
+                        .create_signature(&identity, &digest, None)
+                        .await?;
+                    IdentityServiceMetrics::increment(&self.metrics.signatures_created);
+
+                    let body = CreateSignatureResponse::new(signature.as_ref());
+
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "begin_threshold_sign"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<BeginThresholdSignRequest>()?;
+                    if !self.vault_allows(args.vault_name(), sender)? {
+                        return Self::response_for_forbidden_vault(req, enc);
+                    }
+                    let threshold = args.threshold() as usize;
+                    if threshold == 0 || threshold > args.signers().len() {
+                        let msg = format!(
+                            "the threshold must be between 1 and the number of signers ({})",
+                            args.signers().len()
+                        );
+                        return Self::response_for_bad_request(req, &msg, enc);
+                    }
+                    let identities_creation = self
+                        .node_identities
+                        .get_identities_creation(args.vault_name())
+                        .await?;
+                    let mut signers: Vec<Identity> = vec![];
+                    for signer in args.signers() {
+                        let signer = identities_creation.decode_identity(signer).await?;
+                        if signers
+                            .iter()
+                            .any(|s| s.identifier() == signer.identifier())
+                        {
+                            return Self::response_for_bad_request(
+                                req,
+                                "the signers must be distinct identities",
+                                enc,
+                            );
+                        }
+                        signers.push(signer);
+                    }
+
+                    // The partial signature of the local co-signer, if any
+                    let local_partial = match args.identity_name() {
+                        None => None,
+                        Some(identity_name) => {
+                            let identity = match self
+                                .node_identities
+                                .get_identity(identity_name.to_string())
+                                .await?
+                            {
+                                Some(identity) => identity,
+                                None => {
+                                    return Self::response_for_bad_request(
+                                        req,
+                                        "unknown identity",
+                                        enc,
+                                    )
+                                }
+                            };
+                            let signer_index = match signers
+                                .iter()
+                                .position(|s| s.identifier() == identity.identifier())
+                            {
+                                Some(signer_index) => signer_index,
+                                None => {
+                                    return Self::response_for_bad_request(
+                                        req,
+                                        "the local identity is not one of the signers",
+                                        enc,
+                                    )
+                                }
+                            };
+                            let signature = self
+                                .node_identities
+                                .get_identities_keys(args.vault_name())
+                                .await?
+                                .create_signature(&identity, args.data(), None)
+                                .await?;
+                            IdentityServiceMetrics::increment(&self.metrics.signatures_created);
+                            Some((signer_index, signature.as_ref().to_vec()))
+                        }
+                    };
+
+                    let signers = signers
+                        .iter()
+                        .map(ThresholdSigner::new)
+                        .collect::<Result<Vec<_>>>()?;
+                    let session_id = match self.threshold_signing_sessions.begin(
+                        args.data().to_vec(),
+                        signers,
+                        threshold,
+                    )? {
+                        Some(session_id) => session_id,
+                        None => {
+                            return Self::response_for_overload(
+                                req,
+                                self.options.retry_after(),
+                                enc,
+                            )
+                        }
+                    };
+                    if let Some((signer_index, signature)) = local_partial {
+                        if let Some(mut session) =
+                            self.threshold_signing_sessions.get(&session_id)?
+                        {
+                            session.add_partial(signer_index, signature);
+                            self.threshold_signing_sessions
+                                .update(&session_id, &mut session)?;
+                        }
+                    }
+                    let body = BeginSignResponse::new(session_id);
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "submit_partial"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<SubmitPartialRequest>()?;
+                    let mut session =
+                        match self.threshold_signing_sessions.get(args.session_id())? {
+                            Some(session) => session,
+                            None => {
+                                return Self::response_for_bad_request(
+                                    req,
+                                    "unknown threshold signing session",
+                                    enc,
+                                )
+                            }
+                        };
+                    let signer_index = args.signer_index() as usize;
+                    let signer = match session.signers().get(signer_index) {
+                        Some(signer) => signer,
+                        None => return Self::response_for_bad_request(req, "unknown signer", enc),
+                    };
+                    let signer = self
+                        .node_identities
+                        .get_default_identities_creation()
+                        .await?
+                        .decode_identity(signer.identity())
+                        .await?;
+                    let is_valid = self
+                        .node_identities
+                        .get_default_identities_keys()
+                        .await?
+                        .verify_signature(
+                            &signer,
+                            &Signature::new(args.signature().to_vec()),
+                            session.data(),
+                            None,
+                        )
+                        .await
+                        .unwrap_or(false);
+                    if !is_valid {
+                        return Self::response_for_bad_request(
+                            req,
+                            "invalid partial signature",
+                            enc,
+                        );
+                    }
+                    session.add_partial(signer_index, args.signature().to_vec());
+                    self.threshold_signing_sessions
+                        .update(args.session_id(), &mut session)?;
+
+                    let body = SubmitPartialResponse::new(
+                        session.collected() as u64,
+                        session.threshold() as u64,
+                    );
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "combine"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<CombineRequest>()?;
+                    // The session stays open until enough partial signatures are collected
+                    match self.threshold_signing_sessions.get(args.session_id())? {
+                        Some(session) if session.collected() >= session.threshold() => {}
+                        Some(session) => {
+                            let msg = format!(
+                                "{} partial signatures were collected, {} are needed",
+                                session.collected(),
+                                session.threshold()
+                            );
+                            return Self::response_for_bad_request(req, &msg, enc);
+                        }
+                        None => {
+                            return Self::response_for_bad_request(
+                                req,
+                                "unknown threshold signing session",
+                                enc,
+                            )
+                        }
+                    }
+                    let session = match self.threshold_signing_sessions.finish(args.session_id())? {
+                        Some(session) => session,
+                        None => {
+                            return Self::response_for_bad_request(
+                                req,
+                                "unknown threshold signing session",
+                                enc,
+                            )
+                        }
+                    };
+                    let threshold = session.threshold() as u64;
+                    let identifiers: Vec<String> = session
+                        .signers()
+                        .iter()
+                        .map(|s| s.identifier().to_string())
+                        .collect();
+                    let partials = session
+                        .partials()
+                        .into_iter()
+                        .map(|(signer_index, signature)| {
+                            PartialSignature::new(
+                                signer_index as u64,
+                                identifiers[signer_index].clone(),
+                                signature,
+                            )
+                        })
+                        .collect();
+
+                    let body = ThresholdSignature::new(threshold, partials);
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "verify_signature"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<VerifySignatureRequest>()?;
+                    // a digest is verified like any data, once it is known to have been computed
+                    // by the algorithm used to sign
+                    if let Some(algorithm) = args.digest_algorithm() {
+                        if let Err(msg) = check_digest(algorithm, args.data()) {
+                            return Self::response_for_bad_request(req, &msg, enc);
+                        }
+                    }
+                    let peer_identity = if !args.signer_identity().is_empty() {
+                        self.cached_signer_identity(args.signer_identity()).await?
+                    } else if let Some(signer_id) = args.signer_id() {
+                        let signer_id = match IdentityIdentifier::try_from(signer_id) {
+                            Ok(signer_id) => signer_id,
+                            Err(_) => {
+                                return Self::response_for_bad_request(
+                                    req,
+                                    "invalid signer identifier",
+                                    enc,
+                                )
+                            }
+                        };
+                        match self.known_signer_identity(&signer_id).await? {
+                            Some(identity) => identity,
+                            None => {
+                                IdentityServiceMetrics::increment(
+                                    &self.metrics.verifications_failed,
+                                );
+                                let body = VerifySignatureResponse::failed(
+                                    VerificationFailureReason::UnknownSigner,
+                                );
+                                return Self::ok_response(req, Some(body), enc);
+                            }
+                        }
+                    } else {
+                        return Self::response_for_bad_request(req, "missing signer", enc);
+                    };
+
+                    let revoked = self.options.check_revocation()
+                        && self
+                            .find_revocation(&peer_identity.identifier())
+                            .await?
+                            .is_some();
+                    let public_key = peer_identity.get_root_public_key()?;
+                    let wrong_signer = args
+                        .required_signer()
+                        .map(|required| required != peer_identity.identifier().to_string())
+                        .unwrap_or(false);
+                    let data = match args.bound_route() {
+                        Some(route) => match canonical_route(route) {
+                            Ok(route) => route_bound_payload(&route, args.data()),
+                            Err(msg) => return Self::response_for_bad_request(req, &msg, enc),
+                        },
+                        None => args.data().to_vec(),
+                    };
+                    let wrong_kid = args
+                        .kid()
+                        .map(|kid| kid != signing_key_id(&public_key))
+                        .unwrap_or(false);
+                    let weak_key = args
+                        .min_key_bits()
+                        .map(|min_key_bits| key_security_bits(public_key.stype()) < min_key_bits)
+                        .unwrap_or(false);
+                    let stale_key = args
+                        .min_change_index()
+                        .map(|min_change_index| {
+                            root_key_change_index(&peer_identity) < min_change_index
+                        })
+                        .unwrap_or(false);
+                    let (verified, failure_reason) =
+                        match normalize_signature(public_key.stype(), args.signature()) {
+                            _ if wrong_signer => {
+                                (false, Some(VerificationFailureReason::WrongSigner))
+                            }
+                            _ if revoked => (false, Some(VerificationFailureReason::Revoked)),
+                            _ if wrong_kid => (false, Some(VerificationFailureReason::KidMismatch)),
+                            _ if weak_key => (false, Some(VerificationFailureReason::WeakKey)),
+                            _ if stale_key => (false, Some(VerificationFailureReason::StaleKey)),
+                            None => (false, Some(VerificationFailureReason::MalformedSignature)),
+                            Some(signature) => {
+                                let identities_keys =
+                                    self.node_identities.get_default_identities_keys().await?;
+                                match identities_keys
+                                    .verify_signature(&peer_identity, &signature, &data, None)
+                                    .await
+                                {
+                                    Ok(true) => (true, None),
+                                    Ok(false) => {
+                                        (false, Some(VerificationFailureReason::KeyMismatch))
+                                    }
+                                    Err(_) => (false, Some(VerificationFailureReason::Unknown)),
+                                }
+                            }
+                        };
+
+                    IdentityServiceMetrics::increment(if verified {
+                        &self.metrics.verifications_passed
+                    } else {
+                        &self.metrics.verifications_failed
+                    });
+
+                    let body = match failure_reason {
+                        Some(reason) => VerifySignatureResponse::failed(reason),
+                        None => VerifySignatureResponse::new(verified),
+                    };
+
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "verify_signatures"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<VerifySignaturesRequest>()?;
+                    let verified = self.verify_signatures(args.records()).await?;
+                    let body = VerifySignaturesResponse::new(verified);
+
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "resign"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<ResignRequest>()?;
+                    if !self.vault_allows(args.vault_name(), sender)? {
+                        return Self::response_for_forbidden_vault(req, enc);
+                    }
+                    match self.resign(&args).await? {
+                        Ok(body) => Self::ok_response(req, Some(body), enc),
+                        Err(msg) => Self::response_for_bad_request(req, &msg, enc),
+                    }
+                }
+                // The expression grammar is described in the `policy_expression` module.
+                // The attributes of the identity are the ones stored by the node
+                ["actions", "evaluate_policy"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<EvaluatePolicyRequest>()?;
+                    let expression = match PolicyExpression::parse(args.expression()) {
+                        Ok(expression) => expression,
+                        Err(e) => {
+                            let msg = format!("invalid policy expression: {e}");
+                            return Self::response_for_bad_request(req, &msg, enc);
+                        }
+                    };
+                    let identity = if let Some(identity) = args.identity() {
+                        self.node_identities
+                            .get_default_identities_creation()
+                            .await?
+                            .decode_identity(identity)
+                            .await?
+                    } else if let Some(identity_id) = args.identity_id() {
+                        let identity_id = match IdentityIdentifier::try_from(identity_id) {
+                            Ok(identity_id) => identity_id,
+                            Err(_) => {
+                                return Self::response_for_bad_request(
+                                    req,
+                                    "invalid identity identifier",
+                                    enc,
+                                )
+                            }
+                        };
+                        match self.known_signer_identity(&identity_id).await? {
+                            Some(identity) => identity,
+                            None => {
+                                let msg = format!("unknown identity: {identity_id}");
+                                return Self::response_with_error(
+                                    Some(req),
+                                    Status::NotFound,
+                                    &msg,
+                                    enc,
+                                );
+                            }
+                        }
+                    } else {
+                        return Self::response_for_bad_request(req, "missing identity", enc);
+                    };
+
+                    let attributes = self
+                        .node_identities
+                        .identities_repository()
+                        .get_attributes(&identity.identifier())
+                        .await?
+                        .map(|entry| entry.attrs().clone())
+                        .unwrap_or_default();
+                    let key_age = match (self.key_created_at(&identity).await?, Timestamp::now()) {
+                        (Some(created_at), Some(now)) => now.elapsed(created_at),
+                        _ => None,
+                    };
+                    let failed_clauses = expression
+                        .failed_clauses(&attributes, key_age)
+                        .into_iter()
+                        .map(|clause| clause.to_string().into())
+                        .collect();
+
+                    let body = EvaluatePolicyResponse::new(failed_clauses);
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "verify_remote"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<VerifyRemoteSignatureRequest>()?;
+                    let directory = match Route::parse(args.directory_route()) {
+                        Some(directory) => directory,
+                        None => {
+                            return Self::response_for_bad_request(
+                                req,
+                                "invalid directory route",
+                                enc,
+                            )
+                        }
+                    };
+                    let signer = match self
+                        .fetch_remote_identity(
+                            args.directory_route(),
+                            directory,
+                            args.signer_name(),
+                        )
+                        .await
+                    {
+                        Ok(signer) => signer,
+                        Err(e) => {
+                            warn!(
+                                directory = %args.directory_route(),
+                                signer = %args.signer_name(),
+                                "unable to fetch the signer identity: {e}"
+                            );
+                            let body = VerifySignatureResponse::failed(
+                                VerificationFailureReason::SignerUnavailable,
+                            );
+                            return Self::ok_response(req, Some(body), enc);
+                        }
+                    };
+
+                    let revoked = self.options.check_revocation()
+                        && self.find_revocation(signer.identifier()).await?.is_some();
+                    let public_key = signer.public_key();
+                    let (verified, failure_reason) =
+                        match normalize_signature(public_key.stype(), args.signature()) {
+                            _ if revoked => (false, Some(VerificationFailureReason::Revoked)),
+                            None => (false, Some(VerificationFailureReason::MalformedSignature)),
+                            Some(signature) => {
+                                let vault = self.node_identities.get_identities_vault(None).await?;
+                                match vault.verify(public_key, args.data(), &signature).await {
+                                    Ok(true) => (true, None),
+                                    Ok(false) => {
+                                        (false, Some(VerificationFailureReason::KeyMismatch))
+                                    }
+                                    Err(_) => (false, Some(VerificationFailureReason::Unknown)),
+                                }
+                            }
+                        };
+
+                    IdentityServiceMetrics::increment(if verified {
+                        &self.metrics.verifications_passed
+                    } else {
+                        &self.metrics.verifications_failed
+                    });
+
+                    let body = match failure_reason {
+                        Some(reason) => VerifySignatureResponse::failed(reason),
+                        None => VerifySignatureResponse::new(verified),
+                    };
+
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "same_signer"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<SameSignerRequest>()?;
+                    if args.candidates().is_empty() {
+                        return Self::response_for_bad_request(req, "no candidate identities", enc);
+                    }
+                    let identities_creation = self
+                        .node_identities
+                        .get_default_identities_creation()
+                        .await?;
+                    let mut candidates = Vec::with_capacity(args.candidates().len());
+                    for candidate in args.candidates() {
+                        match identities_creation.decode_identity(candidate).await {
+                            Ok(identity) => candidates.push(identity),
+                            Err(_) => {
+                                return Self::response_for_bad_request(
+                                    req,
+                                    "invalid candidate identity",
+                                    enc,
+                                )
+                            }
+                        }
+                    }
+
+                    let first_signer = self.find_signer(&candidates, args.first()).await?;
+                    let second_signer = self.find_signer(&candidates, args.second()).await?;
+                    let body = SameSignerResponse::new(
+                        first_signer.map(|i| i.to_string()),
+                        second_signer.map(|i| i.to_string()),
+                    );
+
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "issue_delegation_token"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<IssueDelegationTokenRequest>()?;
+                    if !self.vault_allows(args.vault_name(), sender)? {
+                        return Self::response_for_forbidden_vault(req, enc);
+                    }
+                    let identities_creation = self
+                        .node_identities
+                        .get_identities_creation(args.vault_name())
+                        .await?;
+                    let issuer = identities_creation.decode_identity(args.issuer()).await?;
+                    let expires_at = match Timestamp::now() {
+                        Some(now) => now.add_seconds(args.ttl_secs()),
+                        None => return Err(ApiError::generic("unable to get the current time")),
+                    };
+                    let data = minicbor::to_vec(DelegationTokenData::new(
+                        issuer.identifier().to_string(),
+                        args.delegate(),
+                        args.scope(),
+                        expires_at,
+                        random::<[u8; 16]>().to_vec(),
+                    ))?;
+                    let identities_keys = self
+                        .node_identities
+                        .get_identities_keys(args.vault_name())
+                        .await?;
+                    let signature = identities_keys
+                        .create_signature(&issuer, &data, None)
+                        .await?;
+                    IdentityServiceMetrics::increment(&self.metrics.signatures_created);
+
+                    let body = IssueDelegationTokenResponse::new(DelegationToken::new(
+                        data,
+                        signature.as_ref().to_vec(),
+                    ));
+
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "verify_delegation_token"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<VerifyDelegationTokenRequest>()?;
+                    let body = self
+                        .verify_delegation_token(args.issuer(), args.token())
+                        .await?;
+
+                    Self::ok_response(req, Some(body), enc)
+                }
+                // Credentials are short-lived, their lifetime being at most the
+                // `max_channel_credential_ttl` option of the service
+                ["actions", "issue_channel_credential"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<IssueChannelCredentialRequest>()?;
+                    if !self.vault_allows(args.vault_name(), sender)? {
+                        return Self::response_for_forbidden_vault(req, enc);
+                    }
+                    let max_ttl_secs = self.options.max_channel_credential_ttl().as_secs();
+                    if args.ttl_secs() == 0 || args.ttl_secs() > max_ttl_secs {
+                        let msg = format!("ttl_secs must be between 1 and {max_ttl_secs}");
+                        return Self::response_for_bad_request(req, &msg, enc);
+                    }
+                    if IdentityIdentifier::try_from(args.subject()).is_err() {
+                        return Self::response_for_bad_request(req, "invalid subject", enc);
+                    }
+                    let identities_creation = self
+                        .node_identities
+                        .get_identities_creation(args.vault_name())
+                        .await?;
+                    let issuer = identities_creation.decode_identity(args.issuer()).await?;
+                    let issued_at = match Timestamp::now() {
+                        Some(now) => now,
+                        None => return Err(ApiError::generic("unable to get the current time")),
+                    };
+                    let data = minicbor::to_vec(ChannelCredentialData::new(
+                        issuer.identifier().to_string(),
+                        args.subject(),
+                        args.service(),
+                        issued_at,
+                        issued_at.add_seconds(args.ttl_secs()),
+                    ))?;
+                    let signature = self
+                        .node_identities
+                        .get_identities_keys(args.vault_name())
+                        .await?
+                        .create_signature(&issuer, &data, None)
+                        .await?;
+                    IdentityServiceMetrics::increment(&self.metrics.signatures_created);
+
+                    let body = IssueChannelCredentialResponse::new(ChannelCredential::new(
+                        data,
+                        signature.as_ref().to_vec(),
+                    ));
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "verify_channel_credential"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<VerifyChannelCredentialRequest>()?;
+                    let body = self.verify_channel_credential(&args).await?;
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "cross_sign"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<CrossSignRequest>()?;
+                    if !self.vault_allows(args.vault_name(), sender)? {
+                        return Self::response_for_forbidden_vault(req, enc);
+                    }
+                    let endorser = self
+                        .node_identities
+                        .get_identity(args.endorser().to_string())
+                        .await?;
+                    let subject = self
+                        .node_identities
+                        .get_identity(args.subject().to_string())
+                        .await?;
+                    let (endorser, subject) = match (endorser, subject) {
+                        (Some(endorser), Some(subject)) => (endorser, subject),
+                        _ => return Self::response_for_bad_request(req, "unknown identity", enc),
+                    };
+                    let identities_keys = self
+                        .node_identities
+                        .get_identities_keys(args.vault_name())
+                        .await?;
+                    if identities_keys
+                        .get_secret_key(&endorser, None)
+                        .await
+                        .is_err()
+                    {
+                        return Self::response_for_bad_request(
+                            req,
+                            "the key of the endorser is not available in the vault",
+                            enc,
+                        );
+                    }
+
+                    let data = minicbor::to_vec(EndorsementData::new(
+                        endorser.identifier().to_string(),
+                        subject.identifier().to_string(),
+                        subject.get_root_public_key()?.data().to_vec(),
+                    ))?;
+                    let signature = identities_keys
+                        .create_signature(&endorser, &data, None)
+                        .await?;
+                    IdentityServiceMetrics::increment(&self.metrics.signatures_created);
+
+                    let body =
+                        CrossSignResponse::new(Endorsement::new(data, signature.as_ref().to_vec()));
+
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "verify_endorsement"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<VerifyEndorsementRequest>()?;
+                    let failure_reason = self
+                        .verify_endorsement(args.endorser(), args.subject(), args.endorsement())
+                        .await?;
+                    IdentityServiceMetrics::increment(if failure_reason.is_none() {
+                        &self.metrics.verifications_passed
+                    } else {
+                        &self.metrics.verifications_failed
+                    });
+
+                    let body = VerifyEndorsementResponse::new(failure_reason);
+
+                    Self::ok_response(req, Some(body), enc)
+                }
+                // The asserted time is read from the clock of this node, which verifiers
+                // must trust: a timestamp token proves who asserted the time, not the time itself
+                ["actions", "timestamp"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<TimestampRequest>()?;
+                    if !self.vault_allows(args.vault_name(), sender)? {
+                        return Self::response_for_forbidden_vault(req, enc);
+                    }
+                    if args.digest().is_empty() || args.digest().len() > MAX_TIMESTAMPED_DIGEST_LEN
+                    {
+                        let msg = format!(
+                            "the digest must contain between 1 and {MAX_TIMESTAMPED_DIGEST_LEN} bytes"
+                        );
+                        return Self::response_for_bad_request(req, &msg, enc);
+                    }
+                    let signer = match self
+                        .node_identities
+                        .get_identity(args.identity().to_string())
+                        .await?
+                    {
+                        Some(signer) => signer,
+                        None => {
+                            return Self::response_for_bad_request(req, "unknown identity", enc)
+                        }
+                    };
+                    let time = match Timestamp::now() {
+                        Some(now) => now,
+                        None => return Err(ApiError::generic("unable to get the current time")),
+                    };
+                    let data = minicbor::to_vec(TimestampTokenData::new(
+                        signer.identifier().to_string(),
+                        args.digest(),
+                        time,
+                        random::<[u8; 16]>().to_vec(),
+                    ))?;
+                    let signature = self
+                        .node_identities
+                        .get_identities_keys(args.vault_name())
+                        .await?
+                        .create_signature(&signer, &data, None)
+                        .await?;
+                    IdentityServiceMetrics::increment(&self.metrics.signatures_created);
+
+                    let body = TimestampResponse::new(TimestampToken::new(
+                        data,
+                        signature.as_ref().to_vec(),
+                    ));
+
+                    Self::ok_response(req, Some(body), enc)
+                }
+                // The tree hashing scheme is described in the `merkle_tree` module
+                ["actions", "sign_merkle_root"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<SignMerkleRootRequest>()?;
+                    if !self.vault_allows(args.vault_name(), sender)? {
+                        return Self::response_for_forbidden_vault(req, enc);
+                    }
+                    let leaves: Vec<&[u8]> = args.leaves().iter().map(|leaf| &leaf[..]).collect();
+                    let levels = merkle_tree_levels(&leaves);
+                    let root = match levels.last() {
+                        Some(root) => root[0],
+                        None => return Self::response_for_bad_request(req, "no leaves", enc),
+                    };
+                    let identity = self
+                        .node_identities
+                        .get_identities_creation(args.vault_name())
+                        .await?
+                        .decode_identity(args.identity())
+                        .await?;
+                    let data = minicbor::to_vec(MerkleRootData::new(
+                        identity.identifier().to_string(),
+                        root.to_vec(),
+                        leaves.len() as u64,
+                    ))?;
+                    let signature = self
+                        .node_identities
+                        .get_identities_keys(args.vault_name())
+                        .await?
+                        .create_signature(&identity, &data, None)
+                        .await?;
+                    IdentityServiceMetrics::increment(&self.metrics.signatures_created);
+                    self.record_key_usage(&identity, 1).await?;
+
+                    let levels = levels
+                        .iter()
+                        .map(|level| level.iter().map(|hash| hash.to_vec().into()).collect())
+                        .collect();
+                    let body = SignMerkleRootResponse::new(
+                        SignedMerkleRoot::new(data, signature.as_ref().to_vec()),
+                        levels,
+                    );
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "verify_inclusion_proof"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<VerifyInclusionProofRequest>()?;
+                    let body = self.verify_inclusion_proof(&args).await?;
+                    IdentityServiceMetrics::increment(if body.verified() {
+                        &self.metrics.verifications_passed
+                    } else {
+                        &self.metrics.verifications_failed
+                    });
+
+                    Self::ok_response(req, Some(body), enc)
+                }
+                // Only the bytes of the signature are inspected: the candidates are described
+                // in the `signature_schemes` module
+                ["actions", "signature_candidates"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<SignatureCandidatesRequest>()?;
+                    if args.signature().is_empty() {
+                        return Self::response_for_bad_request(req, "empty signature", enc);
+                    }
+                    let body = SignatureCandidatesResponse::new(
+                        args.signature().len() as u64,
+                        signature_candidates(args.signature()),
+                    );
+
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "verify_timestamp"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<VerifyTimestampRequest>()?;
+                    let body = self
+                        .verify_timestamp(args.signer(), args.digest(), args.token())
+                        .await?;
+                    IdentityServiceMetrics::increment(if body.verified() {
+                        &self.metrics.verifications_passed
+                    } else {
+                        &self.metrics.verifications_failed
+                    });
+
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "store_import"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<StoreImportRequest>()?;
+                    let data = match self
+                        .verify_identities_bundle(args.admin(), args.bundle())
+                        .await?
+                    {
+                        Some(data) => data,
+                        None => {
+                            return Self::response_with_error(
+                                Some(req),
+                                Status::Forbidden,
+                                "the bundle is not signed by the admin identity",
+                                enc,
+                            )
+                        }
+                    };
+
+                    // All the identities are checked before any of them is imported
+                    let identities_creation = self
+                        .node_identities
+                        .get_default_identities_creation()
+                        .await?;
+                    let mut identities = vec![];
+                    let mut collisions = vec![];
+                    for bundled in data.identities() {
+                        if !is_valid_identity_name(bundled.name()) {
+                            let msg = format!("invalid identity name: {}", bundled.name());
+                            return Self::response_for_bad_request(req, &msg, enc);
+                        }
+                        let identity = match identities_creation
+                            .decode_identity(bundled.identity())
+                            .await
+                        {
+                            Ok(identity) => identity,
+                            Err(_) => {
+                                let msg = format!("invalid identity: {}", bundled.name());
+                                return Self::response_for_bad_request(req, &msg, enc);
+                            }
+                        };
+                        let existing = self
+                            .node_identities
+                            .find_identities_by_name_prefix(bundled.name())?
+                            .into_iter()
+                            .find(|(name, _)| name == bundled.name());
+                        let collides = matches!(
+                            existing,
+                            Some((_, identifier)) if identifier != identity.identifier()
+                        );
+                        if collides {
+                            collisions.push(bundled.name().to_string());
+                        }
+                        identities.push((bundled.name().to_string(), identity, collides));
+                    }
+                    if !collisions.is_empty() && args.on_collision() == NameCollisionPolicy::Fail {
+                        let msg = format!(
+                            "these names are already used by other identities: {}",
+                            collisions.join(", ")
+                        );
+                        return Self::response_with_error(Some(req), Status::Conflict, &msg, enc);
+                    }
+
+                    let repository = self.node_identities.identities_repository();
+                    let mut imported: Vec<CowStr> = vec![];
+                    let mut skipped: Vec<CowStr> = vec![];
+                    for (name, identity, collides) in identities {
+                        if collides && args.on_collision() == NameCollisionPolicy::Skip {
+                            skipped.push(name.into());
+                            continue;
+                        }
+                        if repository.update_identity(&identity).await.is_err() {
+                            // the node knows a conflicting change history for this identity
+                            skipped.push(name.into());
+                            continue;
+                        }
+                        self.node_identities
+                            .set_identity_name(&name, &identity.identifier())
+                            .await?;
+                        imported.push(name.into());
+                    }
+                    info!(
+                        imported = imported.len(),
+                        skipped = skipped.len(),
+                        "imported an identities bundle"
+                    );
+
+                    let body = StoreImportResponse::new(imported, skipped);
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["admin", "purge_sessions"] => {
+                    if !sender.map_or(false, |sender| self.options.is_admin(sender)) {
+                        return Self::response_with_error(
+                            Some(req),
+                            Status::Forbidden,
+                            "only an administrator of the identity service can purge the sessions",
+                            enc,
+                        );
+                    }
+                    let args = if req.has_body() {
+                        dec.decode::<PurgeSessionsRequest>()?
+                    } else {
+                        PurgeSessionsRequest::new(0)
+                    };
+                    let idle_for = Duration::from_secs(args.idle_for_secs());
+                    let purged = self.signing_sessions.purge(idle_for)
+                        + self.threshold_signing_sessions.purge(idle_for)?;
+                    info!(
+                        purged,
+                        idle_for_secs = args.idle_for_secs(),
+                        "purged signing sessions"
+                    );
+
+                    let body = PurgeSessionsResponse::new(purged as u64);
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "revoke_identity"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<RevokeIdentityRequest>()?;
+                    if !self.vault_allows(args.vault_name(), sender)? {
+                        return Self::response_for_forbidden_vault(req, enc);
+                    }
+                    let identity = match self
+                        .node_identities
+                        .get_identity(args.identity().to_string())
+                        .await?
+                    {
+                        Some(identity) => identity,
+                        None => {
+                            return Self::response_for_bad_request(req, "unknown identity", enc)
+                        }
+                    };
+                    if self
+                        .find_revocation(&identity.identifier())
+                        .await?
+                        .is_some()
+                    {
+                        return Self::response_for_bad_request(
+                            req,
+                            "the identity is already revoked",
+                            enc,
+                        );
+                    }
+
+                    let revoked_at = match Timestamp::now() {
+                        Some(now) => now,
+                        None => return Err(ApiError::generic("unable to get the current time")),
+                    };
+                    let data = minicbor::to_vec(RevocationData::new(
+                        identity.identifier().to_string(),
+                        revoked_at,
+                        args.reason().map(|r| r.into()),
+                    ))?;
+                    let identities_keys = self
+                        .node_identities
                         .get_identities_keys(args.vault_name())
                         .await?;
                     let signature = identities_keys
-                        .create_signature(&identity, args.data(), None)
+                        .create_signature(&identity, &data, None)
+                        .await?;
+                    IdentityServiceMetrics::increment(&self.metrics.signatures_created);
+
+                    let revocation = Revocation::new(data, signature.as_ref().to_vec());
+                    self.store_revocation(&identity.identifier(), &revocation)
+                        .await?;
+                    let body = RevokeIdentityResponse::new(revocation);
+
+                    Self::ok_response(req, Some(body), enc)
+                }
+                // The proof is signed by the signing identity of the service and expires
+                // after the `freshness_proof_ttl` option of the service
+                ["actions", "prove_not_revoked"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<ProveNotRevokedRequest>()?;
+                    let subject = match IdentityIdentifier::try_from(args.identity_id()) {
+                        Ok(subject) => subject,
+                        Err(_) => {
+                            return Self::response_for_bad_request(
+                                req,
+                                "invalid identity identifier",
+                                enc,
+                            )
+                        }
+                    };
+                    let issuer = match self.options.service_identity() {
+                        Some(name) => self.node_identities.get_identity(name.to_string()).await?,
+                        None => None,
+                    };
+                    let issuer = match issuer {
+                        Some(issuer) => issuer,
+                        None => {
+                            return Self::response_with_error(
+                                Some(req),
+                                Status::NotFound,
+                                "the identity service has no signing identity",
+                                enc,
+                            )
+                        }
+                    };
+                    if self.find_revocation(&subject).await?.is_some() {
+                        return Self::response_for_bad_request(req, "the identity is revoked", enc);
+                    }
+
+                    let checked_at = match Timestamp::now() {
+                        Some(now) => now,
+                        None => return Err(ApiError::generic("unable to get the current time")),
+                    };
+                    let data = minicbor::to_vec(FreshnessProofData::new(
+                        issuer.identifier().to_string(),
+                        subject.to_string(),
+                        checked_at,
+                        checked_at.add_seconds(self.options.freshness_proof_ttl().as_secs()),
+                    ))?;
+                    let signature = self
+                        .node_identities
+                        .get_default_identities_keys()
+                        .await?
+                        .create_signature(&issuer, &data, None)
+                        .await?;
+                    IdentityServiceMetrics::increment(&self.metrics.signatures_created);
+
+                    let body = ProveNotRevokedResponse::new(FreshnessProof::new(
+                        data,
+                        signature.as_ref().to_vec(),
+                    ));
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "verify_freshness_proof"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<VerifyFreshnessProofRequest>()?;
+                    let body = self.verify_freshness_proof(&args).await?;
+                    IdentityServiceMetrics::increment(if body.verified() {
+                        &self.metrics.verifications_passed
+                    } else {
+                        &self.metrics.verifications_failed
+                    });
+
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "compare_identity_change_history"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<CompareIdentityChangeHistoryRequest>()?;
+
+                    let identities_creation = self
+                        .node_identities
+                        .get_default_identities_creation()
+                        .await?;
+
+                    let current_identity = identities_creation
+                        .decode_identity(args.current_identity())
                         .await?;
 
-                    let body = CreateSignatureResponse::new(signature.as_ref());
+                    let body = if args.known_identity().is_empty() {
+                        IdentityHistoryComparison::Newer
+                    } else {
+                        let known_identity = identities_creation
+                            .decode_identity(args.known_identity())
+                            .await?;
+                        current_identity.compare(&known_identity)
+                    };
+
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "history_delta"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<HistoryDeltaRequest>()?;
+                    let identifier = match IdentityIdentifier::try_from(args.identity_id()) {
+                        Ok(identifier) => identifier,
+                        Err(_) => {
+                            return Self::response_for_bad_request(req, "invalid identity id", enc)
+                        }
+                    };
+                    let identity = match self
+                        .node_identities
+                        .identities_repository()
+                        .retrieve_identity(&identifier)
+                        .await?
+                    {
+                        Some(identity) => identity,
+                        None => {
+                            return Self::response_for_bad_request(req, "unknown identity", enc)
+                        }
+                    };
+
+                    let body = Self::history_delta(&identity.change_history(), &args)?;
+                    Self::ok_response(req, Some(body), enc)
+                }
+                ["actions", "verify_identity_change_history"] => {
+                    if !req.has_body() {
+                        return Self::response_for_bad_request(req, "empty body", enc);
+                    }
+
+                    let args = dec.decode::<VerifyIdentityChangeHistoryRequest>()?;
+                    let body = self.verify_identity_change_history(&args).await?;
+
+                    Self::ok_response(req, Some(body), enc)
+                }
+                _ => Self::response_for_bad_request(req, "unknown path", enc),
+            },
+            Put | Patch | Delete => Self::response_for_bad_request(req, "unknown method", enc),
+        }
+    }
+
+    /// Sign the payload of a `create_signature` request with the requested vault or,
+    /// when a vault group is requested, with a vault of that group
+    async fn sign_payload(
+        &mut self,
+        args: &CreateSignatureRequest<'_>,
+        sender: Option<&IdentityIdentifier>,
+        identity: &[u8],
+        payload: &[u8],
+    ) -> Result<std::result::Result<CreatedSignature, String>> {
+        let group_name = match args.vault_group() {
+            None => {
+                let identities_creation = self
+                    .node_identities
+                    .get_identities_creation(args.vault_name())
+                    .await?;
+                let identity = identities_creation.decode_identity(identity).await?;
+                let identities_keys = self
+                    .node_identities
+                    .get_identities_keys(args.vault_name())
+                    .await?;
+                let signature = identities_keys
+                    .create_signature(&identity, payload, None)
+                    .await?;
+                return Ok(Ok(CreatedSignature {
+                    identity,
+                    identities_keys,
+                    signature,
+                    vault_name: None,
+                }));
+            }
+            Some(_) if args.vault_name().is_some() => {
+                return Ok(Err(
+                    "a vault name and a vault group can't be used together".to_string()
+                ))
+            }
+            Some(group_name) => group_name,
+        };
+        self.sign_with_vault_group(group_name, sender, identity, payload)
+            .await
+    }
+
+    /// Return true if the access policy of a named vault, if any, allows the sender of a
+    /// request to use it. A vault with a policy can't be used by an unauthenticated sender.
+    ///
+    /// The policy covers every action which signs with the keys of a named vault: store
+    /// export, signatures (including the vaults of a vault group, derived keys and streamed
+    /// signing sessions), proofs of possession, threshold signing, re-signing, delegation
+    /// tokens, channel credentials, cross-signing, timestamps, Merkle roots and revocations.
+    /// Creating an identity is not covered since it only uses a new key, and the default
+    /// vault, used when no vault is named, has no policy
+    fn vault_allows(
+        &self,
+        vault_name: Option<String>,
+        sender: Option<&IdentityIdentifier>,
+    ) -> Result<bool> {
+        let vault_name = match vault_name {
+            Some(vault_name) => vault_name,
+            None => return Ok(true),
+        };
+        Ok(match self.node_identities.vault_policy(&vault_name)? {
+            None => true,
+            Some(policy) => sender
+                .map(|sender| policy.allows(&sender.to_string()))
+                .unwrap_or(false),
+        })
+    }
+
+    /// Sign again some data which was signed with a previous root key of an identity.
+    /// The new signature is created with the current root key and covers the data, a hash
+    /// of the old signature and the old public key, so that the migration can be audited.
+    /// Return an error message if the old signature is not verified by a previous root key
+    async fn resign(
+        &self,
+        args: &ResignRequest<'_>,
+    ) -> Result<std::result::Result<ResignResponse<'static>, String>> {
+        let identity = self
+            .node_identities
+            .get_identities_creation(args.vault_name())
+            .await?
+            .decode_identity(args.identity())
+            .await?;
+        let identities_keys = self
+            .node_identities
+            .get_identities_keys(args.vault_name())
+            .await?;
+        let vault = self
+            .node_identities
+            .get_identities_vault(args.vault_name())
+            .await?;
+
+        // the last root key is the current one, which is not an old key
+        let mut root_keys = vec![];
+        for change in identity.change_history().as_ref() {
+            if change.change().label() == IdentityChangeConstants::ROOT_LABEL {
+                root_keys.push(change.change().public_key()?);
+            }
+        }
+        root_keys.pop();
+
+        let mut old_public_key = None;
+        for public_key in root_keys {
+            let signature = match normalize_signature(public_key.stype(), args.old_signature()) {
+                Some(signature) => signature,
+                None => continue,
+            };
+            if vault
+                .verify(&public_key, args.data(), &signature)
+                .await
+                .unwrap_or(false)
+            {
+                old_public_key = Some(public_key);
+                break;
+            }
+        }
+        IdentityServiceMetrics::increment(if old_public_key.is_some() {
+            &self.metrics.verifications_passed
+        } else {
+            &self.metrics.verifications_failed
+        });
+        let old_public_key = match old_public_key {
+            Some(old_public_key) => old_public_key,
+            None => {
+                return Ok(Err(
+                    "the old signature is not verified by a previous root key of the identity"
+                        .to_string(),
+                ))
+            }
+        };
+
+        let attestation = minicbor::to_vec(ResignedData::new(
+            args.data(),
+            Vault::sha256(args.old_signature()).to_vec(),
+            old_public_key.data().to_vec(),
+        ))?;
+        let signature = identities_keys
+            .create_signature(&identity, &attestation, None)
+            .await?;
+        IdentityServiceMetrics::increment(&self.metrics.signatures_created);
+        self.record_key_usage(&identity, 1).await?;
+
+        Ok(Ok(ResignResponse::new(
+            attestation,
+            signature.as_ref().to_vec(),
+        )))
+    }
+
+    /// Rotate the root key of the identity of a signature request when that key has created
+    /// as many signatures as allowed by the key usage limit of the identity.
+    /// Return the rotated identity, if the key was rotated
+    async fn rotate_overused_key(
+        &self,
+        args: &CreateSignatureRequest<'_>,
+    ) -> Result<std::result::Result<Option<Identity>, String>> {
+        let mut identity = self
+            .node_identities
+            .get_identities_creation(args.vault_name())
+            .await?
+            .decode_identity(args.identity())
+            .await?;
+        let max_signatures = match self.options.key_usage_limit(&identity.identifier()) {
+            Some(max_signatures) => max_signatures,
+            None => return Ok(Ok(None)),
+        };
+        // the rotated key would only be created in one of the vaults of the group
+        if args.vault_group().is_some() {
+            return Ok(Err(
+                "key usage limits are not supported with vault groups".to_string()
+            ));
+        }
+        if self.key_usage(&identity).await? < max_signatures {
+            return Ok(Ok(None));
+        }
+        self.node_identities
+            .get_identities_keys(args.vault_name())
+            .await?
+            .rotate_root_key(&mut identity)
+            .await?;
+        self.node_identities
+            .identities_repository()
+            .update_identity(&identity)
+            .await?;
+        self.record_key_creation(&identity).await?;
+        info!(
+            identity = %identity.identifier(),
+            max_signatures,
+            "rotated a root key which reached its usage limit"
+        );
+        Ok(Ok(Some(identity)))
+    }
+
+    /// Record that the current root key of an identity was just created
+    async fn record_key_creation(&self, identity: &Identity) -> Result<()> {
+        let now = match Timestamp::now() {
+            Some(now) => now,
+            None => return Err(ApiError::generic("unable to get the current time")),
+        };
+        let creation = KeyCreation::new(identity.get_root_public_key()?.data(), now);
+        self.store_attribute(
+            &identity.identifier(),
+            KEY_CREATION_ATTRIBUTE,
+            minicbor::to_vec(creation)?,
+        )
+        .await
+    }
+
+    /// Return the creation time of the current root key of an identity, if it is known
+    async fn key_created_at(&self, identity: &Identity) -> Result<Option<Timestamp>> {
+        let stored = self
+            .node_identities
+            .identities_repository()
+            .get_attributes(&identity.identifier())
+            .await?
+            .and_then(|entry| entry.attrs().get(KEY_CREATION_ATTRIBUTE).cloned());
+        let creation = match stored {
+            Some(stored) => minicbor::decode::<KeyCreation>(&stored)?,
+            None => return Ok(None),
+        };
+        // the creation time of a previous root key doesn't apply to the current one
+        if creation.public_key() != identity.get_root_public_key()?.data() {
+            return Ok(None);
+        }
+        Ok(Some(creation.created_at()))
+    }
+
+    /// Return the number of signatures created by the current root key of an identity
+    async fn key_usage(&self, identity: &Identity) -> Result<u64> {
+        let stored = self
+            .node_identities
+            .identities_repository()
+            .get_attributes(&identity.identifier())
+            .await?
+            .and_then(|entry| entry.attrs().get(KEY_USAGE_ATTRIBUTE).cloned());
+        let usage = match stored {
+            Some(stored) => minicbor::decode::<KeyUsage>(&stored)?,
+            None => return Ok(0),
+        };
+        // the count of a previous root key doesn't apply to the current one
+        if usage.public_key() != identity.get_root_public_key()?.data() {
+            return Ok(0);
+        }
+        Ok(usage.signatures())
+    }
+
+    /// Add signatures to the count of the current root key of an identity, when the key usage
+    /// of that identity is limited
+    async fn record_key_usage(&self, identity: &Identity, signatures: u64) -> Result<()> {
+        if self
+            .options
+            .key_usage_limit(&identity.identifier())
+            .is_none()
+        {
+            return Ok(());
+        }
+        let signatures = self.key_usage(identity).await? + signatures;
+        let usage = KeyUsage::new(identity.get_root_public_key()?.data(), signatures);
+        self.store_attribute(
+            &identity.identifier(),
+            KEY_USAGE_ATTRIBUTE,
+            minicbor::to_vec(usage)?,
+        )
+        .await
+    }
+
+    /// Sign a payload with the first vault of a vault group which holds the identity key,
+    /// trying the vaults in the order given by the strategy of the group. A vault which
+    /// can't be opened, or fails to sign, is skipped. Return the reason why no vault could
+    /// sign otherwise
+    async fn sign_with_vault_group(
+        &mut self,
+        group_name: &str,
+        sender: Option<&IdentityIdentifier>,
+        identity: &[u8],
+        payload: &[u8],
+    ) -> Result<std::result::Result<CreatedSignature, String>> {
+        let group = match self.options.vault_group(group_name) {
+            Some(group) => group.clone(),
+            None => return Ok(Err(format!("unknown vault group: {group_name}"))),
+        };
+        for vault_name in self.vault_selection.order(group_name, &group) {
+            // The group doesn't give access to the vaults whose policy denies the sender
+            if !self.vault_allows(Some(vault_name.clone()), sender)? {
+                debug!(vault = %vault_name, group = %group_name, "vault denied to the sender");
+                continue;
+            }
+            self.vault_selection.record_attempt(&vault_name);
+            let identities_creation = match self
+                .node_identities
+                .get_identities_creation(Some(vault_name.clone()))
+                .await
+            {
+                Ok(identities_creation) => identities_creation,
+                Err(e) => {
+                    warn!(%e, vault = %vault_name, group = %group_name, "vault unavailable");
+                    continue;
+                }
+            };
+            let identity = identities_creation.decode_identity(identity).await?;
+            let identities_keys = self
+                .node_identities
+                .get_identities_keys(Some(vault_name.clone()))
+                .await?;
+            match identities_keys
+                .create_signature(&identity, payload, None)
+                .await
+            {
+                Ok(signature) => {
+                    return Ok(Ok(CreatedSignature {
+                        identity,
+                        identities_keys,
+                        signature,
+                        vault_name: Some(vault_name),
+                    }))
+                }
+                Err(e) => {
+                    warn!(%e, vault = %vault_name, group = %group_name, "vault failed to sign");
+                }
+            }
+        }
+        Ok(Err(format!(
+            "no vault of the vault group {group_name} could create the signature"
+        )))
+    }
+
+    /// Probe a vault with a trivial operation: looking up the attributes of a key which
+    /// doesn't exist. The vault is available if it answers, even with a "not found" error
+    async fn ping_vault(&self, vault_name: &str) -> VaultPingResponse<'static> {
+        let started_at = Instant::now();
+        let result = match self
+            .node_identities
+            .get_identities_vault(Some(vault_name.to_string()))
+            .await
+        {
+            Ok(vault) => match vault.get_secret_attributes(&VAULT_PING_KEY_ID.into()).await {
+                Err(e) if e.code().kind != Kind::NotFound => Err(e),
+                _ => Ok(()),
+            },
+            Err(e) => Err(e),
+        };
+        let latency_ms = started_at.elapsed().as_millis() as u64;
+
+        let body = VaultPingResponse::new(vault_name.to_string(), result.is_ok(), latency_ms);
+        match result {
+            Ok(()) => body,
+            Err(e) => body.with_error(e.to_string()),
+        }
+    }
+
+    /// Compare a change history to the history known by a client, described by its length and
+    /// the identifier of its last change, and return the changes which the client is missing.
+    /// Since each change refers to the identifier of the previous one, a matching last change
+    /// means that the whole known history matches
+    fn history_delta(
+        history: &IdentityChangeHistory,
+        args: &HistoryDeltaRequest<'_>,
+    ) -> Result<HistoryDeltaResponse<'static>> {
+        let changes = history.as_ref();
+        let current_length = changes.len() as u64;
+        let known_length = args.known_length() as usize;
+
+        let comparison = if known_length == 0 {
+            IdentityHistoryComparison::Newer
+        } else if known_length > changes.len() {
+            IdentityHistoryComparison::Older
+        } else if args.last_change_id()
+            != Some(changes[known_length - 1].identifier().to_string().as_str())
+        {
+            IdentityHistoryComparison::Conflict
+        } else if known_length == changes.len() {
+            IdentityHistoryComparison::Equal
+        } else {
+            IdentityHistoryComparison::Newer
+        };
+
+        let response = HistoryDeltaResponse::new(comparison.clone(), current_length);
+        match comparison {
+            IdentityHistoryComparison::Newer | IdentityHistoryComparison::Equal => {
+                Ok(response.with_changes(history.export_delta(known_length)?))
+            }
+            IdentityHistoryComparison::Older | IdentityHistoryComparison::Conflict => Ok(response),
+        }
+    }
+
+    /// Verify that every change of a change history is signed by the keys of the preceding
+    /// changes and, when a trust anchor is given, that the change history extends the change
+    /// history of the trust anchor
+    async fn verify_identity_change_history(
+        &self,
+        args: &VerifyIdentityChangeHistoryRequest<'_>,
+    ) -> Result<VerifyIdentityChangeHistoryResponse> {
+        use ChangeHistoryFailureReason::*;
+
+        let history = match IdentityChangeHistory::import(args.identity()) {
+            Ok(history) => history,
+            Err(_) => return Ok(VerifyIdentityChangeHistoryResponse::failed(Malformed, None)),
+        };
+
+        let identities_keys = self.node_identities.get_default_identities_keys().await?;
+        if let Some(index) = identities_keys.find_invalid_change(&history).await {
+            return Ok(VerifyIdentityChangeHistoryResponse::failed(
+                InvalidSignature,
+                Some(index as u64),
+            ));
+        }
+
+        if let Some(trust_anchor) = args.trust_anchor() {
+            let anchor = match IdentityChangeHistory::import(trust_anchor) {
+                Ok(anchor) => anchor,
+                Err(_) => return Ok(VerifyIdentityChangeHistoryResponse::failed(Malformed, None)),
+            };
+            match history.compare(&anchor) {
+                IdentityHistoryComparison::Conflict => {
+                    let index = history.divergence_index(&anchor).map(|i| i as u64);
+                    return Ok(VerifyIdentityChangeHistoryResponse::failed(
+                        UntrustedRoot,
+                        index,
+                    ));
+                }
+                IdentityHistoryComparison::Older => {
+                    let index = history.as_ref().len() as u64;
+                    return Ok(VerifyIdentityChangeHistoryResponse::failed(
+                        Outdated,
+                        Some(index),
+                    ));
+                }
+                IdentityHistoryComparison::Equal | IdentityHistoryComparison::Newer => {}
+            }
+        }
+
+        Ok(VerifyIdentityChangeHistoryResponse::new())
+    }
 
-                    Self::ok_response(req, Some(body), enc)
-                }
-                ["actions", "verify_signature"] => {
-                    if !req.has_body() {
-                        return Self::response_for_bad_request(req, "empty body", enc);
-                    }
+    /// Check that a device signed a challenge issued by this service and return an
+    /// enrollment token for the device identity if it did.
+    /// A challenge can only be attested once, even if the attestation fails
+    async fn attest_challenge(
+        &mut self,
+        args: &AttestChallengeRequest<'_>,
+    ) -> Result<AttestChallengeResponse<'static>> {
+        if !self
+            .state_store
+            .delete(CHALLENGES, &hex::encode(args.challenge()))?
+        {
+            return Ok(AttestChallengeResponse::failed(
+                AttestationFailureReason::UnknownChallenge,
+            ));
+        }
 
-                    let args = dec.decode::<VerifySignatureRequest>()?;
-                    let identities_creation = self
-                        .node_identities
-                        .get_default_identities_creation()
-                        .await?;
-                    let peer_identity = identities_creation
-                        .decode_identity(args.signer_identity())
-                        .await?;
+        let identity = self
+            .node_identities
+            .get_default_identities_creation()
+            .await?
+            .decode_identity(args.identity())
+            .await?;
+        let signature =
+            normalize_signature(identity.get_root_public_key()?.stype(), args.signature());
+        let verified = match signature {
+            Some(signature) => self
+                .node_identities
+                .get_default_identities_keys()
+                .await?
+                .verify_signature(&identity, &signature, args.challenge(), None)
+                .await
+                .unwrap_or(false),
+            None => false,
+        };
+        IdentityServiceMetrics::increment(if verified {
+            &self.metrics.verifications_passed
+        } else {
+            &self.metrics.verifications_failed
+        });
+        if !verified {
+            return Ok(AttestChallengeResponse::failed(
+                AttestationFailureReason::InvalidSignature,
+            ));
+        }
 
-                    let identities_keys =
-                        self.node_identities.get_default_identities_keys().await?;
-                    let verified = identities_keys
-                        .verify_signature(
-                            &peer_identity,
-                            &Signature::new(args.signature().to_vec()),
-                            args.data(),
-                            None,
-                        )
-                        .await?;
+        Ok(AttestChallengeResponse::new(
+            identity.identifier().to_string(),
+            OneTimeCode::new(),
+        ))
+    }
 
-                    let body = VerifySignatureResponse::new(verified);
+    /// Check that an endorsement was signed by the endorser and is about the current
+    /// root key of the subject. Return the reason of the failure if it is not valid
+    async fn verify_endorsement(
+        &self,
+        endorser: &[u8],
+        subject: &[u8],
+        endorsement: &Endorsement<'_>,
+    ) -> Result<Option<EndorsementFailureReason>> {
+        let data = match minicbor::decode::<EndorsementData>(endorsement.data()) {
+            Ok(data) => data,
+            Err(_) => return Ok(Some(EndorsementFailureReason::Malformed)),
+        };
 
-                    Self::ok_response(req, Some(body), enc)
-                }
-                ["actions", "compare_identity_change_history"] => {
-                    if !req.has_body() {
-                        return Self::response_for_bad_request(req, "empty body", enc);
-                    }
+        let identities_creation = self
+            .node_identities
+            .get_default_identities_creation()
+            .await?;
+        let endorser = identities_creation.decode_identity(endorser).await?;
+        let subject = identities_creation.decode_identity(subject).await?;
 
-                    let args = dec.decode::<CompareIdentityChangeHistoryRequest>()?;
+        let signature = normalize_signature(
+            endorser.get_root_public_key()?.stype(),
+            endorsement.signature(),
+        );
+        let verified = match signature {
+            Some(signature) if data.endorser() == endorser.identifier().to_string() => self
+                .node_identities
+                .get_default_identities_keys()
+                .await?
+                .verify_signature(&endorser, &signature, endorsement.data(), None)
+                .await
+                .unwrap_or(false),
+            _ => false,
+        };
+        if !verified {
+            return Ok(Some(EndorsementFailureReason::InvalidSignature));
+        }
 
-                    let identities_creation = self
-                        .node_identities
-                        .get_default_identities_creation()
-                        .await?;
+        if data.subject() != subject.identifier().to_string()
+            || data.subject_public_key() != subject.get_root_public_key()?.data()
+        {
+            return Ok(Some(EndorsementFailureReason::SubjectMismatch));
+        }
+        Ok(None)
+    }
 
-                    let current_identity = identities_creation
-                        .decode_identity(args.current_identity())
-                        .await?;
+    /// Return the content of an identities bundle if it was signed by the admin identity
+    async fn verify_identities_bundle(
+        &self,
+        admin: &[u8],
+        bundle: &IdentitiesBundle<'_>,
+    ) -> Result<Option<IdentitiesBundleData<'static>>> {
+        let data = match minicbor::decode::<IdentitiesBundleData>(bundle.data()) {
+            Ok(data) => data,
+            Err(_) => return Ok(None),
+        };
+        let admin = self
+            .node_identities
+            .get_default_identities_creation()
+            .await?
+            .decode_identity(admin)
+            .await?;
+        let signature =
+            normalize_signature(admin.get_root_public_key()?.stype(), bundle.signature());
+        let verified = match signature {
+            Some(signature) if data.signer() == admin.identifier().to_string() => self
+                .node_identities
+                .get_default_identities_keys()
+                .await?
+                .verify_signature(&admin, &signature, bundle.data(), None)
+                .await
+                .unwrap_or(false),
+            _ => false,
+        };
+        Ok(verified.then(|| data.into_owned()))
+    }
 
-                    let body = if args.known_identity().is_empty() {
-                        IdentityHistoryComparison::Newer
-                    } else {
-                        let known_identity = identities_creation
-                            .decode_identity(args.known_identity())
-                            .await?;
-                        current_identity.compare(&known_identity)
-                    };
+    /// Verify that a timestamp token was signed by the signer over the digest, and return
+    /// the time asserted by the signer
+    async fn verify_timestamp(
+        &self,
+        signer: &[u8],
+        digest: &[u8],
+        token: &TimestampToken<'_>,
+    ) -> Result<VerifyTimestampResponse<'static>> {
+        let data = match minicbor::decode::<TimestampTokenData>(token.data()) {
+            Ok(data) => data,
+            Err(_) => {
+                return Ok(VerifyTimestampResponse::failed(
+                    TimestampFailureReason::Malformed,
+                ))
+            }
+        };
 
-                    Self::ok_response(req, Some(body), enc)
+        let signer = self
+            .node_identities
+            .get_default_identities_creation()
+            .await?
+            .decode_identity(signer)
+            .await?;
+        let signature =
+            normalize_signature(signer.get_root_public_key()?.stype(), token.signature());
+        let verified = match signature {
+            Some(signature) if data.signer() == signer.identifier().to_string() => self
+                .node_identities
+                .get_default_identities_keys()
+                .await?
+                .verify_signature(&signer, &signature, token.data(), None)
+                .await
+                .unwrap_or(false),
+            _ => false,
+        };
+        if !verified {
+            return Ok(VerifyTimestampResponse::failed(
+                TimestampFailureReason::InvalidSignature,
+            ));
+        }
+
+        if data.digest() != digest {
+            return Ok(VerifyTimestampResponse::failed(
+                TimestampFailureReason::DigestMismatch,
+            ));
+        }
+        Ok(VerifyTimestampResponse::new(
+            data.signer().to_string(),
+            data.time(),
+        ))
+    }
+
+    async fn verify_inclusion_proof(
+        &self,
+        request: &VerifyInclusionProofRequest<'_>,
+    ) -> Result<VerifyInclusionProofResponse> {
+        let signed_root = request.signed_root();
+        let data = match minicbor::decode::<MerkleRootData>(signed_root.data()) {
+            Ok(data) => data,
+            Err(_) => {
+                return Ok(VerifyInclusionProofResponse::failed(
+                    InclusionProofFailureReason::Malformed,
+                ))
+            }
+        };
+
+        let signer = self
+            .node_identities
+            .get_default_identities_creation()
+            .await?
+            .decode_identity(request.signer())
+            .await?;
+        let signature = normalize_signature(
+            signer.get_root_public_key()?.stype(),
+            signed_root.signature(),
+        );
+        let verified = match signature {
+            Some(signature) if data.signer() == signer.identifier().to_string() => self
+                .node_identities
+                .get_default_identities_keys()
+                .await?
+                .verify_signature(&signer, &signature, signed_root.data(), None)
+                .await
+                .unwrap_or(false),
+            _ => false,
+        };
+        if !verified {
+            return Ok(VerifyInclusionProofResponse::failed(
+                InclusionProofFailureReason::InvalidSignature,
+            ));
+        }
+
+        let siblings: Vec<&[u8]> = request
+            .siblings()
+            .iter()
+            .map(|sibling| &sibling[..])
+            .collect();
+        let root =
+            merkle_root_from_proof(request.leaf(), request.index(), data.tree_size(), &siblings);
+        Ok(match root {
+            Some(root) if root.as_slice() == data.root() => VerifyInclusionProofResponse::new(),
+            _ => VerifyInclusionProofResponse::failed(InclusionProofFailureReason::NotIncluded),
+        })
+    }
+
+    /// Verify a delegation token presented on behalf of an issuer.
+    /// A token is accepted only once: its nonce is remembered until the token expires
+    async fn verify_delegation_token(
+        &mut self,
+        issuer: &[u8],
+        token: &DelegationToken<'_>,
+    ) -> Result<VerifyDelegationTokenResponse<'static>> {
+        let data = match minicbor::decode::<DelegationTokenData>(token.data()) {
+            Ok(data) => data,
+            Err(_) => {
+                return Ok(VerifyDelegationTokenResponse::failed(
+                    DelegationTokenFailureReason::Malformed,
+                ))
+            }
+        };
+
+        let issuer = self
+            .node_identities
+            .get_default_identities_creation()
+            .await?
+            .decode_identity(issuer)
+            .await?;
+        let signature =
+            normalize_signature(issuer.get_root_public_key()?.stype(), token.signature());
+        let verified = match signature {
+            Some(signature) if data.issuer() == issuer.identifier().to_string() => self
+                .node_identities
+                .get_default_identities_keys()
+                .await?
+                .verify_signature(&issuer, &signature, token.data(), None)
+                .await
+                .unwrap_or(false),
+            _ => false,
+        };
+        if !verified {
+            return Ok(VerifyDelegationTokenResponse::failed(
+                DelegationTokenFailureReason::InvalidSignature,
+            ));
+        }
+
+        let now = match Timestamp::now() {
+            Some(now) => now,
+            None => return Err(ApiError::generic("unable to get the current time")),
+        };
+        if data.expires_at() <= now {
+            return Ok(VerifyDelegationTokenResponse::failed(
+                DelegationTokenFailureReason::Expired,
+            ));
+        }
+
+        // The nonce is kept until the token expires, after which the token is rejected anyway
+        let nonce = hex::encode(data.nonce());
+        if self.state_store.get(DELEGATION_TOKENS, &nonce)?.is_some() {
+            return Ok(VerifyDelegationTokenResponse::failed(
+                DelegationTokenFailureReason::AlreadyUsed,
+            ));
+        }
+        let ttl = Duration::from_secs(data.expires_at().unix_time() - now.unix_time());
+        self.state_store
+            .put(DELEGATION_TOKENS, &nonce, vec![], Some(ttl))?;
+
+        Ok(VerifyDelegationTokenResponse::new(
+            data.delegate().to_string(),
+            data.scope().to_string(),
+        ))
+    }
+
+    /// Check that a channel credential was signed by its issuer, is not expired, and was issued
+    /// to the identity opening a secure channel for the service it is opened to
+    async fn verify_channel_credential(
+        &self,
+        request: &VerifyChannelCredentialRequest<'_>,
+    ) -> Result<VerifyChannelCredentialResponse> {
+        let credential = request.credential();
+        let data = match minicbor::decode::<ChannelCredentialData>(credential.data()) {
+            Ok(data) => data,
+            Err(_) => {
+                return Ok(VerifyChannelCredentialResponse::failed(
+                    ChannelCredentialFailureReason::Malformed,
+                ))
+            }
+        };
+
+        let issuer = self
+            .node_identities
+            .get_default_identities_creation()
+            .await?
+            .decode_identity(request.issuer())
+            .await?;
+        let signature = normalize_signature(
+            issuer.get_root_public_key()?.stype(),
+            credential.signature(),
+        );
+        let verified = match signature {
+            Some(signature) if data.issuer() == issuer.identifier().to_string() => self
+                .node_identities
+                .get_default_identities_keys()
+                .await?
+                .verify_signature(&issuer, &signature, credential.data(), None)
+                .await
+                .unwrap_or(false),
+            _ => false,
+        };
+        if !verified {
+            return Ok(VerifyChannelCredentialResponse::failed(
+                ChannelCredentialFailureReason::InvalidSignature,
+            ));
+        }
+
+        let now = match Timestamp::now() {
+            Some(now) => now,
+            None => return Err(ApiError::generic("unable to get the current time")),
+        };
+        let failure_reason = if data.expires_at() <= now {
+            Some(ChannelCredentialFailureReason::Expired)
+        } else if data.subject() != request.subject() {
+            Some(ChannelCredentialFailureReason::SubjectMismatch)
+        } else if data.service() != request.service() {
+            Some(ChannelCredentialFailureReason::ServiceMismatch)
+        } else {
+            None
+        };
+        Ok(match failure_reason {
+            Some(reason) => VerifyChannelCredentialResponse::failed(reason),
+            None => VerifyChannelCredentialResponse::new(data.expires_at()),
+        })
+    }
+
+    async fn verify_freshness_proof(
+        &self,
+        request: &VerifyFreshnessProofRequest<'_>,
+    ) -> Result<VerifyFreshnessProofResponse> {
+        let proof = request.proof();
+        let data = match minicbor::decode::<FreshnessProofData>(proof.data()) {
+            Ok(data) => data,
+            Err(_) => {
+                return Ok(VerifyFreshnessProofResponse::failed(
+                    FreshnessProofFailureReason::Malformed,
+                ))
+            }
+        };
+
+        let issuer = self
+            .node_identities
+            .get_default_identities_creation()
+            .await?
+            .decode_identity(request.issuer())
+            .await?;
+        let signature =
+            normalize_signature(issuer.get_root_public_key()?.stype(), proof.signature());
+        let verified = match signature {
+            Some(signature) if data.issuer() == issuer.identifier().to_string() => self
+                .node_identities
+                .get_default_identities_keys()
+                .await?
+                .verify_signature(&issuer, &signature, proof.data(), None)
+                .await
+                .unwrap_or(false),
+            _ => false,
+        };
+        if !verified {
+            return Ok(VerifyFreshnessProofResponse::failed(
+                FreshnessProofFailureReason::InvalidSignature,
+            ));
+        }
+
+        let now = match Timestamp::now() {
+            Some(now) => now,
+            None => return Err(ApiError::generic("unable to get the current time")),
+        };
+        Ok(if data.expires_at() <= now {
+            VerifyFreshnessProofResponse::failed(FreshnessProofFailureReason::Expired)
+        } else if data.subject() != request.subject() {
+            VerifyFreshnessProofResponse::failed(FreshnessProofFailureReason::SubjectMismatch)
+        } else {
+            VerifyFreshnessProofResponse::new(data.expires_at())
+        })
+    }
+
+    /// Return the identifier of the first candidate identity whose root key verifies a signature.
+    /// Revoked candidates are ignored when revocations are checked
+    async fn find_signer(
+        &self,
+        candidates: &[Identity],
+        signed_data: &SignedData<'_>,
+    ) -> Result<Option<IdentityIdentifier>> {
+        let identities_keys = self.node_identities.get_default_identities_keys().await?;
+        for candidate in candidates {
+            let signature = match normalize_signature(
+                candidate.get_root_public_key()?.stype(),
+                signed_data.signature(),
+            ) {
+                Some(signature) => signature,
+                None => continue,
+            };
+            let verified = identities_keys
+                .verify_signature(candidate, &signature, signed_data.data(), None)
+                .await
+                .unwrap_or(false);
+            if verified
+                && !(self.options.check_revocation()
+                    && self
+                        .find_revocation(&candidate.identifier())
+                        .await?
+                        .is_some())
+            {
+                return Ok(Some(candidate.identifier()));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Decode the identity of a signer, unless an identity with the same change history
+    /// is cached
+    async fn cached_signer_identity(&mut self, exported: &[u8]) -> Result<Identity> {
+        let digest = history_digest(exported);
+        if let Some(identity) = self.verification_keys.get_by_digest(&digest) {
+            IdentityServiceMetrics::increment(&self.metrics.verification_key_cache_hits);
+            return Ok(identity);
+        }
+        IdentityServiceMetrics::increment(&self.metrics.verification_key_cache_misses);
+        let identity = self
+            .node_identities
+            .get_default_identities_creation()
+            .await?
+            .decode_identity(exported)
+            .await?;
+        self.verification_keys.insert(identity.clone(), digest);
+        Ok(identity)
+    }
+
+    /// Verify signatures made by different signers, returning the result of each verification
+    /// in the order of the records. Each distinct signer identity is decoded once for the whole
+    /// batch. A record fails to verify when its signer identity can't be decoded, when its
+    /// signer was revoked and revocations are checked, or when its signature is malformed
+    async fn verify_signatures(&mut self, records: &[SignedRecord<'_>]) -> Result<Vec<bool>> {
+        let identities_keys = self.node_identities.get_default_identities_keys().await?;
+        // The signers of the batch, keyed by the digest of their exported change history,
+        // or nothing for the signers which can't be used
+        let mut signers: BTreeMap<HistoryDigest, Option<Identity>> = BTreeMap::new();
+        let mut verified = Vec::with_capacity(records.len());
+        for record in records {
+            let digest = history_digest(record.signer_identity());
+            if !signers.contains_key(&digest) {
+                let signer = self
+                    .cached_signer_identity(record.signer_identity())
+                    .await
+                    .ok();
+                let signer = match signer {
+                    Some(signer)
+                        if self.options.check_revocation()
+                            && self.find_revocation(&signer.identifier()).await?.is_some() =>
+                    {
+                        None
+                    }
+                    signer => signer,
+                };
+                signers.insert(digest, signer);
+            }
+            let is_valid = match &signers[&digest] {
+                Some(signer) => {
+                    let public_key = signer.get_root_public_key()?;
+                    match normalize_signature(public_key.stype(), record.signature()) {
+                        Some(signature) => identities_keys
+                            .verify_signature(signer, &signature, record.data(), None)
+                            .await
+                            .unwrap_or(false),
+                        None => false,
+                    }
                 }
-                _ => Self::response_for_bad_request(req, "unknown path", enc),
-            },
-            Put | Patch | Delete => Self::response_for_bad_request(req, "unknown method", enc),
+                None => false,
+            };
+            IdentityServiceMetrics::increment(if is_valid {
+                &self.metrics.verifications_passed
+            } else {
+                &self.metrics.verifications_failed
+            });
+            verified.push(is_valid);
         }
+        Ok(verified)
     }
 
-    async fn on_request(&mut self, data: &[u8]) -> Result<Vec<u8>> {
+    /// Return the identity of a signer referenced by its identifier, from the cache or from
+    /// the identities known to the node. A cached identity is not used when the node knows
+    /// a newer or conflicting change history for that identifier
+    async fn known_signer_identity(
+        &mut self,
+        identifier: &IdentityIdentifier,
+    ) -> Result<Option<Identity>> {
+        let stored = self
+            .node_identities
+            .identities_repository()
+            .retrieve_identity(identifier)
+            .await?;
+        if let Some(identity) = self.verification_keys.get(identifier) {
+            match &stored {
+                Some(stored)
+                    if !matches!(
+                        identity.compare(stored),
+                        IdentityHistoryComparison::Equal | IdentityHistoryComparison::Newer
+                    ) =>
+                {
+                    self.verification_keys.remove(identifier)
+                }
+                _ => {
+                    IdentityServiceMetrics::increment(&self.metrics.verification_key_cache_hits);
+                    return Ok(Some(identity));
+                }
+            }
+        }
+        IdentityServiceMetrics::increment(&self.metrics.verification_key_cache_misses);
+        if let Some(identity) = &stored {
+            self.verification_keys
+                .insert(identity.clone(), history_digest(&identity.export()?));
+        }
+        Ok(stored)
+    }
+
+    /// Return the encoded revocation record of an identity, if it was revoked
+    async fn find_revocation(&self, identifier: &IdentityIdentifier) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .node_identities
+            .identities_repository()
+            .get_attributes(identifier)
+            .await?
+            .and_then(|entry| entry.attrs().get(REVOCATION_ATTRIBUTE).cloned()))
+    }
+
+    /// Store the revocation record of an identity with its attributes, so that the revocation
+    /// persists across restarts. Attested attributes keep their expiry
+    async fn store_revocation(
+        &self,
+        identifier: &IdentityIdentifier,
+        revocation: &Revocation<'_>,
+    ) -> Result<()> {
+        self.store_attribute(
+            identifier,
+            REVOCATION_ATTRIBUTE,
+            minicbor::to_vec(revocation)?,
+        )
+        .await
+    }
+
+    /// Store a value with the attributes of an identity, keeping its other attributes.
+    /// The entry keeps its creation time, expiry and attester, so that storing a value
+    /// doesn't extend the lifetime of attested attributes. The value is forgotten with
+    /// the other attributes when they expire
+    async fn store_attribute(
+        &self,
+        identifier: &IdentityIdentifier,
+        name: &str,
+        value: Vec<u8>,
+    ) -> Result<()> {
+        let repository = self.node_identities.identities_repository();
+        let entry = match repository.get_attributes(identifier).await? {
+            Some(entry) => {
+                let mut attributes = entry.attrs().clone();
+                attributes.insert(name.to_string(), value);
+                AttributesEntry::new(
+                    attributes,
+                    entry.added(),
+                    entry.expires(),
+                    entry.attested_by(),
+                )
+            }
+            None => {
+                let now = match Timestamp::now() {
+                    Some(now) => now,
+                    None => return Err(ApiError::generic("unable to get the current time")),
+                };
+                AttributesEntry::new(BTreeMap::from([(name.to_string(), value)]), now, None, None)
+            }
+        };
+        repository.put_attributes(identifier, entry).await
+    }
+
+    /// Remove an attribute of a stored identity, keeping its other attributes
+    async fn remove_attribute(&self, identifier: &IdentityIdentifier, name: &str) -> Result<()> {
+        let repository = self.node_identities.identities_repository();
+        let entry = match repository.get_attributes(identifier).await? {
+            Some(entry) if entry.attrs().contains_key(name) => entry,
+            _ => return Ok(()),
+        };
+        let mut attributes = entry.attrs().clone();
+        attributes.remove(name);
+        repository
+            .put_attributes(
+                identifier,
+                AttributesEntry::new(
+                    attributes,
+                    entry.added(),
+                    entry.expires(),
+                    entry.attested_by(),
+                ),
+            )
+            .await
+    }
+
+    /// Return the label of a stored identity, if it has one
+    async fn identity_label(&self, identifier: &IdentityIdentifier) -> Result<Option<String>> {
+        Ok(self
+            .node_identities
+            .identities_repository()
+            .get_attributes(identifier)
+            .await?
+            .and_then(|entry| entry.attrs().get(LABEL_ATTRIBUTE).cloned())
+            .map(|label| String::from_utf8_lossy(&label).to_string()))
+    }
+
+    /// Return the identifiers of the stored identities having attested attributes matching all
+    /// the given filters (AND semantics), with their label.
+    ///
+    /// A filter `name = value` matches an identity if that identity has an attested attribute
+    /// named `name` whose value is exactly the UTF-8 bytes of `value`. Names and values are
+    /// case-sensitive. The attributes stored by the service itself, whose names start with
+    /// `ockam_`, are not attested and never match, except for the label which can be used
+    /// as a filter. Identities without attested attributes, or whose attributes have expired,
+    /// never match. An empty set of filters returns all the identities having attested
+    /// attributes.
+    async fn list_identities(
+        &self,
+        filters: &BTreeMap<String, String>,
+    ) -> Result<Vec<(String, Option<String>)>> {
+        let entries = self.node_identities.identities_repository().list().await?;
+        let is_attested = |name: &str| !name.starts_with(RESERVED_ATTRIBUTE_PREFIX);
+
+        Ok(entries
+            .into_iter()
+            .filter(|(_, entry)| {
+                entry.attested_by().is_some() && entry.attrs().keys().any(|name| is_attested(name))
+            })
+            .filter(|(_, entry)| {
+                filters.iter().all(|(name, value)| {
+                    (is_attested(name) || name == LABEL_ATTRIBUTE)
+                        && entry.attrs().get(name).map(|v| v.as_slice()) == Some(value.as_bytes())
+                })
+            })
+            .map(|(identifier, entry)| {
+                let label = entry
+                    .attrs()
+                    .get(LABEL_ATTRIBUTE)
+                    .map(|label| String::from_utf8_lossy(label).to_string());
+                (identifier.to_string(), label)
+            })
+            .collect())
+    }
+
+    /// Return the public identity of a signer, as published by the identity service at the
+    /// directory route. The change history returned by the directory is verified, and refused
+    /// if it is older than, or conflicts with, the change history known to this node.
+    /// The identity is fetched again once its cache entry expires
+    async fn fetch_remote_identity(
+        &mut self,
+        directory_name: &str,
+        directory: Route,
+        signer_name: &str,
+    ) -> Result<RemoteIdentity> {
+        if let Some(identity) = self.remote_identities.get(directory_name, signer_name) {
+            return Ok(identity);
+        }
+        let ctx = match &self.client_ctx {
+            Some(ctx) => ctx,
+            None => return Err(ApiError::generic("the identity service is not started")),
+        };
+
+        let req = Request::get(signer_name.to_string());
+        let options = MessageSendReceiveOptions::new()
+            .with_timeout(self.options.remote_identity_fetch_timeout());
+        let buf = request_with_options(ctx, "fetch public identity", None, directory, req, options)
+            .await?;
+
+        let mut dec = Decoder::new(&buf);
+        let res: Response = dec.decode()?;
+        if res.status() != Some(Status::Ok) {
+            let msg = match dec.decode::<Error>() {
+                Ok(error) => error.message().unwrap_or_default().to_string(),
+                Err(_) => String::new(),
+            };
+            return Err(ApiError::message(format!(
+                "the directory returned {}: {msg}",
+                res.status().unwrap_or(Status::InternalServerError)
+            )));
+        }
+        let res: CreateResponse = dec.decode()?;
+        let identity = self
+            .node_identities
+            .get_default_identities_creation()
+            .await?
+            .decode_identity(res.identity())
+            .await?;
+        let identifier = identity.identifier();
+        if identifier.to_string() != res.identity_id() {
+            return Err(ApiError::message(format!(
+                "the directory returned the identity {identifier} instead of {}",
+                res.identity_id()
+            )));
+        }
+        if let Some(known) = self
+            .node_identities
+            .identities_repository()
+            .retrieve_identity(&identifier)
+            .await?
+        {
+            if !matches!(
+                identity.compare(&known),
+                IdentityHistoryComparison::Equal | IdentityHistoryComparison::Newer
+            ) {
+                return Err(ApiError::message(format!(
+                    "the directory returned an outdated change history for {identifier}"
+                )));
+            }
+        }
+        let identity = RemoteIdentity::new(identifier, identity.get_root_public_key()?);
+        self.remote_identities
+            .insert(directory_name, signer_name, identity.clone());
+        Ok(identity)
+    }
+
+    /// Discard the expired signing sessions, then schedule the next run of the reaper
+    async fn reap_sessions(&mut self) -> Result<()> {
+        let expired = self.signing_sessions.remove_expired()
+            + self.threshold_signing_sessions.remove_expired()?;
+        if expired > 0 {
+            debug!(expired, "discarded expired signing sessions");
+        }
+        if let Some(rate_limiter) = self.rate_limiter.as_mut() {
+            let idle = rate_limiter.remove_idle();
+            if idle > 0 {
+                debug!(idle, "discarded the rate limits of idle senders");
+            }
+        }
+        let interval = self.options.session_reaper_interval();
+        if let Some(session_reaper) = self.session_reaper.as_mut() {
+            session_reaper.schedule(interval).await?;
+        }
+        Ok(())
+    }
+
+    /// Check the authentication token of a request, when the service requires one.
+    /// Tokens are compared in constant time
+    fn is_authorized(&self, req: &Request) -> bool {
+        match (self.options.auth_token(), req.auth_token()) {
+            (None, _) => true,
+            (Some(expected), Some(actual)) => expected.as_bytes().ct_eq(actual.as_bytes()).into(),
+            (Some(_), None) => false,
+        }
+    }
+
+    /// Record the metadata of a processed request in the audit log.
+    /// Only the headers of the request and of its response are read
+    fn record_audit_entry(
+        &mut self,
+        sender: Option<&IdentityIdentifier>,
+        request: &[u8],
+        response: &[u8],
+    ) {
+        if !self.audit_log.is_enabled() {
+            return;
+        }
+        let timestamp = match Timestamp::now() {
+            Some(now) => now,
+            None => return,
+        };
+        let (method, path) = match Decoder::new(request).decode::<Request>() {
+            Ok(req) => (req.method(), split_query(req.path()).0.to_string()),
+            Err(_) => (None, String::new()),
+        };
+        let status = Decoder::new(response)
+            .decode::<Response>()
+            .ok()
+            .and_then(|res| res.status());
+        self.audit_log.record(AuditEntry::new(
+            timestamp,
+            method,
+            path,
+            sender.map(|sender| sender.to_string()),
+            status,
+        ));
+    }
+
+    /// Handle a request. `sender` is the identity authenticated by the secure channel
+    /// the request was received on, if any
+    async fn on_request(
+        &mut self,
+        sender: Option<&IdentityIdentifier>,
+        data: &[u8],
+    ) -> Result<Vec<u8>> {
         let mut buf = Vec::new();
 
         let mut dec = Decoder::new(data);
@@ -249,8 +3930,41 @@ impl IdentityService {
             }
         };
 
-        match self.handle_request(&req, &mut dec, &mut buf).await {
-            Ok(_) => {}
+        if !self.is_authorized(&req) {
+            Response::unauthorized(req.id()).encode(&mut buf)?;
+            return Ok(buf);
+        }
+
+        if let (Some(rate_limiter), Some(sender)) = (self.rate_limiter.as_mut(), sender) {
+            if let Err(retry_after) = rate_limiter.acquire(sender) {
+                Self::response_for_rate_limit(&req, sender, retry_after, &mut buf)?;
+                return Ok(buf);
+            }
+        }
+
+        let request_timeout = self.options.request_timeout();
+        let result = match timeout(
+            request_timeout,
+            self.handle_request(sender, &req, &mut dec, &mut buf),
+        )
+        .await
+        {
+            Ok(result) => result,
+            // the aborted request may have partially written its response
+            Err(_) => {
+                buf.clear();
+                Self::response_for_timeout(&req, request_timeout, &mut buf)
+            }
+        };
+
+        match result {
+            Ok(_) => {
+                if let Some(threshold) = self.options.response_compression_threshold() {
+                    if req.accepts_compression() {
+                        return compress_response(buf, threshold);
+                    }
+                }
+            }
             Err(err) => Self::response_with_error(
                 Some(&req),
                 Status::InternalServerError,
@@ -263,17 +3977,247 @@ impl IdentityService {
     }
 }
 
+/// Split a request path into the path itself and its query, if any
+fn split_query(path: &str) -> (&str, Option<&str>) {
+    match path.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (path, None),
+    }
+}
+
+/// Return the value of a parameter of the query of a request path, formatted as
+/// `path?name1=value1&name2=value2`
+fn query_parameter<'a>(path: &'a str, name: &str) -> Option<&'a str> {
+    split_query(path)
+        .1?
+        .split('&')
+        .filter_map(|parameter| parameter.split_once('='))
+        .find(|(n, _)| *n == name)
+        .map(|(_, value)| value)
+}
+
+/// Check that an identity name can be used as the name of its file in the node state
+fn is_valid_identity_name(name: &str) -> bool {
+    !name.is_empty() && !name.starts_with('.') && !name.contains(['/', '\\'])
+}
+
+/// Return the reason why a label can't be given to an identity, if any
+fn invalid_label(label: &str) -> Option<String> {
+    if label.is_empty() {
+        Some("the label must not be empty".to_string())
+    } else if label.len() > MAX_LABEL_LEN {
+        Some(format!(
+            "the label must not be longer than {MAX_LABEL_LEN} bytes"
+        ))
+    } else {
+        None
+    }
+}
+
+/// Return the secret attributes for a key type which can be used as an identity root key.
+/// Key type names are case-insensitive
+fn parse_key_type(key_type: &str) -> Option<SecretAttributes> {
+    match key_type.to_lowercase().as_str() {
+        "ed25519" => Some(SecretAttributes::Ed25519),
+        "p256" | "p-256" | "nistp256" => Some(SecretAttributes::NistP256),
+        _ => None,
+    }
+}
+
+/// Return the first change of the change history of an identity having a given root public key,
+/// with its identifier. The change is deterministic, so that the change identifier can be signed
+/// with the private key before the identity is created
+fn initial_key_change(
+    secret_attributes: SecretAttributes,
+    public_key: PublicKey,
+) -> Result<(ChangeIdentifier, IdentityChange)> {
+    let prev_change_id =
+        ChangeIdentifier::from_hash(Vault::sha256(IdentityChangeConstants::INITIAL_CHANGE));
+    let key_attributes = KeyAttributes::new(
+        IdentityChangeConstants::ROOT_LABEL.to_string(),
+        secret_attributes,
+    );
+    let change = IdentityChange::CreateKey(CreateKeyChangeData::new(
+        prev_change_id,
+        key_attributes,
+        public_key,
+    ));
+    let change_id = ChangeIdentifier::from_hash(Vault::sha256(&change.encode()?));
+    Ok((change_id, change))
+}
+
+/// Return the index, in the change history of an identity, of the change which created or
+/// rotated its current root key
+fn root_key_change_index(identity: &Identity) -> u64 {
+    identity
+        .change_history()
+        .as_ref()
+        .iter()
+        .rposition(|change| change.change().label() == IdentityChangeConstants::ROOT_LABEL)
+        .unwrap_or_default() as u64
+}
+
+/// Check that a digest has the length of the digests computed by an algorithm
+fn check_digest(algorithm: &str, digest: &[u8]) -> std::result::Result<(), String> {
+    let length = match algorithm {
+        SHA256_DIGEST_ALGORITHM => 32,
+        _ => return Err(format!("unsupported digest algorithm: {algorithm}")),
+    };
+    if digest.len() != length {
+        return Err(format!(
+            "a {algorithm} digest has {length} bytes, but the digest has {} bytes",
+            digest.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Return the bytes which are signed for a signature request, or the reason why the request
+/// data can't be signed.
+/// This is used both to create signatures and to let clients check their own canonicalization
+fn signing_payload(request: &CreateSignatureRequest) -> std::result::Result<Vec<u8>, String> {
+    let payload = match request.json_pointers() {
+        Some(json_pointers) => select_json_fields(request.data(), json_pointers)?,
+        None => request.data().to_vec(),
+    };
+    match request.bound_route() {
+        Some(route) => Ok(route_bound_payload(&canonical_route(route)?, &payload)),
+        None => Ok(payload),
+    }
+}
+
+/// Check that a signature is structurally valid for a given key type and return it
+/// in the form expected by the vault.
+/// ECDSA signatures are verified in their DER form, but raw signatures produced by other
+/// tools are accepted as well
+fn normalize_signature(key_type: SecretType, signature: &[u8]) -> Option<Signature> {
+    let signature = Signature::new(signature.to_vec());
+    match key_type {
+        SecretType::Ed25519 if signature.as_ref().len() != 64 => None,
+        SecretType::NistP256 => signature
+            .to_ecdsa_encoding(EcdsaSignatureEncoding::Der)
+            .ok(),
+        _ => Some(signature),
+    }
+}
+
 #[ockam_core::worker]
 impl Worker for IdentityService {
     type Message = Vec<u8>;
     type Context = Context;
 
+    async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        let mut handler = self
+            .handler
+            .take()
+            .ok_or_else(|| ApiError::generic("the identity service was already started"))?;
+        let handler_address = Address::random_tagged("IdentityService.handler");
+        let mut session_reaper = DelayedEvent::create(ctx, handler_address.clone(), vec![]).await?;
+        session_reaper
+            .schedule(self.options.session_reaper_interval())
+            .await?;
+        // the handler only receives the requests forwarded by this worker, and the
+        // periodic events discarding the expired signing sessions
+        let allowed_sources = vec![ctx.address(), session_reaper.address()];
+        handler.session_reaper = Some(session_reaper);
+        ctx.start_worker_with_access_control(
+            handler_address.clone(),
+            handler,
+            AllowSourceAddresses(allowed_sources),
+            AllowAll,
+        )
+        .await?;
+        self.handler_address = Some(handler_address);
+        Ok(())
+    }
+
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        // the handler may already be stopped when the whole node is stopping
+        if let Some(handler_address) = self.handler_address.take() {
+            if let Err(e) = ctx.stop_worker(handler_address).await {
+                debug!(%e, "unable to stop the identity service handler");
+            }
+        }
+        Ok(())
+    }
+
+    async fn handle_message(
+        &mut self,
+        ctx: &mut Self::Context,
+        msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        let handler_address = match &self.handler_address {
+            Some(handler_address) => handler_address.clone(),
+            None => return Err(ApiError::generic("the identity service is not started")),
+        };
+        // the requests are processed one at a time, so the requests in flight are the ones
+        // being processed or waiting in the mailbox of the handler
+        let in_flight_requests = self.in_flight_requests.fetch_add(1, Ordering::Relaxed) + 1;
+        if in_flight_requests > self.options.max_in_flight_requests() {
+            self.in_flight_requests.fetch_sub(1, Ordering::Relaxed);
+            let mut buf = Vec::new();
+            match Decoder::new(msg.as_body()).decode::<Request>() {
+                Ok(req) => IdentityServiceHandler::response_for_overload(
+                    &req,
+                    self.options.retry_after(),
+                    &mut buf,
+                )?,
+                Err(_) => IdentityServiceHandler::response_with_error(
+                    None,
+                    Status::BadRequest,
+                    "invalid Request structure",
+                    &mut buf,
+                )?,
+            }
+            return ctx.send(msg.return_route(), buf).await;
+        }
+
+        let mut message = msg.into_local_message();
+        let transport_message = message.transport_mut();
+        transport_message.onward_route.step()?;
+        transport_message
+            .onward_route
+            .modify()
+            .prepend(handler_address);
+        ctx.forward(message).await
+    }
+}
+
+#[ockam_core::worker]
+impl Worker for IdentityServiceHandler {
+    type Message = Vec<u8>;
+    type Context = Context;
+
+    async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        let client_ctx = ctx
+            .new_detached(
+                Address::random_tagged("IdentityService.client"),
+                DenyAll,
+                DenyAll,
+            )
+            .await?;
+        self.client_ctx = Some(client_ctx);
+        Ok(())
+    }
+
     async fn handle_message(
         &mut self,
         ctx: &mut Self::Context,
         msg: Routed<Self::Message>,
     ) -> Result<()> {
-        let buf = self.on_request(msg.as_body()).await?;
+        let reaper_address = self.session_reaper.as_ref().map(|r| r.address());
+        if reaper_address == Some(msg.src_addr()) {
+            self.reap_sessions().await?;
+            return Ok(());
+        }
+
+        let sender = IdentitySecureChannelLocalInfo::find_info(msg.local_message())
+            .ok()
+            .map(|info| info.their_identity_id());
+        let buf = self.on_request(sender.as_ref(), msg.as_body()).await;
+        self.in_flight_requests.fetch_sub(1, Ordering::Relaxed);
+        let buf = buf?;
+        self.record_audit_entry(sender.as_ref(), msg.as_body(), &buf);
         ctx.send(msg.return_route(), buf).await
     }
 }
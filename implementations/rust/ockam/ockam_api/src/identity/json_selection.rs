@@ -0,0 +1,40 @@
+//! Selection of the fields of a JSON document signed by `create_signature`.
+//!
+//! A client can sign only some fields of a JSON document, designated by JSON Pointers
+//! (RFC 6901). The signed payload is then the canonical serialization of a JSON object which
+//! maps each pointer to the value it designates: the keys of every object are sorted and no
+//! whitespace is added. A verifier given the document, or only the selected fields, and the
+//! pointers can rebuild that payload to check the signature.
+
+use serde_json::{Map, Value};
+
+/// Return the canonical serialization of the values designated by the pointers in a JSON
+/// document, or a description of the invalid document or pointers
+pub(crate) fn select_json_fields(
+    document: &[u8],
+    pointers: &[impl AsRef<str>],
+) -> Result<Vec<u8>, String> {
+    if pointers.is_empty() {
+        return Err("at least one JSON pointer is required".to_string());
+    }
+    let document: Value = serde_json::from_slice(document)
+        .map_err(|e| format!("the data is not a JSON document: {e}"))?;
+    let mut selection = Map::new();
+    let mut unresolved = vec![];
+    for pointer in pointers {
+        let pointer = pointer.as_ref();
+        match document.pointer(pointer) {
+            Some(value) => {
+                selection.insert(pointer.to_string(), value.clone());
+            }
+            None => unresolved.push(pointer),
+        }
+    }
+    if !unresolved.is_empty() {
+        return Err(format!(
+            "these JSON pointers don't resolve within the document: {}",
+            unresolved.join(", ")
+        ));
+    }
+    serde_json::to_vec(&Value::Object(selection)).map_err(|e| e.to_string())
+}
@@ -0,0 +1,68 @@
+//! JSON Web Key (RFC 7517) representation of the public keys of an identity.
+//!
+//! Ed25519 keys are represented as `OKP` keys (RFC 8037) and P-256 keys as `EC` keys
+//! (RFC 7518). The `kid` of a key is `<identifier>#<key label>`, so that the keys of
+//! a multi-key identity can be told apart in a JWK Set.
+
+use data_encoding::BASE64URL_NOPAD;
+use ockam::identity::Identity;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::Result;
+use ockam_vault::{PublicKey, SecretType};
+use serde_json::{json, Value};
+
+/// Media type of a single JWK, returned by default
+pub const JWK_MEDIA_TYPE: &str = "application/jwk+json";
+
+/// Media type of a JWK Set containing the current key of each label of an identity
+pub const JWK_SET_MEDIA_TYPE: &str = "application/jwk-set+json";
+
+/// DER prefix of the SubjectPublicKeyInfo of a P-256 public key, up to the uncompressed point
+const P256_SPKI_PREFIX: [u8; 26] = [
+    0x30, 0x59, 0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x08, 0x2a,
+    0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07, 0x03, 0x42, 0x00,
+];
+
+/// Return the JWK of a public key, or `None` if the key type can't be represented as a JWK
+pub(crate) fn public_key_to_jwk(public_key: &PublicKey, kid: &str) -> Option<Value> {
+    match public_key.stype() {
+        SecretType::Ed25519 => Some(json!({
+            "kty": "OKP",
+            "crv": "Ed25519",
+            "x": BASE64URL_NOPAD.encode(public_key.data()),
+            "kid": kid,
+        })),
+        SecretType::NistP256 => {
+            let point = public_key.data().strip_prefix(&P256_SPKI_PREFIX[..])?;
+            // only uncompressed points are produced by the vaults
+            if point.len() != 65 || point[0] != 0x04 {
+                return None;
+            }
+            Some(json!({
+                "kty": "EC",
+                "crv": "P-256",
+                "x": BASE64URL_NOPAD.encode(&point[1..33]),
+                "y": BASE64URL_NOPAD.encode(&point[33..]),
+                "kid": kid,
+            }))
+        }
+        _ => None,
+    }
+}
+
+/// Return the current public key of each label of an identity, by label
+pub(crate) fn current_public_keys(identity: &Identity) -> Result<BTreeMap<String, PublicKey>> {
+    let mut keys = BTreeMap::new();
+    for change in identity.change_history().as_ref() {
+        keys.insert(
+            change.change().label().to_string(),
+            change.change().public_key()?,
+        );
+    }
+    Ok(keys)
+}
+
+/// Return the key identifier of a key of an identity
+pub(crate) fn key_id(identity: &Identity, label: &str) -> String {
+    format!("{}#{label}", identity.identifier())
+}
@@ -0,0 +1,23 @@
+//! Security strength of the keys creating signatures.
+//!
+//! The strength of a key is the number of bits of security of its key type, following the
+//! equivalences of NIST SP 800-57 Part 1, Table 2:
+//!
+//! | key type   | security bits |
+//! |------------|---------------|
+//! | Ed25519    | 128           |
+//! | NIST P-256 | 128           |
+//!
+//! The key types which can't create signatures have a strength of 0, so that they never meet
+//! a requirement. Note that the strength is not the size of the key: a 256-bit elliptic curve
+//! key provides 128 bits of security, like a 3072-bit RSA key.
+
+use ockam_vault::SecretType;
+
+/// Return the number of bits of security of a type of signing key
+pub fn key_security_bits(key_type: SecretType) -> u16 {
+    match key_type {
+        SecretType::Ed25519 | SecretType::NistP256 => 128,
+        SecretType::Buffer | SecretType::Aes | SecretType::X25519 => 0,
+    }
+}
@@ -0,0 +1,117 @@
+//! Merkle trees of digests, for transparency logs.
+//!
+//! The tree is built over a list of leaf digests, in order, with SHA-256 and a domain
+//! separation between the leaves and the inner nodes, so that an inner node can't be
+//! presented as a leaf:
+//!
+//!  - the hash of a leaf is `SHA-256(0x00 || leaf digest)`,
+//!  - the hash of an inner node is `SHA-256(0x01 || left hash || right hash)`.
+//!
+//! Each level of the tree is built by hashing the nodes of the level below two by two, from
+//! left to right. When a level has an odd number of nodes, its last node is promoted to the
+//! next level unchanged, instead of being hashed with a copy of itself. The root is the only
+//! node of the last level.
+//!
+//! An inclusion proof of a leaf is made of the index of the leaf, the number of leaves of
+//! the tree and the hashes of the siblings of the nodes on the path from the leaf to the
+//! root, from the bottom up. A promoted node has no sibling on its level.
+
+use sha2::{Digest, Sha256};
+
+/// Prefix of the hashed bytes of a leaf
+pub const MERKLE_LEAF_PREFIX: u8 = 0x00;
+
+/// Prefix of the hashed bytes of an inner node
+pub const MERKLE_NODE_PREFIX: u8 = 0x01;
+
+/// Return the hash of a leaf digest
+pub fn merkle_leaf_hash(digest: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([MERKLE_LEAF_PREFIX]);
+    hasher.update(digest);
+    hasher.finalize().into()
+}
+
+/// Return the hash of an inner node
+pub fn merkle_node_hash(left: &[u8], right: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([MERKLE_NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Return the levels of the tree built over some leaf digests, starting with the hashes of
+/// the leaves and ending with the root. There are no levels when there are no leaves
+pub fn merkle_tree_levels(leaves: &[&[u8]]) -> Vec<Vec<[u8; 32]>> {
+    if leaves.is_empty() {
+        return vec![];
+    }
+    let mut levels = vec![leaves
+        .iter()
+        .map(|leaf| merkle_leaf_hash(leaf))
+        .collect::<Vec<_>>()];
+    while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+        let next = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => merkle_node_hash(left, right),
+                [promoted] => *promoted,
+                _ => unreachable!(),
+            })
+            .collect();
+        levels.push(next);
+    }
+    levels
+}
+
+/// Return the sibling hashes proving that the leaf at an index is included in a tree,
+/// given the levels of the tree
+pub fn merkle_inclusion_proof(levels: &[Vec<[u8; 32]>], index: usize) -> Option<Vec<[u8; 32]>> {
+    if index >= levels.first()?.len() {
+        return None;
+    }
+    let mut siblings = vec![];
+    let mut index = index;
+    for level in &levels[..levels.len() - 1] {
+        if let Some(sibling) = level.get(index ^ 1) {
+            siblings.push(*sibling);
+        }
+        index /= 2;
+    }
+    Some(siblings)
+}
+
+/// Return the root of the tree of `tree_size` leaves containing a leaf digest at an index,
+/// given the sibling hashes of an inclusion proof. Return `None` if the proof doesn't have
+/// the number of siblings expected for the position of the leaf
+pub fn merkle_root_from_proof(
+    leaf: &[u8],
+    index: u64,
+    tree_size: u64,
+    siblings: &[&[u8]],
+) -> Option<[u8; 32]> {
+    if index >= tree_size {
+        return None;
+    }
+    let mut hash = merkle_leaf_hash(leaf);
+    let mut siblings = siblings.iter();
+    let (mut index, mut size) = (index, tree_size);
+    while size > 1 {
+        // the last node of a level with an odd number of nodes is promoted
+        if !(index == size - 1 && size % 2 == 1) {
+            let sibling = siblings.next()?;
+            hash = if index % 2 == 0 {
+                merkle_node_hash(&hash, sibling)
+            } else {
+                merkle_node_hash(sibling, &hash)
+            };
+        }
+        index /= 2;
+        size = size / 2 + size % 2;
+    }
+    match siblings.next() {
+        Some(_) => None,
+        None => Some(hash),
+    }
+}
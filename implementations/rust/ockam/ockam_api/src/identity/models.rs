@@ -1,6 +1,11 @@
 #![allow(missing_docs)]
 
+use crate::identity::LABEL_ATTRIBUTE;
+use ockam::identity::{IdentityHistoryComparison, OneTimeCode, Timestamp};
+use ockam_core::api::{Method, Status};
+use ockam_core::compat::collections::BTreeMap;
 use ockam_core::{CowBytes, CowStr};
+use ockam_vault::{EcdsaSignatureEncoding, SecretType};
 
 use minicbor::{Decode, Encode};
 
@@ -15,6 +20,7 @@ pub struct CreateResponse<'a> {
     #[n(0)] tag: TypeTag<3500430>,
     #[b(1)] identity: CowBytes<'a>,
     #[b(2)] identity_id: CowStr<'a>,
+    #[b(3)] label: Option<CowStr<'a>>,
 }
 
 impl<'a> CreateResponse<'a> {
@@ -24,14 +30,22 @@ impl<'a> CreateResponse<'a> {
             tag: TypeTag,
             identity: identity.into(),
             identity_id: identity_id.into(),
+            label: None,
         }
     }
+    pub fn with_label(mut self, label: Option<impl Into<CowStr<'a>>>) -> Self {
+        self.label = label.map(|l| l.into());
+        self
+    }
     pub fn identity(&self) -> &[u8] {
         &self.identity
     }
     pub fn identity_id(&self) -> &str {
         &self.identity_id
     }
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
 }
 
 #[derive(Debug, Clone, Encode, Decode)]
@@ -78,6 +92,75 @@ impl<'a> ValidateIdentityChangeHistoryResponse<'a> {
     }
 }
 
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct FingerprintRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6150493>,
+    #[b(1)] identity: CowBytes<'a>,
+}
+
+impl<'a> FingerprintRequest<'a> {
+    pub fn new(identity: impl Into<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity: identity.into(),
+        }
+    }
+    pub fn identity(&self) -> &[u8] {
+        &self.identity
+    }
+}
+
+/// Fingerprint of an identity, in several formats computed from the SHA-256 digest of its
+/// exported change history
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct FingerprintResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<3924781>,
+    #[b(1)] identity_id: CowStr<'a>,
+    /// The whole digest, as colon-separated hex bytes
+    #[b(2)] hex: CowStr<'a>,
+    /// The BIP-39 English mnemonic of the first 16 bytes of the digest
+    #[b(3)] words: CowStr<'a>,
+    /// The first 10 bytes of the digest in base32, in groups of 4 characters
+    #[b(4)] base32: CowStr<'a>,
+}
+
+impl<'a> FingerprintResponse<'a> {
+    pub fn new(
+        identity_id: impl Into<CowStr<'a>>,
+        hex: impl Into<CowStr<'a>>,
+        words: impl Into<CowStr<'a>>,
+        base32: impl Into<CowStr<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity_id: identity_id.into(),
+            hex: hex.into(),
+            words: words.into(),
+            base32: base32.into(),
+        }
+    }
+    pub fn identity_id(&self) -> &str {
+        &self.identity_id
+    }
+    pub fn hex(&self) -> &str {
+        &self.hex
+    }
+    pub fn words(&self) -> &str {
+        &self.words
+    }
+    pub fn base32(&self) -> &str {
+        &self.base32
+    }
+}
+
 #[derive(Debug, Clone, Encode, Decode)]
 #[rustfmt::skip]
 #[cbor(map)]
@@ -108,6 +191,85 @@ impl<'a> CompareIdentityChangeHistoryRequest<'a> {
     }
 }
 
+/// The public part of an identity which a peer needs to verify the signatures made with
+/// the current root key of that identity
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PublicIdentityResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<9228454>,
+    #[b(1)] identity_id: CowStr<'a>,
+    #[n(2)] key_type: SecretType,
+    #[b(3)] public_key: CowBytes<'a>,
+}
+
+impl<'a> PublicIdentityResponse<'a> {
+    pub fn new(
+        identity_id: impl Into<CowStr<'a>>,
+        key_type: SecretType,
+        public_key: impl Into<CowBytes<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity_id: identity_id.into(),
+            key_type,
+            public_key: public_key.into(),
+        }
+    }
+    pub fn identity_id(&self) -> &str {
+        &self.identity_id
+    }
+    pub fn key_type(&self) -> SecretType {
+        self.key_type
+    }
+    pub fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+}
+
+/// Return the change which created the current key of an identity.
+///
+/// Change histories don't record when a change was made, so the creation time is
+/// only set if it is known. Clients must not assume any key age when it is missing
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CurrentKeyResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<3202337>,
+    /// Index of the change which created or rotated the current key
+    #[n(1)] change_index: u64,
+    #[b(2)] public_key: CowBytes<'a>,
+    #[n(3)] created_at: Option<Timestamp>,
+}
+
+impl<'a> CurrentKeyResponse<'a> {
+    pub fn new(change_index: u64, public_key: impl Into<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            change_index,
+            public_key: public_key.into(),
+            created_at: None,
+        }
+    }
+    pub fn with_created_at(mut self, created_at: Timestamp) -> Self {
+        self.created_at = Some(created_at);
+        self
+    }
+    pub fn change_index(&self) -> u64 {
+        self.change_index
+    }
+    pub fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+    pub fn created_at(&self) -> Option<Timestamp> {
+        self.created_at
+    }
+}
+
 #[derive(Debug, Clone, Encode, Decode)]
 #[rustfmt::skip]
 #[cbor(map)]
@@ -117,6 +279,17 @@ pub struct CreateSignatureRequest<'a> {
     #[b(1)] identity: CowBytes<'a>,
     #[b(2)] data: CowBytes<'a>,
     #[b(3)] vault_name: Option<CowStr<'a>>,
+    #[n(4)] signature_encoding: Option<EcdsaSignatureEncoding>,
+    #[n(5)] deterministic: Option<bool>,
+    /// Name of a vault group configured on the service, used instead of `vault_name`
+    #[b(6)] vault_group: Option<CowStr<'a>>,
+    /// JSON Pointers to the fields of a JSON document which are signed, instead of the
+    /// whole data
+    #[b(7)] json_pointers: Option<Vec<CowStr<'a>>>,
+    /// Return the key identifier of the signing key with the signature
+    #[n(8)] include_kid: Option<bool>,
+    /// Route the signature is bound to, in addition to the data
+    #[b(9)] bound_route: Option<CowStr<'a>>,
 }
 
 impl<'a> CreateSignatureRequest<'a> {
@@ -127,8 +300,53 @@ impl<'a> CreateSignatureRequest<'a> {
             identity: identity.into(),
             data: data.into(),
             vault_name: None,
+            signature_encoding: None,
+            deterministic: None,
+            vault_group: None,
+            json_pointers: None,
+            include_kid: None,
+            bound_route: None,
         }
     }
+    /// Sign with the keys of a named vault, which may have an access policy
+    pub fn with_vault_name(mut self, vault_name: impl Into<CowStr<'a>>) -> Self {
+        self.vault_name = Some(vault_name.into());
+        self
+    }
+    pub fn with_signature_encoding(mut self, encoding: EcdsaSignatureEncoding) -> Self {
+        self.signature_encoding = Some(encoding);
+        self
+    }
+    /// Require the signature to be deterministic: signing the same data with the same key
+    /// always returns the same signature. P-256 signatures then use RFC 6979 nonces
+    pub fn with_deterministic_signature(mut self) -> Self {
+        self.deterministic = Some(true);
+        self
+    }
+    /// Sign with any vault of a vault group holding the identity key
+    pub fn with_vault_group(mut self, vault_group: impl Into<CowStr<'a>>) -> Self {
+        self.vault_group = Some(vault_group.into());
+        self
+    }
+    /// Only sign the fields designated by these JSON Pointers (RFC 6901), the data being
+    /// a JSON document. The signed payload is the canonical serialization of a JSON object
+    /// mapping each pointer to the value it designates, as returned by `canonicalize`
+    pub fn with_json_pointers(mut self, json_pointers: Vec<impl Into<CowStr<'a>>>) -> Self {
+        self.json_pointers = Some(json_pointers.into_iter().map(|p| p.into()).collect());
+        self
+    }
+    /// Return the key identifier of the signing key, as computed by `signing_key_id`,
+    /// with the signature
+    pub fn with_kid(mut self) -> Self {
+        self.include_kid = Some(true);
+        self
+    }
+    /// Bind the signature to the route of the message carrying the data, so that it is only
+    /// valid for that route. See `route_bound_payload` for the signed bytes
+    pub fn with_bound_route(mut self, route: impl Into<CowStr<'a>>) -> Self {
+        self.bound_route = Some(route.into());
+        self
+    }
     pub fn identity(&self) -> &[u8] {
         &self.identity
     }
@@ -138,6 +356,24 @@ impl<'a> CreateSignatureRequest<'a> {
     pub fn vault_name(&self) -> Option<String> {
         self.vault_name.as_ref().map(|x| x.to_string())
     }
+    pub fn signature_encoding(&self) -> Option<EcdsaSignatureEncoding> {
+        self.signature_encoding
+    }
+    pub fn deterministic(&self) -> bool {
+        self.deterministic.unwrap_or(false)
+    }
+    pub fn vault_group(&self) -> Option<&str> {
+        self.vault_group.as_deref()
+    }
+    pub fn json_pointers(&self) -> Option<&[CowStr<'a>]> {
+        self.json_pointers.as_deref()
+    }
+    pub fn include_kid(&self) -> bool {
+        self.include_kid.unwrap_or(false)
+    }
+    pub fn bound_route(&self) -> Option<&str> {
+        self.bound_route.as_deref()
+    }
 }
 
 #[derive(Debug, Clone, Encode, Decode)]
@@ -147,6 +383,18 @@ pub struct CreateSignatureResponse<'a> {
     #[cfg(feature = "tag")]
     #[n(0)] tag: TypeTag<2592832>,
     #[b(1)] signature: CowBytes<'a>,
+    #[n(2)] deterministic: bool,
+    /// Vault of the requested vault group which created the signature
+    #[b(3)] vault_name: Option<CowStr<'a>>,
+    /// Identity whose root key was rotated before signing, because the previous key
+    /// reached its usage limit
+    #[b(4)] rotated_identity: Option<CowBytes<'a>>,
+    /// JSON Pointers to the signed fields, when only some fields of a JSON document were signed
+    #[b(5)] json_pointers: Option<Vec<CowStr<'a>>>,
+    /// Key identifier of the signing key, when it was requested
+    #[b(6)] kid: Option<CowStr<'a>>,
+    /// Canonical route the signature is bound to
+    #[b(7)] bound_route: Option<CowStr<'a>>,
 }
 
 impl<'a> CreateSignatureResponse<'a> {
@@ -155,11 +403,93 @@ impl<'a> CreateSignatureResponse<'a> {
             #[cfg(feature = "tag")]
             tag: TypeTag,
             signature: signature.into(),
+            deterministic: false,
+            vault_name: None,
+            rotated_identity: None,
+            json_pointers: None,
+            kid: None,
+            bound_route: None,
         }
     }
+    /// Indicate that the signature is guaranteed to be deterministic
+    pub fn with_deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self
+    }
+    pub fn with_vault_name(mut self, vault_name: impl Into<CowStr<'a>>) -> Self {
+        self.vault_name = Some(vault_name.into());
+        self
+    }
+    pub fn with_rotated_identity(mut self, rotated_identity: impl Into<CowBytes<'a>>) -> Self {
+        self.rotated_identity = Some(rotated_identity.into());
+        self
+    }
+    pub fn with_json_pointers(mut self, json_pointers: Vec<CowStr<'a>>) -> Self {
+        self.json_pointers = Some(json_pointers);
+        self
+    }
+    pub fn with_kid(mut self, kid: impl Into<CowStr<'a>>) -> Self {
+        self.kid = Some(kid.into());
+        self
+    }
+    pub fn with_bound_route(mut self, bound_route: impl Into<CowStr<'a>>) -> Self {
+        self.bound_route = Some(bound_route.into());
+        self
+    }
     pub fn signature(&self) -> &[u8] {
         &self.signature
     }
+    /// Return true if signing the same data with the same key always returns this signature
+    pub fn deterministic(&self) -> bool {
+        self.deterministic
+    }
+    pub fn vault_name(&self) -> Option<&str> {
+        self.vault_name.as_deref()
+    }
+    /// Return true if the root key was rotated before signing. The signature must then be
+    /// verified with the rotated identity
+    pub fn key_rotated(&self) -> bool {
+        self.rotated_identity.is_some()
+    }
+    pub fn rotated_identity(&self) -> Option<&[u8]> {
+        self.rotated_identity.as_deref()
+    }
+    /// JSON Pointers to the signed fields. The verifier rebuilds the signed payload from them
+    pub fn json_pointers(&self) -> Option<&[CowStr<'a>]> {
+        self.json_pointers.as_deref()
+    }
+    /// Key identifier of the signing key. See `signing_key_id` for its derivation
+    pub fn kid(&self) -> Option<&str> {
+        self.kid.as_deref()
+    }
+    /// Canonical route the signature is bound to. The verifier checks that the message
+    /// arrived through that route and verifies the signature with it
+    pub fn bound_route(&self) -> Option<&str> {
+        self.bound_route.as_deref()
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CanonicalizeResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6647693>,
+    #[b(1)] payload: CowBytes<'a>,
+}
+
+impl<'a> CanonicalizeResponse<'a> {
+    pub fn new(payload: impl Into<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            payload: payload.into(),
+        }
+    }
+    /// The exact bytes which are signed for the request
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
 }
 
 #[derive(Debug, Clone, Encode, Decode)]
@@ -171,6 +501,20 @@ pub struct VerifySignatureRequest<'a> {
     #[b(1)] signer_identity: CowBytes<'a>,
     #[b(2)] data: CowBytes<'a>,
     #[b(3)] signature: CowBytes<'a>,
+    #[b(4)] required_signer: Option<CowStr<'a>>,
+    #[b(5)] signer_id: Option<CowStr<'a>>,
+    /// Key identifier of the key which must have created the signature
+    #[b(6)] kid: Option<CowStr<'a>>,
+    /// Route the signature is expected to be bound to
+    #[b(7)] bound_route: Option<CowStr<'a>>,
+    /// Minimum number of bits of security of the key which created the signature
+    #[n(8)] min_key_bits: Option<u16>,
+    /// Minimum index, in the change history of the signer, of the change which created or
+    /// rotated the key which created the signature
+    #[n(9)] min_change_index: Option<u64>,
+    /// Algorithm of the digest given as data, when the signature was created over the
+    /// digest of the data instead of the data itself
+    #[b(10)] digest_algorithm: Option<CowStr<'a>>,
 }
 
 impl<'a> VerifySignatureRequest<'a> {
@@ -185,8 +529,62 @@ impl<'a> VerifySignatureRequest<'a> {
             signer_identity: signer_identity.into(),
             data: data.into(),
             signature: signature.into(),
+            required_signer: None,
+            signer_id: None,
+            kid: None,
+            bound_route: None,
+            min_key_bits: None,
+            min_change_index: None,
+            digest_algorithm: None,
         }
     }
+    /// Verify a signature of a signer which is referenced by its identifier. The signer must be
+    /// known to the node, or its identity must have been sent with a previous request
+    pub fn with_signer_id(
+        signer_id: impl Into<CowStr<'a>>,
+        data: impl Into<CowBytes<'a>>,
+        signature: impl Into<CowBytes<'a>>,
+    ) -> Self {
+        let mut request = Self::new(vec![], data, signature);
+        request.signer_id = Some(signer_id.into());
+        request
+    }
+    /// Only accept the signature if the signer has the given identifier
+    pub fn with_required_signer(mut self, required_signer: impl Into<CowStr<'a>>) -> Self {
+        self.required_signer = Some(required_signer.into());
+        self
+    }
+    /// Only accept the signature if the current key of the signer has this key identifier,
+    /// as computed by `signing_key_id`
+    pub fn with_kid(mut self, kid: impl Into<CowStr<'a>>) -> Self {
+        self.kid = Some(kid.into());
+        self
+    }
+    /// Verify a signature bound to a route: the signature is only valid if it was created
+    /// for the data and this route
+    pub fn with_bound_route(mut self, route: impl Into<CowStr<'a>>) -> Self {
+        self.bound_route = Some(route.into());
+        self
+    }
+    /// Only accept the signature if the key of the signer provides at least this number of
+    /// bits of security, as returned by `key_security_bits`
+    pub fn with_min_key_bits(mut self, min_key_bits: u16) -> Self {
+        self.min_key_bits = Some(min_key_bits);
+        self
+    }
+    /// Only accept the signature if the key of the signer was created or rotated by a change
+    /// at this index of its change history or after it, so that the keys which should have
+    /// been rotated out are rejected
+    pub fn with_min_change_index(mut self, min_change_index: u64) -> Self {
+        self.min_change_index = Some(min_change_index);
+        self
+    }
+    /// Verify a signature created over the digest of some data, given the digest as data
+    /// and the algorithm which computed it, such as `sha256`. The data itself is not needed
+    pub fn with_digest_algorithm(mut self, digest_algorithm: impl Into<CowStr<'a>>) -> Self {
+        self.digest_algorithm = Some(digest_algorithm.into());
+        self
+    }
     pub fn signer_identity(&self) -> &[u8] {
         &self.signer_identity
     }
@@ -196,6 +594,27 @@ impl<'a> VerifySignatureRequest<'a> {
     pub fn signature(&self) -> &[u8] {
         &self.signature
     }
+    pub fn required_signer(&self) -> Option<&str> {
+        self.required_signer.as_deref()
+    }
+    pub fn signer_id(&self) -> Option<&str> {
+        self.signer_id.as_deref()
+    }
+    pub fn kid(&self) -> Option<&str> {
+        self.kid.as_deref()
+    }
+    pub fn bound_route(&self) -> Option<&str> {
+        self.bound_route.as_deref()
+    }
+    pub fn min_key_bits(&self) -> Option<u16> {
+        self.min_key_bits
+    }
+    pub fn min_change_index(&self) -> Option<u64> {
+        self.min_change_index
+    }
+    pub fn digest_algorithm(&self) -> Option<&str> {
+        self.digest_algorithm.as_deref()
+    }
 }
 
 #[derive(Debug, Clone, Encode, Decode)]
@@ -205,6 +624,7 @@ pub struct VerifySignatureResponse {
     #[cfg(feature = "tag")]
     #[n(0)] tag: TypeTag<1236745>,
     #[n(1)] verified: bool,
+    #[n(2)] failure_reason: Option<VerificationFailureReason>,
 }
 
 impl VerifySignatureResponse {
@@ -213,9 +633,4023 @@ impl VerifySignatureResponse {
             #[cfg(feature = "tag")]
             tag: TypeTag,
             verified,
+            failure_reason: None,
+        }
+    }
+    pub fn failed(reason: VerificationFailureReason) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            verified: false,
+            failure_reason: Some(reason),
         }
     }
     pub fn verified(&self) -> bool {
         self.verified
     }
+    pub fn failure_reason(&self) -> Option<VerificationFailureReason> {
+        self.failure_reason
+    }
+}
+
+/// A signature verified by `actions/verify_signatures`, with the identity of its signer
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SignedRecord<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<5893021>,
+    #[b(1)] signer_identity: CowBytes<'a>,
+    #[b(2)] data: CowBytes<'a>,
+    #[b(3)] signature: CowBytes<'a>,
+}
+
+impl<'a> SignedRecord<'a> {
+    pub fn new(
+        signer_identity: impl Into<CowBytes<'a>>,
+        data: impl Into<CowBytes<'a>>,
+        signature: impl Into<CowBytes<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            signer_identity: signer_identity.into(),
+            data: data.into(),
+            signature: signature.into(),
+        }
+    }
+    pub fn signer_identity(&self) -> &[u8] {
+        &self.signer_identity
+    }
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+}
+
+/// Verify signatures made by different signers
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct VerifySignaturesRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<7461930>,
+    #[b(1)] records: Vec<SignedRecord<'a>>,
+}
+
+impl<'a> VerifySignaturesRequest<'a> {
+    pub fn new(records: Vec<SignedRecord<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            records,
+        }
+    }
+    pub fn records(&self) -> &[SignedRecord<'a>] {
+        &self.records
+    }
+}
+
+/// Result of the verification of each signature of a batch, in the order of the request
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct VerifySignaturesResponse {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<2390187>,
+    #[n(1)] verified: Vec<bool>,
+}
+
+impl VerifySignaturesResponse {
+    pub fn new(verified: Vec<bool>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            verified,
+        }
+    }
+    pub fn verified(&self) -> &[bool] {
+        &self.verified
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ResignRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<4721983>,
+    #[b(1)] identity: CowBytes<'a>,
+    #[b(2)] data: CowBytes<'a>,
+    /// Signature of the data created with a previous root key of the identity
+    #[b(3)] old_signature: CowBytes<'a>,
+    #[b(4)] vault_name: Option<CowStr<'a>>,
+}
+
+impl<'a> ResignRequest<'a> {
+    pub fn new(
+        identity: impl Into<CowBytes<'a>>,
+        data: impl Into<CowBytes<'a>>,
+        old_signature: impl Into<CowBytes<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity: identity.into(),
+            data: data.into(),
+            old_signature: old_signature.into(),
+            vault_name: None,
+        }
+    }
+    pub fn with_vault_name(mut self, vault_name: impl Into<CowStr<'a>>) -> Self {
+        self.vault_name = Some(vault_name.into());
+        self
+    }
+    pub fn identity(&self) -> &[u8] {
+        &self.identity
+    }
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+    pub fn old_signature(&self) -> &[u8] {
+        &self.old_signature
+    }
+    pub fn vault_name(&self) -> Option<String> {
+        self.vault_name.as_ref().map(|x| x.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ResignResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6035218>,
+    /// Encoded `ResignedData`, which is what the new signature covers
+    #[b(1)] attestation: CowBytes<'a>,
+    #[b(2)] signature: CowBytes<'a>,
+}
+
+impl<'a> ResignResponse<'a> {
+    pub fn new(attestation: impl Into<CowBytes<'a>>, signature: impl Into<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            attestation: attestation.into(),
+            signature: signature.into(),
+        }
+    }
+    pub fn attestation(&self) -> &[u8] {
+        &self.attestation
+    }
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+}
+
+/// Data signed with the current root key of an identity when re-signing data which was
+/// signed with a previous root key. It links the new signature to the old one
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ResignedData<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<2268540>,
+    #[b(1)] data: CowBytes<'a>,
+    /// SHA-256 hash of the old signature
+    #[b(2)] old_signature_hash: CowBytes<'a>,
+    /// Public key which verified the old signature
+    #[b(3)] old_public_key: CowBytes<'a>,
+}
+
+impl<'a> ResignedData<'a> {
+    pub fn new(
+        data: impl Into<CowBytes<'a>>,
+        old_signature_hash: impl Into<CowBytes<'a>>,
+        old_public_key: impl Into<CowBytes<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            data: data.into(),
+            old_signature_hash: old_signature_hash.into(),
+            old_public_key: old_public_key.into(),
+        }
+    }
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+    pub fn old_signature_hash(&self) -> &[u8] {
+        &self.old_signature_hash
+    }
+    pub fn old_public_key(&self) -> &[u8] {
+        &self.old_public_key
+    }
+}
+
+#[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum VerificationFailureReason {
+    /// The signature can't be decoded for the signer's key type
+    #[n(0)] MalformedSignature,
+    /// The signature is well-formed but was not produced by the signer's key over the data
+    #[n(1)] KeyMismatch,
+    /// The verification could not be performed
+    #[n(2)] Unknown,
+    /// The signer is not the identity required by the request
+    #[n(3)] WrongSigner,
+    /// The signer was revoked
+    #[n(4)] Revoked,
+    /// The signer identity could not be fetched from the directory
+    #[n(5)] SignerUnavailable,
+    /// The signer referenced by its identifier is not known
+    #[n(6)] UnknownSigner,
+    /// The key of the signer doesn't have the key identifier required by the request
+    #[n(7)] KidMismatch,
+    /// The key of the signer is weaker than required by the request
+    #[n(8)] WeakKey,
+    /// The key of the signer was created by a change older than required by the request
+    #[n(9)] StaleKey,
+}
+
+#[derive(Debug, Clone, Encode, Decode, Default)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ListIdentitiesRequest {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<5200920>,
+    #[n(1)] attributes: BTreeMap<String, String>,
+}
+
+impl ListIdentitiesRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Only keep identities having an attested attribute `name` with the exact value `value`
+    pub fn with_attribute(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attributes.insert(name.into(), value.into());
+        self
+    }
+    /// Only keep identities having this label
+    pub fn with_label(self, label: impl Into<String>) -> Self {
+        self.with_attribute(LABEL_ATTRIBUTE, label)
+    }
+    pub fn attributes(&self) -> &BTreeMap<String, String> {
+        &self.attributes
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ListIdentitiesResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<8468855>,
+    #[b(1)] identity_ids: Vec<CowStr<'a>>,
+    #[n(2)] labels: Option<BTreeMap<String, String>>,
+}
+
+impl<'a> ListIdentitiesResponse<'a> {
+    pub fn new(identity_ids: Vec<CowStr<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity_ids,
+            labels: None,
+        }
+    }
+    /// Labels of the listed identities, by identifier. Identities without a label are absent
+    pub fn with_labels(mut self, labels: BTreeMap<String, String>) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+    pub fn identity_ids(&self) -> Vec<String> {
+        self.identity_ids.iter().map(|x| x.to_string()).collect()
+    }
+    pub fn label(&self, identity_id: &str) -> Option<&str> {
+        self.labels
+            .as_ref()
+            .and_then(|labels| labels.get(identity_id))
+            .map(|l| l.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct FindIdentitiesResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<2821079>,
+    #[b(1)] identities: Vec<NamedIdentity<'a>>,
+}
+
+impl<'a> FindIdentitiesResponse<'a> {
+    pub fn new(identities: Vec<NamedIdentity<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identities,
+        }
+    }
+    pub fn identities(&self) -> &[NamedIdentity<'a>] {
+        &self.identities
+    }
+}
+
+/// The name of an identity on the node, with its identifier
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct NamedIdentity<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<4441936>,
+    #[b(1)] name: CowStr<'a>,
+    #[b(2)] identity_id: CowStr<'a>,
+}
+
+impl<'a> NamedIdentity<'a> {
+    pub fn new(name: impl Into<CowStr<'a>>, identity_id: impl Into<CowStr<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            name: name.into(),
+            identity_id: identity_id.into(),
+        }
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn identity_id(&self) -> &str {
+        &self.identity_id
+    }
+}
+
+/// The public keys of an identity as a JWK or a JWK Set, depending on the media type
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PublicKeyJwkResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<4980401>,
+    #[b(1)] media_type: CowStr<'a>,
+    #[b(2)] jwk: CowStr<'a>,
+}
+
+impl<'a> PublicKeyJwkResponse<'a> {
+    pub fn new(media_type: impl Into<CowStr<'a>>, jwk: impl Into<CowStr<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            media_type: media_type.into(),
+            jwk: jwk.into(),
+        }
+    }
+    pub fn media_type(&self) -> &str {
+        &self.media_type
+    }
+    /// The JSON text of the JWK or JWK Set
+    pub fn jwk(&self) -> &str {
+        &self.jwk
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ListIdentityListenersResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<7991244>,
+    #[b(1)] identities: Vec<IdentityListeners<'a>>,
+}
+
+impl<'a> ListIdentityListenersResponse<'a> {
+    pub fn new(identities: Vec<IdentityListeners<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identities,
+        }
+    }
+    pub fn identities(&self) -> &[IdentityListeners<'a>] {
+        &self.identities
+    }
+}
+
+/// A named identity with the addresses of its active secure channel listeners.
+/// The list of addresses is empty when the identity is not listening
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct IdentityListeners<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<3046193>,
+    #[b(1)] name: CowStr<'a>,
+    #[b(2)] identity_id: CowStr<'a>,
+    #[b(3)] listener_addresses: Vec<CowStr<'a>>,
+}
+
+impl<'a> IdentityListeners<'a> {
+    pub fn new(
+        name: impl Into<CowStr<'a>>,
+        identity_id: impl Into<CowStr<'a>>,
+        listener_addresses: Vec<CowStr<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            name: name.into(),
+            identity_id: identity_id.into(),
+            listener_addresses,
+        }
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn identity_id(&self) -> &str {
+        &self.identity_id
+    }
+    pub fn listener_addresses(&self) -> Vec<String> {
+        self.listener_addresses
+            .iter()
+            .map(|a| a.to_string())
+            .collect()
+    }
+    pub fn is_listening(&self) -> bool {
+        !self.listener_addresses.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct HealthResponse {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<4668260>,
+    #[n(1)] in_flight_requests: u64,
+    #[n(2)] max_in_flight_requests: u64,
+}
+
+impl HealthResponse {
+    pub fn new(in_flight_requests: u64, max_in_flight_requests: u64) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            in_flight_requests,
+            max_in_flight_requests,
+        }
+    }
+    pub fn in_flight_requests(&self) -> u64 {
+        self.in_flight_requests
+    }
+    pub fn max_in_flight_requests(&self) -> u64 {
+        self.max_in_flight_requests
+    }
+}
+
+/// Version of the API of an identity service.
+/// The build commit is only known when the `GIT_HASH` variable was set when building the service
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct VersionResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<4419736>,
+    #[b(1)] api_version: CowStr<'a>,
+    #[b(2)] min_client_version: CowStr<'a>,
+    #[b(3)] build_commit: Option<CowStr<'a>>,
+}
+
+impl<'a> VersionResponse<'a> {
+    pub fn new(
+        api_version: impl Into<CowStr<'a>>,
+        min_client_version: impl Into<CowStr<'a>>,
+        build_commit: Option<impl Into<CowStr<'a>>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            api_version: api_version.into(),
+            min_client_version: min_client_version.into(),
+            build_commit: build_commit.map(|c| c.into()),
+        }
+    }
+    pub fn api_version(&self) -> &str {
+        &self.api_version
+    }
+    pub fn min_client_version(&self) -> &str {
+        &self.min_client_version
+    }
+    pub fn build_commit(&self) -> Option<&str> {
+        self.build_commit.as_deref()
+    }
+    /// Return true if a client using the given version of the API can use the service:
+    /// the client must not be older than the minimum client version, and must not use
+    /// a major version newer than the one of the service
+    pub fn supports_client(&self, client_version: &str) -> bool {
+        match (
+            parse_version(client_version),
+            parse_version(&self.min_client_version),
+            parse_version(&self.api_version),
+        ) {
+            (Some(client), Some(min), Some(api)) => client >= min && client.0 <= api.0,
+            _ => false,
+        }
+    }
+}
+
+/// Parse a `major.minor.patch` version
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let mut numbers = version.split('.').map(|n| n.parse::<u64>().ok());
+    let version = (numbers.next()??, numbers.next()??, numbers.next()??);
+    match numbers.next() {
+        None => Some(version),
+        Some(_) => None,
+    }
+}
+
+/// A list of metrics which can be directly translated to the Prometheus exposition format
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct MetricsResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<7779561>,
+    #[b(1)] metrics: Vec<Metric<'a>>,
+}
+
+impl<'a> MetricsResponse<'a> {
+    pub fn new(metrics: Vec<Metric<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            metrics,
+        }
+    }
+    pub fn metrics(&self) -> &[Metric<'a>] {
+        &self.metrics
+    }
+    pub fn get(&self, name: &str) -> Option<u64> {
+        self.metrics
+            .iter()
+            .find(|m| m.name() == name)
+            .map(|m| m.value())
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct Metric<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<5369067>,
+    #[b(1)] name: CowStr<'a>,
+    #[n(2)] kind: MetricKind,
+    #[n(3)] value: u64,
+}
+
+impl<'a> Metric<'a> {
+    pub fn new(name: impl Into<CowStr<'a>>, kind: MetricKind, value: u64) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            name: name.into(),
+            kind,
+            value,
+        }
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn kind(&self) -> MetricKind {
+        self.kind
+    }
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+}
+
+#[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum MetricKind {
+    /// A value which only increases
+    #[n(0)] Counter,
+    /// A value which can go up and down
+    #[n(1)] Gauge,
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ProvePossessionRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<7026865>,
+    #[b(1)] identity: CowBytes<'a>,
+    #[b(2)] challenge: CowBytes<'a>,
+    #[b(3)] vault_name: Option<CowStr<'a>>,
+}
+
+impl<'a> ProvePossessionRequest<'a> {
+    pub fn new(identity: impl Into<CowBytes<'a>>, challenge: impl Into<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity: identity.into(),
+            challenge: challenge.into(),
+            vault_name: None,
+        }
+    }
+    pub fn identity(&self) -> &[u8] {
+        &self.identity
+    }
+    pub fn challenge(&self) -> &[u8] {
+        &self.challenge
+    }
+    pub fn vault_name(&self) -> Option<String> {
+        self.vault_name.as_ref().map(|x| x.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ProvePossessionResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<8535358>,
+    #[b(1)] identity_id: CowStr<'a>,
+    #[b(2)] signature: CowBytes<'a>,
+}
+
+impl<'a> ProvePossessionResponse<'a> {
+    pub fn new(identity_id: impl Into<CowStr<'a>>, signature: impl Into<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity_id: identity_id.into(),
+            signature: signature.into(),
+        }
+    }
+    pub fn identity_id(&self) -> &str {
+        &self.identity_id
+    }
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ChallengeResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6093718>,
+    #[b(1)] challenge: CowBytes<'a>,
+    #[n(2)] ttl_secs: u64,
+}
+
+impl<'a> ChallengeResponse<'a> {
+    pub fn new(challenge: impl Into<CowBytes<'a>>, ttl_secs: u64) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            challenge: challenge.into(),
+            ttl_secs,
+        }
+    }
+    /// Nonce which must be signed by the device
+    pub fn challenge(&self) -> &[u8] {
+        &self.challenge
+    }
+    /// Number of seconds after which the challenge can't be attested anymore
+    pub fn ttl_secs(&self) -> u64 {
+        self.ttl_secs
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct AttestChallengeRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<2748350>,
+    #[b(1)] identity: CowBytes<'a>,
+    #[b(2)] challenge: CowBytes<'a>,
+    #[b(3)] signature: CowBytes<'a>,
+}
+
+impl<'a> AttestChallengeRequest<'a> {
+    /// Attest a challenge with the exported identity of a device and its signature of the challenge
+    pub fn new(
+        identity: impl Into<CowBytes<'a>>,
+        challenge: impl Into<CowBytes<'a>>,
+        signature: impl Into<CowBytes<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity: identity.into(),
+            challenge: challenge.into(),
+            signature: signature.into(),
+        }
+    }
+    pub fn identity(&self) -> &[u8] {
+        &self.identity
+    }
+    pub fn challenge(&self) -> &[u8] {
+        &self.challenge
+    }
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct AttestChallengeResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<9517462>,
+    #[n(1)] verified: bool,
+    #[n(2)] failure_reason: Option<AttestationFailureReason>,
+    #[b(3)] identity_id: Option<CowStr<'a>>,
+    #[n(4)] enrollment_token: Option<OneTimeCode>,
+}
+
+impl<'a> AttestChallengeResponse<'a> {
+    pub fn new(identity_id: impl Into<CowStr<'a>>, enrollment_token: OneTimeCode) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            verified: true,
+            failure_reason: None,
+            identity_id: Some(identity_id.into()),
+            enrollment_token: Some(enrollment_token),
+        }
+    }
+    pub fn failed(reason: AttestationFailureReason) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            verified: false,
+            failure_reason: Some(reason),
+            identity_id: None,
+            enrollment_token: None,
+        }
+    }
+    pub fn verified(&self) -> bool {
+        self.verified
+    }
+    pub fn failure_reason(&self) -> Option<AttestationFailureReason> {
+        self.failure_reason
+    }
+    /// Identifier of the device identity, when the attestation is valid
+    pub fn identity_id(&self) -> Option<&str> {
+        self.identity_id.as_deref()
+    }
+    /// One-time code which can be used to enroll the device, when the attestation is valid
+    pub fn enrollment_token(&self) -> Option<&OneTimeCode> {
+        self.enrollment_token.as_ref()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum AttestationFailureReason {
+    /// The challenge was not issued by the service, has expired or was already attested
+    #[n(0)] UnknownChallenge,
+    /// The challenge was not signed by the device identity
+    #[n(1)] InvalidSignature,
+}
+
+#[derive(Debug, Clone, Encode, Decode, Default)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CreateIdentityRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<7685120>,
+    #[b(1)] key_type: Option<CowStr<'a>>,
+    #[b(2)] vault_name: Option<CowStr<'a>>,
+    #[n(3)] persist: Option<bool>,
+    #[b(4)] label: Option<CowStr<'a>>,
+}
+
+impl<'a> CreateIdentityRequest<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Key type of the identity root key: "ed25519" (default) or "p256"
+    pub fn with_key_type(mut self, key_type: impl Into<CowStr<'a>>) -> Self {
+        self.key_type = Some(key_type.into());
+        self
+    }
+    pub fn with_vault_name(mut self, vault_name: impl Into<CowStr<'a>>) -> Self {
+        self.vault_name = Some(vault_name.into());
+        self
+    }
+    /// Store the identity and its key in the node (default), or only return the identity.
+    ///
+    /// An identity which is not persisted is ephemeral: its key is discarded once the response
+    /// is sent, so nothing can be signed with it afterwards, its key can't be rotated and the
+    /// identity can't be revoked. It is not listed by the service either.
+    /// Use it for identities which only need to exist for the duration of a single task
+    pub fn with_persist(mut self, persist: bool) -> Self {
+        self.persist = Some(persist);
+        self
+    }
+    /// Tag the identity with a purpose, such as "device", "service" or "user".
+    /// The label is stored with the identity and returned when it is read or listed
+    pub fn with_label(mut self, label: impl Into<CowStr<'a>>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+    pub fn key_type(&self) -> Option<&str> {
+        self.key_type.as_deref()
+    }
+    pub fn vault_name(&self) -> Option<String> {
+        self.vault_name.as_ref().map(|x| x.to_string())
+    }
+    pub fn persist(&self) -> bool {
+        self.persist.unwrap_or(true)
+    }
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+}
+
+/// Replace the label of a stored identity, or remove it when there is no label
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SetLabelRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6204817>,
+    #[b(1)] identity_id: CowStr<'a>,
+    #[b(2)] label: Option<CowStr<'a>>,
+}
+
+impl<'a> SetLabelRequest<'a> {
+    pub fn new(identity_id: impl Into<CowStr<'a>>, label: Option<impl Into<CowStr<'a>>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity_id: identity_id.into(),
+            label: label.map(|l| l.into()),
+        }
+    }
+    pub fn identity_id(&self) -> &str {
+        &self.identity_id
+    }
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct IssueDelegationTokenRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<7032479>,
+    #[b(1)] issuer: CowBytes<'a>,
+    #[b(2)] delegate: CowStr<'a>,
+    #[b(3)] scope: CowStr<'a>,
+    #[n(4)] ttl_secs: u64,
+    #[b(5)] vault_name: Option<CowStr<'a>>,
+}
+
+impl<'a> IssueDelegationTokenRequest<'a> {
+    pub fn new(
+        issuer: impl Into<CowBytes<'a>>,
+        delegate: impl Into<CowStr<'a>>,
+        scope: impl Into<CowStr<'a>>,
+        ttl_secs: u64,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            issuer: issuer.into(),
+            delegate: delegate.into(),
+            scope: scope.into(),
+            ttl_secs,
+            vault_name: None,
+        }
+    }
+    pub fn issuer(&self) -> &[u8] {
+        &self.issuer
+    }
+    pub fn delegate(&self) -> &str {
+        &self.delegate
+    }
+    pub fn scope(&self) -> &str {
+        &self.scope
+    }
+    pub fn ttl_secs(&self) -> u64 {
+        self.ttl_secs
+    }
+    pub fn vault_name(&self) -> Option<String> {
+        self.vault_name.as_ref().map(|x| x.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct IssueDelegationTokenResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<2235898>,
+    #[b(1)] token: DelegationToken<'a>,
+}
+
+impl<'a> IssueDelegationTokenResponse<'a> {
+    pub fn new(token: DelegationToken<'a>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            token,
+        }
+    }
+    pub fn token(&self) -> &DelegationToken<'a> {
+        &self.token
+    }
+}
+
+/// A token granting a delegate identity the authority to act within a scope on behalf of an
+/// issuer identity. The token data is signed by the issuer
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct DelegationToken<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<5502611>,
+    #[b(1)] data: CowBytes<'a>,
+    #[b(2)] signature: CowBytes<'a>,
+}
+
+impl<'a> DelegationToken<'a> {
+    pub fn new(data: impl Into<CowBytes<'a>>, signature: impl Into<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            data: data.into(),
+            signature: signature.into(),
+        }
+    }
+    /// CBOR-encoded [`DelegationTokenData`]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct DelegationTokenData<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<1571389>,
+    #[b(1)] issuer: CowStr<'a>,
+    #[b(2)] delegate: CowStr<'a>,
+    #[b(3)] scope: CowStr<'a>,
+    #[n(4)] expires_at: Timestamp,
+    #[b(5)] nonce: CowBytes<'a>,
+}
+
+impl<'a> DelegationTokenData<'a> {
+    pub fn new(
+        issuer: impl Into<CowStr<'a>>,
+        delegate: impl Into<CowStr<'a>>,
+        scope: impl Into<CowStr<'a>>,
+        expires_at: Timestamp,
+        nonce: impl Into<CowBytes<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            issuer: issuer.into(),
+            delegate: delegate.into(),
+            scope: scope.into(),
+            expires_at,
+            nonce: nonce.into(),
+        }
+    }
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+    pub fn delegate(&self) -> &str {
+        &self.delegate
+    }
+    pub fn scope(&self) -> &str {
+        &self.scope
+    }
+    pub fn expires_at(&self) -> Timestamp {
+        self.expires_at
+    }
+    pub fn nonce(&self) -> &[u8] {
+        &self.nonce
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct VerifyDelegationTokenRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<7204729>,
+    #[b(1)] issuer: CowBytes<'a>,
+    #[b(2)] token: DelegationToken<'a>,
+}
+
+impl<'a> VerifyDelegationTokenRequest<'a> {
+    pub fn new(issuer: impl Into<CowBytes<'a>>, token: DelegationToken<'a>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            issuer: issuer.into(),
+            token,
+        }
+    }
+    pub fn issuer(&self) -> &[u8] {
+        &self.issuer
+    }
+    pub fn token(&self) -> &DelegationToken<'a> {
+        &self.token
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct VerifyDelegationTokenResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<8066606>,
+    #[n(1)] verified: bool,
+    #[b(2)] delegate: Option<CowStr<'a>>,
+    #[b(3)] scope: Option<CowStr<'a>>,
+    #[n(4)] failure_reason: Option<DelegationTokenFailureReason>,
+}
+
+impl<'a> VerifyDelegationTokenResponse<'a> {
+    pub fn new(delegate: impl Into<CowStr<'a>>, scope: impl Into<CowStr<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            verified: true,
+            delegate: Some(delegate.into()),
+            scope: Some(scope.into()),
+            failure_reason: None,
+        }
+    }
+    pub fn failed(reason: DelegationTokenFailureReason) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            verified: false,
+            delegate: None,
+            scope: None,
+            failure_reason: Some(reason),
+        }
+    }
+    pub fn verified(&self) -> bool {
+        self.verified
+    }
+    pub fn delegate(&self) -> Option<&str> {
+        self.delegate.as_deref()
+    }
+    pub fn scope(&self) -> Option<&str> {
+        self.scope.as_deref()
+    }
+    pub fn failure_reason(&self) -> Option<DelegationTokenFailureReason> {
+        self.failure_reason
+    }
+}
+
+#[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum DelegationTokenFailureReason {
+    /// The token data can't be decoded
+    #[n(0)] Malformed,
+    /// The token was not signed by the issuer
+    #[n(1)] InvalidSignature,
+    /// The token is past its expiry time
+    #[n(2)] Expired,
+    /// The token has already been presented
+    #[n(3)] AlreadyUsed,
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct IssueChannelCredentialRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6403187>,
+    #[b(1)] issuer: CowBytes<'a>,
+    /// Identifier of the identity authorized to open the secure channel
+    #[b(2)] subject: CowStr<'a>,
+    /// Address of the service the secure channel is opened to
+    #[b(3)] service: CowStr<'a>,
+    #[n(4)] ttl_secs: u64,
+    #[b(5)] vault_name: Option<CowStr<'a>>,
+}
+
+impl<'a> IssueChannelCredentialRequest<'a> {
+    pub fn new(
+        issuer: impl Into<CowBytes<'a>>,
+        subject: impl Into<CowStr<'a>>,
+        service: impl Into<CowStr<'a>>,
+        ttl_secs: u64,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            issuer: issuer.into(),
+            subject: subject.into(),
+            service: service.into(),
+            ttl_secs,
+            vault_name: None,
+        }
+    }
+    pub fn issuer(&self) -> &[u8] {
+        &self.issuer
+    }
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+    pub fn service(&self) -> &str {
+        &self.service
+    }
+    pub fn ttl_secs(&self) -> u64 {
+        self.ttl_secs
+    }
+    pub fn vault_name(&self) -> Option<String> {
+        self.vault_name.as_ref().map(|x| x.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct IssueChannelCredentialResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<2958716>,
+    #[b(1)] credential: ChannelCredential<'a>,
+}
+
+impl<'a> IssueChannelCredentialResponse<'a> {
+    pub fn new(credential: ChannelCredential<'a>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            credential,
+        }
+    }
+    pub fn credential(&self) -> &ChannelCredential<'a> {
+        &self.credential
+    }
+}
+
+/// A short-lived credential asserting that an identity is authorized to open a secure channel
+/// to a service. The credential data is signed by the issuer, so that a secure channel
+/// listener trusting the issuer can require the credential instead of a list of identifiers
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ChannelCredential<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<4817350>,
+    #[b(1)] data: CowBytes<'a>,
+    #[b(2)] signature: CowBytes<'a>,
+}
+
+impl<'a> ChannelCredential<'a> {
+    pub fn new(data: impl Into<CowBytes<'a>>, signature: impl Into<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            data: data.into(),
+            signature: signature.into(),
+        }
+    }
+    /// CBOR-encoded [`ChannelCredentialData`]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+}
+
+/// Claims of a [`ChannelCredential`]
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ChannelCredentialData<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<7126594>,
+    #[b(1)] issuer: CowStr<'a>,
+    #[b(2)] subject: CowStr<'a>,
+    #[b(3)] service: CowStr<'a>,
+    #[n(4)] issued_at: Timestamp,
+    #[n(5)] expires_at: Timestamp,
+}
+
+impl<'a> ChannelCredentialData<'a> {
+    pub fn new(
+        issuer: impl Into<CowStr<'a>>,
+        subject: impl Into<CowStr<'a>>,
+        service: impl Into<CowStr<'a>>,
+        issued_at: Timestamp,
+        expires_at: Timestamp,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            issuer: issuer.into(),
+            subject: subject.into(),
+            service: service.into(),
+            issued_at,
+            expires_at,
+        }
+    }
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+    pub fn service(&self) -> &str {
+        &self.service
+    }
+    pub fn issued_at(&self) -> Timestamp {
+        self.issued_at
+    }
+    pub fn expires_at(&self) -> Timestamp {
+        self.expires_at
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct VerifyChannelCredentialRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<3390842>,
+    #[b(1)] issuer: CowBytes<'a>,
+    #[b(2)] credential: ChannelCredential<'a>,
+    /// Identifier of the identity opening the secure channel
+    #[b(3)] subject: CowStr<'a>,
+    /// Address of the service the secure channel is opened to
+    #[b(4)] service: CowStr<'a>,
+}
+
+impl<'a> VerifyChannelCredentialRequest<'a> {
+    pub fn new(
+        issuer: impl Into<CowBytes<'a>>,
+        credential: ChannelCredential<'a>,
+        subject: impl Into<CowStr<'a>>,
+        service: impl Into<CowStr<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            issuer: issuer.into(),
+            credential,
+            subject: subject.into(),
+            service: service.into(),
+        }
+    }
+    pub fn issuer(&self) -> &[u8] {
+        &self.issuer
+    }
+    pub fn credential(&self) -> &ChannelCredential<'a> {
+        &self.credential
+    }
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+    pub fn service(&self) -> &str {
+        &self.service
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct VerifyChannelCredentialResponse {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<8520163>,
+    #[n(1)] verified: bool,
+    #[n(2)] expires_at: Option<Timestamp>,
+    #[n(3)] failure_reason: Option<ChannelCredentialFailureReason>,
+}
+
+impl VerifyChannelCredentialResponse {
+    pub fn new(expires_at: Timestamp) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            verified: true,
+            expires_at: Some(expires_at),
+            failure_reason: None,
+        }
+    }
+    pub fn failed(reason: ChannelCredentialFailureReason) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            verified: false,
+            expires_at: None,
+            failure_reason: Some(reason),
+        }
+    }
+    pub fn verified(&self) -> bool {
+        self.verified
+    }
+    /// Expiry of a verified credential, after which the secure channel should be closed
+    pub fn expires_at(&self) -> Option<Timestamp> {
+        self.expires_at
+    }
+    pub fn failure_reason(&self) -> Option<ChannelCredentialFailureReason> {
+        self.failure_reason
+    }
+}
+
+#[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum ChannelCredentialFailureReason {
+    /// The credential data can't be decoded
+    #[n(0)] Malformed,
+    /// The credential was not signed by the issuer
+    #[n(1)] InvalidSignature,
+    /// The credential is past its expiry time
+    #[n(2)] Expired,
+    /// The credential was issued to another identity
+    #[n(3)] SubjectMismatch,
+    /// The credential was issued for another service
+    #[n(4)] ServiceMismatch,
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CrossSignRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<2795659>,
+    #[b(1)] endorser: CowStr<'a>,
+    #[b(2)] subject: CowStr<'a>,
+    #[b(3)] vault_name: Option<CowStr<'a>>,
+}
+
+impl<'a> CrossSignRequest<'a> {
+    /// Endorse the subject identity with the endorser identity, both given by name
+    pub fn new(endorser: impl Into<CowStr<'a>>, subject: impl Into<CowStr<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            endorser: endorser.into(),
+            subject: subject.into(),
+            vault_name: None,
+        }
+    }
+    pub fn with_vault_name(mut self, vault_name: impl Into<CowStr<'a>>) -> Self {
+        self.vault_name = Some(vault_name.into());
+        self
+    }
+    pub fn endorser(&self) -> &str {
+        &self.endorser
+    }
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+    pub fn vault_name(&self) -> Option<String> {
+        self.vault_name.as_ref().map(|x| x.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CrossSignResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<3024039>,
+    #[b(1)] endorsement: Endorsement<'a>,
+}
+
+impl<'a> CrossSignResponse<'a> {
+    pub fn new(endorsement: Endorsement<'a>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            endorsement,
+        }
+    }
+    pub fn endorsement(&self) -> &Endorsement<'a> {
+        &self.endorsement
+    }
+}
+
+/// A statement by an endorser identity that a subject identity is identified by
+/// a given public key. The endorsement data is signed by the endorser
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct Endorsement<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<5816883>,
+    #[b(1)] data: CowBytes<'a>,
+    #[b(2)] signature: CowBytes<'a>,
+}
+
+impl<'a> Endorsement<'a> {
+    pub fn new(data: impl Into<CowBytes<'a>>, signature: impl Into<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            data: data.into(),
+            signature: signature.into(),
+        }
+    }
+    /// CBOR-encoded [`EndorsementData`]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct EndorsementData<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<9282410>,
+    #[b(1)] endorser: CowStr<'a>,
+    #[b(2)] subject: CowStr<'a>,
+    #[b(3)] subject_public_key: CowBytes<'a>,
+}
+
+impl<'a> EndorsementData<'a> {
+    pub fn new(
+        endorser: impl Into<CowStr<'a>>,
+        subject: impl Into<CowStr<'a>>,
+        subject_public_key: impl Into<CowBytes<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            endorser: endorser.into(),
+            subject: subject.into(),
+            subject_public_key: subject_public_key.into(),
+        }
+    }
+    pub fn endorser(&self) -> &str {
+        &self.endorser
+    }
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+    pub fn subject_public_key(&self) -> &[u8] {
+        &self.subject_public_key
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct VerifyEndorsementRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<1966705>,
+    #[b(1)] endorser: CowBytes<'a>,
+    #[b(2)] subject: CowBytes<'a>,
+    #[b(3)] endorsement: Endorsement<'a>,
+}
+
+impl<'a> VerifyEndorsementRequest<'a> {
+    /// Verify an endorsement given the exported endorser and subject identities
+    pub fn new(
+        endorser: impl Into<CowBytes<'a>>,
+        subject: impl Into<CowBytes<'a>>,
+        endorsement: Endorsement<'a>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            endorser: endorser.into(),
+            subject: subject.into(),
+            endorsement,
+        }
+    }
+    pub fn endorser(&self) -> &[u8] {
+        &self.endorser
+    }
+    pub fn subject(&self) -> &[u8] {
+        &self.subject
+    }
+    pub fn endorsement(&self) -> &Endorsement<'a> {
+        &self.endorsement
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct VerifyEndorsementResponse {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<2451620>,
+    #[n(1)] verified: bool,
+    #[n(2)] failure_reason: Option<EndorsementFailureReason>,
+}
+
+impl VerifyEndorsementResponse {
+    pub fn new(failure_reason: Option<EndorsementFailureReason>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            verified: failure_reason.is_none(),
+            failure_reason,
+        }
+    }
+    pub fn verified(&self) -> bool {
+        self.verified
+    }
+    pub fn failure_reason(&self) -> Option<EndorsementFailureReason> {
+        self.failure_reason
+    }
+}
+
+#[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum EndorsementFailureReason {
+    /// The endorsement data can't be decoded
+    #[n(0)] Malformed,
+    /// The endorsement was not signed by the endorser
+    #[n(1)] InvalidSignature,
+    /// The endorsement is about another identity, or another key of the subject
+    #[n(2)] SubjectMismatch,
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RevokeIdentityRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<4418027>,
+    #[b(1)] identity: CowStr<'a>,
+    #[b(2)] reason: Option<CowStr<'a>>,
+    #[b(3)] vault_name: Option<CowStr<'a>>,
+}
+
+impl<'a> RevokeIdentityRequest<'a> {
+    /// Revoke an identity given by name
+    pub fn new(identity: impl Into<CowStr<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity: identity.into(),
+            reason: None,
+            vault_name: None,
+        }
+    }
+    pub fn with_reason(mut self, reason: impl Into<CowStr<'a>>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+    pub fn with_vault_name(mut self, vault_name: impl Into<CowStr<'a>>) -> Self {
+        self.vault_name = Some(vault_name.into());
+        self
+    }
+    pub fn identity(&self) -> &str {
+        &self.identity
+    }
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+    pub fn vault_name(&self) -> Option<String> {
+        self.vault_name.as_ref().map(|x| x.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RevokeIdentityResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6201364>,
+    #[b(1)] revocation: Revocation<'a>,
+}
+
+impl<'a> RevokeIdentityResponse<'a> {
+    pub fn new(revocation: Revocation<'a>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            revocation,
+        }
+    }
+    pub fn revocation(&self) -> &Revocation<'a> {
+        &self.revocation
+    }
+}
+
+/// A statement by an identity that it must not be trusted anymore.
+/// The revocation data is signed by the revoked identity, so that the record can be
+/// distributed to peers which only know the public identity
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct Revocation<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<8730512>,
+    #[b(1)] data: CowBytes<'a>,
+    #[b(2)] signature: CowBytes<'a>,
+}
+
+impl<'a> Revocation<'a> {
+    pub fn new(data: impl Into<CowBytes<'a>>, signature: impl Into<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            data: data.into(),
+            signature: signature.into(),
+        }
+    }
+    /// CBOR-encoded [`RevocationData`]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RevocationData<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<3359871>,
+    #[b(1)] identity: CowStr<'a>,
+    #[n(2)] revoked_at: Timestamp,
+    #[b(3)] reason: Option<CowStr<'a>>,
+}
+
+impl<'a> RevocationData<'a> {
+    pub fn new(
+        identity: impl Into<CowStr<'a>>,
+        revoked_at: Timestamp,
+        reason: Option<CowStr<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity: identity.into(),
+            revoked_at,
+            reason,
+        }
+    }
+    pub fn identity(&self) -> &str {
+        &self.identity
+    }
+    pub fn revoked_at(&self) -> Timestamp {
+        self.revoked_at
+    }
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ProveNotRevokedRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<5180473>,
+    #[b(1)] identity_id: CowStr<'a>,
+}
+
+impl<'a> ProveNotRevokedRequest<'a> {
+    pub fn new(identity_id: impl Into<CowStr<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity_id: identity_id.into(),
+        }
+    }
+    pub fn identity_id(&self) -> &str {
+        &self.identity_id
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ProveNotRevokedResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<7732916>,
+    #[b(1)] proof: FreshnessProof<'a>,
+}
+
+impl<'a> ProveNotRevokedResponse<'a> {
+    pub fn new(proof: FreshnessProof<'a>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            proof,
+        }
+    }
+    pub fn proof(&self) -> &FreshnessProof<'a> {
+        &self.proof
+    }
+}
+
+/// A short-lived statement, signed by the signing identity of the service, that an identity
+/// was not revoked at a given time. Relying parties can cache it until it expires instead of
+/// checking the revocation status of the identity for each verification
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct FreshnessProof<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<3306258>,
+    #[b(1)] data: CowBytes<'a>,
+    #[b(2)] signature: CowBytes<'a>,
+}
+
+impl<'a> FreshnessProof<'a> {
+    pub fn new(data: impl Into<CowBytes<'a>>, signature: impl Into<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            data: data.into(),
+            signature: signature.into(),
+        }
+    }
+    /// CBOR-encoded [`FreshnessProofData`]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+}
+
+/// Claims of a [`FreshnessProof`]
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct FreshnessProofData<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<8647021>,
+    #[b(1)] issuer: CowStr<'a>,
+    /// Identifier of the identity which is not revoked
+    #[b(2)] subject: CowStr<'a>,
+    #[n(3)] checked_at: Timestamp,
+    #[n(4)] expires_at: Timestamp,
+}
+
+impl<'a> FreshnessProofData<'a> {
+    pub fn new(
+        issuer: impl Into<CowStr<'a>>,
+        subject: impl Into<CowStr<'a>>,
+        checked_at: Timestamp,
+        expires_at: Timestamp,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            issuer: issuer.into(),
+            subject: subject.into(),
+            checked_at,
+            expires_at,
+        }
+    }
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+    pub fn checked_at(&self) -> Timestamp {
+        self.checked_at
+    }
+    pub fn expires_at(&self) -> Timestamp {
+        self.expires_at
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct VerifyFreshnessProofRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<1592364>,
+    #[b(1)] issuer: CowBytes<'a>,
+    #[b(2)] proof: FreshnessProof<'a>,
+    /// Identifier of the identity which must not be revoked
+    #[b(3)] subject: CowStr<'a>,
+}
+
+impl<'a> VerifyFreshnessProofRequest<'a> {
+    pub fn new(
+        issuer: impl Into<CowBytes<'a>>,
+        proof: FreshnessProof<'a>,
+        subject: impl Into<CowStr<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            issuer: issuer.into(),
+            proof,
+            subject: subject.into(),
+        }
+    }
+    pub fn issuer(&self) -> &[u8] {
+        &self.issuer
+    }
+    pub fn proof(&self) -> &FreshnessProof<'a> {
+        &self.proof
+    }
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct VerifyFreshnessProofResponse {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<4419902>,
+    #[n(1)] verified: bool,
+    #[n(2)] expires_at: Option<Timestamp>,
+    #[n(3)] failure_reason: Option<FreshnessProofFailureReason>,
+}
+
+impl VerifyFreshnessProofResponse {
+    pub fn new(expires_at: Timestamp) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            verified: true,
+            expires_at: Some(expires_at),
+            failure_reason: None,
+        }
+    }
+    pub fn failed(reason: FreshnessProofFailureReason) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            verified: false,
+            expires_at: None,
+            failure_reason: Some(reason),
+        }
+    }
+    pub fn verified(&self) -> bool {
+        self.verified
+    }
+    /// Expiry of a verified proof, after which the revocation status must be checked again
+    pub fn expires_at(&self) -> Option<Timestamp> {
+        self.expires_at
+    }
+    pub fn failure_reason(&self) -> Option<FreshnessProofFailureReason> {
+        self.failure_reason
+    }
+}
+
+#[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum FreshnessProofFailureReason {
+    /// The proof data can't be decoded
+    #[n(0)] Malformed,
+    /// The proof was not signed by the issuer
+    #[n(1)] InvalidSignature,
+    /// The proof is past its expiry time
+    #[n(2)] Expired,
+    /// The proof is about another identity
+    #[n(3)] SubjectMismatch,
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct BeginSignRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<5010187>,
+    #[b(1)] identity: CowBytes<'a>,
+    #[b(2)] vault_name: Option<CowStr<'a>>,
+}
+
+impl<'a> BeginSignRequest<'a> {
+    pub fn new(identity: impl Into<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity: identity.into(),
+            vault_name: None,
+        }
+    }
+    pub fn with_vault_name(mut self, vault_name: impl Into<CowStr<'a>>) -> Self {
+        self.vault_name = Some(vault_name.into());
+        self
+    }
+    pub fn identity(&self) -> &[u8] {
+        &self.identity
+    }
+    pub fn vault_name(&self) -> Option<String> {
+        self.vault_name.as_ref().map(|x| x.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct BeginSignResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<3367050>,
+    #[b(1)] session_id: CowStr<'a>,
+}
+
+impl<'a> BeginSignResponse<'a> {
+    pub fn new(session_id: impl Into<CowStr<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            session_id: session_id.into(),
+        }
+    }
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SignChunkRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<1091433>,
+    #[b(1)] session_id: CowStr<'a>,
+    #[b(2)] chunk: CowBytes<'a>,
+}
+
+impl<'a> SignChunkRequest<'a> {
+    pub fn new(session_id: impl Into<CowStr<'a>>, chunk: impl Into<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            session_id: session_id.into(),
+            chunk: chunk.into(),
+        }
+    }
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+    pub fn chunk(&self) -> &[u8] {
+        &self.chunk
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct FinishSignRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<9138524>,
+    #[b(1)] session_id: CowStr<'a>,
+}
+
+impl<'a> FinishSignRequest<'a> {
+    pub fn new(session_id: impl Into<CowStr<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            session_id: session_id.into(),
+        }
+    }
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SigningCapabilitiesResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<3137920>,
+    #[b(1)] schemes: Vec<SigningScheme<'a>>,
+}
+
+impl<'a> SigningCapabilitiesResponse<'a> {
+    pub fn new(schemes: Vec<SigningScheme<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            schemes,
+        }
+    }
+    pub fn schemes(&self) -> &[SigningScheme<'a>] {
+        &self.schemes
+    }
+}
+
+/// A signature scheme supported by the identity service
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SigningScheme<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<7858352>,
+    /// Key type, as accepted when creating an identity
+    #[b(1)] key_type: CowStr<'a>,
+    /// Signature encodings which can be requested when creating a signature
+    #[b(2)] encodings: Vec<CowStr<'a>>,
+}
+
+impl<'a> SigningScheme<'a> {
+    pub fn new(key_type: impl Into<CowStr<'a>>, encodings: Vec<CowStr<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            key_type: key_type.into(),
+            encodings,
+        }
+    }
+    pub fn key_type(&self) -> &str {
+        &self.key_type
+    }
+    pub fn encodings(&self) -> Vec<String> {
+        self.encodings.iter().map(|e| e.to_string()).collect()
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct VerifyIdentityChangeHistoryRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<8012296>,
+    /// Change history to verify
+    #[b(1)] identity: CowBytes<'a>,
+    /// Change history of the identity trusted as the root of the verified change history
+    #[b(2)] trust_anchor: Option<CowBytes<'a>>,
+}
+
+impl<'a> VerifyIdentityChangeHistoryRequest<'a> {
+    pub fn new(identity: impl Into<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity: identity.into(),
+            trust_anchor: None,
+        }
+    }
+    pub fn with_trust_anchor(mut self, trust_anchor: impl Into<CowBytes<'a>>) -> Self {
+        self.trust_anchor = Some(trust_anchor.into());
+        self
+    }
+    pub fn identity(&self) -> &[u8] {
+        &self.identity
+    }
+    pub fn trust_anchor(&self) -> Option<&[u8]> {
+        self.trust_anchor.as_deref()
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct VerifyIdentityChangeHistoryResponse {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<3121061>,
+    #[n(1)] verified: bool,
+    /// Index of the first change which failed the verification
+    #[n(2)] failed_change: Option<u64>,
+    #[n(3)] failure_reason: Option<ChangeHistoryFailureReason>,
+}
+
+impl VerifyIdentityChangeHistoryResponse {
+    pub fn new() -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            verified: true,
+            failed_change: None,
+            failure_reason: None,
+        }
+    }
+    pub fn failed(reason: ChangeHistoryFailureReason, failed_change: Option<u64>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            verified: false,
+            failed_change,
+            failure_reason: Some(reason),
+        }
+    }
+    pub fn verified(&self) -> bool {
+        self.verified
+    }
+    pub fn failed_change(&self) -> Option<u64> {
+        self.failed_change
+    }
+    pub fn failure_reason(&self) -> Option<ChangeHistoryFailureReason> {
+        self.failure_reason
+    }
+}
+
+impl Default for VerifyIdentityChangeHistoryResponse {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum ChangeHistoryFailureReason {
+    /// The change history or the trust anchor can't be decoded, or their changes are not consistent
+    #[n(0)] Malformed,
+    /// A change is not signed by the keys of the preceding changes
+    #[n(1)] InvalidSignature,
+    /// The change history doesn't start with the changes of the trust anchor
+    #[n(2)] UntrustedRoot,
+    /// The change history is missing changes known by the trust anchor
+    #[n(3)] Outdated,
+}
+
+/// Sign data with a key derived from the root key of an identity.
+/// See the `derived_keys` module for the derivation parameters
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct DerivedKeySignatureRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<1016443>,
+    #[b(1)] identity: CowBytes<'a>,
+    #[b(2)] salt: CowBytes<'a>,
+    #[b(3)] info: CowBytes<'a>,
+    /// Data to sign. Only the derived public key is returned when no data is given
+    #[b(4)] data: Option<CowBytes<'a>>,
+    #[b(5)] vault_name: Option<CowStr<'a>>,
+}
+
+impl<'a> DerivedKeySignatureRequest<'a> {
+    pub fn new(
+        identity: impl Into<CowBytes<'a>>,
+        salt: impl Into<CowBytes<'a>>,
+        info: impl Into<CowBytes<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity: identity.into(),
+            salt: salt.into(),
+            info: info.into(),
+            data: None,
+            vault_name: None,
+        }
+    }
+    pub fn with_data(mut self, data: impl Into<CowBytes<'a>>) -> Self {
+        self.data = Some(data.into());
+        self
+    }
+    pub fn identity(&self) -> &[u8] {
+        &self.identity
+    }
+    pub fn salt(&self) -> &[u8] {
+        &self.salt
+    }
+    pub fn info(&self) -> &[u8] {
+        &self.info
+    }
+    pub fn data(&self) -> Option<&[u8]> {
+        self.data.as_deref()
+    }
+    pub fn vault_name(&self) -> Option<String> {
+        self.vault_name.as_ref().map(|x| x.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct DerivedKeySignatureResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6850482>,
+    /// Ed25519 public key of the derived key
+    #[b(1)] public_key: CowBytes<'a>,
+    #[b(2)] signature: Option<CowBytes<'a>>,
+}
+
+impl<'a> DerivedKeySignatureResponse<'a> {
+    pub fn new(public_key: impl Into<CowBytes<'a>>, signature: Option<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            public_key: public_key.into(),
+            signature,
+        }
+    }
+    pub fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+    pub fn signature(&self) -> Option<&[u8]> {
+        self.signature.as_deref()
+    }
+}
+
+/// Request the changes of an identity change history which a client doesn't know yet.
+/// The client describes the history it knows by its length and the identifier of its last change
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct HistoryDeltaRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<5471906>,
+    #[b(1)] identity_id: CowStr<'a>,
+    #[n(2)] known_length: u64,
+    /// Hex-encoded identifier of the last known change, required when `known_length` is not 0
+    #[b(3)] last_change_id: Option<CowStr<'a>>,
+}
+
+impl<'a> HistoryDeltaRequest<'a> {
+    pub fn new(identity_id: impl Into<CowStr<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity_id: identity_id.into(),
+            known_length: 0,
+            last_change_id: None,
+        }
+    }
+    pub fn with_known_history(
+        mut self,
+        known_length: u64,
+        last_change_id: impl Into<CowStr<'a>>,
+    ) -> Self {
+        self.known_length = known_length;
+        self.last_change_id = Some(last_change_id.into());
+        self
+    }
+    pub fn identity_id(&self) -> &str {
+        &self.identity_id
+    }
+    pub fn known_length(&self) -> u64 {
+        self.known_length
+    }
+    pub fn last_change_id(&self) -> Option<&str> {
+        self.last_change_id.as_deref()
+    }
+}
+
+/// Changes missing from the history known by the client. The changes are only returned when
+/// the current history is newer than the known history, and must be imported with
+/// `IdentityChangeHistory::import_delta`
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct HistoryDeltaResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<1958343>,
+    #[n(1)] comparison: IdentityHistoryComparison,
+    #[n(2)] current_length: u64,
+    #[b(3)] changes: Option<CowBytes<'a>>,
+}
+
+impl<'a> HistoryDeltaResponse<'a> {
+    pub fn new(comparison: IdentityHistoryComparison, current_length: u64) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            comparison,
+            current_length,
+            changes: None,
+        }
+    }
+    pub fn with_changes(mut self, changes: impl Into<CowBytes<'a>>) -> Self {
+        self.changes = Some(changes.into());
+        self
+    }
+    pub fn comparison(&self) -> &IdentityHistoryComparison {
+        &self.comparison
+    }
+    pub fn current_length(&self) -> u64 {
+        self.current_length
+    }
+    pub fn changes(&self) -> Option<&[u8]> {
+        self.changes.as_deref()
+    }
+}
+
+/// Availability of a vault, as probed by the identity service
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct VaultPingResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<4127583>,
+    #[b(1)] vault_name: CowStr<'a>,
+    #[n(2)] available: bool,
+    #[n(3)] latency_ms: u64,
+    #[b(4)] error: Option<CowStr<'a>>,
+}
+
+impl<'a> VaultPingResponse<'a> {
+    pub fn new(vault_name: impl Into<CowStr<'a>>, available: bool, latency_ms: u64) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            vault_name: vault_name.into(),
+            available,
+            latency_ms,
+            error: None,
+        }
+    }
+    pub fn with_error(mut self, error: impl Into<CowStr<'a>>) -> Self {
+        self.error = Some(error.into());
+        self
+    }
+    pub fn vault_name(&self) -> &str {
+        &self.vault_name
+    }
+    pub fn available(&self) -> bool {
+        self.available
+    }
+    /// Round-trip time of the probe, in milliseconds
+    pub fn latency_ms(&self) -> u64 {
+        self.latency_ms
+    }
+    /// Reason why the vault is not available
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+}
+
+/// Open a threshold signature session, where `threshold` of the `signers` must sign `data`.
+/// When `identity_name` is set, the service contributes the partial signature of this
+/// local identity, which must be one of the signers
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct BeginThresholdSignRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6627190>,
+    #[b(1)] data: CowBytes<'a>,
+    #[b(2)] signers: Vec<CowBytes<'a>>,
+    #[n(3)] threshold: u64,
+    #[b(4)] identity_name: Option<CowStr<'a>>,
+    #[b(5)] vault_name: Option<CowStr<'a>>,
+}
+
+impl<'a> BeginThresholdSignRequest<'a> {
+    pub fn new(data: impl Into<CowBytes<'a>>, signers: Vec<CowBytes<'a>>, threshold: u64) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            data: data.into(),
+            signers,
+            threshold,
+            identity_name: None,
+            vault_name: None,
+        }
+    }
+    pub fn with_identity_name(mut self, identity_name: impl Into<CowStr<'a>>) -> Self {
+        self.identity_name = Some(identity_name.into());
+        self
+    }
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+    pub fn signers(&self) -> &[CowBytes<'a>] {
+        &self.signers
+    }
+    pub fn threshold(&self) -> u64 {
+        self.threshold
+    }
+    pub fn identity_name(&self) -> Option<&str> {
+        self.identity_name.as_deref()
+    }
+    pub fn vault_name(&self) -> Option<String> {
+        self.vault_name.as_ref().map(|x| x.to_string())
+    }
+}
+
+/// Partial signature of the co-signer at index `signer_index` in the signers of a session
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SubmitPartialRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<2285406>,
+    #[b(1)] session_id: CowStr<'a>,
+    #[n(2)] signer_index: u64,
+    #[b(3)] signature: CowBytes<'a>,
+}
+
+impl<'a> SubmitPartialRequest<'a> {
+    pub fn new(
+        session_id: impl Into<CowStr<'a>>,
+        signer_index: u64,
+        signature: impl Into<CowBytes<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            session_id: session_id.into(),
+            signer_index,
+            signature: signature.into(),
+        }
+    }
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+    pub fn signer_index(&self) -> u64 {
+        self.signer_index
+    }
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SubmitPartialResponse {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<8390251>,
+    #[n(1)] collected: u64,
+    #[n(2)] threshold: u64,
+}
+
+impl SubmitPartialResponse {
+    pub fn new(collected: u64, threshold: u64) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            collected,
+            threshold,
+        }
+    }
+    /// Number of partial signatures collected so far
+    pub fn collected(&self) -> u64 {
+        self.collected
+    }
+    /// Number of partial signatures needed to combine them
+    pub fn threshold(&self) -> u64 {
+        self.threshold
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CombineRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<4702968>,
+    #[b(1)] session_id: CowStr<'a>,
+}
+
+impl<'a> CombineRequest<'a> {
+    pub fn new(session_id: impl Into<CowStr<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            session_id: session_id.into(),
+        }
+    }
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+}
+
+/// Signatures of a payload by at least `threshold` distinct co-signers of a session.
+/// Each partial signature is verified with the identity of its co-signer
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ThresholdSignature<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<5531477>,
+    #[n(1)] threshold: u64,
+    #[b(2)] partials: Vec<PartialSignature<'a>>,
+}
+
+impl<'a> ThresholdSignature<'a> {
+    pub fn new(threshold: u64, partials: Vec<PartialSignature<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            threshold,
+            partials,
+        }
+    }
+    pub fn threshold(&self) -> u64 {
+        self.threshold
+    }
+    pub fn partials(&self) -> &[PartialSignature<'a>] {
+        &self.partials
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PartialSignature<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<1846620>,
+    /// Index of the co-signer in the signers of the session
+    #[n(1)] signer_index: u64,
+    #[b(2)] identity_id: CowStr<'a>,
+    #[b(3)] signature: CowBytes<'a>,
+}
+
+impl<'a> PartialSignature<'a> {
+    pub fn new(
+        signer_index: u64,
+        identity_id: impl Into<CowStr<'a>>,
+        signature: impl Into<CowBytes<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            signer_index,
+            identity_id: identity_id.into(),
+            signature: signature.into(),
+        }
+    }
+    pub fn signer_index(&self) -> u64 {
+        self.signer_index
+    }
+    pub fn identity_id(&self) -> &str {
+        &self.identity_id
+    }
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+}
+
+/// Purge the signing sessions which have not received any message for at least
+/// `idle_for_secs` seconds. All the sessions are purged when `idle_for_secs` is 0
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PurgeSessionsRequest {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<7729314>,
+    #[n(1)] idle_for_secs: u64,
+}
+
+impl PurgeSessionsRequest {
+    pub fn new(idle_for_secs: u64) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            idle_for_secs,
+        }
+    }
+    pub fn idle_for_secs(&self) -> u64 {
+        self.idle_for_secs
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PurgeSessionsResponse {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<3094857>,
+    #[n(1)] purged: u64,
+}
+
+impl PurgeSessionsResponse {
+    pub fn new(purged: u64) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            purged,
+        }
+    }
+    /// Number of streaming and threshold signing sessions which were purged
+    pub fn purged(&self) -> u64 {
+        self.purged
+    }
+}
+
+/// Verify a signature with the public key of a signer published by the identity service
+/// of another node, acting as a directory
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct VerifyRemoteSignatureRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6318027>,
+    #[b(1)] directory_route: CowStr<'a>,
+    #[b(2)] signer_name: CowStr<'a>,
+    #[b(3)] data: CowBytes<'a>,
+    #[b(4)] signature: CowBytes<'a>,
+}
+
+impl<'a> VerifyRemoteSignatureRequest<'a> {
+    pub fn new(
+        directory_route: impl Into<CowStr<'a>>,
+        signer_name: impl Into<CowStr<'a>>,
+        data: impl Into<CowBytes<'a>>,
+        signature: impl Into<CowBytes<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            directory_route: directory_route.into(),
+            signer_name: signer_name.into(),
+            data: data.into(),
+            signature: signature.into(),
+        }
+    }
+    /// Route to the identity service of the directory, for example `1#127.0.0.1:4000 => identity_service`
+    pub fn directory_route(&self) -> &str {
+        &self.directory_route
+    }
+    /// Name of the signer identity on the directory
+    pub fn signer_name(&self) -> &str {
+        &self.signer_name
+    }
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+}
+
+/// Timestamp a digest with an identity given by name.
+/// The service asserts the current time of its clock and signs it together with the digest
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TimestampRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<8861504>,
+    #[b(1)] identity: CowStr<'a>,
+    #[b(2)] digest: CowBytes<'a>,
+    #[b(3)] vault_name: Option<CowStr<'a>>,
+}
+
+impl<'a> TimestampRequest<'a> {
+    pub fn new(identity: impl Into<CowStr<'a>>, digest: impl Into<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity: identity.into(),
+            digest: digest.into(),
+            vault_name: None,
+        }
+    }
+    pub fn with_vault_name(mut self, vault_name: impl Into<CowStr<'a>>) -> Self {
+        self.vault_name = Some(vault_name.into());
+        self
+    }
+    pub fn identity(&self) -> &str {
+        &self.identity
+    }
+    pub fn digest(&self) -> &[u8] {
+        &self.digest
+    }
+    pub fn vault_name(&self) -> Option<String> {
+        self.vault_name.as_ref().map(|x| x.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TimestampResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<2930716>,
+    #[b(1)] token: TimestampToken<'a>,
+}
+
+impl<'a> TimestampResponse<'a> {
+    pub fn new(token: TimestampToken<'a>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            token,
+        }
+    }
+    pub fn token(&self) -> &TimestampToken<'a> {
+        &self.token
+    }
+}
+
+/// A statement, signed by a timestamping identity, that a digest existed at a given time.
+/// The timestamp token data is signed by the timestamping identity
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TimestampToken<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<4496218>,
+    #[b(1)] data: CowBytes<'a>,
+    #[b(2)] signature: CowBytes<'a>,
+}
+
+impl<'a> TimestampToken<'a> {
+    pub fn new(data: impl Into<CowBytes<'a>>, signature: impl Into<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            data: data.into(),
+            signature: signature.into(),
+        }
+    }
+    /// CBOR-encoded [`TimestampTokenData`]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TimestampTokenData<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<7052841>,
+    #[b(1)] signer: CowStr<'a>,
+    #[b(2)] digest: CowBytes<'a>,
+    #[n(3)] time: Timestamp,
+    #[b(4)] nonce: CowBytes<'a>,
+}
+
+impl<'a> TimestampTokenData<'a> {
+    pub fn new(
+        signer: impl Into<CowStr<'a>>,
+        digest: impl Into<CowBytes<'a>>,
+        time: Timestamp,
+        nonce: impl Into<CowBytes<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            signer: signer.into(),
+            digest: digest.into(),
+            time,
+            nonce: nonce.into(),
+        }
+    }
+    /// Identifier of the timestamping identity
+    pub fn signer(&self) -> &str {
+        &self.signer
+    }
+    pub fn digest(&self) -> &[u8] {
+        &self.digest
+    }
+    /// Time asserted by the clock of the timestamping node
+    pub fn time(&self) -> Timestamp {
+        self.time
+    }
+    /// Random value making every token unique, even for the same digest and time
+    pub fn nonce(&self) -> &[u8] {
+        &self.nonce
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct VerifyTimestampRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<3318570>,
+    #[b(1)] signer: CowBytes<'a>,
+    #[b(2)] digest: CowBytes<'a>,
+    #[b(3)] token: TimestampToken<'a>,
+}
+
+impl<'a> VerifyTimestampRequest<'a> {
+    /// Verify a timestamp token given the exported timestamping identity and the digest
+    pub fn new(
+        signer: impl Into<CowBytes<'a>>,
+        digest: impl Into<CowBytes<'a>>,
+        token: TimestampToken<'a>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            signer: signer.into(),
+            digest: digest.into(),
+            token,
+        }
+    }
+    pub fn signer(&self) -> &[u8] {
+        &self.signer
+    }
+    pub fn digest(&self) -> &[u8] {
+        &self.digest
+    }
+    pub fn token(&self) -> &TimestampToken<'a> {
+        &self.token
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct VerifyTimestampResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6104953>,
+    #[n(1)] verified: bool,
+    #[n(2)] failure_reason: Option<TimestampFailureReason>,
+    #[b(3)] signer: Option<CowStr<'a>>,
+    #[n(4)] time: Option<Timestamp>,
+}
+
+impl<'a> VerifyTimestampResponse<'a> {
+    pub fn new(signer: impl Into<CowStr<'a>>, time: Timestamp) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            verified: true,
+            failure_reason: None,
+            signer: Some(signer.into()),
+            time: Some(time),
+        }
+    }
+    pub fn failed(reason: TimestampFailureReason) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            verified: false,
+            failure_reason: Some(reason),
+            signer: None,
+            time: None,
+        }
+    }
+    pub fn verified(&self) -> bool {
+        self.verified
+    }
+    pub fn failure_reason(&self) -> Option<TimestampFailureReason> {
+        self.failure_reason
+    }
+    /// Identifier of the timestamping identity, when the token is valid
+    pub fn signer(&self) -> Option<&str> {
+        self.signer.as_deref()
+    }
+    /// Time asserted by the timestamping identity, when the token is valid
+    pub fn time(&self) -> Option<Timestamp> {
+        self.time
+    }
+}
+
+#[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum TimestampFailureReason {
+    /// The timestamp token data can't be decoded
+    #[n(0)] Malformed,
+    /// The timestamp token was not signed by the signer
+    #[n(1)] InvalidSignature,
+    /// The timestamp token is about another digest
+    #[n(2)] DigestMismatch,
+}
+
+/// Sign the root of the Merkle tree built over some leaf digests.
+/// The tree hashing scheme is described in the `merkle_tree` module
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SignMerkleRootRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6158220>,
+    #[b(1)] identity: CowBytes<'a>,
+    #[b(2)] leaves: Vec<CowBytes<'a>>,
+    #[b(3)] vault_name: Option<CowStr<'a>>,
+}
+
+impl<'a> SignMerkleRootRequest<'a> {
+    pub fn new(identity: impl Into<CowBytes<'a>>, leaves: Vec<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity: identity.into(),
+            leaves,
+            vault_name: None,
+        }
+    }
+    pub fn with_vault_name(mut self, vault_name: impl Into<CowStr<'a>>) -> Self {
+        self.vault_name = Some(vault_name.into());
+        self
+    }
+    pub fn identity(&self) -> &[u8] {
+        &self.identity
+    }
+    pub fn leaves(&self) -> &[CowBytes<'a>] {
+        &self.leaves
+    }
+    pub fn vault_name(&self) -> Option<String> {
+        self.vault_name.as_ref().map(|x| x.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SignMerkleRootResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<9043317>,
+    #[b(1)] signed_root: SignedMerkleRoot<'a>,
+    /// Hashes of the nodes of the tree, level by level, from the leaves to the root.
+    /// They are needed to generate the inclusion proofs of the leaves
+    #[b(2)] levels: Vec<Vec<CowBytes<'a>>>,
+}
+
+impl<'a> SignMerkleRootResponse<'a> {
+    pub fn new(signed_root: SignedMerkleRoot<'a>, levels: Vec<Vec<CowBytes<'a>>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            signed_root,
+            levels,
+        }
+    }
+    pub fn signed_root(&self) -> &SignedMerkleRoot<'a> {
+        &self.signed_root
+    }
+    pub fn levels(&self) -> &[Vec<CowBytes<'a>>] {
+        &self.levels
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SignedMerkleRoot<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<2746681>,
+    #[b(1)] data: CowBytes<'a>,
+    #[b(2)] signature: CowBytes<'a>,
+}
+
+impl<'a> SignedMerkleRoot<'a> {
+    pub fn new(data: impl Into<CowBytes<'a>>, signature: impl Into<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            data: data.into(),
+            signature: signature.into(),
+        }
+    }
+    /// CBOR-encoded [`MerkleRootData`]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct MerkleRootData<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<5531094>,
+    #[b(1)] signer: CowStr<'a>,
+    #[b(2)] root: CowBytes<'a>,
+    /// Number of leaves of the tree
+    #[n(3)] tree_size: u64,
+}
+
+impl<'a> MerkleRootData<'a> {
+    pub fn new(
+        signer: impl Into<CowStr<'a>>,
+        root: impl Into<CowBytes<'a>>,
+        tree_size: u64,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            signer: signer.into(),
+            root: root.into(),
+            tree_size,
+        }
+    }
+    pub fn signer(&self) -> &str {
+        &self.signer
+    }
+    pub fn root(&self) -> &[u8] {
+        &self.root
+    }
+    pub fn tree_size(&self) -> u64 {
+        self.tree_size
+    }
+}
+
+/// Verify that a leaf digest is included in a tree whose root was signed by an identity
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct VerifyInclusionProofRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<7380452>,
+    #[b(1)] signer: CowBytes<'a>,
+    #[b(2)] signed_root: SignedMerkleRoot<'a>,
+    #[b(3)] leaf: CowBytes<'a>,
+    /// Index of the leaf in the tree
+    #[n(4)] index: u64,
+    /// Hashes of the siblings of the nodes on the path from the leaf to the root
+    #[b(5)] siblings: Vec<CowBytes<'a>>,
+}
+
+impl<'a> VerifyInclusionProofRequest<'a> {
+    pub fn new(
+        signer: impl Into<CowBytes<'a>>,
+        signed_root: SignedMerkleRoot<'a>,
+        leaf: impl Into<CowBytes<'a>>,
+        index: u64,
+        siblings: Vec<CowBytes<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            signer: signer.into(),
+            signed_root,
+            leaf: leaf.into(),
+            index,
+            siblings,
+        }
+    }
+    pub fn signer(&self) -> &[u8] {
+        &self.signer
+    }
+    pub fn signed_root(&self) -> &SignedMerkleRoot<'a> {
+        &self.signed_root
+    }
+    pub fn leaf(&self) -> &[u8] {
+        &self.leaf
+    }
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+    pub fn siblings(&self) -> &[CowBytes<'a>] {
+        &self.siblings
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct VerifyInclusionProofResponse {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<3917726>,
+    #[n(1)] verified: bool,
+    #[n(2)] failure_reason: Option<InclusionProofFailureReason>,
+}
+
+impl VerifyInclusionProofResponse {
+    pub fn new() -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            verified: true,
+            failure_reason: None,
+        }
+    }
+    pub fn failed(reason: InclusionProofFailureReason) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            verified: false,
+            failure_reason: Some(reason),
+        }
+    }
+    pub fn verified(&self) -> bool {
+        self.verified
+    }
+    pub fn failure_reason(&self) -> Option<InclusionProofFailureReason> {
+        self.failure_reason
+    }
+}
+
+impl Default for VerifyInclusionProofResponse {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum InclusionProofFailureReason {
+    /// The signed root data can't be decoded
+    #[n(0)] Malformed,
+    /// The root was not signed by the signer
+    #[n(1)] InvalidSignature,
+    /// The proof doesn't lead from the leaf to the signed root
+    #[n(2)] NotIncluded,
+}
+
+/// List the signature schemes which could have produced a signature
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SignatureCandidatesRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6402917>,
+    #[b(1)] signature: CowBytes<'a>,
+}
+
+impl<'a> SignatureCandidatesRequest<'a> {
+    pub fn new(signature: impl Into<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            signature: signature.into(),
+        }
+    }
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SignatureCandidatesResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<3158840>,
+    /// Length of the signature, in bytes
+    #[n(1)] length: u64,
+    /// Schemes consistent with the signature, the most likely first. Several candidates
+    /// mean that the signature alone doesn't tell which scheme produced it
+    #[b(2)] candidates: Vec<SignatureCandidate<'a>>,
+}
+
+impl<'a> SignatureCandidatesResponse<'a> {
+    pub fn new(length: u64, candidates: Vec<SignatureCandidate<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            length,
+            candidates,
+        }
+    }
+    pub fn length(&self) -> u64 {
+        self.length
+    }
+    pub fn candidates(&self) -> &[SignatureCandidate<'a>] {
+        &self.candidates
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SignatureCandidate<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<8824073>,
+    #[n(1)] scheme: SignatureScheme,
+    #[n(2)] confidence: CandidateConfidence,
+    /// Why the signature is consistent with the scheme
+    #[b(3)] note: CowStr<'a>,
+}
+
+impl<'a> SignatureCandidate<'a> {
+    pub fn new(
+        scheme: SignatureScheme,
+        confidence: CandidateConfidence,
+        note: impl Into<CowStr<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            scheme,
+            confidence,
+            note: note.into(),
+        }
+    }
+    pub fn scheme(&self) -> SignatureScheme {
+        self.scheme
+    }
+    pub fn confidence(&self) -> CandidateConfidence {
+        self.confidence
+    }
+    pub fn note(&self) -> &str {
+        &self.note
+    }
+}
+
+#[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum SignatureScheme {
+    /// EdDSA over Curve25519, encoded as 64 bytes
+    #[n(0)] Ed25519,
+    /// ECDSA over NIST P-256, encoded as the 32-byte r and s values
+    #[n(1)] EcdsaP256Raw,
+    /// ECDSA over NIST P-256, encoded as a DER sequence
+    #[n(2)] EcdsaP256Der,
+}
+
+#[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum CandidateConfidence {
+    /// The structure of the signature is specific to the scheme
+    #[n(0)] Likely,
+    /// The signature is consistent with the scheme, and with other schemes
+    #[n(1)] Possible,
+}
+
+/// Export all the named identities of the node in a bundle signed by an admin identity.
+/// Only the change histories are exported: the secret keys stay in the vaults
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct StoreExportRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<5746391>,
+    #[b(1)] admin: CowStr<'a>,
+    #[b(2)] vault_name: Option<CowStr<'a>>,
+}
+
+impl<'a> StoreExportRequest<'a> {
+    pub fn new(admin: impl Into<CowStr<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            admin: admin.into(),
+            vault_name: None,
+        }
+    }
+    pub fn with_vault_name(mut self, vault_name: impl Into<CowStr<'a>>) -> Self {
+        self.vault_name = Some(vault_name.into());
+        self
+    }
+    /// Name of the identity signing the bundle
+    pub fn admin(&self) -> &str {
+        &self.admin
+    }
+    pub fn vault_name(&self) -> Option<String> {
+        self.vault_name.as_ref().map(|x| x.to_string())
+    }
+}
+
+/// The named identities of a node. The bundle data is signed by an admin identity
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct IdentitiesBundle<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<2067735>,
+    #[b(1)] data: CowBytes<'a>,
+    #[b(2)] signature: CowBytes<'a>,
+}
+
+impl<'a> IdentitiesBundle<'a> {
+    pub fn new(data: impl Into<CowBytes<'a>>, signature: impl Into<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            data: data.into(),
+            signature: signature.into(),
+        }
+    }
+    /// CBOR-encoded [`IdentitiesBundleData`]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct IdentitiesBundleData<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<9301582>,
+    #[b(1)] signer: CowStr<'a>,
+    #[n(2)] exported_at: Timestamp,
+    #[b(3)] identities: Vec<BundledIdentity<'a>>,
+}
+
+impl<'a> IdentitiesBundleData<'a> {
+    pub fn new(
+        signer: impl Into<CowStr<'a>>,
+        exported_at: Timestamp,
+        identities: Vec<BundledIdentity<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            signer: signer.into(),
+            exported_at,
+            identities,
+        }
+    }
+    /// Identifier of the admin identity which signed the bundle
+    pub fn signer(&self) -> &str {
+        &self.signer
+    }
+    pub fn exported_at(&self) -> Timestamp {
+        self.exported_at
+    }
+    pub fn identities(&self) -> &[BundledIdentity<'a>] {
+        &self.identities
+    }
+    pub fn into_owned(self) -> IdentitiesBundleData<'static> {
+        IdentitiesBundleData {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            signer: self.signer.to_string().into(),
+            exported_at: self.exported_at,
+            identities: self
+                .identities
+                .into_iter()
+                .map(|i| BundledIdentity::new(i.name.to_string(), i.identity.to_vec()))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct BundledIdentity<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<4483106>,
+    #[b(1)] name: CowStr<'a>,
+    #[b(2)] identity: CowBytes<'a>,
+}
+
+impl<'a> BundledIdentity<'a> {
+    pub fn new(name: impl Into<CowStr<'a>>, identity: impl Into<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            name: name.into(),
+            identity: identity.into(),
+        }
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Exported change history of the identity
+    pub fn identity(&self) -> &[u8] {
+        &self.identity
+    }
+}
+
+/// What to do when an imported identity has the name of another identity of the node
+#[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum NameCollisionPolicy {
+    /// Reject the whole import
+    #[n(0)] Fail,
+    /// Keep the identity of the node and don't import the other one
+    #[n(1)] Skip,
+    /// Give the name to the imported identity
+    #[n(2)] Overwrite,
+}
+
+/// Import the identities of a bundle signed by an admin identity
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct StoreImportRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6759024>,
+    #[b(1)] admin: CowBytes<'a>,
+    #[b(2)] bundle: IdentitiesBundle<'a>,
+    #[n(3)] on_collision: NameCollisionPolicy,
+}
+
+impl<'a> StoreImportRequest<'a> {
+    /// Import a bundle given the exported admin identity trusted to sign it.
+    /// The import fails if an imported name is already used by another identity
+    pub fn new(admin: impl Into<CowBytes<'a>>, bundle: IdentitiesBundle<'a>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            admin: admin.into(),
+            bundle,
+            on_collision: NameCollisionPolicy::Fail,
+        }
+    }
+    pub fn with_collision_policy(mut self, on_collision: NameCollisionPolicy) -> Self {
+        self.on_collision = on_collision;
+        self
+    }
+    pub fn admin(&self) -> &[u8] {
+        &self.admin
+    }
+    pub fn bundle(&self) -> &IdentitiesBundle<'a> {
+        &self.bundle
+    }
+    pub fn on_collision(&self) -> NameCollisionPolicy {
+        self.on_collision
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct StoreImportResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<1183649>,
+    #[b(1)] imported: Vec<CowStr<'a>>,
+    #[b(2)] skipped: Vec<CowStr<'a>>,
+}
+
+impl<'a> StoreImportResponse<'a> {
+    pub fn new(imported: Vec<CowStr<'a>>, skipped: Vec<CowStr<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            imported,
+            skipped,
+        }
+    }
+    /// Names of the imported identities
+    pub fn imported(&self) -> Vec<String> {
+        self.imported.iter().map(|x| x.to_string()).collect()
+    }
+    /// Names of the identities which were not imported because of a name collision or
+    /// of a conflicting change history
+    pub fn skipped(&self) -> Vec<String> {
+        self.skipped.iter().map(|x| x.to_string()).collect()
+    }
+}
+
+/// Data and its signature
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SignedData<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<3859027>,
+    #[b(1)] data: CowBytes<'a>,
+    #[b(2)] signature: CowBytes<'a>,
+}
+
+impl<'a> SignedData<'a> {
+    pub fn new(data: impl Into<CowBytes<'a>>, signature: impl Into<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            data: data.into(),
+            signature: signature.into(),
+        }
+    }
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+}
+
+/// Find out if two signatures were created by the same identity, among candidate identities
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SameSignerRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<5172608>,
+    #[b(1)] first: SignedData<'a>,
+    #[b(2)] second: SignedData<'a>,
+    #[b(3)] candidates: Vec<CowBytes<'a>>,
+}
+
+impl<'a> SameSignerRequest<'a> {
+    /// `candidates` are the exported identities which may have created the signatures
+    pub fn new(
+        first: SignedData<'a>,
+        second: SignedData<'a>,
+        candidates: Vec<CowBytes<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            first,
+            second,
+            candidates,
+        }
+    }
+    pub fn first(&self) -> &SignedData<'a> {
+        &self.first
+    }
+    pub fn second(&self) -> &SignedData<'a> {
+        &self.second
+    }
+    pub fn candidates(&self) -> &[CowBytes<'a>] {
+        &self.candidates
+    }
+}
+
+#[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum SignerComparison {
+    /// Both signatures were created by the same candidate identity
+    #[n(0)] Same,
+    /// The signatures were created by two different candidate identities
+    #[n(1)] Different,
+    /// At least one of the signatures was not created by any of the candidate identities
+    #[n(2)] Unverifiable,
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SameSignerResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<7420395>,
+    #[n(1)] comparison: SignerComparison,
+    #[b(2)] first_signer: Option<CowStr<'a>>,
+    #[b(3)] second_signer: Option<CowStr<'a>>,
+}
+
+impl<'a> SameSignerResponse<'a> {
+    pub fn new(
+        first_signer: Option<impl Into<CowStr<'a>>>,
+        second_signer: Option<impl Into<CowStr<'a>>>,
+    ) -> Self {
+        let first_signer = first_signer.map(|x| x.into());
+        let second_signer = second_signer.map(|x| x.into());
+        let comparison = match (&first_signer, &second_signer) {
+            (Some(first), Some(second)) if first == second => SignerComparison::Same,
+            (Some(_), Some(_)) => SignerComparison::Different,
+            _ => SignerComparison::Unverifiable,
+        };
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            comparison,
+            first_signer,
+            second_signer,
+        }
+    }
+    pub fn comparison(&self) -> SignerComparison {
+        self.comparison
+    }
+    /// Identifier of the candidate identity which created the first signature, if any
+    pub fn first_signer(&self) -> Option<&str> {
+        self.first_signer.as_deref()
+    }
+    /// Identifier of the candidate identity which created the second signature, if any
+    pub fn second_signer(&self) -> Option<&str> {
+        self.second_signer.as_deref()
+    }
+}
+
+/// Create an identity whose root key is held outside of the node, for example in an HSM.
+///
+/// The node never holds the private key of such an identity: it can verify the signatures of
+/// the identity, but it can't sign for it. Signatures must be created where the key is held.
+///
+/// The creation takes two requests. The first one, without a self-signature, returns the
+/// identifier of the change which creates the root key. That identifier must be signed with the
+/// private key, and the signature sent with a second request, which creates the identity
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CreateFromPublicKeyRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<2816473>,
+    #[b(1)] key_type: CowStr<'a>,
+    #[b(2)] public_key: CowBytes<'a>,
+    #[b(3)] self_signature: Option<CowBytes<'a>>,
+    #[b(4)] name: Option<CowStr<'a>>,
+}
+
+impl<'a> CreateFromPublicKeyRequest<'a> {
+    /// Key type of the public key: "ed25519" or "p256".
+    /// A P-256 public key is expected in its uncompressed SEC1 form
+    pub fn new(key_type: impl Into<CowStr<'a>>, public_key: impl Into<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            key_type: key_type.into(),
+            public_key: public_key.into(),
+            self_signature: None,
+            name: None,
+        }
+    }
+    /// Signature of the change identifier returned by a request without self-signature
+    pub fn with_self_signature(mut self, self_signature: impl Into<CowBytes<'a>>) -> Self {
+        self.self_signature = Some(self_signature.into());
+        self
+    }
+    pub fn with_name(mut self, name: impl Into<CowStr<'a>>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+    pub fn key_type(&self) -> &str {
+        &self.key_type
+    }
+    pub fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+    pub fn self_signature(&self) -> Option<&[u8]> {
+        self.self_signature.as_deref()
+    }
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CreateFromPublicKeyResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6048192>,
+    #[b(1)] change_id: CowBytes<'a>,
+    #[b(2)] identity: Option<CowBytes<'a>>,
+    #[b(3)] identity_id: Option<CowStr<'a>>,
+}
+
+impl<'a> CreateFromPublicKeyResponse<'a> {
+    pub fn new(
+        change_id: impl Into<CowBytes<'a>>,
+        identity: impl Into<CowBytes<'a>>,
+        identity_id: impl Into<CowStr<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            change_id: change_id.into(),
+            identity: Some(identity.into()),
+            identity_id: Some(identity_id.into()),
+        }
+    }
+    /// Response to a request without self-signature: the identity is not created yet
+    pub fn unsigned(change_id: impl Into<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            change_id: change_id.into(),
+            identity: None,
+            identity_id: None,
+        }
+    }
+    /// Identifier of the change creating the root key, which must be signed with the private key
+    pub fn change_id(&self) -> &[u8] {
+        &self.change_id
+    }
+    pub fn identity(&self) -> Option<&[u8]> {
+        self.identity.as_deref()
+    }
+    pub fn identity_id(&self) -> Option<&str> {
+        self.identity_id.as_deref()
+    }
+}
+
+/// Identity the service signs its own attestations with
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ServiceIdentityResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<3375918>,
+    #[b(1)] name: CowStr<'a>,
+    #[b(2)] identity: CowBytes<'a>,
+    #[b(3)] identity_id: CowStr<'a>,
+}
+
+impl<'a> ServiceIdentityResponse<'a> {
+    pub fn new(
+        name: impl Into<CowStr<'a>>,
+        identity: impl Into<CowBytes<'a>>,
+        identity_id: impl Into<CowStr<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            name: name.into(),
+            identity: identity.into(),
+            identity_id: identity_id.into(),
+        }
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn identity(&self) -> &[u8] {
+        &self.identity
+    }
+    pub fn identity_id(&self) -> &str {
+        &self.identity_id
+    }
+}
+
+/// Number of signatures created by the root key of an identity, stored with its attributes
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct KeyUsage<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<5590362>,
+    #[b(1)] public_key: CowBytes<'a>,
+    #[n(2)] signatures: u64,
+}
+
+impl<'a> KeyUsage<'a> {
+    pub fn new(public_key: impl Into<CowBytes<'a>>, signatures: u64) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            public_key: public_key.into(),
+            signatures,
+        }
+    }
+    /// Root public key which created the signatures
+    pub fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+    pub fn signatures(&self) -> u64 {
+        self.signatures
+    }
+}
+
+/// Creation time of the root key of an identity, stored with its attributes when the key is
+/// created by the service
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct KeyCreation<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<4418273>,
+    #[b(1)] public_key: CowBytes<'a>,
+    #[n(2)] created_at: Timestamp,
+}
+
+impl<'a> KeyCreation<'a> {
+    pub fn new(public_key: impl Into<CowBytes<'a>>, created_at: Timestamp) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            public_key: public_key.into(),
+            created_at,
+        }
+    }
+    pub fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+    pub fn created_at(&self) -> Timestamp {
+        self.created_at
+    }
+}
+
+/// Evaluate a policy expression for an identity, given either by its change history or by
+/// its identifier. See `evaluate_policy` for the grammar of the expressions
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct EvaluatePolicyRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6092154>,
+    #[b(1)] identity: Option<CowBytes<'a>>,
+    #[b(2)] identity_id: Option<CowStr<'a>>,
+    #[b(3)] expression: CowStr<'a>,
+}
+
+impl<'a> EvaluatePolicyRequest<'a> {
+    pub fn new(identity: impl Into<CowBytes<'a>>, expression: impl Into<CowStr<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity: Some(identity.into()),
+            identity_id: None,
+            expression: expression.into(),
+        }
+    }
+    /// Evaluate the expression for an identity known to the node, referenced by its identifier
+    pub fn with_identity_id(
+        identity_id: impl Into<CowStr<'a>>,
+        expression: impl Into<CowStr<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity: None,
+            identity_id: Some(identity_id.into()),
+            expression: expression.into(),
+        }
+    }
+    pub fn identity(&self) -> Option<&[u8]> {
+        self.identity.as_deref()
+    }
+    pub fn identity_id(&self) -> Option<&str> {
+        self.identity_id.as_deref()
+    }
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct EvaluatePolicyResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<3870561>,
+    #[n(1)] satisfied: bool,
+    /// Text of the clauses which don't hold, in the order of the expression
+    #[b(2)] failed_clauses: Vec<CowStr<'a>>,
+}
+
+impl<'a> EvaluatePolicyResponse<'a> {
+    pub fn new(failed_clauses: Vec<CowStr<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            satisfied: failed_clauses.is_empty(),
+            failed_clauses,
+        }
+    }
+    pub fn satisfied(&self) -> bool {
+        self.satisfied
+    }
+    pub fn failed_clauses(&self) -> Vec<&str> {
+        self.failed_clauses.iter().map(|c| c.as_ref()).collect()
+    }
+}
+
+/// Groups of names of the identities store which resolve to the same identifier
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct DuplicateIdentitiesResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<5730418>,
+    #[b(1)] duplicates: Vec<DuplicateIdentities<'a>>,
+}
+
+impl<'a> DuplicateIdentitiesResponse<'a> {
+    pub fn new(duplicates: Vec<DuplicateIdentities<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            duplicates,
+        }
+    }
+    /// Groups of duplicate names, empty when every identity has a single name
+    pub fn duplicates(&self) -> &[DuplicateIdentities<'a>] {
+        &self.duplicates
+    }
+}
+
+/// The names under which the same identity is stored, sorted
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct DuplicateIdentities<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<1849263>,
+    #[b(1)] identity_id: CowStr<'a>,
+    #[b(2)] names: Vec<CowStr<'a>>,
+}
+
+impl<'a> DuplicateIdentities<'a> {
+    pub fn new(identity_id: impl Into<CowStr<'a>>, names: Vec<CowStr<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity_id: identity_id.into(),
+            names,
+        }
+    }
+    pub fn identity_id(&self) -> &str {
+        &self.identity_id
+    }
+    pub fn names(&self) -> Vec<String> {
+        self.names.iter().map(|n| n.to_string()).collect()
+    }
+}
+
+/// Metadata of a request processed by the service. The request and response bodies are
+/// never recorded
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct AuditEntry<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<8162047>,
+    #[n(1)] timestamp: Timestamp,
+    #[n(2)] method: Option<Method>,
+    /// Path of the request, without its query
+    #[b(3)] path: CowStr<'a>,
+    /// Identifier of the sender, when the request was received over a secure channel
+    #[b(4)] sender: Option<CowStr<'a>>,
+    /// Status of the response, or nothing if no response could be decoded
+    #[n(5)] status: Option<Status>,
+}
+
+impl<'a> AuditEntry<'a> {
+    pub fn new(
+        timestamp: Timestamp,
+        method: Option<Method>,
+        path: impl Into<CowStr<'a>>,
+        sender: Option<impl Into<CowStr<'a>>>,
+        status: Option<Status>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            timestamp,
+            method,
+            path: path.into(),
+            sender: sender.map(|s| s.into()),
+            status,
+        }
+    }
+    pub fn into_owned(self) -> AuditEntry<'static> {
+        AuditEntry {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            timestamp: self.timestamp,
+            method: self.method,
+            path: self.path.to_owned(),
+            sender: self.sender.map(|s| s.to_owned()),
+            status: self.status,
+        }
+    }
+    pub fn timestamp(&self) -> Timestamp {
+        self.timestamp
+    }
+    pub fn method(&self) -> Option<Method> {
+        self.method
+    }
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+    pub fn sender(&self) -> Option<&str> {
+        self.sender.as_deref()
+    }
+    pub fn status(&self) -> Option<Status> {
+        self.status
+    }
+}
+
+/// Most recent entries of the audit log, the oldest first
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct AuditLogResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<2904731>,
+    #[b(1)] entries: Vec<AuditEntry<'a>>,
+}
+
+impl<'a> AuditLogResponse<'a> {
+    pub fn new(entries: Vec<AuditEntry<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            entries,
+        }
+    }
+    pub fn entries(&self) -> &[AuditEntry<'a>] {
+        &self.entries
+    }
+}
+
+/// Distinct types of the keys used across the change history of an identity
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct KeyTypesResponse {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<4925716>,
+    #[n(1)] key_types: Vec<SecretType>,
+}
+
+impl KeyTypesResponse {
+    pub fn new(key_types: Vec<SecretType>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            key_types,
+        }
+    }
+    /// Key types, in the order in which they first appear in the change history
+    pub fn key_types(&self) -> &[SecretType] {
+        &self.key_types
+    }
 }
@@ -0,0 +1,426 @@
+use crate::identity::{IdentityServiceStore, InMemoryStore, RateLimit, VaultGroup};
+use core::time::Duration;
+use ockam::identity::IdentityIdentifier;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::sync::Arc;
+use std::path::PathBuf;
+
+/// Default maximum number of requests which can be queued or processed by an IdentityService
+pub const DEFAULT_MAX_IN_FLIGHT_REQUESTS: usize = 64;
+
+/// Default delay suggested to clients when an IdentityService is overloaded
+pub const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// Default maximum number of streaming signature sessions which can be open at the same time
+pub const DEFAULT_MAX_SIGNING_SESSIONS: usize = 16;
+
+/// Default delay after which an idle streaming signature session is discarded
+pub const DEFAULT_SIGNING_SESSION_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Default delay after which a request which is still being processed is aborted
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default delay between two runs of the task discarding the expired signing sessions
+pub const DEFAULT_SESSION_REAPER_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Default delay during which an identity fetched from a directory is cached
+pub const DEFAULT_REMOTE_IDENTITY_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Default delay after which fetching an identity from a directory fails
+pub const DEFAULT_REMOTE_IDENTITY_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default maximum number of entries kept by the audit log
+pub const DEFAULT_AUDIT_LOG_SIZE: usize = 1000;
+
+/// Default delay after which the warm-up of the identity keys is abandoned
+pub const DEFAULT_WARM_UP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default maximum number of signer identities cached to verify signatures
+pub const DEFAULT_VERIFICATION_KEY_CACHE_SIZE: usize = 256;
+
+/// Default delay after which an unattested device onboarding challenge expires
+pub const DEFAULT_CHALLENGE_TTL: Duration = Duration::from_secs(60);
+
+/// Default maximum lifetime of the credentials authorizing an identity to open a secure channel
+pub const DEFAULT_MAX_CHANNEL_CREDENTIAL_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Default lifetime of the proofs that an identity is not revoked
+pub const DEFAULT_FRESHNESS_PROOF_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Configuration options for an IdentityService
+#[derive(Debug, Clone)]
+pub struct IdentityServiceOptions {
+    max_in_flight_requests: usize,
+    retry_after: Duration,
+    max_signing_sessions: usize,
+    signing_session_timeout: Duration,
+    response_compression_threshold: Option<usize>,
+    auth_token: Option<String>,
+    request_timeout: Duration,
+    check_revocation: bool,
+    challenge_ttl: Duration,
+    session_reaper_interval: Duration,
+    remote_identity_ttl: Duration,
+    remote_identity_fetch_timeout: Duration,
+    rate_limit: Option<RateLimit>,
+    verification_key_cache_size: usize,
+    service_identity: Option<String>,
+    key_usage_limits: BTreeMap<IdentityIdentifier, u64>,
+    audit_log_size: usize,
+    audit_log_path: Option<PathBuf>,
+    warm_up_identities: Vec<String>,
+    warm_up_timeout: Duration,
+    max_channel_credential_ttl: Duration,
+    freshness_proof_ttl: Duration,
+    state_store: Arc<dyn IdentityServiceStore>,
+    vault_groups: BTreeMap<String, VaultGroup>,
+    admins: Vec<IdentityIdentifier>,
+}
+
+impl Default for IdentityServiceOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IdentityServiceOptions {
+    /// Default options
+    pub fn new() -> Self {
+        Self {
+            max_in_flight_requests: DEFAULT_MAX_IN_FLIGHT_REQUESTS,
+            retry_after: DEFAULT_RETRY_AFTER,
+            max_signing_sessions: DEFAULT_MAX_SIGNING_SESSIONS,
+            signing_session_timeout: DEFAULT_SIGNING_SESSION_TIMEOUT,
+            response_compression_threshold: None,
+            auth_token: None,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            check_revocation: false,
+            challenge_ttl: DEFAULT_CHALLENGE_TTL,
+            session_reaper_interval: DEFAULT_SESSION_REAPER_INTERVAL,
+            remote_identity_ttl: DEFAULT_REMOTE_IDENTITY_TTL,
+            remote_identity_fetch_timeout: DEFAULT_REMOTE_IDENTITY_FETCH_TIMEOUT,
+            rate_limit: None,
+            verification_key_cache_size: DEFAULT_VERIFICATION_KEY_CACHE_SIZE,
+            service_identity: None,
+            key_usage_limits: BTreeMap::new(),
+            audit_log_size: DEFAULT_AUDIT_LOG_SIZE,
+            audit_log_path: None,
+            warm_up_identities: vec![],
+            warm_up_timeout: DEFAULT_WARM_UP_TIMEOUT,
+            max_channel_credential_ttl: DEFAULT_MAX_CHANNEL_CREDENTIAL_TTL,
+            freshness_proof_ttl: DEFAULT_FRESHNESS_PROOF_TTL,
+            state_store: Arc::new(InMemoryStore::new()),
+            vault_groups: BTreeMap::new(),
+            admins: vec![],
+        }
+    }
+
+    /// Set the number of in-flight requests above which new requests
+    /// are rejected with a `ServiceUnavailable` status. The requests in flight are the ones
+    /// being processed and the ones waiting for the previous requests to be processed
+    pub fn with_max_in_flight_requests(mut self, max_in_flight_requests: usize) -> Self {
+        self.max_in_flight_requests = max_in_flight_requests;
+        self
+    }
+
+    /// Set the delay suggested to clients before retrying a rejected request
+    pub fn with_retry_after(mut self, retry_after: Duration) -> Self {
+        self.retry_after = retry_after;
+        self
+    }
+
+    /// Set the maximum number of streaming signature sessions which can be open at the same time
+    pub fn with_max_signing_sessions(mut self, max_signing_sessions: usize) -> Self {
+        self.max_signing_sessions = max_signing_sessions;
+        self
+    }
+
+    /// Set the delay after which an idle streaming signature session is discarded
+    pub fn with_signing_session_timeout(mut self, signing_session_timeout: Duration) -> Self {
+        self.signing_session_timeout = signing_session_timeout;
+        self
+    }
+
+    /// Compress the response bodies which are larger than `threshold` bytes,
+    /// when the client accepts compressed responses. Compression is disabled by default
+    pub fn with_response_compression(mut self, threshold: usize) -> Self {
+        self.response_compression_threshold = Some(threshold);
+        self
+    }
+
+    /// Require each request to carry this authentication token.
+    /// Requests with a missing or different token are rejected with an `Unauthorized` status.
+    /// No token is required by default
+    pub fn with_auth_token(mut self, auth_token: impl Into<String>) -> Self {
+        self.auth_token = Some(auth_token.into());
+        self
+    }
+
+    /// Set the delay after which a request which is still being processed, for example
+    /// by a stalled vault, is aborted and answered with a `GatewayTimeout` status
+    pub fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    /// Reject the signatures of the identities which were revoked with the `revoke_identity` action.
+    /// Revocations are not checked by default
+    pub fn with_revocation_check(mut self) -> Self {
+        self.check_revocation = true;
+        self
+    }
+
+    /// Set the delay during which a device onboarding challenge can be attested
+    pub fn with_challenge_ttl(mut self, challenge_ttl: Duration) -> Self {
+        self.challenge_ttl = challenge_ttl;
+        self
+    }
+
+    /// Set the delay between two runs of the task discarding the expired signing sessions
+    pub fn with_session_reaper_interval(mut self, session_reaper_interval: Duration) -> Self {
+        self.session_reaper_interval = session_reaper_interval;
+        self
+    }
+
+    /// Set the delay during which an identity fetched from a directory is cached
+    pub fn with_remote_identity_ttl(mut self, remote_identity_ttl: Duration) -> Self {
+        self.remote_identity_ttl = remote_identity_ttl;
+        self
+    }
+
+    /// Set the delay after which fetching an identity from a directory fails
+    pub fn with_remote_identity_fetch_timeout(
+        mut self,
+        remote_identity_fetch_timeout: Duration,
+    ) -> Self {
+        self.remote_identity_fetch_timeout = remote_identity_fetch_timeout;
+        self
+    }
+
+    /// Limit the rate of the requests of each sender identity, as authenticated by the secure
+    /// channel the requests are received on. Requests above the limit are rejected with a
+    /// `TooManyRequests` status. Requests are not limited by default
+    pub fn with_rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    /// Set the maximum number of signer identities cached to verify signatures.
+    /// The signers are not cached when the size is 0
+    pub fn with_verification_key_cache_size(mut self, verification_key_cache_size: usize) -> Self {
+        self.verification_key_cache_size = verification_key_cache_size;
+        self
+    }
+
+    /// Set the name of the identity the service signs its own attestations with, such as
+    /// timestamps, delegation tokens and endorsements. Clients fetch it with `service/identity`
+    /// to verify those attestations
+    pub fn with_service_identity(mut self, service_identity: impl Into<String>) -> Self {
+        self.service_identity = Some(service_identity.into());
+        self
+    }
+
+    /// Limit the number of signatures created by each root key of an identity.
+    /// Once a key has created `max_signatures` signatures, `create_signature` rotates it
+    /// before signing again. The counts are stored with the identity attributes, so they
+    /// persist across restarts
+    pub fn with_key_usage_limit(
+        mut self,
+        identifier: IdentityIdentifier,
+        max_signatures: u64,
+    ) -> Self {
+        self.key_usage_limits
+            .insert(identifier, max_signatures.max(1));
+        self
+    }
+
+    /// Set the maximum number of entries kept by the audit log, the oldest entries being
+    /// dropped first. The requests are not audited when the size is 0
+    pub fn with_audit_log_size(mut self, audit_log_size: usize) -> Self {
+        self.audit_log_size = audit_log_size;
+        self
+    }
+
+    /// Persist the audit log to a file, so that it is kept across restarts.
+    /// The audit log is only kept in memory by default
+    pub fn with_audit_log_path(mut self, audit_log_path: impl Into<PathBuf>) -> Self {
+        self.audit_log_path = Some(audit_log_path.into());
+        self
+    }
+
+    /// Load the keys of these identities, given by name, from the default vault when the
+    /// service is created, so that the first requests using them are not slowed down
+    pub fn with_warm_up_identities(mut self, warm_up_identities: Vec<impl Into<String>>) -> Self {
+        self.warm_up_identities = warm_up_identities.into_iter().map(|n| n.into()).collect();
+        self
+    }
+
+    /// Set the delay after which the warm-up of the identity keys is abandoned.
+    /// The service is created anyway, the remaining keys being loaded by the first requests
+    pub fn with_warm_up_timeout(mut self, warm_up_timeout: Duration) -> Self {
+        self.warm_up_timeout = warm_up_timeout;
+        self
+    }
+
+    /// Set the maximum lifetime of the credentials issued by `issue_channel_credential`
+    pub fn with_max_channel_credential_ttl(mut self, max_channel_credential_ttl: Duration) -> Self {
+        self.max_channel_credential_ttl = max_channel_credential_ttl;
+        self
+    }
+
+    /// Set the lifetime of the proofs returned by `prove_not_revoked`. Relying parties may
+    /// trust that an identity is not revoked until a proof expires
+    pub fn with_freshness_proof_ttl(mut self, freshness_proof_ttl: Duration) -> Self {
+        self.freshness_proof_ttl = freshness_proof_ttl;
+        self
+    }
+
+    /// Set the store holding the state which the service keeps between requests, such as
+    /// the used delegation tokens and the open threshold signature sessions.
+    /// The state is only kept in memory by default
+    pub fn with_state_store(mut self, state_store: Arc<dyn IdentityServiceStore>) -> Self {
+        self.state_store = state_store;
+        self
+    }
+
+    /// Declare a group of vaults holding the same keys, which `create_signature`
+    /// requests can name instead of a single vault
+    pub fn with_vault_group(mut self, name: impl Into<String>, vault_group: VaultGroup) -> Self {
+        self.vault_groups.insert(name.into(), vault_group);
+        self
+    }
+
+    /// Allow an identity to call the `admin/...` endpoints, like `admin/purge_sessions`.
+    /// The requests of an administrator must be received over a secure channel authenticating
+    /// that identity. Without administrators, the admin endpoints reject all the requests
+    pub fn with_admin(mut self, identifier: IdentityIdentifier) -> Self {
+        self.admins.push(identifier);
+        self
+    }
+
+    /// Return the maximum number of in-flight requests
+    pub fn max_in_flight_requests(&self) -> usize {
+        self.max_in_flight_requests
+    }
+
+    /// Return the delay suggested to clients before retrying a rejected request
+    pub fn retry_after(&self) -> Duration {
+        self.retry_after
+    }
+
+    /// Return the maximum number of open streaming signature sessions
+    pub fn max_signing_sessions(&self) -> usize {
+        self.max_signing_sessions
+    }
+
+    /// Return the delay after which an idle streaming signature session is discarded
+    pub fn signing_session_timeout(&self) -> Duration {
+        self.signing_session_timeout
+    }
+
+    /// Return the size above which response bodies are compressed, if compression is enabled
+    pub fn response_compression_threshold(&self) -> Option<usize> {
+        self.response_compression_threshold
+    }
+
+    /// Return the authentication token required from clients, if any
+    pub fn auth_token(&self) -> Option<&str> {
+        self.auth_token.as_deref()
+    }
+
+    /// Return the delay after which a request is aborted
+    pub fn request_timeout(&self) -> Duration {
+        self.request_timeout
+    }
+
+    /// Return true if the signatures of revoked identities are rejected
+    pub fn check_revocation(&self) -> bool {
+        self.check_revocation
+    }
+
+    /// Return the delay during which a device onboarding challenge can be attested
+    pub fn challenge_ttl(&self) -> Duration {
+        self.challenge_ttl
+    }
+
+    /// Return the delay between two runs of the task discarding the expired signing sessions
+    pub fn session_reaper_interval(&self) -> Duration {
+        self.session_reaper_interval
+    }
+
+    /// Return the delay during which an identity fetched from a directory is cached
+    pub fn remote_identity_ttl(&self) -> Duration {
+        self.remote_identity_ttl
+    }
+
+    /// Return the delay after which fetching an identity from a directory fails
+    pub fn remote_identity_fetch_timeout(&self) -> Duration {
+        self.remote_identity_fetch_timeout
+    }
+
+    /// Return the rate limit applied to each sender identity, if any
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        self.rate_limit
+    }
+
+    /// Return the maximum number of signer identities cached to verify signatures
+    pub fn verification_key_cache_size(&self) -> usize {
+        self.verification_key_cache_size
+    }
+
+    /// Return the name of the identity the service signs its own attestations with, if any
+    pub fn service_identity(&self) -> Option<&str> {
+        self.service_identity.as_deref()
+    }
+
+    /// Return the maximum number of signatures created by each root key of an identity, if any
+    pub fn key_usage_limit(&self, identifier: &IdentityIdentifier) -> Option<u64> {
+        self.key_usage_limits.get(identifier).copied()
+    }
+
+    /// Return the maximum number of entries kept by the audit log
+    pub fn audit_log_size(&self) -> usize {
+        self.audit_log_size
+    }
+
+    /// Return the file the audit log is persisted to, if any
+    pub fn audit_log_path(&self) -> Option<&PathBuf> {
+        self.audit_log_path.as_ref()
+    }
+
+    /// Return the names of the identities whose keys are loaded when the service is created
+    pub fn warm_up_identities(&self) -> &[String] {
+        &self.warm_up_identities
+    }
+
+    /// Return the delay after which the warm-up of the identity keys is abandoned
+    pub fn warm_up_timeout(&self) -> Duration {
+        self.warm_up_timeout
+    }
+
+    /// Return the maximum lifetime of the credentials authorizing an identity to open a
+    /// secure channel
+    pub fn max_channel_credential_ttl(&self) -> Duration {
+        self.max_channel_credential_ttl
+    }
+
+    /// Return the lifetime of the proofs that an identity is not revoked
+    pub fn freshness_proof_ttl(&self) -> Duration {
+        self.freshness_proof_ttl
+    }
+
+    /// Return the store holding the state which the service keeps between requests
+    pub fn state_store(&self) -> Arc<dyn IdentityServiceStore> {
+        self.state_store.clone()
+    }
+
+    /// Return the vault group with this name, if it was declared
+    pub fn vault_group(&self, name: &str) -> Option<&VaultGroup> {
+        self.vault_groups.get(name)
+    }
+
+    /// Return true if the identity can call the admin endpoints
+    pub fn is_admin(&self, identifier: &IdentityIdentifier) -> bool {
+        self.admins.contains(identifier)
+    }
+}
@@ -0,0 +1,291 @@
+//! Policy expressions evaluated by the `evaluate_policy` action.
+//!
+//! An expression is a conjunction of clauses which must all hold for an identity to satisfy it:
+//!
+//! ```text
+//! expression = clause *( "AND" clause )
+//! clause     = attribute / key-age
+//! attribute  = "has" "attribute" name [ "=" value ]
+//! key-age    = "key_age" ( "<" / "<=" / ">" / ">=" ) duration
+//! duration   = 1*DIGIT ( "s" / "m" / "h" / "d" )
+//! name       = 1*( ALPHA / DIGIT / "_" / "-" / "." / ":" / "/" )
+//! value      = name
+//! ```
+//!
+//! Keywords are case-sensitive. Tokens are separated by whitespace, which is optional around
+//! `=` and the comparison operators, so `has attribute role=admin AND key_age < 30d` is valid.
+//!
+//! `has attribute role=admin` holds if the identity has an attribute `role` whose value is
+//! exactly the UTF-8 bytes of `admin`. Without a value, the attribute only needs to exist.
+//! `key_age < 30d` holds if the current root key of the identity was created less than 30 days
+//! ago. The creation time of a key is only known for the keys created by the service, so a
+//! `key_age` clause never holds for the other keys.
+
+use core::fmt;
+use core::time::Duration;
+use ockam_core::compat::collections::BTreeMap;
+
+/// A parsed policy expression
+#[derive(Debug)]
+pub(crate) struct PolicyExpression {
+    clauses: Vec<Clause>,
+}
+
+#[derive(Debug)]
+struct Clause {
+    /// Text of the clause in the expression, returned when the clause doesn't hold
+    text: String,
+    condition: Condition,
+}
+
+#[derive(Debug)]
+enum Condition {
+    Attribute {
+        name: String,
+        value: Option<String>,
+    },
+    KeyAge {
+        comparison: Comparison,
+        age: Duration,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    fn holds(self, left: Duration, right: Duration) -> bool {
+        match self {
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Greater => left > right,
+            Comparison::GreaterOrEqual => left >= right,
+        }
+    }
+}
+
+/// Error returned for a malformed expression, pointing at the offending token
+#[derive(Debug)]
+pub(crate) struct ParseError {
+    /// Offset, in bytes, of the offending token in the expression
+    offset: usize,
+    found: String,
+    expected: &'static str,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unexpected {} at offset {}, expected {}",
+            self.found, self.offset, self.expected
+        )
+    }
+}
+
+impl PolicyExpression {
+    pub(crate) fn parse(input: &str) -> Result<Self, ParseError> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser {
+            input,
+            tokens,
+            position: 0,
+        };
+        let mut clauses = vec![parser.clause()?];
+        while parser.peek().is_some() {
+            parser.keyword("AND", "`AND`")?;
+            clauses.push(parser.clause()?);
+        }
+        Ok(Self { clauses })
+    }
+
+    /// Return the text of the clauses which don't hold for an identity having these
+    /// attributes and whose current key has this age, if it is known
+    pub(crate) fn failed_clauses(
+        &self,
+        attributes: &BTreeMap<String, Vec<u8>>,
+        key_age: Option<Duration>,
+    ) -> Vec<&str> {
+        self.clauses
+            .iter()
+            .filter(|clause| match &clause.condition {
+                Condition::Attribute { name, value } => match (attributes.get(name), value) {
+                    (Some(actual), Some(value)) => actual.as_slice() != value.as_bytes(),
+                    (Some(_), None) => false,
+                    (None, _) => true,
+                },
+                Condition::KeyAge { comparison, age } => match key_age {
+                    Some(key_age) => !comparison.holds(key_age, *age),
+                    None => true,
+                },
+            })
+            .map(|clause| clause.text.as_str())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    text: &'a str,
+    offset: usize,
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "_-.:/".contains(c)
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token<'_>>, ParseError> {
+    let mut tokens = vec![];
+    let mut chars = input.char_indices().peekable();
+    while let Some((offset, c)) = chars.next() {
+        let end = if c.is_whitespace() {
+            continue;
+        } else if c == '<' || c == '>' {
+            match chars.peek() {
+                Some((_, '=')) => {
+                    chars.next();
+                    offset + 2
+                }
+                _ => offset + 1,
+            }
+        } else if c == '=' {
+            offset + 1
+        } else if is_word_char(c) {
+            let mut end = offset + 1;
+            while let Some((next_offset, _)) = chars.next_if(|(_, c)| is_word_char(*c)) {
+                end = next_offset + 1;
+            }
+            end
+        } else {
+            return Err(ParseError {
+                offset,
+                found: format!("character `{c}`"),
+                expected: "a keyword, a name or an operator",
+            });
+        };
+        tokens.push(Token {
+            text: &input[offset..end],
+            offset,
+        });
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    tokens: Vec<Token<'a>>,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.position).copied()
+    }
+
+    fn next(&mut self, expected: &'static str) -> Result<Token<'a>, ParseError> {
+        match self.peek() {
+            Some(token) => {
+                self.position += 1;
+                Ok(token)
+            }
+            None => Err(ParseError {
+                offset: self.input.len(),
+                found: "end of expression".to_string(),
+                expected,
+            }),
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str, expected: &'static str) -> Result<Token<'a>, ParseError> {
+        let token = self.next(expected)?;
+        if token.text != keyword {
+            return Err(unexpected(token, expected));
+        }
+        Ok(token)
+    }
+
+    fn name(&mut self, expected: &'static str) -> Result<Token<'a>, ParseError> {
+        let token = self.next(expected)?;
+        if !token.text.chars().all(is_word_char) {
+            return Err(unexpected(token, expected));
+        }
+        Ok(token)
+    }
+
+    fn clause(&mut self) -> Result<Clause, ParseError> {
+        let expected = "`has attribute` or `key_age`";
+        let start = self.next(expected)?;
+        let (condition, end) = match start.text {
+            "has" => {
+                self.keyword("attribute", "`attribute`")?;
+                let name = self.name("an attribute name")?;
+                match self.peek() {
+                    Some(token) if token.text == "=" => {
+                        self.position += 1;
+                        let value = self.name("an attribute value")?;
+                        let condition = Condition::Attribute {
+                            name: name.text.to_string(),
+                            value: Some(value.text.to_string()),
+                        };
+                        (condition, value)
+                    }
+                    _ => {
+                        let condition = Condition::Attribute {
+                            name: name.text.to_string(),
+                            value: None,
+                        };
+                        (condition, name)
+                    }
+                }
+            }
+            "key_age" => {
+                let expected = "`<`, `<=`, `>` or `>=`";
+                let operator = self.next(expected)?;
+                let comparison = match operator.text {
+                    "<" => Comparison::Less,
+                    "<=" => Comparison::LessOrEqual,
+                    ">" => Comparison::Greater,
+                    ">=" => Comparison::GreaterOrEqual,
+                    _ => return Err(unexpected(operator, expected)),
+                };
+                let expected = "a duration such as `30d`, `12h`, `15m` or `60s`";
+                let duration = self.next(expected)?;
+                let age =
+                    parse_duration(duration.text).ok_or_else(|| unexpected(duration, expected))?;
+                (Condition::KeyAge { comparison, age }, duration)
+            }
+            _ => return Err(unexpected(start, expected)),
+        };
+        let text = self.input[start.offset..end.offset + end.text.len()].to_string();
+        Ok(Clause { text, condition })
+    }
+}
+
+fn unexpected(token: Token, expected: &'static str) -> ParseError {
+    ParseError {
+        offset: token.offset,
+        found: format!("token `{}`", token.text),
+        expected,
+    }
+}
+
+fn parse_duration(text: &str) -> Option<Duration> {
+    let unit = match text.chars().last()? {
+        's' => 1,
+        'm' => 60,
+        'h' => 60 * 60,
+        'd' => 24 * 60 * 60,
+        _ => return None,
+    };
+    let count = &text[..text.len() - 1];
+    if count.is_empty() || !count.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let seconds = count.parse::<u64>().ok()?.checked_mul(unit)?;
+    Some(Duration::from_secs(seconds))
+}
@@ -0,0 +1,29 @@
+//! Proofs of possession of the key of an identity.
+//!
+//! A proof of possession is a signature of a challenge chosen by the verifier. The challenge is
+//! not signed as-is, otherwise the proof would be a signature of arbitrary data chosen by the
+//! verifier, which could be presented as a signature of that data. The signed bytes are the
+//! concatenation of:
+//!
+//!  - the ASCII string [`POSSESSION_PROOF_CONTEXT`] followed by a zero byte,
+//!  - the length, in bytes, of the identifier of the prover as a 4-byte big-endian integer,
+//!  - the identifier of the prover, as text,
+//!  - the challenge.
+//!
+//! A verifier checks the proof with the `verify_signature` action, over the bytes returned by
+//! [`possession_proof_payload`].
+
+/// Domain separation string prefixing the bytes signed for a proof of possession
+pub const POSSESSION_PROOF_CONTEXT: &str = "ockam/identity/proof_of_possession/v1";
+
+/// Return the bytes signed to prove that an identity possesses its key
+pub fn possession_proof_payload(identifier: &str, challenge: &[u8]) -> Vec<u8> {
+    let mut payload =
+        Vec::with_capacity(POSSESSION_PROOF_CONTEXT.len() + 5 + identifier.len() + challenge.len());
+    payload.extend_from_slice(POSSESSION_PROOF_CONTEXT.as_bytes());
+    payload.push(0);
+    payload.extend_from_slice(&(identifier.len() as u32).to_be_bytes());
+    payload.extend_from_slice(identifier.as_bytes());
+    payload.extend_from_slice(challenge);
+    payload
+}
@@ -0,0 +1,72 @@
+//! `ockam://identity/` URIs to share a public identity.
+//!
+//! The URI is `ockam://identity/` followed by the base64url encoding, without padding, of the
+//! CBOR-encoded [`PublicIdentityResponse`] returned by the identity service at `<name>/public`.
+//! It only holds the identifier of an identity and its current root public key, so that it
+//! stays short enough to be pasted in a message or rendered as a QR code.
+
+use crate::identity::models::PublicIdentityResponse;
+use data_encoding::BASE64URL_NOPAD;
+use ockam::identity::IdentityIdentifier;
+use ockam_core::Result;
+use ockam_vault::SecretType;
+
+/// Scheme of the URIs of public identities
+pub const PUBLIC_IDENTITY_URI_SCHEME: &str = "ockam";
+
+/// Prefix of the URIs of public identities, followed by the encoded public identity
+pub const PUBLIC_IDENTITY_URI_PREFIX: &str = "ockam://identity/";
+
+/// Return the `ockam://identity/` URI of a public identity
+pub fn public_identity_uri(public_identity: &PublicIdentityResponse) -> Result<String> {
+    let encoded = BASE64URL_NOPAD.encode(&minicbor::to_vec(public_identity)?);
+    Ok(format!("{PUBLIC_IDENTITY_URI_PREFIX}{encoded}"))
+}
+
+/// Decode the public identity of an `ockam://identity/` URI, or return a description of the
+/// reason why the URI is invalid
+pub fn parse_public_identity_uri(
+    uri: &str,
+) -> core::result::Result<PublicIdentityResponse<'static>, String> {
+    let uri = uri.trim();
+    let (scheme, rest) = uri
+        .split_once("://")
+        .ok_or_else(|| format!("the URI must start with {PUBLIC_IDENTITY_URI_PREFIX}"))?;
+    if scheme != PUBLIC_IDENTITY_URI_SCHEME {
+        return Err(format!(
+            "unsupported URI scheme '{scheme}', expected '{PUBLIC_IDENTITY_URI_SCHEME}'"
+        ));
+    }
+    let encoded = rest
+        .strip_prefix("identity/")
+        .ok_or_else(|| format!("the URI must start with {PUBLIC_IDENTITY_URI_PREFIX}"))?;
+    let decoded = BASE64URL_NOPAD
+        .decode(encoded.as_bytes())
+        .map_err(|e| format!("the public identity is not valid base64url without padding: {e}"))?;
+    let public_identity: PublicIdentityResponse = minicbor::decode(&decoded)
+        .map_err(|e| format!("the URI does not contain a public identity: {e}"))?;
+    if IdentityIdentifier::try_from(public_identity.identity_id()).is_err() {
+        return Err(format!(
+            "invalid identity identifier: {}",
+            public_identity.identity_id()
+        ));
+    }
+    // P-256 public keys are DER-encoded SubjectPublicKeyInfo holding an uncompressed point
+    let expected_length = match public_identity.key_type() {
+        SecretType::Ed25519 => 32,
+        SecretType::NistP256 => 91,
+        key_type => return Err(format!("unsupported key type: {key_type}")),
+    };
+    if public_identity.public_key().len() != expected_length {
+        return Err(format!(
+            "invalid {} public key: expected {expected_length} bytes, got {}",
+            public_identity.key_type(),
+            public_identity.public_key().len()
+        ));
+    }
+    Ok(PublicIdentityResponse::new(
+        public_identity.identity_id().to_string(),
+        public_identity.key_type(),
+        public_identity.public_key().to_vec(),
+    ))
+}
@@ -0,0 +1,103 @@
+//! Rate limiting of the requests sent to an IdentityService.
+//!
+//! Each sender identity, as authenticated by the secure channel the request was received on,
+//! gets a token bucket holding up to `burst` tokens and refilled at `requests_per_second`.
+//! A request consumes a token and is rejected when the bucket is empty. A bucket which was
+//! not used for long enough to be refilled is equivalent to a new bucket, so it is discarded.
+
+use core::time::Duration;
+use ockam::identity::IdentityIdentifier;
+use ockam_core::compat::collections::BTreeMap;
+use std::time::Instant;
+
+/// Rate at which an IdentityService accepts the requests of a single sender identity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    requests_per_second: u32,
+    burst: u32,
+}
+
+impl RateLimit {
+    /// Accept `requests_per_second` requests per second on average, and up to `burst`
+    /// requests at once after a sender has been idle
+    pub fn new(requests_per_second: u32, burst: u32) -> Self {
+        Self {
+            requests_per_second: requests_per_second.max(1),
+            burst: burst.max(1),
+        }
+    }
+
+    pub fn requests_per_second(&self) -> u32 {
+        self.requests_per_second
+    }
+
+    pub fn burst(&self) -> u32 {
+        self.burst
+    }
+
+    /// Return the delay after which an empty bucket is full again
+    fn refill_delay(&self) -> Duration {
+        Duration::from_secs_f64(self.burst as f64 / self.requests_per_second as f64)
+    }
+}
+
+struct TokenBucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// The token buckets of the senders of an IdentityService
+pub(crate) struct SenderRateLimiter {
+    rate_limit: RateLimit,
+    buckets: BTreeMap<IdentityIdentifier, TokenBucket>,
+}
+
+impl SenderRateLimiter {
+    pub(crate) fn new(rate_limit: RateLimit) -> Self {
+        Self {
+            rate_limit,
+            buckets: BTreeMap::new(),
+        }
+    }
+
+    /// Consume a token from the bucket of a sender.
+    /// Return the delay after which a token is available when the bucket is empty
+    pub(crate) fn acquire(&mut self, sender: &IdentityIdentifier) -> Result<(), Duration> {
+        let now = Instant::now();
+        let RateLimit {
+            requests_per_second,
+            burst,
+        } = self.rate_limit;
+        let requests_per_second = requests_per_second as f64;
+        let bucket = self
+            .buckets
+            .entry(sender.clone())
+            .or_insert_with(|| TokenBucket {
+                tokens: burst as f64,
+                updated_at: now,
+            });
+
+        let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * requests_per_second).min(burst as f64);
+        bucket.updated_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / requests_per_second,
+            ))
+        }
+    }
+
+    /// Discard the buckets of the senders which were idle long enough for their bucket to be full.
+    /// Return the number of discarded buckets
+    pub(crate) fn remove_idle(&mut self) -> usize {
+        let refill_delay = self.rate_limit.refill_delay();
+        let before = self.buckets.len();
+        self.buckets
+            .retain(|_, bucket| bucket.updated_at.elapsed() < refill_delay);
+        before - self.buckets.len()
+    }
+}
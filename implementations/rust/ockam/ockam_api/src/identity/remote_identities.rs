@@ -0,0 +1,76 @@
+//! Identities fetched from a remote identity service.
+//!
+//! `actions/verify_remote` verifies a signature with the public key of a signer which is
+//! published by the identity service of another node, acting as a directory, at
+//! `<signer name>`. The change history of the signer is verified before its current public
+//! key is used. Fetched public keys are cached for the configured time to live, so that
+//! consecutive verifications don't query the directory again.
+
+use core::time::Duration;
+use ockam::identity::IdentityIdentifier;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_vault::PublicKey;
+use std::time::Instant;
+
+/// Public identity of a signer, as published by a directory
+#[derive(Clone)]
+pub(crate) struct RemoteIdentity {
+    identifier: IdentityIdentifier,
+    public_key: PublicKey,
+}
+
+impl RemoteIdentity {
+    pub(crate) fn new(identifier: IdentityIdentifier, public_key: PublicKey) -> Self {
+        Self {
+            identifier,
+            public_key,
+        }
+    }
+
+    pub(crate) fn identifier(&self) -> &IdentityIdentifier {
+        &self.identifier
+    }
+
+    pub(crate) fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+}
+
+/// The identities fetched by an IdentityService, keyed by directory route and signer name
+pub(crate) struct RemoteIdentities {
+    identities: BTreeMap<(String, String), (RemoteIdentity, Instant)>,
+    ttl: Duration,
+}
+
+impl RemoteIdentities {
+    pub(crate) fn new(ttl: Duration) -> Self {
+        Self {
+            identities: BTreeMap::new(),
+            ttl,
+        }
+    }
+
+    /// Return the identity fetched from a directory, unless it expired
+    pub(crate) fn get(&mut self, directory: &str, signer_name: &str) -> Option<RemoteIdentity> {
+        self.remove_expired();
+        self.identities
+            .get(&(directory.to_string(), signer_name.to_string()))
+            .map(|(identity, _)| identity.clone())
+    }
+
+    /// Cache an identity fetched from a directory
+    pub(crate) fn insert(&mut self, directory: &str, signer_name: &str, identity: RemoteIdentity) {
+        self.remove_expired();
+        self.identities.insert(
+            (directory.to_string(), signer_name.to_string()),
+            (identity, Instant::now()),
+        );
+    }
+
+    /// Discard the identities which were fetched for longer than the time to live
+    fn remove_expired(&mut self) {
+        let ttl = self.ttl;
+        self.identities
+            .retain(|_, (_, fetched_at)| fetched_at.elapsed() < ttl);
+    }
+}
@@ -0,0 +1,47 @@
+//! Signatures bound to a route.
+//!
+//! A signature can cover the route of a message in addition to its payload, so that a signed
+//! message can't be replayed on another route. The route is given as text, with addresses
+//! separated by `=>`, each address being `<transport type>#<address>` or a local address.
+//! It is canonicalized by trimming the whitespace around each address, writing the transport
+//! type of local addresses explicitly and joining the addresses with ` => `. For example
+//! `1#127.0.0.1:4000=>api` is canonicalized to `1#127.0.0.1:4000 => 0#api`.
+//!
+//! The signed bytes are the concatenation of:
+//!
+//!  - the ASCII string [`ROUTE_BINDING_CONTEXT`] followed by a zero byte,
+//!  - the length, in bytes, of the UTF-8 canonical route as a 4-byte big-endian integer,
+//!  - the UTF-8 canonical route,
+//!  - the payload, as it would be signed without binding.
+
+use ockam_core::Address;
+
+/// Domain separation string prefixing the bytes signed for a route-bound signature
+pub const ROUTE_BINDING_CONTEXT: &str = "ockam/identity/route_binding/v1";
+
+/// Return the canonical form of a route, or a description of the invalid address
+pub fn canonical_route(route: &str) -> Result<String, String> {
+    let mut addresses = vec![];
+    for address in route.split("=>").map(|a| a.trim()) {
+        if address.is_empty() {
+            return Err(format!("the route '{route}' contains an empty address"));
+        }
+        let address = address
+            .parse::<Address>()
+            .map_err(|e| format!("invalid address '{address}' in the route: {e}"))?;
+        addresses.push(address.to_string());
+    }
+    Ok(addresses.join(" => "))
+}
+
+/// Return the bytes signed to bind a payload to a canonical route
+pub fn route_bound_payload(canonical_route: &str, payload: &[u8]) -> Vec<u8> {
+    let mut bound =
+        Vec::with_capacity(ROUTE_BINDING_CONTEXT.len() + 5 + canonical_route.len() + payload.len());
+    bound.extend_from_slice(ROUTE_BINDING_CONTEXT.as_bytes());
+    bound.push(0);
+    bound.extend_from_slice(&(canonical_route.len() as u32).to_be_bytes());
+    bound.extend_from_slice(canonical_route.as_bytes());
+    bound.extend_from_slice(payload);
+    bound
+}
@@ -0,0 +1,77 @@
+//! Guess of the schemes which could have produced a signature, from its bytes alone.
+//!
+//! The signature schemes supported by Ockam produce these encodings:
+//!
+//! | scheme            | length         | structure                                      |
+//! |-------------------|----------------|------------------------------------------------|
+//! | Ed25519           | 64 bytes       | R then S, with S lower than the group order    |
+//! | P-256 ECDSA, raw  | 64 bytes       | r then s, as 32-byte big-endian integers       |
+//! | P-256 ECDSA, DER  | 8 to 72 bytes  | SEQUENCE of two INTEGERs of at most 33 bytes   |
+//!
+//! A DER signature is recognized by its structure, so it is a `Likely` candidate. The 64-byte
+//! encodings only differ by the range of their values, which doesn't tell them apart, so they
+//! are both `Possible` candidates: the key type of the signer decides. An Ed25519 candidate
+//! is discarded when S can't be lower than the group order.
+
+use crate::identity::models::{CandidateConfidence, SignatureCandidate, SignatureScheme};
+
+/// Length of the Ed25519 and raw P-256 ECDSA signatures
+const FIXED_WIDTH_SIGNATURE_LEN: usize = 64;
+
+/// Maximum length of the DER encoding of an integer lower than the P-256 group order,
+/// which is 32 bytes with a leading zero byte when the high bit is set
+const MAX_DER_P256_INTEGER_LEN: usize = 33;
+
+/// Return the signature schemes which could have produced a signature, the most likely first.
+/// The list is empty when no supported scheme produces signatures of that form
+pub fn signature_candidates(signature: &[u8]) -> Vec<SignatureCandidate<'static>> {
+    let mut candidates = vec![];
+    if is_der_p256_signature(signature) {
+        candidates.push(SignatureCandidate::new(
+            SignatureScheme::EcdsaP256Der,
+            CandidateConfidence::Likely,
+            "the signature is a DER sequence of two integers which fit the P-256 group order",
+        ));
+    }
+    if signature.len() == FIXED_WIDTH_SIGNATURE_LEN {
+        // S is encoded in little-endian, and the group order is slightly above 2^252
+        if signature[FIXED_WIDTH_SIGNATURE_LEN - 1] <= 0x10 {
+            candidates.push(SignatureCandidate::new(
+                SignatureScheme::Ed25519,
+                CandidateConfidence::Possible,
+                "64 bytes is the length of both Ed25519 and raw P-256 signatures",
+            ));
+        }
+        candidates.push(SignatureCandidate::new(
+            SignatureScheme::EcdsaP256Raw,
+            CandidateConfidence::Possible,
+            "64 bytes is the length of both Ed25519 and raw P-256 signatures",
+        ));
+    }
+    candidates
+}
+
+/// Return true if the bytes are a DER SEQUENCE of two INTEGERs, each of them fitting a P-256
+/// scalar, and nothing else
+fn is_der_p256_signature(signature: &[u8]) -> bool {
+    match signature {
+        // the sequence is short enough for its length to be encoded on one byte
+        [0x30, length, integers @ ..] if *length < 0x80 && *length as usize == integers.len() => {
+            matches!(der_integer(integers).and_then(der_integer), Some(rest) if rest.is_empty())
+        }
+        _ => false,
+    }
+}
+
+/// Parse a DER INTEGER of at most 33 bytes, and return the bytes following it
+fn der_integer(bytes: &[u8]) -> Option<&[u8]> {
+    match bytes {
+        [0x02, length, rest @ ..]
+            if (1..=MAX_DER_P256_INTEGER_LEN).contains(&(*length as usize))
+                && rest.len() >= *length as usize =>
+        {
+            Some(&rest[*length as usize..])
+        }
+        _ => None,
+    }
+}
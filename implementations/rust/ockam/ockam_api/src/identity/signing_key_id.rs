@@ -0,0 +1,18 @@
+//! Key identifiers (`kid`) of the keys creating signatures.
+//!
+//! The `kid` of a public key is the lowercase hexadecimal encoding of the SHA-256 digest of the
+//! public key as held by the vault: the 32 bytes of an Ed25519 key, or the DER-encoded
+//! SubjectPublicKeyInfo of a P-256 key. It only depends on the public key, so that a verifier
+//! holding several keys of a signer can compute their identifiers and pick the key matching a
+//! signature.
+//!
+//! This is not the `kid` of the JWK representation of a key, which designates a key by the
+//! identifier of its identity and its label.
+
+use ockam_vault::PublicKey;
+use sha2::{Digest, Sha256};
+
+/// Return the key identifier of a public key
+pub fn signing_key_id(public_key: &PublicKey) -> String {
+    hex::encode(Sha256::digest(public_key.data()))
+}
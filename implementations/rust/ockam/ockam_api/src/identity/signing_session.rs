@@ -0,0 +1,139 @@
+//! Streaming signature sessions.
+//!
+//! A payload which is too large to be sent in a single `create_signature` request can be
+//! signed in several steps:
+//!
+//!  1. `actions/begin_sign` opens a session for an identity and returns a session id.
+//!  2. `actions/sign_chunk` feeds a chunk of the payload to the session. Chunks are hashed
+//!     as they are received, so the payload is never held in memory.
+//!  3. `actions/finish_sign` closes the session and returns the signature of the
+//!     SHA-256 digest of the whole payload. That digest must be used as the signed data
+//!     when verifying the signature.
+//!
+//! A session is bound to the sender which opened it, as authenticated by the secure channel:
+//! chunks and the end of the session are only accepted from that sender, and a session opened
+//! by another sender is reported as unknown.
+//!
+//! A session which doesn't receive any message during the configured session timeout is
+//! discarded, and no more than the configured maximum number of sessions can be open at once.
+//! Expired sessions are discarded when sessions are accessed and periodically by the service,
+//! and idle sessions can be purged by an administrator with `admin/purge_sessions`. A purged
+//! session is cancelled: its next request fails as if the session had never been opened.
+
+use core::time::Duration;
+use ockam::identity::{Identity, IdentityIdentifier};
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::rand::random;
+use sha2::{Digest, Sha256};
+use std::time::Instant;
+
+/// State of an open streaming signature session
+pub(crate) struct SigningSession {
+    identity: Identity,
+    vault_name: Option<String>,
+    /// Sender of the request which opened the session, if it was authenticated
+    owner: Option<IdentityIdentifier>,
+    hasher: Sha256,
+    last_activity: Instant,
+}
+
+impl SigningSession {
+    pub(crate) fn identity(&self) -> &Identity {
+        &self.identity
+    }
+
+    pub(crate) fn vault_name(&self) -> Option<String> {
+        self.vault_name.clone()
+    }
+
+    /// Hash a new chunk of the payload
+    pub(crate) fn update(&mut self, chunk: &[u8]) {
+        self.hasher.update(chunk);
+        self.last_activity = Instant::now();
+    }
+
+    /// Return the digest of all the chunks received so far
+    pub(crate) fn finalize(self) -> Vec<u8> {
+        self.hasher.finalize().to_vec()
+    }
+}
+
+/// The open streaming signature sessions of an IdentityService, keyed by session id
+pub(crate) struct SigningSessions {
+    sessions: BTreeMap<String, SigningSession>,
+    max_sessions: usize,
+    timeout: Duration,
+}
+
+impl SigningSessions {
+    pub(crate) fn new(max_sessions: usize, timeout: Duration) -> Self {
+        Self {
+            sessions: BTreeMap::new(),
+            max_sessions,
+            timeout,
+        }
+    }
+
+    /// Open a new session for a sender and return its id.
+    /// Return None if the maximum number of open sessions is reached
+    pub(crate) fn begin(
+        &mut self,
+        identity: Identity,
+        vault_name: Option<String>,
+        owner: Option<&IdentityIdentifier>,
+    ) -> Option<String> {
+        self.remove_expired();
+        if self.sessions.len() >= self.max_sessions {
+            return None;
+        }
+        let session_id = hex::encode(random::<[u8; 16]>());
+        self.sessions.insert(
+            session_id.clone(),
+            SigningSession {
+                identity,
+                vault_name,
+                owner: owner.cloned(),
+                hasher: Sha256::new(),
+                last_activity: Instant::now(),
+            },
+        );
+        Some(session_id)
+    }
+
+    /// Return an open session, if it was opened by the sender
+    pub(crate) fn get_mut(
+        &mut self,
+        session_id: &str,
+        sender: Option<&IdentityIdentifier>,
+    ) -> Option<&mut SigningSession> {
+        self.remove_expired();
+        self.sessions
+            .get_mut(session_id)
+            .filter(|session| session.owner.as_ref() == sender)
+    }
+
+    /// Close a session opened by the sender and return its state
+    pub(crate) fn finish(
+        &mut self,
+        session_id: &str,
+        sender: Option<&IdentityIdentifier>,
+    ) -> Option<SigningSession> {
+        self.get_mut(session_id, sender)?;
+        self.sessions.remove(session_id)
+    }
+
+    /// Discard the sessions which have been idle for longer than the session timeout.
+    /// Return the number of discarded sessions
+    pub(crate) fn remove_expired(&mut self) -> usize {
+        self.purge(self.timeout)
+    }
+
+    /// Discard the sessions which have been idle for at least `idle_for`, whether or not
+    /// they expired. Return the number of discarded sessions
+    pub(crate) fn purge(&mut self, idle_for: Duration) -> usize {
+        let count = self.sessions.len();
+        self.sessions
+            .retain(|_, session| session.last_activity.elapsed() < idle_for);
+        count - self.sessions.len()
+    }
+}
@@ -0,0 +1,132 @@
+//! Storage of the state an IdentityService keeps between requests.
+//!
+//! The state which must survive a restart of the service to keep its guarantees is read and
+//! written through an [`IdentityServiceStore`]:
+//!
+//!  - `delegation_tokens`: the nonces of the delegation tokens which have already been
+//!    presented, so that a token can't be replayed after a restart. They expire with the token.
+//!  - `challenges`: the device onboarding challenges which were issued and not attested yet.
+//!    They expire after the configured challenge TTL.
+//!  - `threshold_signing_sessions`: the open threshold signature sessions, CBOR-encoded.
+//!    They expire when they stay idle for the configured session timeout.
+//!
+//! The state is kept in memory by default, see [`InMemoryStore`]. Operators can provide a
+//! durable implementation with `IdentityServiceOptions::with_state_store`.
+//!
+//! Streaming signature sessions are not stored, since the state of their digest can't be
+//! exported, and neither are the caches of decoded identities, which are rebuilt on demand.
+
+use core::fmt::Debug;
+use core::time::Duration;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::sync::Mutex;
+use ockam_core::Result;
+use std::time::Instant;
+
+/// Namespace of the nonces of the delegation tokens which were presented
+pub(crate) const DELEGATION_TOKENS: &str = "delegation_tokens";
+
+/// Namespace of the issued device onboarding challenges
+pub(crate) const CHALLENGES: &str = "challenges";
+
+/// Namespace of the open threshold signature sessions
+pub(crate) const THRESHOLD_SIGNING_SESSIONS: &str = "threshold_signing_sessions";
+
+/// A key-value store for the state of an IdentityService.
+///
+/// Values are stored under a key in a namespace, and can expire. An expired value must be
+/// treated as deleted: it is never returned, and doesn't count as an existing key.
+/// The methods are called while a request is processed, so they should return quickly.
+pub trait IdentityServiceStore: Debug + Send + Sync + 'static {
+    /// Return the value stored under a key, if any
+    fn get(&self, namespace: &str, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Store a value under a key, replacing the current value.
+    /// The value expires after `ttl`, or never if there is no TTL
+    fn put(&self, namespace: &str, key: &str, value: Vec<u8>, ttl: Option<Duration>) -> Result<()>;
+
+    /// Delete the value stored under a key. Return true if there was a value
+    fn delete(&self, namespace: &str, key: &str) -> Result<bool>;
+
+    /// Return the keys of a namespace holding a value
+    fn keys(&self, namespace: &str) -> Result<Vec<String>>;
+}
+
+struct StoredValue {
+    value: Vec<u8>,
+    expires_at: Option<Instant>,
+}
+
+impl StoredValue {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at
+            .map_or(false, |expires_at| expires_at <= now)
+    }
+}
+
+/// An IdentityServiceStore keeping the values in memory. They are lost when the node stops.
+/// Expired values are dropped when their namespace is accessed
+#[derive(Default)]
+pub struct InMemoryStore {
+    namespaces: Mutex<BTreeMap<String, BTreeMap<String, StoredValue>>>,
+}
+
+impl InMemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Debug for InMemoryStore {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("InMemoryStore").finish_non_exhaustive()
+    }
+}
+
+impl IdentityServiceStore for InMemoryStore {
+    fn get(&self, namespace: &str, key: &str) -> Result<Option<Vec<u8>>> {
+        let now = Instant::now();
+        let namespaces = self.namespaces.lock().unwrap();
+        Ok(namespaces
+            .get(namespace)
+            .and_then(|values| values.get(key))
+            .filter(|stored| !stored.is_expired(now))
+            .map(|stored| stored.value.clone()))
+    }
+
+    fn put(&self, namespace: &str, key: &str, value: Vec<u8>, ttl: Option<Duration>) -> Result<()> {
+        let now = Instant::now();
+        let mut namespaces = self.namespaces.lock().unwrap();
+        let values = namespaces.entry(namespace.to_string()).or_default();
+        values.retain(|_, stored| !stored.is_expired(now));
+        values.insert(
+            key.to_string(),
+            StoredValue {
+                value,
+                expires_at: ttl.map(|ttl| now + ttl),
+            },
+        );
+        Ok(())
+    }
+
+    fn delete(&self, namespace: &str, key: &str) -> Result<bool> {
+        let now = Instant::now();
+        let mut namespaces = self.namespaces.lock().unwrap();
+        Ok(namespaces
+            .get_mut(namespace)
+            .and_then(|values| values.remove(key))
+            .map_or(false, |stored| !stored.is_expired(now)))
+    }
+
+    fn keys(&self, namespace: &str) -> Result<Vec<String>> {
+        let now = Instant::now();
+        let mut namespaces = self.namespaces.lock().unwrap();
+        Ok(match namespaces.get_mut(namespace) {
+            Some(values) => {
+                values.retain(|_, stored| !stored.is_expired(now));
+                values.keys().cloned().collect()
+            }
+            None => vec![],
+        })
+    }
+}
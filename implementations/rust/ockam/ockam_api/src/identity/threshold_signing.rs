@@ -0,0 +1,216 @@
+//! Threshold signature sessions.
+//!
+//! A payload can be signed by M of a set of N co-signers, each co-signer holding its own
+//! identity key as a share:
+//!
+//!  1. `actions/begin_threshold_sign` opens a session for a payload, the identities of the
+//!     N co-signers and the threshold M, and returns a session id. When one of the co-signers
+//!     is an identity of the node, the service contributes its partial signature right away.
+//!  2. `actions/submit_partial` adds the partial signature of a co-signer, which is the
+//!     signature of the payload with the co-signer identity key. Partial signatures are
+//!     verified when they are submitted.
+//!  3. `actions/combine` closes the session once M partial signatures were collected and
+//!     returns a threshold signature made of those partial signatures.
+//!
+//! The supported scheme is a multi-signature: a threshold signature is valid if it contains
+//! at least M valid signatures of the payload, made by distinct co-signers of the set. It is
+//! not an aggregated Schnorr signature, as produced by FROST: the co-signers don't share
+//! a single public key, the threshold signature grows with M, and verifying it requires
+//! the identities of the co-signers.
+//!
+//! A session which doesn't receive any message during the configured session timeout is
+//! discarded, and no more than the configured maximum number of sessions can be open at once.
+//! The sessions are kept in the state store of the service, so they can outlive the service
+//! when the store is durable. The co-signers are stored as exported identities and decoded
+//! again when a partial signature is verified.
+
+use crate::identity::state_store::{IdentityServiceStore, THRESHOLD_SIGNING_SESSIONS};
+use core::time::Duration;
+use minicbor::bytes::ByteVec;
+use minicbor::{Decode, Encode};
+use ockam::identity::Identity;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::rand::random;
+use ockam_core::compat::sync::Arc;
+use ockam_core::Result;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A co-signer of a threshold signature session
+#[derive(Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub(crate) struct ThresholdSigner {
+    #[n(1)] identifier: String,
+    /// Exported change history of the co-signer identity
+    #[n(2)] identity: ByteVec,
+}
+
+impl ThresholdSigner {
+    pub(crate) fn new(identity: &Identity) -> Result<Self> {
+        Ok(Self {
+            identifier: identity.identifier().to_string(),
+            identity: ByteVec::from(identity.export()?),
+        })
+    }
+
+    pub(crate) fn identifier(&self) -> &str {
+        &self.identifier
+    }
+
+    pub(crate) fn identity(&self) -> &[u8] {
+        &self.identity
+    }
+}
+
+/// State of an open threshold signature session
+#[derive(Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub(crate) struct ThresholdSigningSession {
+    #[n(1)] data: ByteVec,
+    #[n(2)] signers: Vec<ThresholdSigner>,
+    #[n(3)] threshold: usize,
+    /// Verified partial signatures, keyed by the index of their co-signer
+    #[n(4)] partials: BTreeMap<usize, ByteVec>,
+    /// Time of the last message of the session, in milliseconds since the Unix epoch
+    #[n(5)] last_activity: u64,
+}
+
+impl ThresholdSigningSession {
+    pub(crate) fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub(crate) fn signers(&self) -> &[ThresholdSigner] {
+        &self.signers
+    }
+
+    pub(crate) fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Return the number of partial signatures collected so far
+    pub(crate) fn collected(&self) -> usize {
+        self.partials.len()
+    }
+
+    /// Record the verified partial signature of a co-signer.
+    /// A co-signer which already submitted a partial signature replaces it
+    pub(crate) fn add_partial(&mut self, signer_index: usize, signature: Vec<u8>) {
+        self.partials.insert(signer_index, ByteVec::from(signature));
+    }
+
+    /// Return the partial signatures with the index of their co-signer
+    pub(crate) fn partials(self) -> BTreeMap<usize, Vec<u8>> {
+        self.partials
+            .into_iter()
+            .map(|(signer_index, signature)| (signer_index, signature.to_vec()))
+            .collect()
+    }
+}
+
+/// The open threshold signature sessions of an IdentityService, keyed by session id
+pub(crate) struct ThresholdSigningSessions {
+    store: Arc<dyn IdentityServiceStore>,
+    max_sessions: usize,
+    timeout: Duration,
+}
+
+impl ThresholdSigningSessions {
+    pub(crate) fn new(
+        store: Arc<dyn IdentityServiceStore>,
+        max_sessions: usize,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            store,
+            max_sessions,
+            timeout,
+        }
+    }
+
+    /// Open a new session and return its id.
+    /// Return None if the maximum number of open sessions is reached
+    pub(crate) fn begin(
+        &self,
+        data: Vec<u8>,
+        signers: Vec<ThresholdSigner>,
+        threshold: usize,
+    ) -> Result<Option<String>> {
+        if self.store.keys(THRESHOLD_SIGNING_SESSIONS)?.len() >= self.max_sessions {
+            return Ok(None);
+        }
+        let session_id = hex::encode(random::<[u8; 16]>());
+        let mut session = ThresholdSigningSession {
+            data: ByteVec::from(data),
+            signers,
+            threshold,
+            partials: BTreeMap::new(),
+            last_activity: 0,
+        };
+        self.update(&session_id, &mut session)?;
+        Ok(Some(session_id))
+    }
+
+    /// Return an open session. The changes made to the session must be saved with `update`
+    pub(crate) fn get(&self, session_id: &str) -> Result<Option<ThresholdSigningSession>> {
+        match self.store.get(THRESHOLD_SIGNING_SESSIONS, session_id)? {
+            Some(session) => Ok(Some(minicbor::decode(&session)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Save the state of an open session, which restarts its timeout
+    pub(crate) fn update(
+        &self,
+        session_id: &str,
+        session: &mut ThresholdSigningSession,
+    ) -> Result<()> {
+        session.last_activity = now_millis();
+        self.store.put(
+            THRESHOLD_SIGNING_SESSIONS,
+            session_id,
+            minicbor::to_vec(&*session)?,
+            Some(self.timeout),
+        )
+    }
+
+    /// Close a session and return its state
+    pub(crate) fn finish(&self, session_id: &str) -> Result<Option<ThresholdSigningSession>> {
+        let session = self.get(session_id)?;
+        self.store.delete(THRESHOLD_SIGNING_SESSIONS, session_id)?;
+        Ok(session)
+    }
+
+    /// Discard the sessions which have been idle for longer than the session timeout.
+    /// Return the number of discarded sessions
+    pub(crate) fn remove_expired(&self) -> Result<usize> {
+        self.purge(self.timeout)
+    }
+
+    /// Discard the sessions which have been idle for at least `idle_for`, whether or not
+    /// they expired. Return the number of discarded sessions
+    pub(crate) fn purge(&self, idle_for: Duration) -> Result<usize> {
+        let now = now_millis();
+        let mut count = 0;
+        for session_id in self.store.keys(THRESHOLD_SIGNING_SESSIONS)? {
+            let is_idle = match self.get(&session_id)? {
+                Some(session) => {
+                    now.saturating_sub(session.last_activity) >= idle_for.as_millis() as u64
+                }
+                None => false,
+            };
+            if is_idle && self.store.delete(THRESHOLD_SIGNING_SESSIONS, &session_id)? {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
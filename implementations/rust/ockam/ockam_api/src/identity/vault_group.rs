@@ -0,0 +1,79 @@
+//! Vault groups.
+//!
+//! A vault group is a named list of vaults which hold the same identity keys, for example
+//! several replicas of a KMS. A `create_signature` request can name a vault group instead of
+//! a single vault: the service then tries the vaults of the group, in the order given by the
+//! group's [`VaultSelectionStrategy`], until one of them creates the signature.
+
+use ockam_core::compat::collections::BTreeMap;
+
+/// Order in which the vaults of a group are tried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VaultSelectionStrategy {
+    /// Try the vaults in the order of their configuration
+    FirstAvailable,
+    /// Start with the vault following the one which was tried first for the previous request
+    RoundRobin,
+    /// Start with the vault which was used the least by the service
+    LeastLoaded,
+}
+
+/// A named list of vaults holding the same keys
+#[derive(Debug, Clone)]
+pub struct VaultGroup {
+    vaults: Vec<String>,
+    strategy: VaultSelectionStrategy,
+}
+
+impl VaultGroup {
+    /// Create a vault group from the names of its vaults
+    pub fn new(vaults: Vec<String>, strategy: VaultSelectionStrategy) -> Self {
+        Self { vaults, strategy }
+    }
+
+    /// Return the names of the vaults of the group
+    pub fn vaults(&self) -> &[String] {
+        &self.vaults
+    }
+
+    /// Return the strategy used to select the vaults of the group
+    pub fn strategy(&self) -> VaultSelectionStrategy {
+        self.strategy
+    }
+}
+
+/// State needed by the selection strategies: the next vault of each round-robin group,
+/// and the number of signing attempts made with each vault. Failed attempts are counted
+/// so that a vault which is down doesn't stay the least loaded one
+#[derive(Default)]
+pub(crate) struct VaultSelection {
+    next_vault: BTreeMap<String, usize>,
+    attempts: BTreeMap<String, u64>,
+}
+
+impl VaultSelection {
+    /// Return the vaults of a group in the order in which they must be tried
+    pub(crate) fn order(&mut self, group_name: &str, group: &VaultGroup) -> Vec<String> {
+        let mut vaults = group.vaults().to_vec();
+        match group.strategy() {
+            VaultSelectionStrategy::FirstAvailable => {}
+            VaultSelectionStrategy::RoundRobin => {
+                let next = self.next_vault.entry(group_name.to_string()).or_insert(0);
+                if !vaults.is_empty() {
+                    vaults.rotate_left(*next % vaults.len());
+                }
+                *next = next.wrapping_add(1);
+            }
+            VaultSelectionStrategy::LeastLoaded => {
+                // the sort is stable, so equally loaded vaults keep the configuration order
+                vaults.sort_by_key(|vault| self.attempts.get(vault).copied().unwrap_or(0));
+            }
+        }
+        vaults
+    }
+
+    /// Record a signing attempt made with a vault
+    pub(crate) fn record_attempt(&mut self, vault: &str) {
+        *self.attempts.entry(vault.to_string()).or_insert(0) += 1;
+    }
+}
@@ -0,0 +1,110 @@
+//! Signer identities cached for `actions/verify_signature`.
+//!
+//! Decoding an identity verifies its whole change history, so the identities of the signers
+//! are kept once decoded. A request can then reference a cached signer by its identifier,
+//! instead of sending the full identity again. An entry is only replaced when a request presents
+//! a newer change history for the same identifier, for example after a key rotation: an older
+//! change history would otherwise bring back a rotated-out key. The least recently used entry
+//! is evicted when the cache is full.
+
+use ockam::identity::{Identity, IdentityHistoryComparison, IdentityIdentifier};
+use ockam_core::compat::collections::BTreeMap;
+use sha2::{Digest, Sha256};
+
+/// Digest of an exported change history
+pub type HistoryDigest = [u8; 32];
+
+/// Return the digest of an exported change history, which is also the digest of the
+/// fingerprints of an identity
+pub fn history_digest(exported: &[u8]) -> HistoryDigest {
+    Sha256::digest(exported).into()
+}
+
+struct CachedSigner {
+    identity: Identity,
+    digest: HistoryDigest,
+    last_used: u64,
+}
+
+/// The signer identities cached by an IdentityService, keyed by identifier
+pub(crate) struct VerificationKeyCache {
+    signers: BTreeMap<IdentityIdentifier, CachedSigner>,
+    /// Identifier of the cached signer with a given change history digest
+    digests: BTreeMap<HistoryDigest, IdentityIdentifier>,
+    capacity: usize,
+    uses: u64,
+}
+
+impl VerificationKeyCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            signers: BTreeMap::new(),
+            digests: BTreeMap::new(),
+            capacity,
+            uses: 0,
+        }
+    }
+
+    /// Return the cached identity of a signer
+    pub(crate) fn get(&mut self, identifier: &IdentityIdentifier) -> Option<Identity> {
+        self.uses += 1;
+        let uses = self.uses;
+        self.signers.get_mut(identifier).map(|signer| {
+            signer.last_used = uses;
+            signer.identity.clone()
+        })
+    }
+
+    /// Return the cached identity of a signer having a change history with this digest
+    pub(crate) fn get_by_digest(&mut self, digest: &HistoryDigest) -> Option<Identity> {
+        let identifier = self.digests.get(digest)?.clone();
+        self.get(&identifier)
+    }
+
+    /// Cache the identity of a signer, replacing the previous version of its change history.
+    /// The identity is not cached if a newer or conflicting change history is already cached
+    pub(crate) fn insert(&mut self, identity: Identity, digest: HistoryDigest) {
+        if self.capacity == 0 {
+            return;
+        }
+        let identifier = identity.identifier();
+        if let Some(cached) = self.signers.get(&identifier) {
+            if identity.compare(&cached.identity) != IdentityHistoryComparison::Newer {
+                return;
+            }
+        }
+        if !self.signers.contains_key(&identifier) && self.signers.len() >= self.capacity {
+            let least_recently_used = self
+                .signers
+                .iter()
+                .min_by_key(|(_, signer)| signer.last_used)
+                .map(|(identifier, _)| identifier.clone());
+            if let Some(least_recently_used) = least_recently_used {
+                self.remove(&least_recently_used);
+            }
+        }
+        // the previous version of the change history is not valid anymore
+        self.remove(&identifier);
+        self.digests.insert(digest, identifier.clone());
+        self.uses += 1;
+        self.signers.insert(
+            identifier,
+            CachedSigner {
+                identity,
+                digest,
+                last_used: self.uses,
+            },
+        );
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.signers.len()
+    }
+
+    /// Remove the cached identity of a signer
+    pub(crate) fn remove(&mut self, identifier: &IdentityIdentifier) {
+        if let Some(signer) = self.signers.remove(identifier) {
+            self.digests.remove(&signer.digest);
+        }
+    }
+}
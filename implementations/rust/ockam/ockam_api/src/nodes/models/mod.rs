@@ -12,4 +12,5 @@ pub mod portal;
 pub mod secure_channel;
 pub mod services;
 pub mod transport;
+pub mod trust;
 pub mod workers;
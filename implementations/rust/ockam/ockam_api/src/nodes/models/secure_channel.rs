@@ -193,15 +193,27 @@ pub struct ShowSecureChannelListenerResponse {
     #[n(0)] tag: TypeTag<9365445>,
     #[n(1)] pub addr: Address,
     #[n(2)] pub flow_control_id: FlowControlId,
+    /// Identifier of the identity accepting the secure channels, when it is known
+    #[n(3)] pub identifier: Option<String>,
+    /// Identifiers of the identities allowed to open a secure channel, `None` when any
+    /// identity is allowed
+    #[n(4)] pub authorized_identifiers: Option<Vec<String>>,
 }
 
 impl ShowSecureChannelListenerResponse {
-    pub(crate) fn new(info: &SecureChannelListenerInfo) -> Self {
+    pub(crate) fn new(
+        info: &SecureChannelListenerInfo,
+        identifier: Option<&IdentityIdentifier>,
+    ) -> Self {
         Self {
             #[cfg(feature = "tag")]
             tag: TypeTag,
             addr: info.listener().address().to_string().into(),
             flow_control_id: info.listener().flow_control_id().clone(),
+            identifier: identifier.map(|identifier| identifier.to_string()),
+            authorized_identifiers: info
+                .authorized_identifiers()
+                .map(|ids| ids.iter().map(|id| id.to_string()).collect()),
         }
     }
 }
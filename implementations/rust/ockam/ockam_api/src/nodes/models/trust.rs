@@ -0,0 +1,80 @@
+use minicbor::{Decode, Encode};
+
+use crate::nodes::models::secure_channel::ShowSecureChannelListenerResponse;
+use ockam_core::compat::collections::BTreeMap;
+#[cfg(feature = "tag")]
+use ockam_core::TypeTag;
+
+/// Response body describing everything a node trusts: the identities trusted when the node
+/// was created, its trust context, and the identities allowed by each secure channel listener
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct NodeTrust {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<3962051>,
+    /// Identifiers of the identities given to the node with `--trusted-identities`
+    /// or `--trusted-identities-file`, with their attributes
+    #[n(1)] pub trusted_identities: Vec<TrustedIdentity>,
+    #[n(2)] pub trust_context: Option<TrustContextSummary>,
+    #[n(3)] pub listeners: Vec<ShowSecureChannelListenerResponse>,
+}
+
+impl NodeTrust {
+    pub fn new(
+        trusted_identities: Vec<TrustedIdentity>,
+        trust_context: Option<TrustContextSummary>,
+        listeners: Vec<ShowSecureChannelListenerResponse>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            trusted_identities,
+            trust_context,
+            listeners,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TrustedIdentity {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<8275104>,
+    #[n(1)] pub identifier: String,
+    #[n(2)] pub attributes: BTreeMap<String, String>,
+}
+
+impl TrustedIdentity {
+    pub fn new(identifier: String, attributes: BTreeMap<String, String>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identifier,
+            attributes,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TrustContextSummary {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<5528063>,
+    #[n(1)] pub id: String,
+    /// Identifiers of the authorities of the trust context
+    #[n(2)] pub authorities: Vec<String>,
+}
+
+impl TrustContextSummary {
+    pub fn new(id: String, authorities: Vec<String>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            id,
+            authorities,
+        }
+    }
+}
@@ -2,6 +2,7 @@ use crate::nodes::service::Alias;
 use ockam::identity::IdentityIdentifier;
 use ockam::remote::RemoteForwarderInfo;
 use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::sync::{Arc, RwLock};
 use ockam_core::{Address, Route};
 use ockam_identity::{SecureChannel, SecureChannelListener};
 use std::fmt::Display;
@@ -74,16 +75,69 @@ impl SecureChannelInfo {
 #[derive(Clone)]
 pub(crate) struct SecureChannelListenerInfo {
     listener: SecureChannelListener,
+    authorized_identifiers: Option<Vec<IdentityIdentifier>>,
 }
 
 impl SecureChannelListenerInfo {
-    pub fn new(listener: SecureChannelListener) -> Self {
-        Self { listener }
+    pub fn new(
+        listener: SecureChannelListener,
+        authorized_identifiers: Option<Vec<IdentityIdentifier>>,
+    ) -> Self {
+        Self {
+            listener,
+            authorized_identifiers,
+        }
     }
 
     pub fn listener(&self) -> &SecureChannelListener {
         &self.listener
     }
+
+    /// Identifiers of the identities allowed to open a secure channel with the listener,
+    /// or `None` when any identity is allowed
+    pub fn authorized_identifiers(&self) -> Option<&[IdentityIdentifier]> {
+        self.authorized_identifiers.as_deref()
+    }
+}
+
+/// Identifiers of the identities listening for secure channels, by listener address.
+/// Clones share the same state, so that services started on a node can observe
+/// the listeners created and deleted by its node manager
+#[derive(Debug, Clone, Default)]
+pub struct ActiveSecureChannelListeners {
+    listeners: Arc<RwLock<BTreeMap<Address, IdentityIdentifier>>>,
+}
+
+impl ActiveSecureChannelListeners {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that an identity is listening for secure channels at an address
+    pub fn insert(&self, address: Address, identifier: IdentityIdentifier) {
+        self.listeners.write().unwrap().insert(address, identifier);
+    }
+
+    /// Remove the listener at an address
+    pub fn remove(&self, address: &Address) {
+        self.listeners.write().unwrap().remove(address);
+    }
+
+    /// Return the identifier of the identity listening at an address, if any
+    pub fn identifier_of(&self, address: &Address) -> Option<IdentityIdentifier> {
+        self.listeners.read().unwrap().get(address).cloned()
+    }
+
+    /// Return the addresses of the listeners of an identity
+    pub fn addresses_of(&self, identifier: &IdentityIdentifier) -> Vec<Address> {
+        self.listeners
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(_, id)| *id == identifier)
+            .map(|(address, _)| address.clone())
+            .collect()
+    }
 }
 
 #[derive(Default)]
@@ -190,6 +244,7 @@ impl OutletInfo {
 pub(crate) struct Registry {
     pub(crate) secure_channels: SecureChannelRegistry,
     pub(crate) secure_channel_listeners: BTreeMap<Address, SecureChannelListenerInfo>,
+    pub(crate) active_secure_channel_listeners: ActiveSecureChannelListeners,
     pub(crate) identity_services: BTreeMap<Address, IdentityServiceInfo>,
     pub(crate) authenticated_services: BTreeMap<Address, AuthenticatedServiceInfo>,
     pub(crate) okta_identity_provider_services: BTreeMap<Address, OktaIdentityProviderServiceInfo>,
@@ -66,6 +66,7 @@ mod policy;
 mod portals;
 mod secure_channel;
 mod transport;
+mod trust;
 
 const TARGET: &str = "ockam_api::nodemanager::service";
 
@@ -111,6 +112,7 @@ pub struct NodeManager {
     pub(crate) secure_channels: Arc<SecureChannels>,
     projects: Arc<BTreeMap<String, ProjectLookup>>,
     trust_context: Option<TrustContext>,
+    pre_trusted_identities: Option<Arc<PreTrustedIdentities>>,
     pub(crate) registry: Registry,
     sessions: Arc<Mutex<Sessions>>,
     medic: JoinHandle<Result<(), ockam_core::Error>>,
@@ -329,13 +331,14 @@ impl NodeManager {
         //TODO: fix this.  Either don't require it to be a bootstrappedidentitystore (and use the
         //trait instead),  or pass it from the general_options always.
         let vault: Arc<dyn IdentitiesVault> = node_state.config().vault().await?;
+        let pre_trusted_identities = general_options.pre_trusted_identities.map(Arc::new);
         let identities_repository: Arc<dyn IdentitiesRepository> =
-            Arc::new(match general_options.pre_trusted_identities {
+            Arc::new(match &pre_trusted_identities {
                 None => BootstrapedIdentityStore::new(
                     Arc::new(PreTrustedIdentities::new_from_string("{}")?),
                     repository.clone(),
                 ),
-                Some(f) => BootstrapedIdentityStore::new(Arc::new(f), repository.clone()),
+                Some(f) => BootstrapedIdentityStore::new(f.clone(), repository.clone()),
             });
 
         let secure_channels = SecureChannels::builder()
@@ -366,6 +369,7 @@ impl NodeManager {
             secure_channels,
             projects: Arc::new(projects_options.projects),
             trust_context: None,
+            pre_trusted_identities,
             registry: Default::default(),
             medic: {
                 let ctx = ctx.async_try_clone().await?;
@@ -636,6 +640,12 @@ impl NodeManagerWorker {
                 self.show_secure_channel_listener(req, dec).await?
             }
 
+            // ==*== Trust ==*==
+            (Get, ["node", "trust"]) => {
+                let node_manager = self.node_manager.read().await;
+                encode_request_result(node_manager.show_trust(req).await)?
+            }
+
             // ==*== Services ==*==
             (Post, ["node", "services", DefaultAddress::IDENTITY_SERVICE]) => {
                 encode_request_result(self.start_identity_service(ctx, req, dec).await)?
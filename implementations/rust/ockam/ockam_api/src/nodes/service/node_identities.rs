@@ -4,8 +4,8 @@ use ockam::identity::{IdentitiesVault, Identity};
 use ockam::Result;
 use ockam_identity::{IdentitiesRepository, IdentityIdentifier};
 
-use crate::cli_state::traits::StateDirTrait;
-use crate::cli_state::CliState;
+use crate::cli_state::traits::{StateDirTrait, StateItemTrait};
+use crate::cli_state::{CliState, IdentityConfig, VaultPolicy};
 
 /// This struct supports identities operation that are either backed by
 /// a specific vault or which are using the default vault
@@ -26,7 +26,7 @@ impl NodeIdentities {
         self.identities.vault()
     }
 
-    pub(super) fn identities_repository(&self) -> Arc<dyn IdentitiesRepository> {
+    pub(crate) fn identities_repository(&self) -> Arc<dyn IdentitiesRepository> {
         self.identities.repository()
     }
 
@@ -40,6 +40,33 @@ impl NodeIdentities {
         }
     }
 
+    /// Return the names and identifiers of the identities whose name starts with a prefix
+    pub(crate) fn find_identities_by_name_prefix(
+        &self,
+        prefix: &str,
+    ) -> Result<Vec<(String, IdentityIdentifier)>> {
+        Ok(self
+            .cli_state
+            .identities
+            .list()?
+            .into_iter()
+            .filter(|idt_state| idt_state.name().starts_with(prefix))
+            .map(|idt_state| (idt_state.name().to_string(), idt_state.identifier()))
+            .collect())
+    }
+
+    /// Give a name to an identity. An identity which already had that name loses it
+    pub(crate) async fn set_identity_name(
+        &self,
+        identity_name: &str,
+        identifier: &IdentityIdentifier,
+    ) -> Result<()> {
+        self.cli_state
+            .identities
+            .overwrite(identity_name, IdentityConfig::new(identifier).await)?;
+        Ok(())
+    }
+
     pub(crate) async fn get_identifier(&self, identity_name: String) -> Result<IdentityIdentifier> {
         let identity_state = self.cli_state.identities.get(identity_name.as_str())?;
         Ok(identity_state.identifier())
@@ -93,6 +120,17 @@ impl NodeIdentities {
         }
     }
 
+    /// Return true if a vault with this name exists
+    pub(crate) fn vault_exists(&self, vault_name: &str) -> bool {
+        self.cli_state.vaults.exists(vault_name)
+    }
+
+    /// Return the access policy of a vault, if it has one
+    pub(crate) fn vault_policy(&self, vault_name: &str) -> Result<Option<VaultPolicy>> {
+        let vault_state = self.cli_state.vaults.get(vault_name)?;
+        Ok(vault_state.config().policy().cloned())
+    }
+
     /// Return a service to perform key operations
     pub(crate) async fn get_identities_keys(
         &self,
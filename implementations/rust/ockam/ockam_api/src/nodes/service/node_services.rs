@@ -56,7 +56,9 @@ impl NodeManager {
             return Err(ApiError::generic("Identity service exists at this address"));
         }
 
-        let service = IdentityService::new(self.node_identities()).await?;
+        let service = IdentityService::new(self.node_identities())
+            .await?
+            .with_secure_channel_listeners(self.registry.active_secure_channel_listeners.clone());
 
         ctx.flow_controls()
             .add_consumer(addr.clone(), &self.api_transport_flow_control_id);
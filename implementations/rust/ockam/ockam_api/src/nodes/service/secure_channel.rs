@@ -169,7 +169,7 @@ impl NodeManager {
         let options =
             SecureChannelListenerOptions::new().as_consumer(&self.api_transport_flow_control_id);
 
-        let options = match authorized_identifiers {
+        let options = match authorized_identifiers.clone() {
             Some(ids) => options.with_trust_policy(TrustMultiIdentifiersPolicy::new(ids)),
             None => options.with_trust_policy(TrustEveryonePolicy),
         };
@@ -186,8 +186,11 @@ impl NodeManager {
 
         self.registry.secure_channel_listeners.insert(
             address.clone(),
-            SecureChannelListenerInfo::new(listener.clone()),
+            SecureChannelListenerInfo::new(listener.clone(), authorized_identifiers),
         );
+        self.registry
+            .active_secure_channel_listeners
+            .insert(address.clone(), identifier);
 
         // TODO: Clean
         // Add Echoer, Uppercase and Cred Exch as a consumer by default
@@ -274,6 +277,7 @@ impl NodeManager {
     ) -> Result<()> {
         info!("Handling request to delete secure channel listener: {addr}");
         self.registry.secure_channel_listeners.remove(addr);
+        self.registry.active_secure_channel_listeners.remove(addr);
         Ok(())
     }
 }
@@ -302,8 +306,13 @@ impl NodeManagerWorker {
         Response::ok(req.id()).body(SecureChannelListenersList::new(
             registry
                 .secure_channel_listeners
-                .values()
-                .map(ShowSecureChannelListenerResponse::new)
+                .iter()
+                .map(|(address, info)| {
+                    let identifier = registry
+                        .active_secure_channel_listeners
+                        .identifier_of(address);
+                    ShowSecureChannelListenerResponse::new(info, identifier.as_ref())
+                })
                 .collect(),
         ))
     }
@@ -504,9 +513,18 @@ impl NodeManagerWorker {
         debug!(%address, "On show secure channel listener");
 
         match node_manager.registry.secure_channel_listeners.get(&address) {
-            Some(info) => Ok(Response::ok(req.id())
-                .body(ShowSecureChannelListenerResponse::new(info))
-                .to_vec()?),
+            Some(info) => {
+                let identifier = node_manager
+                    .registry
+                    .active_secure_channel_listeners
+                    .identifier_of(&address);
+                Ok(Response::ok(req.id())
+                    .body(ShowSecureChannelListenerResponse::new(
+                        info,
+                        identifier.as_ref(),
+                    ))
+                    .to_vec()?)
+            }
             None => {
                 let err_body = Error::new(req.path())
                     .with_message(format!("Secure Channel Listener, {}, not found.", address));
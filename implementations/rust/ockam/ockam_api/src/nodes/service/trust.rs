@@ -0,0 +1,74 @@
+use crate::nodes::models::secure_channel::ShowSecureChannelListenerResponse;
+use crate::nodes::models::trust::{NodeTrust, TrustContextSummary, TrustedIdentity};
+use ockam::identity::IdentityAttributesReader;
+use ockam_core::api::{Error, Request, Response, ResponseBuilder};
+use ockam_core::Result;
+
+use super::NodeManager;
+
+impl NodeManager {
+    /// Return the trust configuration of the node. The trusted identities are read again
+    /// when they are reloaded from a file
+    pub(super) async fn show_trust(
+        &self,
+        req: &Request<'_>,
+    ) -> Result<ResponseBuilder<NodeTrust>, ResponseBuilder<Error>> {
+        let trusted_identities = match &self.pre_trusted_identities {
+            Some(pre_trusted_identities) => pre_trusted_identities
+                .list()
+                .await?
+                .into_iter()
+                .map(|(identifier, entry)| {
+                    let attributes = entry
+                        .attrs()
+                        .iter()
+                        .map(|(name, value)| {
+                            (name.clone(), String::from_utf8_lossy(value).to_string())
+                        })
+                        .collect();
+                    TrustedIdentity::new(identifier.to_string(), attributes)
+                })
+                .collect(),
+            None => vec![],
+        };
+
+        let trust_context = match &self.trust_context {
+            Some(trust_context) => {
+                // A trust context can be configured without an authority
+                let authorities = match trust_context.authority() {
+                    Ok(_) => trust_context
+                        .authorities()
+                        .await?
+                        .iter()
+                        .map(|authority| authority.identifier().to_string())
+                        .collect(),
+                    Err(_) => vec![],
+                };
+                Some(TrustContextSummary::new(
+                    trust_context.id().to_string(),
+                    authorities,
+                ))
+            }
+            None => None,
+        };
+
+        let listeners = self
+            .registry
+            .secure_channel_listeners
+            .iter()
+            .map(|(address, info)| {
+                let identifier = self
+                    .registry
+                    .active_secure_channel_listeners
+                    .identifier_of(address);
+                ShowSecureChannelListenerResponse::new(info, identifier.as_ref())
+            })
+            .collect();
+
+        Ok(Response::ok(req.id()).body(NodeTrust::new(
+            trusted_identities,
+            trust_context,
+            listeners,
+        )))
+    }
+}
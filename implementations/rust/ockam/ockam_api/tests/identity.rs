@@ -1,16 +1,42 @@
 use minicbor::Decoder;
 
 use ockam::identity::identity::IdentityHistoryComparison;
+use ockam::identity::{
+    AttributesEntry, Identities, IdentitiesReader, IdentityAttributesReader,
+    IdentityAttributesWriter, IdentityIdentifier, OneTimeCode, SecureChannelListenerOptions,
+    SecureChannelOptions, Timestamp,
+};
 use ockam::node;
+use ockam_api::cli_state::traits::StateDirTrait;
+use ockam_api::cli_state::vaults::{VaultConfig, VaultPolicy};
 use ockam_api::cli_state::CliState;
 use ockam_api::identity::models::*;
-use ockam_api::identity::IdentityService;
+use ockam_api::identity::{
+    canonical_route, key_security_bits, merkle_inclusion_proof, merkle_tree_levels,
+    parse_public_identity_uri, possession_proof_payload, public_identity_uri, response_body,
+    route_bound_payload, signing_key_id, IdentityService, IdentityServiceOptions, InMemoryStore,
+    RateLimit, VaultGroup, VaultSelectionStrategy, IDENTITY_SERVICE_API_VERSION,
+    IDENTITY_SERVICE_MIN_CLIENT_VERSION, JWK_SET_MEDIA_TYPE, KEY_USAGE_ATTRIBUTE,
+    PUBLIC_IDENTITY_URI_PREFIX, SHA256_DIGEST_ALGORITHM,
+};
+use ockam_api::nodes::registry::ActiveSecureChannelListeners;
 use ockam_api::nodes::service::NodeIdentities;
 use ockam_core::api::{Request, Response, Status};
 use ockam_core::compat::rand::random;
+use ockam_core::compat::sync::Arc;
 use ockam_core::errcode::{Kind, Origin};
-use ockam_core::{route, AsyncTryClone, Error, Result};
+use ockam_core::{async_trait, route, Address, AsyncTryClone, CowBytes, Error, Result, Route};
+use ockam_node::tokio::time::sleep;
 use ockam_node::Context;
+use ockam_vault::{
+    EcdsaSignatureEncoding, EphemeralSecretsStore, Implementation, KeyId, PersistentSecretsStore,
+    PublicKey, Secret, SecretAttributes, SecretType, SecretsStoreReader, Signature, Signer,
+    StoredSecret, Vault,
+};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
 async fn create_identity(ctx: &mut Context, service_address: &str) -> Result<(Vec<u8>, String)> {
     let req = Request::post("").to_vec()?;
@@ -154,6 +180,32 @@ async fn verify_signature(
     Ok(res.verified())
 }
 
+async fn list_identities(
+    ctx: &mut Context,
+    request: ListIdentitiesRequest,
+    service_address: &str,
+) -> Result<Vec<String>> {
+    let req = Request::get("").body(request).to_vec()?;
+
+    let receiving_buf: Vec<u8> = ctx.send_and_receive(route![service_address], req).await?;
+    let mut dec = Decoder::new(&receiving_buf);
+
+    let res: Response = dec.decode()?;
+
+    if let Some(Status::Ok) = res.status() {
+    } else {
+        return Err(Error::new(
+            Origin::Identity,
+            Kind::Other,
+            "consistency error",
+        ));
+    }
+
+    let res: ListIdentitiesResponse = dec.decode()?;
+
+    Ok(res.identity_ids())
+}
+
 #[ockam_macros::test]
 async fn full_flow(ctx: &mut Context) -> Result<()> {
     let cli_state = CliState::test().unwrap();
@@ -200,3 +252,5135 @@ async fn full_flow(ctx: &mut Context) -> Result<()> {
 
     Ok(())
 }
+
+#[ockam_macros::test]
+async fn list_identities_by_attributes(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+    let repository = node.identities().repository();
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let (_, admin) = create_identity(ctx, "identity_service").await?;
+    let (_, member) = create_identity(ctx, "identity_service").await?;
+    let admin = IdentityIdentifier::try_from(admin)?;
+    let member = IdentityIdentifier::try_from(member)?;
+
+    repository
+        .put_attribute_value(&admin, "role", "admin")
+        .await?;
+    repository
+        .put_attribute_value(&admin, "team", "blue")
+        .await?;
+    repository
+        .put_attribute_value(&member, "role", "member")
+        .await?;
+    repository
+        .put_attribute_value(&member, "team", "blue")
+        .await?;
+
+    // an identity without attested attributes is not listed
+    create_identity(ctx, "identity_service").await?;
+    let all = list_identities(ctx, ListIdentitiesRequest::new(), "identity_service").await?;
+    assert_eq!(all.len(), 2);
+
+    let blue = list_identities(
+        ctx,
+        ListIdentitiesRequest::new().with_attribute("team", "blue"),
+        "identity_service",
+    )
+    .await?;
+    assert_eq!(blue.len(), 2);
+
+    let blue_admins = list_identities(
+        ctx,
+        ListIdentitiesRequest::new()
+            .with_attribute("team", "blue")
+            .with_attribute("role", "admin"),
+        "identity_service",
+    )
+    .await?;
+    assert_eq!(blue_admins, vec![admin.to_string()]);
+
+    let none = list_identities(
+        ctx,
+        ListIdentitiesRequest::new().with_attribute("role", "ADMIN"),
+        "identity_service",
+    )
+    .await?;
+    assert!(none.is_empty());
+
+    // the names reserved for the attributes stored by the service never match
+    repository
+        .put_attribute_value(&admin, "ockam_role", "admin")
+        .await?;
+    let none = list_identities(
+        ctx,
+        ListIdentitiesRequest::new().with_attribute("ockam_role", "admin"),
+        "identity_service",
+    )
+    .await?;
+    assert!(none.is_empty());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn reject_requests_when_overloaded(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    let options = IdentityServiceOptions::new().with_max_in_flight_requests(0);
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new_with_options(
+            NodeIdentities::new(node.identities(), cli_state),
+            options,
+        )
+        .await?,
+    )
+    .await?;
+
+    let req = Request::post("").to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::ServiceUnavailable));
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn prove_possession(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let (identity, identity_id) = create_identity(ctx, "identity_service").await?;
+    let challenge: [u8; 32] = random();
+
+    let req = Request::post("actions/prove_possession")
+        .body(ProvePossessionRequest::new(
+            identity.as_slice(),
+            &challenge[..],
+        ))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: ProvePossessionResponse = dec.decode()?;
+    assert_eq!(res.identity_id(), identity_id);
+
+    let verified = verify_signature(
+        ctx,
+        &identity,
+        &possession_proof_payload(&identity_id, &challenge),
+        res.signature(),
+        "identity_service",
+    )
+    .await?;
+    assert!(verified);
+
+    // the proof is not a signature of the challenge itself
+    let verified = verify_signature(
+        ctx,
+        &identity,
+        &challenge,
+        res.signature(),
+        "identity_service",
+    )
+    .await?;
+    assert!(!verified);
+
+    // an empty challenge is rejected
+    let req = Request::post("actions/prove_possession")
+        .body(ProvePossessionRequest::new(identity.as_slice(), Vec::new()))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::BadRequest));
+
+    ctx.stop().await
+}
+
+async fn get_challenge(ctx: &mut Context) -> Result<Vec<u8>> {
+    let req = Request::get("challenge").to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: ChallengeResponse = dec.decode()?;
+    assert_eq!(res.challenge().len(), 32);
+    Ok(res.challenge().to_vec())
+}
+
+async fn attest_challenge(
+    ctx: &mut Context,
+    identity: &[u8],
+    challenge: &[u8],
+    signature: &[u8],
+) -> Result<std::result::Result<(String, OneTimeCode), AttestationFailureReason>> {
+    let req = Request::post("actions/attest_challenge")
+        .body(AttestChallengeRequest::new(identity, challenge, signature))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: AttestChallengeResponse = dec.decode()?;
+    assert_eq!(res.verified(), res.failure_reason().is_none());
+    Ok(match res.failure_reason() {
+        Some(reason) => Err(reason),
+        None => Ok((
+            res.identity_id().unwrap().to_string(),
+            res.enrollment_token().unwrap().clone(),
+        )),
+    })
+}
+
+#[ockam_macros::test]
+async fn attest_challenge_for_device_onboarding(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new_with_options(
+            NodeIdentities::new(node.identities(), cli_state),
+            IdentityServiceOptions::new().with_challenge_ttl(Duration::from_millis(500)),
+        )
+        .await?,
+    )
+    .await?;
+
+    let (device, device_id) = create_identity(ctx, "identity_service").await?;
+
+    let challenge = get_challenge(ctx).await?;
+    let signature = create_signature(ctx, &device, &challenge, "identity_service").await?;
+    let (identity_id, _) = attest_challenge(ctx, &device, &challenge, &signature)
+        .await?
+        .unwrap();
+    assert_eq!(identity_id, device_id);
+
+    // a challenge can't be replayed
+    assert_eq!(
+        attest_challenge(ctx, &device, &challenge, &signature)
+            .await?
+            .err(),
+        Some(AttestationFailureReason::UnknownChallenge)
+    );
+
+    // the challenge must be signed by the device
+    let challenge = get_challenge(ctx).await?;
+    assert_eq!(
+        attest_challenge(ctx, &device, &challenge, &signature)
+            .await?
+            .err(),
+        Some(AttestationFailureReason::InvalidSignature)
+    );
+
+    // an expired challenge is rejected
+    let challenge = get_challenge(ctx).await?;
+    let signature = create_signature(ctx, &device, &challenge, "identity_service").await?;
+    sleep(Duration::from_secs(1)).await;
+    assert_eq!(
+        attest_challenge(ctx, &device, &challenge, &signature)
+            .await?
+            .err(),
+        Some(AttestationFailureReason::UnknownChallenge)
+    );
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn create_identity_with_key_type(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let req = Request::post("")
+        .body(CreateIdentityRequest::new().with_key_type("p256"))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+
+    let req = Request::post("")
+        .body(CreateIdentityRequest::new().with_key_type("rsa"))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::BadRequest));
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn create_signature_with_encoding(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let req = Request::post("")
+        .body(CreateIdentityRequest::new().with_key_type("p256"))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let identity = dec.decode::<CreateResponse>()?.identity().to_vec();
+
+    let data = random::<[u8; 32]>();
+
+    for encoding in [EcdsaSignatureEncoding::Raw, EcdsaSignatureEncoding::Der] {
+        let req = Request::post("actions/create_signature")
+            .body(
+                CreateSignatureRequest::new(identity.as_slice(), &data[..])
+                    .with_signature_encoding(encoding),
+            )
+            .to_vec()?;
+        let receiving_buf: Vec<u8> = ctx
+            .send_and_receive(route!["identity_service"], req)
+            .await?;
+        let mut dec = Decoder::new(&receiving_buf);
+        let res: Response = dec.decode()?;
+        assert_eq!(res.status(), Some(Status::Ok));
+        let signature = dec
+            .decode::<CreateSignatureResponse>()?
+            .signature()
+            .to_vec();
+
+        if encoding == EcdsaSignatureEncoding::Raw {
+            assert_eq!(signature.len(), 64);
+        }
+        assert!(verify_signature(ctx, &identity, &data, &signature, "identity_service").await?);
+    }
+
+    // The DER encoding does not apply to Ed25519 signatures
+    let (identity, _) = create_identity(ctx, "identity_service").await?;
+    let req = Request::post("actions/create_signature")
+        .body(
+            CreateSignatureRequest::new(identity.as_slice(), &data[..])
+                .with_signature_encoding(EcdsaSignatureEncoding::Der),
+        )
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::BadRequest));
+
+    ctx.stop().await
+}
+
+async fn sign_with_request(
+    ctx: &mut Context,
+    request: CreateSignatureRequest<'_>,
+) -> Result<(Vec<u8>, bool)> {
+    let req = Request::post("actions/create_signature")
+        .body(request)
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: CreateSignatureResponse = dec.decode()?;
+    Ok((res.signature().to_vec(), res.deterministic()))
+}
+
+#[ockam_macros::test]
+async fn create_deterministic_signature(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let data = random::<[u8; 32]>();
+
+    // Ed25519 signatures are always deterministic
+    let (identity, _) = create_identity(ctx, "identity_service").await?;
+    let request = CreateSignatureRequest::new(identity.as_slice(), &data[..]);
+    let (signature1, deterministic) = sign_with_request(ctx, request.clone()).await?;
+    assert!(deterministic);
+    let (signature2, _) = sign_with_request(ctx, request).await?;
+    assert_eq!(signature1, signature2);
+
+    // P-256 signatures are only guaranteed to be deterministic when requested
+    let req = Request::post("")
+        .body(CreateIdentityRequest::new().with_key_type("p256"))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let identity = dec.decode::<CreateResponse>()?.identity().to_vec();
+
+    let request = CreateSignatureRequest::new(identity.as_slice(), &data[..]);
+    let (_, deterministic) = sign_with_request(ctx, request.clone()).await?;
+    assert!(!deterministic);
+
+    let request = request.with_deterministic_signature();
+    let (signature1, deterministic) = sign_with_request(ctx, request.clone()).await?;
+    assert!(deterministic);
+    let (signature2, _) = sign_with_request(ctx, request).await?;
+    assert_eq!(signature1, signature2);
+    assert!(verify_signature(ctx, &identity, &data, &signature1, "identity_service").await?);
+
+    ctx.stop().await
+}
+
+async fn sign_with_vault_group(
+    ctx: &mut Context,
+    identity: &[u8],
+    data: &[u8],
+    vault_group: &str,
+) -> Result<std::result::Result<(Vec<u8>, String), Status>> {
+    let req = Request::post("actions/create_signature")
+        .body(CreateSignatureRequest::new(identity, data).with_vault_group(vault_group))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    if res.status() != Some(Status::Ok) {
+        return Ok(Err(res.status().unwrap()));
+    }
+    let res: CreateSignatureResponse = dec.decode()?;
+    Ok(Ok((
+        res.signature().to_vec(),
+        res.vault_name().unwrap().to_string(),
+    )))
+}
+
+#[ockam_macros::test]
+async fn create_signature_with_vault_group(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+    for vault_name in ["v1", "v2"] {
+        cli_state
+            .vaults
+            .create_async(vault_name, VaultConfig::default())
+            .await
+            .unwrap();
+    }
+
+    let vaults = |names: &[&str]| -> Vec<String> { names.iter().map(|n| n.to_string()).collect() };
+    let options = IdentityServiceOptions::new()
+        .with_vault_group(
+            "first",
+            VaultGroup::new(
+                vaults(&["missing", "v2", "v1"]),
+                VaultSelectionStrategy::FirstAvailable,
+            ),
+        )
+        .with_vault_group(
+            "round-robin",
+            VaultGroup::new(vaults(&["v1", "v2"]), VaultSelectionStrategy::RoundRobin),
+        )
+        .with_vault_group(
+            "unavailable",
+            VaultGroup::new(vaults(&["v2"]), VaultSelectionStrategy::LeastLoaded),
+        );
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new_with_options(
+            NodeIdentities::new(node.identities(), cli_state),
+            options,
+        )
+        .await?,
+    )
+    .await?;
+
+    // the identity key is only stored in the vault v1
+    let req = Request::post("")
+        .body(CreateIdentityRequest::new().with_vault_name("v1"))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let identity = dec.decode::<CreateResponse>()?.identity().to_vec();
+    let data = random::<[u8; 32]>();
+
+    // the vaults which don't exist or don't hold the key are skipped
+    let (signature, vault_name) = sign_with_vault_group(ctx, &identity, &data, "first")
+        .await?
+        .unwrap();
+    assert_eq!(vault_name, "v1");
+    assert!(verify_signature(ctx, &identity, &data, &signature, "identity_service").await?);
+
+    // the second request starts with the vault v2, which can't sign
+    for _ in 0..2 {
+        let (_, vault_name) = sign_with_vault_group(ctx, &identity, &data, "round-robin")
+            .await?
+            .unwrap();
+        assert_eq!(vault_name, "v1");
+    }
+
+    assert_eq!(
+        sign_with_vault_group(ctx, &identity, &data, "unavailable").await?,
+        Err(Status::BadRequest)
+    );
+    assert_eq!(
+        sign_with_vault_group(ctx, &identity, &data, "unknown").await?,
+        Err(Status::BadRequest)
+    );
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn metrics(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let (identity, _) = create_identity(ctx, "identity_service").await?;
+    let data = random::<[u8; 32]>();
+    let signature = create_signature(ctx, &identity, &data, "identity_service").await?;
+    assert!(verify_signature(ctx, &identity, &data, &signature, "identity_service").await?);
+    assert!(
+        !verify_signature(
+            ctx,
+            &identity,
+            b"other data",
+            &signature,
+            "identity_service"
+        )
+        .await?
+    );
+
+    let req = Request::get("metrics").to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: MetricsResponse = dec.decode()?;
+
+    assert_eq!(
+        res.get("identity_service_identities_created_total"),
+        Some(1)
+    );
+    assert_eq!(
+        res.get("identity_service_signatures_created_total"),
+        Some(1)
+    );
+    assert_eq!(res.get("identity_service_verifications_total"), Some(2));
+    assert_eq!(
+        res.get("identity_service_verifications_passed_total"),
+        Some(1)
+    );
+    assert_eq!(
+        res.get("identity_service_verifications_failed_total"),
+        Some(1)
+    );
+    assert!(res.get("identity_service_uptime_seconds").is_some());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn verify_signature_failure_reason(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let (identity, _) = create_identity(ctx, "identity_service").await?;
+    let data = random::<[u8; 32]>();
+    let signature = create_signature(ctx, &identity, &data, "identity_service").await?;
+
+    let cases = [
+        (&data[..], &signature[..], None),
+        (
+            &b"other data"[..],
+            &signature[..],
+            Some(VerificationFailureReason::KeyMismatch),
+        ),
+        (
+            &data[..],
+            &signature[..10],
+            Some(VerificationFailureReason::MalformedSignature),
+        ),
+    ];
+
+    for (data, signature, expected) in cases {
+        let req = Request::post("actions/verify_signature")
+            .body(VerifySignatureRequest::new(
+                identity.as_slice(),
+                data,
+                signature,
+            ))
+            .to_vec()?;
+        let receiving_buf: Vec<u8> = ctx
+            .send_and_receive(route!["identity_service"], req)
+            .await?;
+        let mut dec = Decoder::new(&receiving_buf);
+        let res: Response = dec.decode()?;
+        assert_eq!(res.status(), Some(Status::Ok));
+        let res: VerifySignatureResponse = dec.decode()?;
+        assert_eq!(res.verified(), expected.is_none());
+        assert_eq!(res.failure_reason(), expected);
+    }
+
+    ctx.stop().await
+}
+
+async fn issue_delegation_token(
+    ctx: &mut Context,
+    issuer: &[u8],
+    delegate: &str,
+    ttl_secs: u64,
+) -> Result<DelegationToken<'static>> {
+    let req = Request::post("actions/issue_delegation_token")
+        .body(IssueDelegationTokenRequest::new(
+            issuer, delegate, "sign", ttl_secs,
+        ))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let token = dec
+        .decode::<IssueDelegationTokenResponse>()?
+        .token()
+        .clone();
+    Ok(DelegationToken::new(
+        token.data().to_vec(),
+        token.signature().to_vec(),
+    ))
+}
+
+async fn verify_delegation_token(
+    ctx: &mut Context,
+    issuer: &[u8],
+    token: DelegationToken<'_>,
+    service_address: &str,
+) -> Result<Option<DelegationTokenFailureReason>> {
+    let req = Request::post("actions/verify_delegation_token")
+        .body(VerifyDelegationTokenRequest::new(issuer, token))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx.send_and_receive(route![service_address], req).await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: VerifyDelegationTokenResponse = dec.decode()?;
+    assert_eq!(res.verified(), res.failure_reason().is_none());
+    Ok(res.failure_reason())
+}
+
+#[ockam_macros::test]
+async fn delegation_token(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let (issuer, _) = create_identity(ctx, "identity_service").await?;
+    let (other, _) = create_identity(ctx, "identity_service").await?;
+    let (_, delegate_id) = create_identity(ctx, "identity_service").await?;
+
+    // A token can only be used once
+    let token = issue_delegation_token(ctx, &issuer, &delegate_id, 60).await?;
+    assert_eq!(
+        verify_delegation_token(ctx, &issuer, token.clone(), "identity_service").await?,
+        None
+    );
+    assert_eq!(
+        verify_delegation_token(ctx, &issuer, token, "identity_service").await?,
+        Some(DelegationTokenFailureReason::AlreadyUsed)
+    );
+
+    // A token is only valid for its issuer
+    let token = issue_delegation_token(ctx, &issuer, &delegate_id, 60).await?;
+    assert_eq!(
+        verify_delegation_token(ctx, &other, token, "identity_service").await?,
+        Some(DelegationTokenFailureReason::InvalidSignature)
+    );
+
+    // An expired token is rejected
+    let token = issue_delegation_token(ctx, &issuer, &delegate_id, 0).await?;
+    assert_eq!(
+        verify_delegation_token(ctx, &issuer, token, "identity_service").await?,
+        Some(DelegationTokenFailureReason::Expired)
+    );
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn used_delegation_tokens_survive_restart(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+    let options = IdentityServiceOptions::new().with_state_store(Arc::new(InMemoryStore::new()));
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new_with_options(
+            NodeIdentities::new(node.identities(), cli_state.clone()),
+            options.clone(),
+        )
+        .await?,
+    )
+    .await?;
+
+    let (issuer, _) = create_identity(ctx, "identity_service").await?;
+    let (_, delegate_id) = create_identity(ctx, "identity_service").await?;
+    let token = issue_delegation_token(ctx, &issuer, &delegate_id, 60).await?;
+    assert_eq!(
+        verify_delegation_token(ctx, &issuer, token.clone(), "identity_service").await?,
+        None
+    );
+
+    // the used tokens are kept in the state store, which outlives the service
+    ctx.stop_worker("identity_service").await?;
+    ctx.start_worker(
+        "restarted_identity_service",
+        IdentityService::new_with_options(
+            NodeIdentities::new(node.identities(), cli_state),
+            options,
+        )
+        .await?,
+    )
+    .await?;
+    assert_eq!(
+        verify_delegation_token(ctx, &issuer, token, "restarted_identity_service").await?,
+        Some(DelegationTokenFailureReason::AlreadyUsed)
+    );
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn streaming_signature(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let (identity, _) = create_identity(ctx, "identity_service").await?;
+
+    let req = Request::post("actions/begin_sign")
+        .body(BeginSignRequest::new(identity.as_slice()))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let session_id = dec.decode::<BeginSignResponse>()?.session_id().to_string();
+
+    let chunks = [
+        random::<[u8; 32]>(),
+        random::<[u8; 32]>(),
+        random::<[u8; 32]>(),
+    ];
+    for chunk in chunks.iter() {
+        let req = Request::post("actions/sign_chunk")
+            .body(SignChunkRequest::new(session_id.as_str(), &chunk[..]))
+            .to_vec()?;
+        let receiving_buf: Vec<u8> = ctx
+            .send_and_receive(route!["identity_service"], req)
+            .await?;
+        let mut dec = Decoder::new(&receiving_buf);
+        let res: Response = dec.decode()?;
+        assert_eq!(res.status(), Some(Status::Ok));
+    }
+
+    let req = Request::post("actions/finish_sign")
+        .body(FinishSignRequest::new(session_id.as_str()))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let signature = dec
+        .decode::<CreateSignatureResponse>()?
+        .signature()
+        .to_vec();
+
+    // The signature is computed over the digest of the whole payload
+    let digest = Sha256::digest(chunks.concat());
+    assert!(verify_signature(ctx, &identity, &digest, &signature, "identity_service").await?);
+
+    // The session is closed once the signature is returned
+    let req = Request::post("actions/sign_chunk")
+        .body(SignChunkRequest::new(session_id.as_str(), &chunks[0][..]))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::BadRequest));
+
+    ctx.stop().await
+}
+
+async fn signing_session_request<B: minicbor::Encode<()>>(
+    ctx: &mut Context,
+    route: Route,
+    action: &str,
+    body: B,
+) -> Result<(Option<Status>, Vec<u8>)> {
+    let req = Request::post(format!("actions/{action}"))
+        .body(body)
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx.send_and_receive(route, req).await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    Ok((res.status(), receiving_buf[dec.position()..].to_vec()))
+}
+
+#[ockam_macros::test]
+async fn streaming_signature_is_bound_to_its_sender(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+    let alice = node.create_identity().await?;
+    let bob = node.create_identity().await?;
+    let mut config = VaultConfig::default();
+    config.set_policy(Some(VaultPolicy::new(
+        vec![alice.identifier().to_string()],
+        vec![],
+    )));
+    cli_state
+        .vaults
+        .create_async("restricted", config.clone())
+        .await
+        .unwrap();
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state.clone())).await?,
+    )
+    .await?;
+    let server = node.create_identity().await?;
+    let listener_options = SecureChannelListenerOptions::new();
+    ctx.flow_controls().add_consumer(
+        "identity_service",
+        &listener_options.spawner_flow_control_id(),
+    );
+    node.create_secure_channel_listener(&server, "api", listener_options)
+        .await?;
+    let alice_channel = node
+        .create_secure_channel(&alice, route!["api"], SecureChannelOptions::new())
+        .await?;
+    let bob_channel = node
+        .create_secure_channel(&bob, route!["api"], SecureChannelOptions::new())
+        .await?;
+    let alice_route = route![
+        alice_channel.encryptor_address().clone(),
+        "identity_service"
+    ];
+    let bob_route = route![bob_channel.encryptor_address().clone(), "identity_service"];
+
+    let req = Request::post("")
+        .body(CreateIdentityRequest::new().with_vault_name("restricted"))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let identity = dec.decode::<CreateResponse>()?.identity().to_vec();
+
+    let (status, body) = signing_session_request(
+        ctx,
+        alice_route.clone(),
+        "begin_sign",
+        BeginSignRequest::new(identity.as_slice()).with_vault_name("restricted"),
+    )
+    .await?;
+    assert_eq!(status, Some(Status::Ok));
+    let session_id = minicbor::decode::<BeginSignResponse>(&body)?
+        .session_id()
+        .to_string();
+    let chunk = random::<[u8; 32]>();
+
+    // the session can't be fed or finished by another sender, authenticated or not
+    for route in [bob_route, route!["identity_service"]] {
+        let (status, _) = signing_session_request(
+            ctx,
+            route.clone(),
+            "sign_chunk",
+            SignChunkRequest::new(session_id.as_str(), &b"injected"[..]),
+        )
+        .await?;
+        assert_eq!(status, Some(Status::BadRequest));
+        let (status, _) = signing_session_request(
+            ctx,
+            route,
+            "finish_sign",
+            FinishSignRequest::new(session_id.as_str()),
+        )
+        .await?;
+        assert_eq!(status, Some(Status::BadRequest));
+    }
+    let (status, _) = signing_session_request(
+        ctx,
+        alice_route.clone(),
+        "sign_chunk",
+        SignChunkRequest::new(session_id.as_str(), &chunk[..]),
+    )
+    .await?;
+    assert_eq!(status, Some(Status::Ok));
+
+    // a sender denied by the vault policy doesn't close the session
+    let mut denied = config.clone();
+    denied.set_policy(Some(VaultPolicy::new(
+        vec![],
+        vec![alice.identifier().to_string()],
+    )));
+    cli_state.vaults.overwrite("restricted", denied).unwrap();
+    let (status, _) = signing_session_request(
+        ctx,
+        alice_route.clone(),
+        "finish_sign",
+        FinishSignRequest::new(session_id.as_str()),
+    )
+    .await?;
+    assert_eq!(status, Some(Status::Forbidden));
+    cli_state.vaults.overwrite("restricted", config).unwrap();
+
+    let (status, body) = signing_session_request(
+        ctx,
+        alice_route,
+        "finish_sign",
+        FinishSignRequest::new(session_id.as_str()),
+    )
+    .await?;
+    assert_eq!(status, Some(Status::Ok));
+    let signature = minicbor::decode::<CreateSignatureResponse>(&body)?
+        .signature()
+        .to_vec();
+    let digest = Sha256::digest(chunk);
+    assert!(verify_signature(ctx, &identity, &digest, &signature, "identity_service").await?);
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn compressed_responses(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new_with_options(
+            NodeIdentities::new(node.identities(), cli_state),
+            IdentityServiceOptions::new().with_response_compression(64),
+        )
+        .await?,
+    )
+    .await?;
+
+    // Responses are not compressed unless the client accepts it
+    let req = Request::post("").to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    assert!(!res.is_compressed());
+
+    let req = Request::post("").accept_compression().to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    assert!(res.is_compressed());
+
+    let body = response_body(&res, &mut dec)?;
+    let res: CreateResponse = minicbor::decode(&body)?;
+    assert!(!res.identity_id().is_empty());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn verify_signature_with_required_signer(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let (identity, identity_id) = create_identity(ctx, "identity_service").await?;
+    let (_, other_id) = create_identity(ctx, "identity_service").await?;
+    let data = random::<[u8; 32]>();
+    let signature = create_signature(ctx, &identity, &data, "identity_service").await?;
+
+    for (required_signer, expected) in [
+        (identity_id, None),
+        (other_id, Some(VerificationFailureReason::WrongSigner)),
+    ] {
+        let body =
+            VerifySignatureRequest::new(identity.as_slice(), &data[..], signature.as_slice())
+                .with_required_signer(required_signer);
+        let req = Request::post("actions/verify_signature")
+            .body(body)
+            .to_vec()?;
+        let receiving_buf: Vec<u8> = ctx
+            .send_and_receive(route!["identity_service"], req)
+            .await?;
+        let mut dec = Decoder::new(&receiving_buf);
+        let res: Response = dec.decode()?;
+        assert_eq!(res.status(), Some(Status::Ok));
+        let res: VerifySignatureResponse = dec.decode()?;
+        assert_eq!(res.verified(), expected.is_none());
+        assert_eq!(res.failure_reason(), expected);
+    }
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn canonicalize(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let (identity, _) = create_identity(ctx, "identity_service").await?;
+    let data = random::<[u8; 32]>();
+
+    let req = Request::post("actions/canonicalize")
+        .body(CreateSignatureRequest::new(identity.as_slice(), &data[..]))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let payload = dec.decode::<CanonicalizeResponse>()?.payload().to_vec();
+
+    // A signature of the data is a signature of the canonical payload
+    let signature = create_signature(ctx, &identity, &data, "identity_service").await?;
+    assert!(verify_signature(ctx, &identity, &payload, &signature, "identity_service").await?);
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn public_identity(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state.clone())).await?,
+    )
+    .await?;
+
+    let (_, identity_id) = create_identity(ctx, "identity_service").await?;
+    let identifier = IdentityIdentifier::try_from(identity_id.as_str())?;
+    cli_state
+        .create_identity_state(&identifier, Some("shared"))
+        .await
+        .unwrap();
+
+    let req = Request::get("shared/public").to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: PublicIdentityResponse = dec.decode()?;
+    assert_eq!(res.identity_id(), identity_id);
+    assert!(!res.public_key().is_empty());
+
+    ctx.stop().await
+}
+
+async fn public_key_jwk(
+    ctx: &mut Context,
+    identity_name: &str,
+    media_type: Option<&str>,
+) -> Result<(Status, Option<serde_json::Value>)> {
+    let req = Request::get(format!("{identity_name}/jwk"));
+    let req = match media_type {
+        Some(media_type) => req.accept(media_type),
+        None => req,
+    };
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req.to_vec()?)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    let status = res.status().unwrap();
+    if status != Status::Ok {
+        return Ok((status, None));
+    }
+    let res: PublicKeyJwkResponse = dec.decode()?;
+    assert_eq!(
+        res.media_type(),
+        media_type.unwrap_or("application/jwk+json")
+    );
+    Ok((status, Some(serde_json::from_str(res.jwk()).unwrap())))
+}
+
+#[ockam_macros::test]
+async fn public_key_as_jwk(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state.clone())).await?,
+    )
+    .await?;
+
+    for (name, key_type) in [("ed", "ed25519"), ("ec", "p256")] {
+        let req = Request::post("")
+            .body(CreateIdentityRequest::new().with_key_type(key_type))
+            .to_vec()?;
+        let receiving_buf: Vec<u8> = ctx
+            .send_and_receive(route!["identity_service"], req)
+            .await?;
+        let mut dec = Decoder::new(&receiving_buf);
+        let res: Response = dec.decode()?;
+        assert_eq!(res.status(), Some(Status::Ok));
+        let res: CreateResponse = dec.decode()?;
+        let identifier = IdentityIdentifier::try_from(res.identity_id())?;
+        cli_state
+            .create_identity_state(&identifier, Some(name))
+            .await
+            .unwrap();
+    }
+
+    let (_, jwk) = public_key_jwk(ctx, "ed", None).await?;
+    let jwk = jwk.unwrap();
+    assert_eq!(jwk["kty"], "OKP");
+    assert_eq!(jwk["crv"], "Ed25519");
+    // 32 bytes in base64url without padding
+    assert_eq!(jwk["x"].as_str().unwrap().len(), 43);
+    assert!(jwk["kid"].as_str().unwrap().ends_with("#OCKAM_RK"));
+
+    let (_, jwk) = public_key_jwk(ctx, "ec", None).await?;
+    let jwk = jwk.unwrap();
+    assert_eq!(jwk["kty"], "EC");
+    assert_eq!(jwk["crv"], "P-256");
+    assert_eq!(jwk["x"].as_str().unwrap().len(), 43);
+    assert_eq!(jwk["y"].as_str().unwrap().len(), 43);
+
+    let (_, jwk_set) = public_key_jwk(ctx, "ed", Some(JWK_SET_MEDIA_TYPE)).await?;
+    let keys = jwk_set.unwrap()["keys"].as_array().unwrap().clone();
+    assert_eq!(keys.len(), 1);
+    assert_eq!(keys[0]["kty"], "OKP");
+
+    let (status, _) = public_key_jwk(ctx, "ed", Some("application/json")).await?;
+    assert_eq!(status, Status::BadRequest);
+    let (status, _) = public_key_jwk(ctx, "unknown", None).await?;
+    assert_eq!(status, Status::BadRequest);
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn signing_capabilities(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let req = Request::get("capabilities/signing").to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: SigningCapabilitiesResponse = dec.decode()?;
+
+    let key_types: Vec<&str> = res.schemes().iter().map(|s| s.key_type()).collect();
+    assert_eq!(key_types, vec!["ed25519", "p256"]);
+    assert_eq!(res.schemes()[0].encodings(), vec!["raw"]);
+    assert_eq!(res.schemes()[1].encodings(), vec!["raw", "der"]);
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn auth_token(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new_with_options(
+            NodeIdentities::new(node.identities(), cli_state),
+            IdentityServiceOptions::new().with_auth_token("secret"),
+        )
+        .await?,
+    )
+    .await?;
+
+    for (token, expected) in [
+        (None, Status::Unauthorized),
+        (Some("wrong"), Status::Unauthorized),
+        (Some("secret"), Status::Ok),
+    ] {
+        let req = Request::post("");
+        let req = match token {
+            Some(token) => req.auth_token(token),
+            None => req,
+        };
+        let receiving_buf: Vec<u8> = ctx
+            .send_and_receive(route!["identity_service"], req.to_vec()?)
+            .await?;
+        let mut dec = Decoder::new(&receiving_buf);
+        let res: Response = dec.decode()?;
+        assert_eq!(res.status(), Some(expected));
+    }
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn find_identities_by_name_prefix(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state.clone())).await?,
+    )
+    .await?;
+
+    for name in ["acme-1", "acme-2", "other"] {
+        let (_, identity_id) = create_identity(ctx, "identity_service").await?;
+        let identifier = IdentityIdentifier::try_from(identity_id.as_str())?;
+        cli_state
+            .create_identity_state(&identifier, Some(name))
+            .await
+            .unwrap();
+    }
+
+    for (path, expected) in [
+        ("acme-*", vec!["acme-1", "acme-2"]),
+        ("*", vec!["acme-1", "acme-2", "other"]),
+        ("none-*", vec![]),
+    ] {
+        let req = Request::get(path).to_vec()?;
+        let receiving_buf: Vec<u8> = ctx
+            .send_and_receive(route!["identity_service"], req)
+            .await?;
+        let mut dec = Decoder::new(&receiving_buf);
+        let res: Response = dec.decode()?;
+        assert_eq!(res.status(), Some(Status::Ok));
+        let res: FindIdentitiesResponse = dec.decode()?;
+        let mut names: Vec<&str> = res.identities().iter().map(|i| i.name()).collect();
+        names.sort();
+        assert_eq!(names, expected);
+    }
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn list_identity_listeners(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+    let listeners = ActiveSecureChannelListeners::new();
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state.clone()))
+            .await?
+            .with_secure_channel_listeners(listeners.clone()),
+    )
+    .await?;
+
+    let mut identifiers = vec![];
+    for name in ["listening", "idle"] {
+        let (_, identity_id) = create_identity(ctx, "identity_service").await?;
+        let identifier = IdentityIdentifier::try_from(identity_id.as_str())?;
+        cli_state
+            .create_identity_state(&identifier, Some(name))
+            .await
+            .unwrap();
+        identifiers.push(identifier);
+    }
+    listeners.insert(Address::from_string("api"), identifiers[0].clone());
+    listeners.insert(Address::from_string("other"), identifiers[0].clone());
+    listeners.insert(Address::from_string("removed"), identifiers[1].clone());
+    listeners.remove(&Address::from_string("removed"));
+
+    let req = Request::get("listeners").to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: ListIdentityListenersResponse = dec.decode()?;
+    assert_eq!(res.identities().len(), 2);
+    for identity in res.identities() {
+        match identity.name() {
+            "listening" => {
+                assert!(identity.is_listening());
+                assert_eq!(identity.identity_id(), identifiers[0].to_string());
+                assert_eq!(identity.listener_addresses(), vec!["0#api", "0#other"]);
+            }
+            _ => assert!(!identity.is_listening()),
+        }
+    }
+
+    ctx.stop().await
+}
+
+async fn verify_endorsement(
+    ctx: &mut Context,
+    endorser: &[u8],
+    subject: &[u8],
+    endorsement: Endorsement<'_>,
+) -> Result<Option<EndorsementFailureReason>> {
+    let req = Request::post("actions/verify_endorsement")
+        .body(VerifyEndorsementRequest::new(
+            endorser,
+            subject,
+            endorsement,
+        ))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: VerifyEndorsementResponse = dec.decode()?;
+    assert_eq!(res.verified(), res.failure_reason().is_none());
+    Ok(res.failure_reason())
+}
+
+#[ockam_macros::test]
+async fn cross_sign(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state.clone())).await?,
+    )
+    .await?;
+
+    let mut identities = vec![];
+    for name in ["endorser", "subject"] {
+        let (identity, identity_id) = create_identity(ctx, "identity_service").await?;
+        let identifier = IdentityIdentifier::try_from(identity_id.as_str())?;
+        cli_state
+            .create_identity_state(&identifier, Some(name))
+            .await
+            .unwrap();
+        identities.push(identity);
+    }
+    let (endorser, subject) = (&identities[0], &identities[1]);
+
+    let req = Request::post("actions/cross_sign")
+        .body(CrossSignRequest::new("endorser", "subject"))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let endorsement = dec.decode::<CrossSignResponse>()?.endorsement().clone();
+    let endorsement = Endorsement::new(
+        endorsement.data().to_vec(),
+        endorsement.signature().to_vec(),
+    );
+
+    assert_eq!(
+        verify_endorsement(ctx, endorser, subject, endorsement.clone()).await?,
+        None
+    );
+    // the endorsement is about another identity
+    assert_eq!(
+        verify_endorsement(ctx, endorser, endorser, endorsement.clone()).await?,
+        Some(EndorsementFailureReason::SubjectMismatch)
+    );
+    // the endorsement was not signed by the subject
+    assert_eq!(
+        verify_endorsement(ctx, subject, subject, endorsement.clone()).await?,
+        Some(EndorsementFailureReason::InvalidSignature)
+    );
+    assert_eq!(
+        verify_endorsement(
+            ctx,
+            endorser,
+            subject,
+            Endorsement::new(
+                b"not an endorsement".to_vec(),
+                endorsement.signature().to_vec()
+            )
+        )
+        .await?,
+        Some(EndorsementFailureReason::Malformed)
+    );
+
+    // the endorser must be a known identity
+    let req = Request::post("actions/cross_sign")
+        .body(CrossSignRequest::new("unknown", "subject"))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::BadRequest));
+
+    ctx.stop().await
+}
+
+async fn verify_signature_failure(
+    ctx: &mut Context,
+    service_address: &str,
+    identity: &[u8],
+    data: &[u8],
+    signature: &[u8],
+) -> Result<Option<VerificationFailureReason>> {
+    let req = Request::post("actions/verify_signature")
+        .body(VerifySignatureRequest::new(identity, data, signature))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx.send_and_receive(route![service_address], req).await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: VerifySignatureResponse = dec.decode()?;
+    assert_eq!(res.verified(), res.failure_reason().is_none());
+    Ok(res.failure_reason())
+}
+
+#[ockam_macros::test]
+async fn revoke_identity(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new_with_options(
+            NodeIdentities::new(node.identities(), cli_state.clone()),
+            IdentityServiceOptions::new().with_revocation_check(),
+        )
+        .await?,
+    )
+    .await?;
+    ctx.start_worker(
+        "unchecked_identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state.clone())).await?,
+    )
+    .await?;
+
+    let (identity, identity_id) = create_identity(ctx, "identity_service").await?;
+    let identifier = IdentityIdentifier::try_from(identity_id.as_str())?;
+    cli_state
+        .create_identity_state(&identifier, Some("revoked"))
+        .await
+        .unwrap();
+    // the identity has attributes attested by an authority, which expire
+    let authority = node.identities_creation().create_identity().await?;
+    let added = Timestamp::now().unwrap();
+    let expires = added.add_seconds(3600);
+    node.identities()
+        .repository()
+        .put_attributes(
+            &identifier,
+            AttributesEntry::new(
+                BTreeMap::from([("role".to_string(), b"member".to_vec())]),
+                added,
+                Some(expires),
+                Some(authority.identifier()),
+            ),
+        )
+        .await?;
+    let data = random::<[u8; 32]>();
+    let signature = create_signature(ctx, &identity, &data, "identity_service").await?;
+    assert_eq!(
+        verify_signature_failure(ctx, "identity_service", &identity, &data, &signature).await?,
+        None
+    );
+
+    let req = Request::post("actions/revoke_identity")
+        .body(RevokeIdentityRequest::new("revoked").with_reason("key compromised"))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: RevokeIdentityResponse = dec.decode()?;
+    let revocation = res.revocation();
+    let revocation_data: RevocationData = minicbor::decode(revocation.data())?;
+    assert_eq!(revocation_data.identity(), identity_id);
+    assert_eq!(revocation_data.reason(), Some("key compromised"));
+
+    // recording the revocation doesn't make the attested attributes permanent
+    let entry = node
+        .identities()
+        .repository()
+        .get_attributes(&identifier)
+        .await?
+        .unwrap();
+    assert!(entry.attrs().contains_key("role"));
+    assert_eq!(entry.added(), added);
+    assert_eq!(entry.expires(), Some(expires));
+    assert_eq!(entry.attested_by(), Some(authority.identifier()));
+
+    // the revocation record is signed by the revoked identity
+    assert_eq!(
+        verify_signature_failure(
+            ctx,
+            "unchecked_identity_service",
+            &identity,
+            revocation.data(),
+            revocation.signature()
+        )
+        .await?,
+        None
+    );
+
+    assert_eq!(
+        verify_signature_failure(ctx, "identity_service", &identity, &data, &signature).await?,
+        Some(VerificationFailureReason::Revoked)
+    );
+    // the revocation is only checked when it is enabled
+    assert_eq!(
+        verify_signature_failure(
+            ctx,
+            "unchecked_identity_service",
+            &identity,
+            &data,
+            &signature
+        )
+        .await?,
+        None
+    );
+
+    // the revocation is persisted, a new service sees it
+    ctx.start_worker(
+        "restarted_identity_service",
+        IdentityService::new_with_options(
+            NodeIdentities::new(node.identities(), cli_state),
+            IdentityServiceOptions::new().with_revocation_check(),
+        )
+        .await?,
+    )
+    .await?;
+    assert_eq!(
+        verify_signature_failure(
+            ctx,
+            "restarted_identity_service",
+            &identity,
+            &data,
+            &signature
+        )
+        .await?,
+        Some(VerificationFailureReason::Revoked)
+    );
+
+    // an identity can only be revoked once
+    let req = Request::post("actions/revoke_identity")
+        .body(RevokeIdentityRequest::new("revoked"))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::BadRequest));
+
+    ctx.stop().await
+}
+
+async fn verify_identity_change_history(
+    ctx: &mut Context,
+    request: VerifyIdentityChangeHistoryRequest<'_>,
+) -> Result<VerifyIdentityChangeHistoryResponse> {
+    let req = Request::post("actions/verify_identity_change_history")
+        .body(request)
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    Ok(dec.decode()?)
+}
+
+#[ockam_macros::test]
+async fn verify_identity_change_history_with_trust_anchor(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let (identity, _) = create_identity(ctx, "identity_service").await?;
+    let (other_identity, _) = create_identity(ctx, "identity_service").await?;
+
+    let res = verify_identity_change_history(
+        ctx,
+        VerifyIdentityChangeHistoryRequest::new(identity.as_slice()),
+    )
+    .await?;
+    assert!(res.verified());
+
+    let res = verify_identity_change_history(
+        ctx,
+        VerifyIdentityChangeHistoryRequest::new(identity.as_slice())
+            .with_trust_anchor(identity.as_slice()),
+    )
+    .await?;
+    assert!(res.verified());
+
+    let res = verify_identity_change_history(
+        ctx,
+        VerifyIdentityChangeHistoryRequest::new(identity.as_slice())
+            .with_trust_anchor(other_identity.as_slice()),
+    )
+    .await?;
+    assert!(!res.verified());
+    assert_eq!(
+        res.failure_reason(),
+        Some(ChangeHistoryFailureReason::UntrustedRoot)
+    );
+    assert_eq!(res.failed_change(), Some(0));
+
+    let res = verify_identity_change_history(
+        ctx,
+        VerifyIdentityChangeHistoryRequest::new(&b"not a change history"[..]),
+    )
+    .await?;
+    assert_eq!(
+        res.failure_reason(),
+        Some(ChangeHistoryFailureReason::Malformed)
+    );
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn current_key(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state.clone())).await?,
+    )
+    .await?;
+
+    let (_, identity_id) = create_identity(ctx, "identity_service").await?;
+    let identifier = IdentityIdentifier::try_from(identity_id.as_str())?;
+    cli_state
+        .create_identity_state(&identifier, Some("shared"))
+        .await
+        .unwrap();
+
+    let req = Request::get("shared/current_key").to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: CurrentKeyResponse = dec.decode()?;
+    assert_eq!(res.change_index(), 0);
+    assert!(!res.public_key().is_empty());
+    // The change history doesn't record when the key was created
+    assert!(res.created_at().is_none());
+
+    ctx.stop().await
+}
+
+async fn history_delta(
+    ctx: &mut Context,
+    request: HistoryDeltaRequest<'_>,
+) -> Result<(IdentityHistoryComparison, u64, Option<Vec<u8>>)> {
+    let req = Request::post("actions/history_delta")
+        .body(request)
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: HistoryDeltaResponse = dec.decode()?;
+    Ok((
+        res.comparison().clone(),
+        res.current_length(),
+        res.changes().map(|c| c.to_vec()),
+    ))
+}
+
+#[ockam_macros::test]
+async fn history_delta_returns_missing_changes(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let identities = node.identities();
+    let mut identity = identities.identities_creation().create_identity().await?;
+    let mut known = identity.change_history();
+    let first_change_id = known.as_ref()[0].identifier().to_string();
+
+    identities
+        .identities_keys()
+        .rotate_root_key(&mut identity)
+        .await?;
+    identities.repository().update_identity(&identity).await?;
+    let identity_id = identity.identifier().to_string();
+
+    // only the rotation is missing from the known history
+    let (comparison, current_length, changes) = history_delta(
+        ctx,
+        HistoryDeltaRequest::new(identity_id.as_str())
+            .with_known_history(1, first_change_id.as_str()),
+    )
+    .await?;
+    assert_eq!(comparison, IdentityHistoryComparison::Newer);
+    assert_eq!(current_length, 2);
+    known.import_delta(&changes.unwrap())?;
+    assert_eq!(
+        known.compare(&identity.change_history()),
+        IdentityHistoryComparison::Equal
+    );
+
+    // a client without history gets all the changes
+    let (comparison, _, changes) =
+        history_delta(ctx, HistoryDeltaRequest::new(identity_id.as_str())).await?;
+    assert_eq!(comparison, IdentityHistoryComparison::Newer);
+    assert!(changes.is_some());
+
+    let last_change_id = known.as_ref()[1].identifier().to_string();
+    let (comparison, _, changes) = history_delta(
+        ctx,
+        HistoryDeltaRequest::new(identity_id.as_str())
+            .with_known_history(2, last_change_id.as_str()),
+    )
+    .await?;
+    assert_eq!(comparison, IdentityHistoryComparison::Equal);
+    known.import_delta(&changes.unwrap())?;
+    assert_eq!(known.as_ref().len(), 2);
+
+    // a known history which doesn't match the current one gets no changes
+    let (comparison, _, changes) = history_delta(
+        ctx,
+        HistoryDeltaRequest::new(identity_id.as_str())
+            .with_known_history(1, last_change_id.as_str()),
+    )
+    .await?;
+    assert_eq!(comparison, IdentityHistoryComparison::Conflict);
+    assert!(changes.is_none());
+
+    let (comparison, _, changes) = history_delta(
+        ctx,
+        HistoryDeltaRequest::new(identity_id.as_str())
+            .with_known_history(3, last_change_id.as_str()),
+    )
+    .await?;
+    assert_eq!(comparison, IdentityHistoryComparison::Older);
+    assert!(changes.is_none());
+
+    ctx.stop().await
+}
+
+async fn derived_key_signature(
+    ctx: &mut Context,
+    request: DerivedKeySignatureRequest<'_>,
+) -> Result<(Vec<u8>, Option<Vec<u8>>)> {
+    let req = Request::post("actions/derived_key_signature")
+        .body(request)
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: DerivedKeySignatureResponse = dec.decode()?;
+    Ok((
+        res.public_key().to_vec(),
+        res.signature().map(|s| s.to_vec()),
+    ))
+}
+
+#[ockam_macros::test]
+async fn derived_key_signature_is_deterministic(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let (identity, _) = create_identity(ctx, "identity_service").await?;
+    let data = random::<[u8; 32]>();
+
+    let (public_key, signature) = derived_key_signature(
+        ctx,
+        DerivedKeySignatureRequest::new(identity.as_slice(), &b"salt"[..], &b"session-1"[..])
+            .with_data(&data[..]),
+    )
+    .await?;
+    let signature = signature.expect("a signature must be returned when data is given");
+    let public_key = PublicKey::new(public_key, SecretType::Ed25519);
+    assert!(
+        Vault::create()
+            .verify(&public_key, &data, &Signature::new(signature))
+            .await?
+    );
+
+    // The same parameters derive the same key, other parameters derive another key
+    let (same_key, no_signature) = derived_key_signature(
+        ctx,
+        DerivedKeySignatureRequest::new(identity.as_slice(), &b"salt"[..], &b"session-1"[..]),
+    )
+    .await?;
+    assert_eq!(same_key, public_key.data());
+    assert!(no_signature.is_none());
+
+    let (other_key, _) = derived_key_signature(
+        ctx,
+        DerivedKeySignatureRequest::new(identity.as_slice(), &b"salt"[..], &b"session-2"[..]),
+    )
+    .await?;
+    assert_ne!(other_key, public_key.data());
+
+    ctx.stop().await
+}
+
+/// A vault which stalls when signing, once it has been made slow
+#[derive(Clone)]
+struct SlowVault {
+    slow: Arc<AtomicBool>,
+    vault: Vault,
+}
+
+impl Implementation for SlowVault {}
+
+#[async_trait]
+impl EphemeralSecretsStore for SlowVault {
+    async fn create_ephemeral_secret(&self, attributes: SecretAttributes) -> Result<KeyId> {
+        self.vault.create_ephemeral_secret(attributes).await
+    }
+
+    async fn import_ephemeral_secret(
+        &self,
+        secret: Secret,
+        attributes: SecretAttributes,
+    ) -> Result<KeyId> {
+        self.vault.import_ephemeral_secret(secret, attributes).await
+    }
+
+    async fn get_ephemeral_secret(
+        &self,
+        key_id: &KeyId,
+        description: &str,
+    ) -> Result<StoredSecret> {
+        self.vault.get_ephemeral_secret(key_id, description).await
+    }
+
+    async fn delete_ephemeral_secret(&self, key_id: KeyId) -> Result<bool> {
+        self.vault.delete_ephemeral_secret(key_id).await
+    }
+
+    async fn list_ephemeral_secrets(&self) -> Result<Vec<KeyId>> {
+        self.vault.list_ephemeral_secrets().await
+    }
+}
+
+#[async_trait]
+impl PersistentSecretsStore for SlowVault {
+    async fn create_persistent_secret(&self, attributes: SecretAttributes) -> Result<KeyId> {
+        self.vault.create_persistent_secret(attributes).await
+    }
+
+    async fn delete_persistent_secret(&self, key_id: KeyId) -> Result<bool> {
+        self.vault.delete_persistent_secret(key_id).await
+    }
+}
+
+#[async_trait]
+impl SecretsStoreReader for SlowVault {
+    async fn get_secret_attributes(&self, key_id: &KeyId) -> Result<SecretAttributes> {
+        self.vault.get_secret_attributes(key_id).await
+    }
+
+    async fn get_public_key(&self, key_id: &KeyId) -> Result<PublicKey> {
+        self.vault.get_public_key(key_id).await
+    }
+
+    async fn get_key_id(&self, public_key: &PublicKey) -> Result<KeyId> {
+        self.vault.get_key_id(public_key).await
+    }
+}
+
+#[async_trait]
+impl Signer for SlowVault {
+    async fn sign(&self, key_id: &KeyId, data: &[u8]) -> Result<Signature> {
+        if self.slow.load(Ordering::Relaxed) {
+            sleep(Duration::from_secs(60)).await;
+        }
+        self.vault.sign(key_id, data).await
+    }
+
+    async fn verify(
+        &self,
+        public_key: &PublicKey,
+        data: &[u8],
+        signature: &Signature,
+    ) -> Result<bool> {
+        self.vault.verify(public_key, data, signature).await
+    }
+}
+
+#[ockam_macros::test]
+async fn request_timeout(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let slow = Arc::new(AtomicBool::new(false));
+    let vault = SlowVault {
+        slow: slow.clone(),
+        vault: Vault::new(),
+    };
+    let identities = Identities::builder()
+        .with_identities_vault(Arc::new(vault))
+        .build();
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new_with_options(
+            NodeIdentities::new(identities, cli_state),
+            IdentityServiceOptions::new().with_request_timeout(Duration::from_millis(200)),
+        )
+        .await?,
+    )
+    .await?;
+
+    let (identity, _) = create_identity(ctx, "identity_service").await?;
+
+    // a stalled signature is aborted
+    slow.store(true, Ordering::Relaxed);
+    let req = Request::post("actions/create_signature")
+        .body(CreateSignatureRequest::new(
+            identity.as_slice(),
+            b"data".as_slice(),
+        ))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::GatewayTimeout));
+
+    // the worker still processes the next requests
+    let req = Request::get("health").to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: HealthResponse = dec.decode()?;
+    assert_eq!(res.in_flight_requests(), 1);
+
+    slow.store(false, Ordering::Relaxed);
+    let signature = create_signature(ctx, &identity, b"data", "identity_service").await?;
+    assert!(!signature.is_empty());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn reject_queued_requests_when_overloaded(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let slow = Arc::new(AtomicBool::new(false));
+    let vault = SlowVault {
+        slow: slow.clone(),
+        vault: Vault::new(),
+    };
+    let identities = Identities::builder()
+        .with_identities_vault(Arc::new(vault))
+        .build();
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new_with_options(
+            NodeIdentities::new(identities, cli_state),
+            IdentityServiceOptions::new()
+                .with_max_in_flight_requests(2)
+                .with_request_timeout(Duration::from_millis(300)),
+        )
+        .await?,
+    )
+    .await?;
+
+    let (identity, _) = create_identity(ctx, "identity_service").await?;
+
+    // while the first signature stalls, the second request waits and the next ones are rejected
+    slow.store(true, Ordering::Relaxed);
+    let req = Request::post("actions/create_signature")
+        .body(CreateSignatureRequest::new(
+            identity.as_slice(),
+            b"data".as_slice(),
+        ))
+        .to_vec()?;
+    for _ in 0..4 {
+        ctx.send(route!["identity_service"], req.clone()).await?;
+    }
+    let mut statuses = vec![];
+    for _ in 0..4 {
+        let receiving_buf = ctx.receive::<Vec<u8>>().await?.body();
+        let res: Response = Decoder::new(&receiving_buf).decode()?;
+        statuses.push(res.status());
+    }
+    let rejected = statuses
+        .iter()
+        .filter(|status| **status == Some(Status::ServiceUnavailable))
+        .count();
+    let timed_out = statuses
+        .iter()
+        .filter(|status| **status == Some(Status::GatewayTimeout))
+        .count();
+    assert_eq!((rejected, timed_out), (2, 2));
+
+    // the requests are accepted again once the queue is drained
+    slow.store(false, Ordering::Relaxed);
+    let req = Request::get("health").to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: HealthResponse = dec.decode()?;
+    assert_eq!(res.in_flight_requests(), 1);
+    assert_eq!(res.max_in_flight_requests(), 2);
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn ping_vault(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+    cli_state
+        .vaults
+        .create_async("v1", VaultConfig::default())
+        .await
+        .unwrap();
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let req = Request::get("vault/v1/ping").to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: VaultPingResponse = dec.decode()?;
+    assert_eq!(res.vault_name(), "v1");
+    assert!(res.available());
+    assert!(res.error().is_none());
+
+    let req = Request::get("vault/unknown/ping").to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::NotFound));
+
+    ctx.stop().await
+}
+
+async fn threshold_sign_request<B: minicbor::Encode<()>>(
+    ctx: &mut Context,
+    action: &str,
+    body: B,
+) -> Result<Vec<u8>> {
+    let req = Request::post(format!("actions/{action}"))
+        .body(body)
+        .to_vec()?;
+    ctx.send_and_receive(route!["identity_service"], req).await
+}
+
+#[ockam_macros::test]
+async fn threshold_signature(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state.clone())).await?,
+    )
+    .await?;
+
+    let (local, local_id) = create_identity(ctx, "identity_service").await?;
+    let identifier = IdentityIdentifier::try_from(local_id.as_str())?;
+    cli_state
+        .create_identity_state(&identifier, Some("local"))
+        .await
+        .unwrap();
+    let (cosigner1, _) = create_identity(ctx, "identity_service").await?;
+    let (cosigner2, _) = create_identity(ctx, "identity_service").await?;
+    let signers: Vec<CowBytes> = vec![
+        local.as_slice().into(),
+        cosigner1.as_slice().into(),
+        cosigner2.as_slice().into(),
+    ];
+    let data = random::<[u8; 32]>();
+
+    // the threshold can't be larger than the number of signers
+    let receiving_buf = threshold_sign_request(
+        ctx,
+        "begin_threshold_sign",
+        BeginThresholdSignRequest::new(&data[..], signers.clone(), 4),
+    )
+    .await?;
+    let res: Response = Decoder::new(&receiving_buf).decode()?;
+    assert_eq!(res.status(), Some(Status::BadRequest));
+
+    // the service contributes the partial signature of the local identity
+    let receiving_buf = threshold_sign_request(
+        ctx,
+        "begin_threshold_sign",
+        BeginThresholdSignRequest::new(&data[..], signers, 2).with_identity_name("local"),
+    )
+    .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let session_id = dec.decode::<BeginSignResponse>()?.session_id().to_string();
+
+    let receiving_buf =
+        threshold_sign_request(ctx, "combine", CombineRequest::new(session_id.as_str())).await?;
+    let res: Response = Decoder::new(&receiving_buf).decode()?;
+    assert_eq!(res.status(), Some(Status::BadRequest));
+
+    // a partial signature which doesn't sign the payload is rejected
+    let other_signature = create_signature(ctx, &cosigner1, b"other", "identity_service").await?;
+    let receiving_buf = threshold_sign_request(
+        ctx,
+        "submit_partial",
+        SubmitPartialRequest::new(session_id.as_str(), 1, other_signature),
+    )
+    .await?;
+    let res: Response = Decoder::new(&receiving_buf).decode()?;
+    assert_eq!(res.status(), Some(Status::BadRequest));
+
+    let partial = create_signature(ctx, &cosigner2, &data, "identity_service").await?;
+    let receiving_buf = threshold_sign_request(
+        ctx,
+        "submit_partial",
+        SubmitPartialRequest::new(session_id.as_str(), 2, partial),
+    )
+    .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: SubmitPartialResponse = dec.decode()?;
+    assert_eq!((res.collected(), res.threshold()), (2, 2));
+
+    let receiving_buf =
+        threshold_sign_request(ctx, "combine", CombineRequest::new(session_id.as_str())).await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let threshold_signature: ThresholdSignature = dec.decode()?;
+    assert_eq!(threshold_signature.threshold(), 2);
+    let partials = threshold_signature.partials();
+    assert_eq!(
+        partials
+            .iter()
+            .map(|p| p.signer_index())
+            .collect::<Vec<_>>(),
+        vec![0, 2]
+    );
+    assert_eq!(partials[0].identity_id(), local_id);
+    assert!(
+        verify_signature(
+            ctx,
+            &local,
+            &data,
+            partials[0].signature(),
+            "identity_service"
+        )
+        .await?
+    );
+    assert!(
+        verify_signature(
+            ctx,
+            &cosigner2,
+            &data,
+            partials[1].signature(),
+            "identity_service"
+        )
+        .await?
+    );
+
+    // the session is closed once the signature is combined
+    let receiving_buf =
+        threshold_sign_request(ctx, "combine", CombineRequest::new(session_id.as_str())).await?;
+    let res: Response = Decoder::new(&receiving_buf).decode()?;
+    assert_eq!(res.status(), Some(Status::BadRequest));
+
+    ctx.stop().await
+}
+
+async fn purge_sessions(
+    ctx: &mut Context,
+    route: Route,
+    idle_for_secs: u64,
+) -> Result<std::result::Result<u64, Status>> {
+    let req = Request::post("admin/purge_sessions")
+        .body(PurgeSessionsRequest::new(idle_for_secs))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx.send_and_receive(route, req).await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    if res.status() != Some(Status::Ok) {
+        return Ok(Err(res.status().unwrap()));
+    }
+    Ok(Ok(dec.decode::<PurgeSessionsResponse>()?.purged()))
+}
+
+#[ockam_macros::test]
+async fn purge_signing_sessions(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+    let admin = node.create_identity().await?;
+    let other = node.create_identity().await?;
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new_with_options(
+            NodeIdentities::new(node.identities(), cli_state),
+            IdentityServiceOptions::new().with_admin(admin.identifier()),
+        )
+        .await?,
+    )
+    .await?;
+    let server = node.create_identity().await?;
+    let listener_options = SecureChannelListenerOptions::new();
+    ctx.flow_controls().add_consumer(
+        "identity_service",
+        &listener_options.spawner_flow_control_id(),
+    );
+    node.create_secure_channel_listener(&server, "api", listener_options)
+        .await?;
+    let admin_channel = node
+        .create_secure_channel(&admin, route!["api"], SecureChannelOptions::new())
+        .await?;
+    let other_channel = node
+        .create_secure_channel(&other, route!["api"], SecureChannelOptions::new())
+        .await?;
+    let admin_route = route![
+        admin_channel.encryptor_address().clone(),
+        "identity_service"
+    ];
+    let other_route = route![
+        other_channel.encryptor_address().clone(),
+        "identity_service"
+    ];
+
+    let (identity, _) = create_identity(ctx, "identity_service").await?;
+
+    let req = Request::post("actions/begin_sign")
+        .body(BeginSignRequest::new(identity.as_slice()))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let session_id = dec.decode::<BeginSignResponse>()?.session_id().to_string();
+
+    // Only an administrator, authenticated by a secure channel, can purge the sessions
+    assert_eq!(
+        purge_sessions(ctx, other_route, 0).await?,
+        Err(Status::Forbidden)
+    );
+    assert_eq!(
+        purge_sessions(ctx, route!["identity_service"], 0).await?,
+        Err(Status::Forbidden)
+    );
+
+    // A session which was recently active is kept
+    assert_eq!(purge_sessions(ctx, admin_route.clone(), 3600).await?, Ok(0));
+
+    // All the sessions are purged when no idle time is given
+    assert_eq!(purge_sessions(ctx, admin_route, 0).await?, Ok(1));
+
+    // A purged session is cancelled
+    let req = Request::post("actions/sign_chunk")
+        .body(SignChunkRequest::new(session_id.as_str(), &[1, 2, 3][..]))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let res: Response = Decoder::new(&receiving_buf).decode()?;
+    assert_eq!(res.status(), Some(Status::BadRequest));
+
+    ctx.stop().await
+}
+
+async fn verify_remote(
+    ctx: &mut Context,
+    directory_route: &str,
+    signer_name: &str,
+    data: &[u8],
+    signature: &[u8],
+) -> Result<VerifySignatureResponse> {
+    let req = Request::post("actions/verify_remote")
+        .body(VerifyRemoteSignatureRequest::new(
+            directory_route,
+            signer_name,
+            data,
+            signature,
+        ))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    dec.decode()
+}
+
+#[ockam_macros::test]
+async fn verify_signature_with_remote_identity(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "directory",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state.clone())).await?,
+    )
+    .await?;
+    let options = IdentityServiceOptions::default()
+        .with_remote_identity_fetch_timeout(Duration::from_secs(1));
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new_with_options(
+            NodeIdentities::new(node.identities(), cli_state.clone()),
+            options,
+        )
+        .await?,
+    )
+    .await?;
+
+    let (identity, identity_id) = create_identity(ctx, "directory").await?;
+    let identifier = IdentityIdentifier::try_from(identity_id.as_str())?;
+    cli_state
+        .create_identity_state(&identifier, Some("signer"))
+        .await
+        .unwrap();
+    let data = random::<[u8; 32]>();
+    let signature = create_signature(ctx, &identity, &data, "directory").await?;
+
+    let res = verify_remote(ctx, "directory", "signer", &data, &signature).await?;
+    assert!(res.verified());
+
+    let res = verify_remote(ctx, "directory", "signer", b"other data", &signature).await?;
+    assert!(!res.verified());
+    assert_eq!(
+        res.failure_reason(),
+        Some(VerificationFailureReason::KeyMismatch)
+    );
+
+    // The fetched identity is cached, so the directory is not needed anymore
+    ctx.stop_worker("directory").await?;
+    let res = verify_remote(ctx, "directory", "signer", &data, &signature).await?;
+    assert!(res.verified());
+
+    // A directory which can't be reached is reported as such
+    let res = verify_remote(ctx, "unknown_directory", "signer", &data, &signature).await?;
+    assert!(!res.verified());
+    assert_eq!(
+        res.failure_reason(),
+        Some(VerificationFailureReason::SignerUnavailable)
+    );
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn verify_remote_refuses_an_outdated_change_history(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let directory_node = node(ctx.async_try_clone().await?);
+    ctx.start_worker(
+        "directory",
+        IdentityService::new(NodeIdentities::new(
+            directory_node.identities(),
+            cli_state.clone(),
+        ))
+        .await?,
+    )
+    .await?;
+    let service_node = node(ctx.async_try_clone().await?);
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(
+            service_node.identities(),
+            cli_state.clone(),
+        ))
+        .await?,
+    )
+    .await?;
+
+    let identities = directory_node.identities();
+    let mut signer = identities.identities_creation().create_identity().await?;
+    cli_state
+        .create_identity_state(&signer.identifier(), Some("signer"))
+        .await
+        .unwrap();
+    let data = random::<[u8; 32]>();
+    let old_signature = identities
+        .identities_keys()
+        .create_signature(&signer, &data, None)
+        .await?;
+
+    // the node running the identity service already knows about a key rotation which the
+    // directory still doesn't publish
+    identities
+        .identities_keys()
+        .rotate_root_key(&mut signer)
+        .await?;
+    service_node
+        .identities()
+        .repository()
+        .update_identity(&signer)
+        .await?;
+    let res = verify_remote(ctx, "directory", "signer", &data, old_signature.as_ref()).await?;
+    assert!(!res.verified());
+    assert_eq!(
+        res.failure_reason(),
+        Some(VerificationFailureReason::SignerUnavailable)
+    );
+
+    // the rotated key is used once the directory publishes the new change history
+    identities.repository().update_identity(&signer).await?;
+    let signature = identities
+        .identities_keys()
+        .create_signature(&signer, &data, None)
+        .await?;
+    let res = verify_remote(ctx, "directory", "signer", &data, signature.as_ref()).await?;
+    assert!(res.verified());
+    let res = verify_remote(ctx, "directory", "signer", &data, old_signature.as_ref()).await?;
+    assert!(!res.verified());
+    assert_eq!(
+        res.failure_reason(),
+        Some(VerificationFailureReason::KeyMismatch)
+    );
+
+    ctx.stop().await
+}
+
+/// Verify a timestamp token and return the failure reason, or the asserted time
+async fn verify_timestamp(
+    ctx: &mut Context,
+    signer: &[u8],
+    digest: &[u8],
+    token: TimestampToken<'_>,
+) -> Result<std::result::Result<u64, TimestampFailureReason>> {
+    let req = Request::post("actions/verify_timestamp")
+        .body(VerifyTimestampRequest::new(signer, digest, token))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: VerifyTimestampResponse = dec.decode()?;
+    match (res.time(), res.failure_reason()) {
+        (Some(time), None) => {
+            assert!(res.verified());
+            Ok(Ok(time.unix_time()))
+        }
+        (_, Some(reason)) => {
+            assert!(!res.verified());
+            Ok(Err(reason))
+        }
+        (None, None) => panic!("a verified timestamp token must have a time"),
+    }
+}
+
+#[ockam_macros::test]
+async fn timestamp_digest(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state.clone())).await?,
+    )
+    .await?;
+
+    let (tsa, tsa_id) = create_identity(ctx, "identity_service").await?;
+    cli_state
+        .create_identity_state(&IdentityIdentifier::try_from(tsa_id.as_str())?, Some("tsa"))
+        .await
+        .unwrap();
+    let (other, _) = create_identity(ctx, "identity_service").await?;
+
+    let digest = Sha256::digest(b"document").to_vec();
+    let req = Request::post("actions/timestamp")
+        .body(TimestampRequest::new("tsa", digest.as_slice()))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let token = dec.decode::<TimestampResponse>()?.token().clone();
+
+    // The token names the timestamping identity and asserts the time of the service clock
+    let data: TimestampTokenData = minicbor::decode(token.data())?;
+    assert_eq!(data.signer(), tsa_id);
+    let now = Timestamp::now().unwrap().unix_time();
+    let time = verify_timestamp(ctx, &tsa, &digest, token.clone())
+        .await?
+        .unwrap();
+    assert!(time <= now && now - time < 60);
+
+    assert_eq!(
+        verify_timestamp(ctx, &tsa, &Sha256::digest(b"other").to_vec(), token.clone()).await?,
+        Err(TimestampFailureReason::DigestMismatch)
+    );
+    assert_eq!(
+        verify_timestamp(ctx, &other, &digest, token).await?,
+        Err(TimestampFailureReason::InvalidSignature)
+    );
+
+    // A digest must be provided
+    let req = Request::post("actions/timestamp")
+        .body(TimestampRequest::new("tsa", &[][..]))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let res: Response = Decoder::new(&receiving_buf).decode()?;
+    assert_eq!(res.status(), Some(Status::BadRequest));
+
+    ctx.stop().await
+}
+
+async fn store_import(
+    ctx: &mut Context,
+    admin: &[u8],
+    bundle: IdentitiesBundle<'_>,
+    on_collision: NameCollisionPolicy,
+) -> Result<(Status, Vec<String>, Vec<String>)> {
+    let req = Request::post("actions/store_import")
+        .body(StoreImportRequest::new(admin, bundle).with_collision_policy(on_collision))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx.send_and_receive(route!["importer"], req).await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    let status = res.status().unwrap();
+    if status != Status::Ok {
+        return Ok((status, vec![], vec![]));
+    }
+    let res: StoreImportResponse = dec.decode()?;
+    Ok((status, res.imported(), res.skipped()))
+}
+
+#[ockam_macros::test]
+async fn export_and_import_identities_store(ctx: &mut Context) -> Result<()> {
+    let exporter_state = CliState::test().unwrap();
+    let exporter_node = node(ctx.async_try_clone().await?);
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(
+            exporter_node.identities(),
+            exporter_state.clone(),
+        ))
+        .await?,
+    )
+    .await?;
+    let importer_state = CliState::test().unwrap();
+    let importer_node = node(ctx.async_try_clone().await?);
+    ctx.start_worker(
+        "importer",
+        IdentityService::new(NodeIdentities::new(
+            importer_node.identities(),
+            importer_state.clone(),
+        ))
+        .await?,
+    )
+    .await?;
+
+    let mut identifiers = vec![];
+    let mut exported = vec![];
+    for name in ["admin", "alice", "bob"] {
+        let (identity, identity_id) = create_identity(ctx, "identity_service").await?;
+        let identifier = IdentityIdentifier::try_from(identity_id.as_str())?;
+        exporter_state
+            .create_identity_state(&identifier, Some(name))
+            .await
+            .unwrap();
+        identifiers.push(identifier);
+        exported.push(identity);
+    }
+    let admin = exported[0].clone();
+
+    let req = Request::get("store/export")
+        .body(StoreExportRequest::new("admin"))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let bundle: IdentitiesBundle = dec.decode()?;
+
+    // The importing node already has another identity named 'alice'
+    let (_, other_id) = create_identity(ctx, "importer").await?;
+    importer_state
+        .create_identity_state(
+            &IdentityIdentifier::try_from(other_id.as_str())?,
+            Some("alice"),
+        )
+        .await
+        .unwrap();
+
+    // A bundle which is not signed by the trusted admin identity is rejected
+    let (status, _, _) = store_import(
+        ctx,
+        &exported[1],
+        bundle.clone(),
+        NameCollisionPolicy::Overwrite,
+    )
+    .await?;
+    assert_eq!(status, Status::Forbidden);
+
+    let (status, _, _) =
+        store_import(ctx, &admin, bundle.clone(), NameCollisionPolicy::Fail).await?;
+    assert_eq!(status, Status::Conflict);
+    assert!(!importer_state.identities.exists("bob"));
+
+    let (status, imported, skipped) =
+        store_import(ctx, &admin, bundle.clone(), NameCollisionPolicy::Skip).await?;
+    assert_eq!(status, Status::Ok);
+    assert_eq!(imported, vec!["admin".to_string(), "bob".to_string()]);
+    assert_eq!(skipped, vec!["alice".to_string()]);
+    assert_eq!(
+        importer_state
+            .identities
+            .get("alice")
+            .unwrap()
+            .identifier()
+            .to_string(),
+        other_id
+    );
+
+    let (status, imported, skipped) =
+        store_import(ctx, &admin, bundle, NameCollisionPolicy::Overwrite).await?;
+    assert_eq!(status, Status::Ok);
+    assert_eq!(imported.len(), 3);
+    assert!(skipped.is_empty());
+    for (name, identifier) in ["admin", "alice", "bob"].iter().zip(identifiers.iter()) {
+        assert_eq!(
+            &importer_state.identities.get(name).unwrap().identifier(),
+            identifier
+        );
+    }
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn rate_limit_per_sender_identity(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+    let options = IdentityServiceOptions::new().with_rate_limit(RateLimit::new(1, 2));
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new_with_options(
+            NodeIdentities::new(node.identities(), cli_state),
+            options,
+        )
+        .await?,
+    )
+    .await?;
+
+    let server = node.create_identity().await?;
+    let listener_options = SecureChannelListenerOptions::new();
+    ctx.flow_controls().add_consumer(
+        "identity_service",
+        &listener_options.spawner_flow_control_id(),
+    );
+    node.create_secure_channel_listener(&server, "api", listener_options)
+        .await?;
+
+    let alice = node.create_identity().await?;
+    let bob = node.create_identity().await?;
+    let alice_channel = node
+        .create_secure_channel(&alice, route!["api"], SecureChannelOptions::new())
+        .await?;
+    let bob_channel = node
+        .create_secure_channel(&bob, route!["api"], SecureChannelOptions::new())
+        .await?;
+
+    async fn health(ctx: &mut Context, route: Route) -> Result<Option<Status>> {
+        let req = Request::get("health").to_vec()?;
+        let receiving_buf: Vec<u8> = ctx.send_and_receive(route, req).await?;
+        let mut dec = Decoder::new(&receiving_buf);
+        let res: Response = dec.decode()?;
+        Ok(res.status())
+    }
+
+    // alice can send a burst of 2 requests, then she is limited
+    let alice_route = route![
+        alice_channel.encryptor_address().clone(),
+        "identity_service"
+    ];
+    assert_eq!(health(ctx, alice_route.clone()).await?, Some(Status::Ok));
+    assert_eq!(health(ctx, alice_route.clone()).await?, Some(Status::Ok));
+    assert_eq!(
+        health(ctx, alice_route.clone()).await?,
+        Some(Status::TooManyRequests)
+    );
+
+    // bob is not limited by the requests of alice
+    let bob_route = route![bob_channel.encryptor_address().clone(), "identity_service"];
+    assert_eq!(health(ctx, bob_route).await?, Some(Status::Ok));
+
+    // the requests which are not received over a secure channel are not limited
+    for _ in 0..3 {
+        assert_eq!(
+            health(ctx, route!["identity_service"]).await?,
+            Some(Status::Ok)
+        );
+    }
+
+    // the bucket of alice is refilled after a while
+    sleep(Duration::from_millis(1100)).await;
+    assert_eq!(health(ctx, alice_route).await?, Some(Status::Ok));
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn same_signer(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let (alice, alice_id) = create_identity(ctx, "identity_service").await?;
+    let (bob, bob_id) = create_identity(ctx, "identity_service").await?;
+    let (carol, _) = create_identity(ctx, "identity_service").await?;
+
+    let first = SignedData::new(
+        b"first".to_vec(),
+        create_signature(ctx, &alice, b"first", "identity_service").await?,
+    );
+    let second = SignedData::new(
+        b"second".to_vec(),
+        create_signature(ctx, &alice, b"second", "identity_service").await?,
+    );
+    let by_bob = SignedData::new(
+        b"third".to_vec(),
+        create_signature(ctx, &bob, b"third", "identity_service").await?,
+    );
+    let by_carol = SignedData::new(
+        b"fourth".to_vec(),
+        create_signature(ctx, &carol, b"fourth", "identity_service").await?,
+    );
+    let candidates = vec![CowBytes::from(alice.clone()), CowBytes::from(bob)];
+
+    async fn compare(
+        ctx: &mut Context,
+        first: &SignedData<'_>,
+        second: &SignedData<'_>,
+        candidates: &[CowBytes<'_>],
+    ) -> Result<(SignerComparison, Option<String>, Option<String>)> {
+        let req = Request::post("actions/same_signer")
+            .body(SameSignerRequest::new(
+                first.clone(),
+                second.clone(),
+                candidates.to_vec(),
+            ))
+            .to_vec()?;
+        let receiving_buf: Vec<u8> = ctx
+            .send_and_receive(route!["identity_service"], req)
+            .await?;
+        let mut dec = Decoder::new(&receiving_buf);
+        let res: Response = dec.decode()?;
+        assert_eq!(res.status(), Some(Status::Ok));
+        let res: SameSignerResponse = dec.decode()?;
+        Ok((
+            res.comparison(),
+            res.first_signer().map(|s| s.to_string()),
+            res.second_signer().map(|s| s.to_string()),
+        ))
+    }
+
+    let (comparison, first_signer, second_signer) =
+        compare(ctx, &first, &second, &candidates).await?;
+    assert_eq!(comparison, SignerComparison::Same);
+    assert_eq!(first_signer, Some(alice_id.clone()));
+    assert_eq!(second_signer, Some(alice_id.clone()));
+
+    let (comparison, first_signer, second_signer) =
+        compare(ctx, &first, &by_bob, &candidates).await?;
+    assert_eq!(comparison, SignerComparison::Different);
+    assert_eq!(first_signer, Some(alice_id.clone()));
+    assert_eq!(second_signer, Some(bob_id));
+
+    // carol is not a candidate
+    let (comparison, first_signer, second_signer) =
+        compare(ctx, &first, &by_carol, &candidates).await?;
+    assert_eq!(comparison, SignerComparison::Unverifiable);
+    assert_eq!(first_signer, Some(alice_id));
+    assert_eq!(second_signer, None);
+
+    // a signature over other data is unverifiable
+    let tampered = SignedData::new(b"tampered".to_vec(), second.signature().to_vec());
+    let (comparison, _, second_signer) = compare(ctx, &first, &tampered, &candidates).await?;
+    assert_eq!(comparison, SignerComparison::Unverifiable);
+    assert_eq!(second_signer, None);
+
+    // at least one candidate is required
+    let req = Request::post("actions/same_signer")
+        .body(SameSignerRequest::new(first, second, vec![]))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::BadRequest));
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn key_types_of_an_identity(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state.clone())).await?,
+    )
+    .await?;
+
+    let identities = node.identities();
+    let mut identity = identities.identities_creation().create_identity().await?;
+    let p256_key = identities
+        .vault()
+        .create_persistent_secret(SecretAttributes::NistP256)
+        .await?;
+    identities
+        .identities_keys()
+        .add_key(&mut identity, "p256".to_string(), &p256_key)
+        .await?;
+    identities
+        .identities_keys()
+        .rotate_root_key(&mut identity)
+        .await?;
+    identities.repository().update_identity(&identity).await?;
+    cli_state
+        .create_identity_state(&identity.identifier(), Some("rotated"))
+        .await
+        .unwrap();
+
+    let req = Request::get("rotated/key_types").to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: KeyTypesResponse = dec.decode()?;
+    assert_eq!(
+        res.key_types(),
+        &[SecretType::Ed25519, SecretType::NistP256]
+    );
+
+    let req = Request::get("unknown/key_types").to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::BadRequest));
+
+    ctx.stop().await
+}
+
+async fn verify_signature_by_id(
+    ctx: &mut Context,
+    request: VerifySignatureRequest<'_>,
+) -> Result<(bool, Option<VerificationFailureReason>)> {
+    let req = Request::post("actions/verify_signature")
+        .body(request)
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: VerifySignatureResponse = dec.decode()?;
+    Ok((res.verified(), res.failure_reason()))
+}
+
+#[ockam_macros::test]
+async fn verify_signature_with_cached_signer(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let service_node = node(ctx.async_try_clone().await?);
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(service_node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    // the signer is not known to the node running the identity service
+    let signer_node = node(ctx.async_try_clone().await?);
+    let identities = signer_node.identities();
+    let mut signer = identities.identities_creation().create_identity().await?;
+    let signer_id = signer.identifier().to_string();
+    let signature = identities
+        .identities_keys()
+        .create_signature(&signer, b"data", None)
+        .await?;
+
+    let (verified, failure_reason) = verify_signature_by_id(
+        ctx,
+        VerifySignatureRequest::with_signer_id(
+            signer_id.as_str(),
+            b"data".to_vec(),
+            signature.as_ref().to_vec(),
+        ),
+    )
+    .await?;
+    assert!(!verified);
+    assert_eq!(
+        failure_reason,
+        Some(VerificationFailureReason::UnknownSigner)
+    );
+
+    // the signer is cached once its identity was sent
+    let exported = signer.export()?;
+    for _ in 0..2 {
+        assert!(
+            verify_signature(
+                ctx,
+                &exported,
+                b"data",
+                signature.as_ref(),
+                "identity_service"
+            )
+            .await?
+        );
+    }
+    let (verified, _) = verify_signature_by_id(
+        ctx,
+        VerifySignatureRequest::with_signer_id(
+            signer_id.as_str(),
+            b"data".to_vec(),
+            signature.as_ref().to_vec(),
+        ),
+    )
+    .await?;
+    assert!(verified);
+
+    // a new change history replaces the cached one
+    let old_signature = signature.as_ref().to_vec();
+    identities
+        .identities_keys()
+        .rotate_root_key(&mut signer)
+        .await?;
+    let signature = identities
+        .identities_keys()
+        .create_signature(&signer, b"data", None)
+        .await?;
+    let (verified, failure_reason) = verify_signature_by_id(
+        ctx,
+        VerifySignatureRequest::with_signer_id(
+            signer_id.as_str(),
+            b"data".to_vec(),
+            signature.as_ref().to_vec(),
+        ),
+    )
+    .await?;
+    assert!(!verified);
+    assert_eq!(failure_reason, Some(VerificationFailureReason::KeyMismatch));
+    assert!(
+        verify_signature(
+            ctx,
+            &signer.export()?,
+            b"data",
+            signature.as_ref(),
+            "identity_service"
+        )
+        .await?
+    );
+    let (verified, _) = verify_signature_by_id(
+        ctx,
+        VerifySignatureRequest::with_signer_id(
+            signer_id.as_str(),
+            b"data".to_vec(),
+            signature.as_ref().to_vec(),
+        ),
+    )
+    .await?;
+    assert!(verified);
+
+    // the change history from before the rotation can still be sent but doesn't replace
+    // the cached one, so the rotated-out key is not trusted for that signer anymore
+    assert!(verify_signature(ctx, &exported, b"data", &old_signature, "identity_service").await?);
+    let (verified, failure_reason) = verify_signature_by_id(
+        ctx,
+        VerifySignatureRequest::with_signer_id(signer_id.as_str(), b"data".to_vec(), old_signature),
+    )
+    .await?;
+    assert!(!verified);
+    assert_eq!(failure_reason, Some(VerificationFailureReason::KeyMismatch));
+    let (verified, _) = verify_signature_by_id(
+        ctx,
+        VerifySignatureRequest::with_signer_id(
+            signer_id.as_str(),
+            b"data".to_vec(),
+            signature.as_ref().to_vec(),
+        ),
+    )
+    .await?;
+    assert!(verified);
+
+    let req = Request::get("metrics").to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: MetricsResponse = dec.decode()?;
+    assert_eq!(
+        res.get("identity_service_verification_key_cache_hits_total"),
+        Some(6)
+    );
+    assert_eq!(
+        res.get("identity_service_verification_key_cache_misses_total"),
+        Some(4)
+    );
+    assert_eq!(
+        res.get("identity_service_verification_key_cache_entries"),
+        Some(1)
+    );
+
+    ctx.stop().await
+}
+
+async fn create_from_public_key(
+    ctx: &mut Context,
+    request: CreateFromPublicKeyRequest<'_>,
+) -> Result<(Status, Option<CreateFromPublicKeyResponse<'static>>)> {
+    let req = Request::post("actions/create_from_public_key")
+        .body(request)
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    let status = res.status().unwrap();
+    if status != Status::Ok {
+        return Ok((status, None));
+    }
+    let res: CreateFromPublicKeyResponse = dec.decode()?;
+    let res = match (res.identity(), res.identity_id()) {
+        (Some(identity), Some(identity_id)) => CreateFromPublicKeyResponse::new(
+            res.change_id().to_vec(),
+            identity.to_vec(),
+            identity_id.to_string(),
+        ),
+        _ => CreateFromPublicKeyResponse::unsigned(res.change_id().to_vec()),
+    };
+    Ok((status, Some(res)))
+}
+
+#[ockam_macros::test]
+async fn create_identity_from_external_public_key(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let service_node = node(ctx.async_try_clone().await?);
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(service_node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    // the key is held by another vault, standing for an HSM
+    let external_vault = Vault::create();
+    let key_id = external_vault
+        .create_ephemeral_secret(SecretAttributes::Ed25519)
+        .await?;
+    let public_key = external_vault.get_public_key(&key_id).await?;
+
+    let (status, res) = create_from_public_key(
+        ctx,
+        CreateFromPublicKeyRequest::new("ed25519", public_key.data()),
+    )
+    .await?;
+    assert_eq!(status, Status::Ok);
+    let res = res.unwrap();
+    assert!(res.identity().is_none());
+    let change_id = res.change_id().to_vec();
+
+    // a signature from another key is rejected
+    let other_key_id = external_vault
+        .create_ephemeral_secret(SecretAttributes::Ed25519)
+        .await?;
+    let wrong_signature = external_vault.sign(&other_key_id, &change_id).await?;
+    let (status, _) = create_from_public_key(
+        ctx,
+        CreateFromPublicKeyRequest::new("ed25519", public_key.data())
+            .with_self_signature(wrong_signature.as_ref()),
+    )
+    .await?;
+    assert_eq!(status, Status::BadRequest);
+
+    let self_signature = external_vault.sign(&key_id, &change_id).await?;
+    let (status, res) = create_from_public_key(
+        ctx,
+        CreateFromPublicKeyRequest::new("ed25519", public_key.data())
+            .with_self_signature(self_signature.as_ref())
+            .with_name("hsm"),
+    )
+    .await?;
+    assert_eq!(status, Status::Ok);
+    let res = res.unwrap();
+    let identity = res.identity().unwrap().to_vec();
+
+    // signatures created with the external key are verified
+    let data = b"signed outside of the node";
+    let signature = external_vault.sign(&key_id, data).await?;
+    let (verified, _) = verify_signature_by_id(
+        ctx,
+        VerifySignatureRequest::new(identity.as_slice(), &data[..], signature.as_ref()),
+    )
+    .await?;
+    assert!(verified);
+    let (verified, _) = verify_signature_by_id(
+        ctx,
+        VerifySignatureRequest::with_signer_id(
+            res.identity_id().unwrap(),
+            &data[..],
+            signature.as_ref(),
+        ),
+    )
+    .await?;
+    assert!(verified);
+
+    // but the node can't sign for the identity
+    let req = Request::post("actions/create_signature")
+        .body(CreateSignatureRequest::new(identity.as_slice(), &data[..]))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_ne!(res.status(), Some(Status::Ok));
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn service_signing_identity(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let service_node = node(ctx.async_try_clone().await?);
+    let identity = service_node
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    cli_state
+        .create_identity_state(&identity.identifier(), Some("attestations"))
+        .await
+        .unwrap();
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new_with_options(
+            NodeIdentities::new(service_node.identities(), cli_state.clone()),
+            IdentityServiceOptions::new().with_service_identity("attestations"),
+        )
+        .await?,
+    )
+    .await?;
+    ctx.start_worker(
+        "unconfigured_identity_service",
+        IdentityService::new(NodeIdentities::new(service_node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let req = Request::get("service/identity").to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: ServiceIdentityResponse = dec.decode()?;
+    assert_eq!(res.name(), "attestations");
+    assert_eq!(res.identity(), identity.export()?.as_slice());
+    assert_eq!(res.identity_id(), identity.identifier().to_string());
+
+    // the operator didn't set a signing identity
+    let req = Request::get("service/identity").to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["unconfigured_identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::NotFound));
+
+    ctx.stop().await
+}
+
+/// Sign some data and return the signature, with the rotated identity if the key was rotated
+async fn create_signature_with_rotation(
+    ctx: &mut Context,
+    identity: &[u8],
+    data: &[u8],
+    service_address: &str,
+) -> Result<(Vec<u8>, Option<Vec<u8>>)> {
+    let req = Request::post("actions/create_signature")
+        .body(CreateSignatureRequest::new(identity, data))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx.send_and_receive(route![service_address], req).await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: CreateSignatureResponse = dec.decode()?;
+    assert_eq!(res.key_rotated(), res.rotated_identity().is_some());
+    Ok((
+        res.signature().to_vec(),
+        res.rotated_identity().map(|identity| identity.to_vec()),
+    ))
+}
+
+#[ockam_macros::test]
+async fn rotate_key_on_usage_limit(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let service_node = node(ctx.async_try_clone().await?);
+    let identity = service_node
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let options = IdentityServiceOptions::new().with_key_usage_limit(identity.identifier(), 2);
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new_with_options(
+            NodeIdentities::new(service_node.identities(), cli_state.clone()),
+            options.clone(),
+        )
+        .await?,
+    )
+    .await?;
+
+    let identity = identity.export()?;
+    let data = b"data";
+    for _ in 0..2 {
+        let (_, rotated) =
+            create_signature_with_rotation(ctx, &identity, data, "identity_service").await?;
+        assert!(rotated.is_none());
+    }
+
+    // the key created 2 signatures, so it is rotated before signing
+    let (signature, rotated) =
+        create_signature_with_rotation(ctx, &identity, data, "identity_service").await?;
+    let rotated = rotated.unwrap();
+    assert!(verify_signature(ctx, &rotated, data, &signature, "identity_service").await?);
+    assert!(!verify_signature(ctx, &identity, data, &signature, "identity_service").await?);
+
+    // the count of the new key persists when the service is restarted
+    ctx.stop_worker("identity_service").await?;
+    ctx.start_worker(
+        "restarted_identity_service",
+        IdentityService::new_with_options(
+            NodeIdentities::new(service_node.identities(), cli_state),
+            options,
+        )
+        .await?,
+    )
+    .await?;
+    let (_, rotated_again) =
+        create_signature_with_rotation(ctx, &rotated, data, "restarted_identity_service").await?;
+    assert!(rotated_again.is_none());
+    let (_, rotated_again) =
+        create_signature_with_rotation(ctx, &rotated, data, "restarted_identity_service").await?;
+    assert!(rotated_again.is_some());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn key_usage_keeps_the_expiry_of_attested_attributes(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let service_node = node(ctx.async_try_clone().await?);
+    let identity = service_node
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let authority = service_node
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let repository = service_node.identities().repository();
+    let added = Timestamp::now().unwrap();
+    let expires = added.add_seconds(3600);
+    repository
+        .put_attributes(
+            &identity.identifier(),
+            AttributesEntry::new(
+                BTreeMap::from([("role".to_string(), b"member".to_vec())]),
+                added,
+                Some(expires),
+                Some(authority.identifier()),
+            ),
+        )
+        .await?;
+    let options = IdentityServiceOptions::new().with_key_usage_limit(identity.identifier(), 10);
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new_with_options(
+            NodeIdentities::new(service_node.identities(), cli_state),
+            options,
+        )
+        .await?,
+    )
+    .await?;
+
+    create_signature_with_rotation(ctx, &identity.export()?, b"data", "identity_service").await?;
+
+    // the key usage is recorded without extending the lifetime of the attested attributes
+    let entry = repository
+        .get_attributes(&identity.identifier())
+        .await?
+        .unwrap();
+    assert!(entry.attrs().contains_key(KEY_USAGE_ATTRIBUTE));
+    assert_eq!(entry.attrs().get("role"), Some(&b"member".to_vec()));
+    assert_eq!(entry.added(), added);
+    assert_eq!(entry.expires(), Some(expires));
+    assert_eq!(entry.attested_by(), Some(authority.identifier()));
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn sign_json_pointer_selection(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let service_node = node(ctx.async_try_clone().await?);
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(service_node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let (identity, _) = create_identity(ctx, "identity_service").await?;
+    let document = br#"{"name": "alice", "address": {"city": "Paris", "zip": "75001"}, "age": 42}"#;
+    let pointers = vec!["/name", "/address/city"];
+
+    let req = Request::post("actions/canonicalize")
+        .body(
+            CreateSignatureRequest::new(identity.as_slice(), &document[..])
+                .with_json_pointers(pointers.clone()),
+        )
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let payload = dec.decode::<CanonicalizeResponse>()?.payload().to_vec();
+    assert_eq!(
+        payload,
+        br#"{"/address/city":"Paris","/name":"alice"}"#.to_vec()
+    );
+
+    let req = Request::post("actions/create_signature")
+        .body(
+            CreateSignatureRequest::new(identity.as_slice(), &document[..])
+                .with_json_pointers(pointers.clone()),
+        )
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: CreateSignatureResponse = dec.decode()?;
+    let recorded: Vec<&str> = res
+        .json_pointers()
+        .unwrap()
+        .iter()
+        .map(|p| p.as_ref())
+        .collect();
+    assert_eq!(recorded, pointers);
+    assert!(
+        verify_signature(
+            ctx,
+            &identity,
+            &payload,
+            res.signature(),
+            "identity_service"
+        )
+        .await?
+    );
+
+    // the pointers which don't resolve are rejected
+    let req = Request::post("actions/create_signature")
+        .body(
+            CreateSignatureRequest::new(identity.as_slice(), &document[..])
+                .with_json_pointers(vec!["/name", "/phone", "/address/country"]),
+        )
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::BadRequest));
+
+    ctx.stop().await
+}
+
+async fn audit_log(ctx: &mut Context, path: &str, service_address: &str) -> Result<Vec<String>> {
+    let req = Request::get(path).to_vec()?;
+    let receiving_buf: Vec<u8> = ctx.send_and_receive(route![service_address], req).await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: AuditLogResponse = dec.decode()?;
+    Ok(res
+        .entries()
+        .iter()
+        .map(|entry| {
+            format!(
+                "{} {} {}",
+                entry.method().map(|m| m.to_string()).unwrap_or_default(),
+                entry.path(),
+                entry.status().map(|s| s.to_string()).unwrap_or_default()
+            )
+        })
+        .collect())
+}
+
+#[ockam_macros::test]
+async fn audit_log_of_the_requests(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let service_node = node(ctx.async_try_clone().await?);
+    let audit_dir = tempfile::tempdir().unwrap();
+    let options = IdentityServiceOptions::new()
+        .with_audit_log_size(3)
+        .with_audit_log_path(audit_dir.path().join("audit"));
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new_with_options(
+            NodeIdentities::new(service_node.identities(), cli_state.clone()),
+            options.clone(),
+        )
+        .await?,
+    )
+    .await?;
+
+    let (identity, _) = create_identity(ctx, "identity_service").await?;
+    create_signature(ctx, &identity, b"secret data", "identity_service").await?;
+    let req = Request::get("unknown/current_key").to_vec()?;
+    let _: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+
+    assert_eq!(
+        audit_log(ctx, "audit", "identity_service").await?,
+        vec![
+            "POST  200 Ok",
+            "POST actions/create_signature 200 Ok",
+            "GET unknown/current_key 400 BadRequest",
+        ]
+    );
+    // the oldest entry was dropped to record the previous audit request
+    assert_eq!(
+        audit_log(ctx, "audit?limit=2", "identity_service").await?,
+        vec!["GET unknown/current_key 400 BadRequest", "GET audit 200 Ok",]
+    );
+
+    // the persisted entries are loaded when the service is restarted
+    ctx.stop_worker("identity_service").await?;
+    ctx.start_worker(
+        "restarted_identity_service",
+        IdentityService::new_with_options(
+            NodeIdentities::new(service_node.identities(), cli_state),
+            options,
+        )
+        .await?,
+    )
+    .await?;
+    assert_eq!(
+        audit_log(ctx, "audit?limit=1", "restarted_identity_service").await?,
+        vec!["GET audit 200 Ok"]
+    );
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn signature_with_key_identifier(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let service_node = node(ctx.async_try_clone().await?);
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(service_node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let (identity, _) = create_identity(ctx, "identity_service").await?;
+    let req = Request::post("actions/create_signature")
+        .body(CreateSignatureRequest::new(identity.as_slice(), b"data".to_vec()).with_kid())
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: CreateSignatureResponse = dec.decode()?;
+    let kid = res.kid().unwrap().to_string();
+    let signature = res.signature().to_vec();
+
+    // the kid can be computed from the public key alone
+    let public_key = service_node
+        .identities()
+        .identities_creation()
+        .decode_identity(&identity)
+        .await?
+        .get_root_public_key()?;
+    assert_eq!(kid, hex::encode(Sha256::digest(public_key.data())));
+    assert_eq!(kid, signing_key_id(&public_key));
+
+    let (verified, failure_reason) = verify_signature_by_id(
+        ctx,
+        VerifySignatureRequest::new(identity.as_slice(), b"data".to_vec(), signature.clone())
+            .with_kid(kid.as_str()),
+    )
+    .await?;
+    assert!(verified);
+    assert_eq!(failure_reason, None);
+
+    let (verified, failure_reason) = verify_signature_by_id(
+        ctx,
+        VerifySignatureRequest::new(identity.as_slice(), b"data".to_vec(), signature)
+            .with_kid(hex::encode([0u8; 32])),
+    )
+    .await?;
+    assert!(!verified);
+    assert_eq!(failure_reason, Some(VerificationFailureReason::KidMismatch));
+
+    // the kid is only returned on request
+    let req = Request::post("actions/create_signature")
+        .body(CreateSignatureRequest::new(
+            identity.as_slice(),
+            b"data".to_vec(),
+        ))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    assert!(dec.decode::<CreateSignatureResponse>()?.kid().is_none());
+
+    ctx.stop().await
+}
+
+async fn evaluate_policy(
+    ctx: &mut Context,
+    request: EvaluatePolicyRequest<'_>,
+) -> Result<(Option<Status>, Option<(bool, Vec<String>)>)> {
+    let req = Request::post("actions/evaluate_policy")
+        .body(request)
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    if res.status() != Some(Status::Ok) {
+        return Ok((res.status(), None));
+    }
+    let res: EvaluatePolicyResponse = dec.decode()?;
+    let failed_clauses = res
+        .failed_clauses()
+        .into_iter()
+        .map(|c| c.to_string())
+        .collect();
+    Ok((Some(Status::Ok), Some((res.satisfied(), failed_clauses))))
+}
+
+#[ockam_macros::test]
+async fn evaluate_policy_expression(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let service_node = node(ctx.async_try_clone().await?);
+    let repository = service_node.identities().repository();
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(service_node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let (identity, identity_id) = create_identity(ctx, "identity_service").await?;
+    repository
+        .put_attribute_value(
+            &IdentityIdentifier::try_from(identity_id.as_str())?,
+            "role",
+            "admin",
+        )
+        .await?;
+
+    let (status, result) = evaluate_policy(
+        ctx,
+        EvaluatePolicyRequest::new(
+            identity.as_slice(),
+            "has attribute role=admin AND key_age < 30d",
+        ),
+    )
+    .await?;
+    assert_eq!(status, Some(Status::Ok));
+    assert_eq!(result, Some((true, vec![])));
+
+    // the failed clauses are returned as written
+    let (status, result) = evaluate_policy(
+        ctx,
+        EvaluatePolicyRequest::with_identity_id(
+            identity_id.as_str(),
+            "has attribute role = member AND has attribute team AND key_age>=1d AND has attribute role",
+        ),
+    )
+    .await?;
+    assert_eq!(status, Some(Status::Ok));
+    assert_eq!(
+        result,
+        Some((
+            false,
+            vec![
+                "has attribute role = member".to_string(),
+                "has attribute team".to_string(),
+                "key_age>=1d".to_string()
+            ]
+        ))
+    );
+
+    // the age of a key which was not created by the service is unknown
+    let signer_node = node(ctx.async_try_clone().await?);
+    let signer = signer_node
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let (status, result) = evaluate_policy(
+        ctx,
+        EvaluatePolicyRequest::new(signer.export()?, "key_age < 30d"),
+    )
+    .await?;
+    assert_eq!(status, Some(Status::Ok));
+    assert_eq!(result, Some((false, vec!["key_age < 30d".to_string()])));
+
+    // malformed expressions are rejected
+    for expression in [
+        "",
+        "has attribute",
+        "has role=admin",
+        "has attribute role=admin OR key_age < 30d",
+        "key_age = 30d",
+        "key_age < 30 days",
+        "has attribute role=\"admin\"",
+    ] {
+        let (status, _) = evaluate_policy(
+            ctx,
+            EvaluatePolicyRequest::new(identity.as_slice(), expression),
+        )
+        .await?;
+        assert_eq!(status, Some(Status::BadRequest), "{expression}");
+    }
+
+    let (status, _) = evaluate_policy(
+        ctx,
+        EvaluatePolicyRequest::with_identity_id(signer.identifier().to_string(), "key_age < 30d"),
+    )
+    .await?;
+    assert_eq!(status, Some(Status::NotFound));
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn public_identity_uri_round_trip(ctx: &mut Context) -> Result<()> {
+    let identity = node(ctx.async_try_clone().await?)
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let public_key = identity.get_root_public_key()?;
+    let public_identity = PublicIdentityResponse::new(
+        identity.identifier().to_string(),
+        public_key.stype(),
+        public_key.data(),
+    );
+
+    let uri = public_identity_uri(&public_identity)?;
+    assert!(uri.starts_with(PUBLIC_IDENTITY_URI_PREFIX));
+    let parsed = parse_public_identity_uri(&uri).unwrap();
+    assert_eq!(parsed.identity_id(), identity.identifier().to_string());
+    assert_eq!(parsed.key_type(), public_key.stype());
+    assert_eq!(parsed.public_key(), public_key.data());
+
+    let encoded = uri.strip_prefix(PUBLIC_IDENTITY_URI_PREFIX).unwrap();
+    for invalid in [
+        format!("https://identity/{encoded}"),
+        format!("ockam://node/{encoded}"),
+        format!("ockam://identity/{encoded}="),
+        "ockam://identity/AAAA".to_string(),
+        encoded.to_string(),
+    ] {
+        assert!(parse_public_identity_uri(&invalid).is_err(), "{invalid}");
+    }
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn signature_bound_to_a_route(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let service_node = node(ctx.async_try_clone().await?);
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(service_node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let (identity, _) = create_identity(ctx, "identity_service").await?;
+    let req = Request::post("actions/create_signature")
+        .body(
+            CreateSignatureRequest::new(identity.as_slice(), b"data".to_vec())
+                .with_bound_route("1#127.0.0.1:4000=>api"),
+        )
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: CreateSignatureResponse = dec.decode()?;
+    assert_eq!(res.bound_route(), Some("1#127.0.0.1:4000 => 0#api"));
+    let signature = res.signature().to_vec();
+
+    // the signed bytes can be rebuilt from the canonical route
+    let mut expected = b"ockam/identity/route_binding/v1\0".to_vec();
+    expected.extend_from_slice(&25u32.to_be_bytes());
+    expected.extend_from_slice(b"1#127.0.0.1:4000 => 0#api");
+    expected.extend_from_slice(b"data");
+    assert_eq!(
+        route_bound_payload(
+            &canonical_route(" 1#127.0.0.1:4000 => 0#api ").unwrap(),
+            b"data"
+        ),
+        expected
+    );
+    assert!(verify_signature(ctx, &identity, &expected, &signature, "identity_service").await?);
+
+    let (verified, _) = verify_signature_by_id(
+        ctx,
+        VerifySignatureRequest::new(identity.as_slice(), b"data".to_vec(), signature.clone())
+            .with_bound_route("1#127.0.0.1:4000 => api"),
+    )
+    .await?;
+    assert!(verified);
+
+    // the signature is not valid for another route, or without the route
+    let (verified, _) = verify_signature_by_id(
+        ctx,
+        VerifySignatureRequest::new(identity.as_slice(), b"data".to_vec(), signature.clone())
+            .with_bound_route("1#127.0.0.1:5000 => api"),
+    )
+    .await?;
+    assert!(!verified);
+    assert!(!verify_signature(ctx, &identity, b"data", &signature, "identity_service").await?);
+
+    let req = Request::post("actions/create_signature")
+        .body(
+            CreateSignatureRequest::new(identity.as_slice(), b"data".to_vec())
+                .with_bound_route("1#127.0.0.1:4000 => => api"),
+        )
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::BadRequest));
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn warm_up_identity_keys(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let service_node = node(ctx.async_try_clone().await?);
+    let identity = service_node
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    cli_state
+        .create_identity_state(&identity.identifier(), Some("warm"))
+        .await
+        .unwrap();
+
+    // the identities which can't be warmed up don't prevent the service from starting
+    let options = IdentityServiceOptions::new()
+        .with_warm_up_identities(vec!["warm", "unknown"])
+        .with_warm_up_timeout(Duration::from_secs(5));
+    assert_eq!(options.warm_up_identities(), ["warm", "unknown"]);
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new_with_options(
+            NodeIdentities::new(service_node.identities(), cli_state),
+            options,
+        )
+        .await?,
+    )
+    .await?;
+
+    let exported = identity.export()?;
+    let signature = create_signature(ctx, &exported, b"data", "identity_service").await?;
+    assert!(verify_signature(ctx, &exported, b"data", &signature, "identity_service").await?);
+
+    ctx.stop().await
+}
+
+async fn duplicate_identities(ctx: &mut Context) -> Result<Vec<(String, Vec<String>)>> {
+    let req = Request::get("store/duplicates").to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: DuplicateIdentitiesResponse = dec.decode()?;
+    Ok(res
+        .duplicates()
+        .iter()
+        .map(|d| (d.identity_id().to_string(), d.names()))
+        .collect())
+}
+
+#[ockam_macros::test]
+async fn find_duplicate_identities(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state.clone())).await?,
+    )
+    .await?;
+
+    let (_, identity_id) = create_identity(ctx, "identity_service").await?;
+    let identifier = IdentityIdentifier::try_from(identity_id.as_str())?;
+    cli_state
+        .create_identity_state(&identifier, Some("first"))
+        .await
+        .unwrap();
+    let (_, other_id) = create_identity(ctx, "identity_service").await?;
+    let other = IdentityIdentifier::try_from(other_id.as_str())?;
+    cli_state
+        .create_identity_state(&other, Some("other"))
+        .await
+        .unwrap();
+
+    // having no duplicates is not an error
+    assert!(duplicate_identities(ctx).await?.is_empty());
+
+    cli_state
+        .create_identity_state(&identifier, Some("again"))
+        .await
+        .unwrap();
+    assert_eq!(
+        duplicate_identities(ctx).await?,
+        vec![(identity_id, vec!["again".to_string(), "first".to_string()])]
+    );
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn verify_signature_with_min_key_strength(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let service_node = node(ctx.async_try_clone().await?);
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(service_node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    assert_eq!(key_security_bits(SecretType::Ed25519), 128);
+    assert_eq!(key_security_bits(SecretType::NistP256), 128);
+    assert_eq!(key_security_bits(SecretType::X25519), 0);
+
+    let (identity, _) = create_identity(ctx, "identity_service").await?;
+    let signature = create_signature(ctx, &identity, b"data", "identity_service").await?;
+
+    let (verified, failure_reason) = verify_signature_by_id(
+        ctx,
+        VerifySignatureRequest::new(identity.as_slice(), b"data".to_vec(), signature.clone())
+            .with_min_key_bits(128),
+    )
+    .await?;
+    assert!(verified);
+    assert_eq!(failure_reason, None);
+
+    // the signature is valid but the key is too weak
+    let (verified, failure_reason) = verify_signature_by_id(
+        ctx,
+        VerifySignatureRequest::new(identity.as_slice(), b"data".to_vec(), signature)
+            .with_min_key_bits(192),
+    )
+    .await?;
+    assert!(!verified);
+    assert_eq!(failure_reason, Some(VerificationFailureReason::WeakKey));
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn verify_signature_with_min_change_index(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let service_node = node(ctx.async_try_clone().await?);
+    let identities = service_node.identities();
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(identities.clone(), cli_state)).await?,
+    )
+    .await?;
+
+    let mut identity = identities.identities_creation().create_identity().await?;
+    let old_signature = identities
+        .identities_keys()
+        .create_signature(&identity, b"data", None)
+        .await?;
+    let (verified, failure_reason) = verify_signature_by_id(
+        ctx,
+        VerifySignatureRequest::new(
+            identity.export()?,
+            b"data".to_vec(),
+            old_signature.as_ref().to_vec(),
+        )
+        .with_min_change_index(0),
+    )
+    .await?;
+    assert!(verified);
+    assert_eq!(failure_reason, None);
+
+    // the first key was created by the change at index 0
+    let (verified, failure_reason) = verify_signature_by_id(
+        ctx,
+        VerifySignatureRequest::new(
+            identity.export()?,
+            b"data".to_vec(),
+            old_signature.as_ref().to_vec(),
+        )
+        .with_min_change_index(1),
+    )
+    .await?;
+    assert!(!verified);
+    assert_eq!(failure_reason, Some(VerificationFailureReason::StaleKey));
+
+    // the rotated key was created by the change at index 1
+    identities
+        .identities_keys()
+        .rotate_root_key(&mut identity)
+        .await?;
+    let signature = identities
+        .identities_keys()
+        .create_signature(&identity, b"data", None)
+        .await?;
+    let (verified, failure_reason) = verify_signature_by_id(
+        ctx,
+        VerifySignatureRequest::new(
+            identity.export()?,
+            b"data".to_vec(),
+            signature.as_ref().to_vec(),
+        )
+        .with_min_change_index(1),
+    )
+    .await?;
+    assert!(verified);
+    assert_eq!(failure_reason, None);
+    let (verified, failure_reason) = verify_signature_by_id(
+        ctx,
+        VerifySignatureRequest::new(
+            identity.export()?,
+            b"data".to_vec(),
+            signature.as_ref().to_vec(),
+        )
+        .with_min_change_index(2),
+    )
+    .await?;
+    assert!(!verified);
+    assert_eq!(failure_reason, Some(VerificationFailureReason::StaleKey));
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn verify_signature_of_a_digest(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let service_node = node(ctx.async_try_clone().await?);
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(service_node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    // the signature is created over the digest of the data, which the verifier doesn't have
+    let (identity, _) = create_identity(ctx, "identity_service").await?;
+    let digest = Sha256::digest(b"a large object").to_vec();
+    let signature = create_signature(ctx, &identity, &digest, "identity_service").await?;
+
+    let (verified, failure_reason) = verify_signature_by_id(
+        ctx,
+        VerifySignatureRequest::new(identity.as_slice(), digest.clone(), signature.clone())
+            .with_digest_algorithm(SHA256_DIGEST_ALGORITHM),
+    )
+    .await?;
+    assert!(verified);
+    assert_eq!(failure_reason, None);
+
+    let (verified, failure_reason) = verify_signature_by_id(
+        ctx,
+        VerifySignatureRequest::new(
+            identity.as_slice(),
+            Sha256::digest(b"another object").to_vec(),
+            signature.clone(),
+        )
+        .with_digest_algorithm(SHA256_DIGEST_ALGORITHM),
+    )
+    .await?;
+    assert!(!verified);
+    assert_eq!(failure_reason, Some(VerificationFailureReason::KeyMismatch));
+
+    // the digest must have been computed by a supported algorithm
+    for request in [
+        VerifySignatureRequest::new(identity.as_slice(), digest.clone(), signature.clone())
+            .with_digest_algorithm("sha512"),
+        VerifySignatureRequest::new(identity.as_slice(), digest[..20].to_vec(), signature)
+            .with_digest_algorithm(SHA256_DIGEST_ALGORITHM),
+    ] {
+        let req = Request::post("actions/verify_signature")
+            .body(request)
+            .to_vec()?;
+        let receiving_buf: Vec<u8> = ctx
+            .send_and_receive(route!["identity_service"], req)
+            .await?;
+        let res: Response = Decoder::new(&receiving_buf).decode()?;
+        assert_eq!(res.status(), Some(Status::BadRequest));
+    }
+
+    ctx.stop().await
+}
+
+async fn issue_channel_credential(
+    ctx: &mut Context,
+    issuer: &[u8],
+    subject: &str,
+    ttl_secs: u64,
+) -> Result<(Status, Option<ChannelCredential<'static>>)> {
+    let req = Request::post("actions/issue_channel_credential")
+        .body(IssueChannelCredentialRequest::new(
+            issuer, subject, "echoer", ttl_secs,
+        ))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    let status = res.status().unwrap();
+    if status != Status::Ok {
+        return Ok((status, None));
+    }
+    let credential = dec
+        .decode::<IssueChannelCredentialResponse>()?
+        .credential()
+        .clone();
+    Ok((
+        status,
+        Some(ChannelCredential::new(
+            credential.data().to_vec(),
+            credential.signature().to_vec(),
+        )),
+    ))
+}
+
+async fn verify_channel_credential(
+    ctx: &mut Context,
+    issuer: &[u8],
+    credential: ChannelCredential<'_>,
+    subject: &str,
+    service: &str,
+) -> Result<Option<ChannelCredentialFailureReason>> {
+    let req = Request::post("actions/verify_channel_credential")
+        .body(VerifyChannelCredentialRequest::new(
+            issuer, credential, subject, service,
+        ))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: VerifyChannelCredentialResponse = dec.decode()?;
+    assert_eq!(res.verified(), res.failure_reason().is_none());
+    assert_eq!(res.verified(), res.expires_at().is_some());
+    Ok(res.failure_reason())
+}
+
+#[ockam_macros::test]
+async fn channel_credential(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new_with_options(
+            NodeIdentities::new(node.identities(), cli_state),
+            IdentityServiceOptions::new().with_max_channel_credential_ttl(Duration::from_secs(60)),
+        )
+        .await?,
+    )
+    .await?;
+
+    let (issuer, _) = create_identity(ctx, "identity_service").await?;
+    let (other, _) = create_identity(ctx, "identity_service").await?;
+    let (_, subject_id) = create_identity(ctx, "identity_service").await?;
+    let (_, other_id) = create_identity(ctx, "identity_service").await?;
+
+    let (_, credential) = issue_channel_credential(ctx, &issuer, &subject_id, 60).await?;
+    let credential = credential.unwrap();
+    let data: ChannelCredentialData = minicbor::decode(credential.data())?;
+    assert_eq!(data.subject(), subject_id);
+    assert_eq!(data.service(), "echoer");
+    assert_eq!(
+        data.expires_at().elapsed(data.issued_at()),
+        Some(Duration::from_secs(60))
+    );
+
+    // A credential can be presented several times until it expires
+    for _ in 0..2 {
+        assert_eq!(
+            verify_channel_credential(ctx, &issuer, credential.clone(), &subject_id, "echoer")
+                .await?,
+            None
+        );
+    }
+    assert_eq!(
+        verify_channel_credential(ctx, &other, credential.clone(), &subject_id, "echoer").await?,
+        Some(ChannelCredentialFailureReason::InvalidSignature)
+    );
+    assert_eq!(
+        verify_channel_credential(ctx, &issuer, credential.clone(), &other_id, "echoer").await?,
+        Some(ChannelCredentialFailureReason::SubjectMismatch)
+    );
+    assert_eq!(
+        verify_channel_credential(ctx, &issuer, credential.clone(), &subject_id, "uppercase")
+            .await?,
+        Some(ChannelCredentialFailureReason::ServiceMismatch)
+    );
+    let tampered = ChannelCredential::new(
+        b"not a credential".to_vec(),
+        credential.signature().to_vec(),
+    );
+    assert_eq!(
+        verify_channel_credential(ctx, &issuer, tampered, &subject_id, "echoer").await?,
+        Some(ChannelCredentialFailureReason::Malformed)
+    );
+
+    // The lifetime of a credential is bounded
+    for ttl_secs in [0, 61] {
+        let (status, _) = issue_channel_credential(ctx, &issuer, &subject_id, ttl_secs).await?;
+        assert_eq!(status, Status::BadRequest);
+    }
+
+    // An expired credential is rejected
+    let (_, credential) = issue_channel_credential(ctx, &issuer, &subject_id, 1).await?;
+    sleep(Duration::from_millis(2100)).await;
+    assert_eq!(
+        verify_channel_credential(ctx, &issuer, credential.unwrap(), &subject_id, "echoer").await?,
+        Some(ChannelCredentialFailureReason::Expired)
+    );
+
+    ctx.stop().await
+}
+
+async fn fingerprint(ctx: &mut Context, identity: &[u8]) -> Result<FingerprintResponse<'static>> {
+    let req = Request::post("actions/fingerprint")
+        .body(FingerprintRequest::new(identity))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: FingerprintResponse = dec.decode()?;
+    Ok(FingerprintResponse::new(
+        res.identity_id().to_string(),
+        res.hex().to_string(),
+        res.words().to_string(),
+        res.base32().to_string(),
+    ))
+}
+
+#[ockam_macros::test]
+async fn identity_fingerprint(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let (identity, identity_id) = create_identity(ctx, "identity_service").await?;
+    let (other, _) = create_identity(ctx, "identity_service").await?;
+    let res = fingerprint(ctx, &identity).await?;
+    assert_eq!(res.identity_id(), identity_id);
+
+    // all the formats are computed from the SHA-256 digest of the change history
+    let digest = Sha256::digest(&identity);
+    let hex: Vec<String> = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+    assert_eq!(res.hex(), hex.join(":"));
+    assert_eq!(res.words().split(' ').count(), 12);
+    assert_eq!(res.base32().len(), 19);
+    assert_eq!(res.base32().replace('-', "").len(), 16);
+
+    // the fingerprint only depends on the identity
+    let again = fingerprint(ctx, &identity).await?;
+    assert_eq!(again.words(), res.words());
+    assert_eq!(again.base32(), res.base32());
+    let other = fingerprint(ctx, &other).await?;
+    assert_ne!(other.hex(), res.hex());
+    assert_ne!(other.words(), res.words());
+    assert_ne!(other.base32(), res.base32());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn verify_signatures_of_different_signers(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let (alice, _) = create_identity(ctx, "identity_service").await?;
+    let (bob, _) = create_identity(ctx, "identity_service").await?;
+    let data = random::<[u8; 32]>();
+    let other_data = random::<[u8; 32]>();
+    let alice_signature = create_signature(ctx, &alice, &data, "identity_service").await?;
+    let alice_other_signature =
+        create_signature(ctx, &alice, &other_data, "identity_service").await?;
+    let bob_signature = create_signature(ctx, &bob, &data, "identity_service").await?;
+
+    let records = vec![
+        SignedRecord::new(alice.as_slice(), &data[..], alice_signature.as_slice()),
+        SignedRecord::new(bob.as_slice(), &data[..], bob_signature.as_slice()),
+        // the signature of another signer
+        SignedRecord::new(bob.as_slice(), &data[..], alice_signature.as_slice()),
+        SignedRecord::new(
+            alice.as_slice(),
+            &other_data[..],
+            alice_other_signature.as_slice(),
+        ),
+        // a signer which can't be decoded
+        SignedRecord::new(
+            &b"not an identity"[..],
+            &data[..],
+            alice_signature.as_slice(),
+        ),
+        // a malformed signature
+        SignedRecord::new(alice.as_slice(), &data[..], &b"not a signature"[..]),
+    ];
+    let req = Request::post("actions/verify_signatures")
+        .body(VerifySignaturesRequest::new(records))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: VerifySignaturesResponse = dec.decode()?;
+    assert_eq!(res.verified(), &[true, true, false, true, false, false]);
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn create_identity_without_persisting_it(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let req = Request::post("")
+        .body(CreateIdentityRequest::new().with_persist(false))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: CreateResponse = dec.decode()?;
+
+    // neither the identity nor its key were stored
+    let identifier = IdentityIdentifier::try_from(res.identity_id()).unwrap();
+    let repository = node.identities().repository();
+    assert!(repository.retrieve_identity(&identifier).await?.is_none());
+    assert!(
+        create_signature(ctx, res.identity(), b"data", "identity_service")
+            .await
+            .is_err()
+    );
+
+    let req = Request::post("")
+        .body(
+            CreateIdentityRequest::new()
+                .with_persist(false)
+                .with_vault_name("vault"),
+        )
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::BadRequest));
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn api_version(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let req = Request::get("version").to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: VersionResponse = dec.decode()?;
+    assert_eq!(res.api_version(), IDENTITY_SERVICE_API_VERSION);
+    assert_eq!(
+        res.min_client_version(),
+        IDENTITY_SERVICE_MIN_CLIENT_VERSION
+    );
+
+    assert!(res.supports_client(IDENTITY_SERVICE_API_VERSION));
+    assert!(!res.supports_client("0.1.0"));
+    assert!(!res.supports_client("2.0.0"));
+    assert!(!res.supports_client("not a version"));
+
+    ctx.stop().await
+}
+
+async fn set_label(
+    ctx: &mut Context,
+    identity_id: &str,
+    label: Option<&str>,
+) -> Result<(Option<Status>, Option<String>)> {
+    let req = Request::post("actions/set_label")
+        .body(SetLabelRequest::new(identity_id, label))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    if res.status() != Some(Status::Ok) {
+        return Ok((res.status(), None));
+    }
+    let res: CreateResponse = dec.decode()?;
+    Ok((Some(Status::Ok), res.label().map(|l| l.to_string())))
+}
+
+async fn listed_label(ctx: &mut Context, identity_id: &str) -> Result<Option<String>> {
+    let req = Request::get("")
+        .body(ListIdentitiesRequest::new())
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: ListIdentitiesResponse = dec.decode()?;
+    assert!(res.identity_ids().contains(&identity_id.to_string()));
+    Ok(res.label(identity_id).map(|l| l.to_string()))
+}
+
+#[ockam_macros::test]
+async fn identity_label(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    let req = Request::post("")
+        .body(CreateIdentityRequest::new().with_label("device"))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: CreateResponse = dec.decode()?;
+    assert_eq!(res.label(), Some("device"));
+    let device_id = res.identity_id().to_string();
+    let (_, other_id) = create_identity(ctx, "identity_service").await?;
+    // only the identities with attested attributes are listed
+    let repository = node.identities().repository();
+    for identity_id in [&device_id, &other_id] {
+        let identifier = IdentityIdentifier::try_from(identity_id.as_str())?;
+        repository
+            .put_attribute_value(&identifier, "role", "device")
+            .await?;
+    }
+
+    assert_eq!(
+        listed_label(ctx, &device_id).await?,
+        Some("device".to_string())
+    );
+    assert_eq!(listed_label(ctx, &other_id).await?, None);
+    let devices = list_identities(
+        ctx,
+        ListIdentitiesRequest::new().with_label("device"),
+        "identity_service",
+    )
+    .await?;
+    assert_eq!(devices, vec![device_id.clone()]);
+
+    // the label is updated without changing the identity
+    assert_eq!(
+        set_label(ctx, &device_id, Some("service")).await?,
+        (Some(Status::Ok), Some("service".to_string()))
+    );
+    assert_eq!(
+        listed_label(ctx, &device_id).await?,
+        Some("service".to_string())
+    );
+    assert_eq!(
+        set_label(ctx, &device_id, None).await?,
+        (Some(Status::Ok), None)
+    );
+    assert_eq!(listed_label(ctx, &device_id).await?, None);
+
+    assert_eq!(
+        set_label(ctx, &device_id, Some("")).await?,
+        (Some(Status::BadRequest), None)
+    );
+    assert_eq!(
+        set_label(ctx, "not an identifier", Some("device")).await?,
+        (Some(Status::BadRequest), None)
+    );
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn resign_data_signed_with_a_previous_key(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+    let identities = node.identities();
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(identities.clone(), cli_state)).await?,
+    )
+    .await?;
+
+    let mut identity = identities.identities_creation().create_identity().await?;
+    let old_public_key = identity.get_root_public_key()?;
+    let old_signature = identities
+        .identities_keys()
+        .create_signature(&identity, b"data", None)
+        .await?;
+    identities
+        .identities_keys()
+        .rotate_root_key(&mut identity)
+        .await?;
+
+    let req = Request::post("actions/resign")
+        .body(ResignRequest::new(
+            identity.export()?,
+            b"data".to_vec(),
+            old_signature.as_ref().to_vec(),
+        ))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: ResignResponse = dec.decode()?;
+
+    // the new signature covers the data and the old signature
+    let attestation: ResignedData = minicbor::decode(res.attestation())?;
+    assert_eq!(attestation.data(), b"data");
+    assert_eq!(
+        attestation.old_signature_hash(),
+        Sha256::digest(old_signature.as_ref()).as_slice()
+    );
+    assert_eq!(attestation.old_public_key(), old_public_key.data());
+    assert!(
+        identities
+            .identities_keys()
+            .verify_signature(
+                &identity,
+                &Signature::new(res.signature().to_vec()),
+                res.attestation(),
+                None
+            )
+            .await?
+    );
+
+    // a signature of other data, or a signature created with the current key, is rejected
+    let other_signature = identities
+        .identities_keys()
+        .create_signature(&identity, b"data", None)
+        .await?;
+    for (data, signature) in [
+        (&b"other data"[..], old_signature.as_ref()),
+        (&b"data"[..], other_signature.as_ref()),
+    ] {
+        let req = Request::post("actions/resign")
+            .body(ResignRequest::new(
+                identity.export()?,
+                data.to_vec(),
+                signature.to_vec(),
+            ))
+            .to_vec()?;
+        let receiving_buf: Vec<u8> = ctx
+            .send_and_receive(route!["identity_service"], req)
+            .await?;
+        let mut dec = Decoder::new(&receiving_buf);
+        let res: Response = dec.decode()?;
+        assert_eq!(res.status(), Some(Status::BadRequest));
+    }
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn create_signature_with_vault_policy(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+    let allowed = node.identities_creation().create_identity().await?;
+    let mut restricted = VaultConfig::default();
+    restricted.set_policy(Some(VaultPolicy::new(
+        vec![allowed.identifier().to_string()],
+        vec![],
+    )));
+    for (vault_name, config) in [("open", VaultConfig::default()), ("restricted", restricted)] {
+        cli_state
+            .vaults
+            .create_async(vault_name, config)
+            .await
+            .unwrap();
+    }
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state)).await?,
+    )
+    .await?;
+
+    for (vault_name, status) in [("open", Status::Ok), ("restricted", Status::Forbidden)] {
+        let req = Request::post("")
+            .body(CreateIdentityRequest::new().with_vault_name(vault_name))
+            .to_vec()?;
+        let receiving_buf: Vec<u8> = ctx
+            .send_and_receive(route!["identity_service"], req)
+            .await?;
+        let mut dec = Decoder::new(&receiving_buf);
+        let res: Response = dec.decode()?;
+        assert_eq!(res.status(), Some(Status::Ok));
+        let identity = dec.decode::<CreateResponse>()?.identity().to_vec();
+
+        // the request is not sent over a secure channel, so the sender is not allowed
+        // by a vault policy
+        let req = Request::post("actions/create_signature")
+            .body(
+                CreateSignatureRequest::new(identity.as_slice(), &b"data"[..])
+                    .with_vault_name(vault_name),
+            )
+            .to_vec()?;
+        let receiving_buf: Vec<u8> = ctx
+            .send_and_receive(route!["identity_service"], req)
+            .await?;
+        let mut dec = Decoder::new(&receiving_buf);
+        let res: Response = dec.decode()?;
+        assert_eq!(res.status(), Some(status));
+    }
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn vault_group_skips_vaults_denied_by_policy(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+    let allowed = node.identities_creation().create_identity().await?;
+    let mut restricted = VaultConfig::default();
+    restricted.set_policy(Some(VaultPolicy::new(
+        vec![allowed.identifier().to_string()],
+        vec![],
+    )));
+    for (vault_name, config) in [("open", VaultConfig::default()), ("restricted", restricted)] {
+        cli_state
+            .vaults
+            .create_async(vault_name, config)
+            .await
+            .unwrap();
+    }
+
+    let options = IdentityServiceOptions::new().with_vault_group(
+        "group",
+        VaultGroup::new(
+            vec!["restricted".to_string(), "open".to_string()],
+            VaultSelectionStrategy::FirstAvailable,
+        ),
+    );
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new_with_options(
+            NodeIdentities::new(node.identities(), cli_state),
+            options,
+        )
+        .await?,
+    )
+    .await?;
+
+    let data = random::<[u8; 32]>();
+    for (vault_name, signed_with) in [("restricted", None), ("open", Some("open"))] {
+        let req = Request::post("")
+            .body(CreateIdentityRequest::new().with_vault_name(vault_name))
+            .to_vec()?;
+        let receiving_buf: Vec<u8> = ctx
+            .send_and_receive(route!["identity_service"], req)
+            .await?;
+        let mut dec = Decoder::new(&receiving_buf);
+        let res: Response = dec.decode()?;
+        assert_eq!(res.status(), Some(Status::Ok));
+        let identity = dec.decode::<CreateResponse>()?.identity().to_vec();
+
+        // the request is not sent over a secure channel, so the group can't use the
+        // restricted vault, even though it is the first vault of the group
+        let result = sign_with_vault_group(ctx, &identity, &data, "group").await?;
+        match signed_with {
+            Some(signed_with) => assert_eq!(result.unwrap().1, signed_with),
+            None => assert_eq!(result, Err(Status::BadRequest)),
+        }
+    }
+
+    ctx.stop().await
+}
+
+async fn prove_not_revoked(
+    ctx: &mut Context,
+    identity_id: &str,
+) -> Result<std::result::Result<FreshnessProof<'static>, Status>> {
+    let req = Request::post("actions/prove_not_revoked")
+        .body(ProveNotRevokedRequest::new(identity_id))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    if res.status() != Some(Status::Ok) {
+        return Ok(Err(res.status().unwrap()));
+    }
+    let res: ProveNotRevokedResponse = dec.decode()?;
+    let proof = res.proof();
+    Ok(Ok(FreshnessProof::new(
+        proof.data().to_vec(),
+        proof.signature().to_vec(),
+    )))
+}
+
+async fn verify_freshness_proof(
+    ctx: &mut Context,
+    issuer: &[u8],
+    proof: &FreshnessProof<'_>,
+    subject: &str,
+) -> Result<Option<FreshnessProofFailureReason>> {
+    let req = Request::post("actions/verify_freshness_proof")
+        .body(VerifyFreshnessProofRequest::new(
+            issuer,
+            proof.clone(),
+            subject,
+        ))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: VerifyFreshnessProofResponse = dec.decode()?;
+    assert_eq!(res.verified(), res.failure_reason().is_none());
+    Ok(res.failure_reason())
+}
+
+#[ockam_macros::test]
+async fn prove_that_an_identity_is_not_revoked(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+    let issuer = node.identities_creation().create_identity().await?;
+    cli_state
+        .create_identity_state(&issuer.identifier(), Some("attestations"))
+        .await
+        .unwrap();
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new_with_options(
+            NodeIdentities::new(node.identities(), cli_state.clone()),
+            IdentityServiceOptions::new()
+                .with_service_identity("attestations")
+                .with_freshness_proof_ttl(Duration::from_secs(1)),
+        )
+        .await?,
+    )
+    .await?;
+
+    let (_, subject_id) = create_identity(ctx, "identity_service").await?;
+    let (_, other_id) = create_identity(ctx, "identity_service").await?;
+    let issuer = issuer.export()?;
+
+    let proof = prove_not_revoked(ctx, &subject_id).await?.unwrap();
+    let data: FreshnessProofData = minicbor::decode(proof.data())?;
+    assert_eq!(data.subject(), subject_id);
+    assert_eq!(
+        data.expires_at().unix_time(),
+        data.checked_at().unix_time() + 1
+    );
+    assert_eq!(
+        verify_freshness_proof(ctx, &issuer, &proof, &subject_id).await?,
+        None
+    );
+    assert_eq!(
+        verify_freshness_proof(ctx, &issuer, &proof, &other_id).await?,
+        Some(FreshnessProofFailureReason::SubjectMismatch)
+    );
+    let forged = FreshnessProof::new(proof.data().to_vec(), vec![0u8; 64]);
+    assert_eq!(
+        verify_freshness_proof(ctx, &issuer, &forged, &subject_id).await?,
+        Some(FreshnessProofFailureReason::InvalidSignature)
+    );
+
+    // the proof can only be trusted until it expires
+    sleep(Duration::from_millis(2100)).await;
+    assert_eq!(
+        verify_freshness_proof(ctx, &issuer, &proof, &subject_id).await?,
+        Some(FreshnessProofFailureReason::Expired)
+    );
+
+    // there is no proof for a revoked identity
+    cli_state
+        .create_identity_state(
+            &IdentityIdentifier::try_from(subject_id.as_str())?,
+            Some("revoked"),
+        )
+        .await
+        .unwrap();
+    let req = Request::post("actions/revoke_identity")
+        .body(RevokeIdentityRequest::new("revoked"))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    assert_eq!(
+        prove_not_revoked(ctx, &subject_id).await?.err(),
+        Some(Status::BadRequest)
+    );
+
+    ctx.stop().await
+}
+
+async fn verify_inclusion_proof(
+    ctx: &mut Context,
+    signer: &[u8],
+    signed_root: &SignedMerkleRoot<'_>,
+    leaf: &[u8],
+    index: u64,
+    siblings: &[[u8; 32]],
+) -> Result<Option<InclusionProofFailureReason>> {
+    let req = Request::post("actions/verify_inclusion_proof")
+        .body(VerifyInclusionProofRequest::new(
+            signer,
+            signed_root.clone(),
+            leaf,
+            index,
+            siblings
+                .iter()
+                .map(|sibling| sibling.to_vec().into())
+                .collect(),
+        ))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: VerifyInclusionProofResponse = dec.decode()?;
+    assert_eq!(res.verified(), res.failure_reason().is_none());
+    Ok(res.failure_reason())
+}
+
+#[ockam_macros::test]
+async fn sign_merkle_root(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state.clone())).await?,
+    )
+    .await?;
+
+    let (signer, signer_id) = create_identity(ctx, "identity_service").await?;
+    let (other, _) = create_identity(ctx, "identity_service").await?;
+
+    // an odd number of leaves, so that the last leaf is promoted
+    let leaves: Vec<Vec<u8>> = (0..5u8).map(|i| Sha256::digest([i]).to_vec()).collect();
+    let req = Request::post("actions/sign_merkle_root")
+        .body(SignMerkleRootRequest::new(
+            signer.as_slice(),
+            leaves.iter().map(|leaf| leaf.clone().into()).collect(),
+        ))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    assert_eq!(res.status(), Some(Status::Ok));
+    let res: SignMerkleRootResponse = dec.decode()?;
+
+    // the returned levels are the ones of the tree computed locally
+    let leaf_slices: Vec<&[u8]> = leaves.iter().map(|leaf| leaf.as_slice()).collect();
+    let levels = merkle_tree_levels(&leaf_slices);
+    let returned: Vec<Vec<Vec<u8>>> = res
+        .levels()
+        .iter()
+        .map(|level| level.iter().map(|hash| hash.to_vec()).collect())
+        .collect();
+    let expected: Vec<Vec<Vec<u8>>> = levels
+        .iter()
+        .map(|level| level.iter().map(|hash| hash.to_vec()).collect())
+        .collect();
+    assert_eq!(returned, expected);
+
+    let signed_root = res.signed_root().clone();
+    let data: MerkleRootData = minicbor::decode(signed_root.data())?;
+    assert_eq!(data.signer(), signer_id);
+    assert_eq!(data.tree_size(), 5);
+    assert_eq!(data.root(), levels.last().unwrap()[0].as_slice());
+
+    for index in [1, 4] {
+        let siblings = merkle_inclusion_proof(&levels, index).unwrap();
+        assert_eq!(
+            verify_inclusion_proof(
+                ctx,
+                &signer,
+                &signed_root,
+                &leaves[index],
+                index as u64,
+                &siblings
+            )
+            .await?,
+            None
+        );
+    }
+
+    let siblings = merkle_inclusion_proof(&levels, 1).unwrap();
+    assert_eq!(
+        verify_inclusion_proof(ctx, &signer, &signed_root, &leaves[2], 1, &siblings).await?,
+        Some(InclusionProofFailureReason::NotIncluded)
+    );
+    assert_eq!(
+        verify_inclusion_proof(ctx, &other, &signed_root, &leaves[1], 1, &siblings).await?,
+        Some(InclusionProofFailureReason::InvalidSignature)
+    );
+    let forged = SignedMerkleRoot::new(signed_root.data().to_vec(), vec![0u8; 64]);
+    assert_eq!(
+        verify_inclusion_proof(ctx, &signer, &forged, &leaves[1], 1, &siblings).await?,
+        Some(InclusionProofFailureReason::InvalidSignature)
+    );
+
+    // at least one leaf must be provided
+    let req = Request::post("actions/sign_merkle_root")
+        .body(SignMerkleRootRequest::new(signer.as_slice(), vec![]))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let res: Response = Decoder::new(&receiving_buf).decode()?;
+    assert_eq!(res.status(), Some(Status::BadRequest));
+
+    ctx.stop().await
+}
+
+async fn signature_candidates(
+    ctx: &mut Context,
+    signature: &[u8],
+) -> Result<Option<Vec<(SignatureScheme, CandidateConfidence)>>> {
+    let req = Request::post("actions/signature_candidates")
+        .body(SignatureCandidatesRequest::new(signature))
+        .to_vec()?;
+    let receiving_buf: Vec<u8> = ctx
+        .send_and_receive(route!["identity_service"], req)
+        .await?;
+    let mut dec = Decoder::new(&receiving_buf);
+    let res: Response = dec.decode()?;
+    if res.status() != Some(Status::Ok) {
+        return Ok(None);
+    }
+    let res: SignatureCandidatesResponse = dec.decode()?;
+    assert_eq!(res.length(), signature.len() as u64);
+    Ok(Some(
+        res.candidates()
+            .iter()
+            .map(|candidate| (candidate.scheme(), candidate.confidence()))
+            .collect(),
+    ))
+}
+
+#[ockam_macros::test]
+async fn list_signature_candidates(ctx: &mut Context) -> Result<()> {
+    let cli_state = CliState::test().unwrap();
+    let node = node(ctx.async_try_clone().await?);
+
+    ctx.start_worker(
+        "identity_service",
+        IdentityService::new(NodeIdentities::new(node.identities(), cli_state.clone())).await?,
+    )
+    .await?;
+
+    // an Ed25519 signature has the length of a raw P-256 signature
+    let (identity, _) = create_identity(ctx, "identity_service").await?;
+    let signature = create_signature(ctx, &identity, b"some data", "identity_service").await?;
+    assert_eq!(
+        signature_candidates(ctx, &signature).await?,
+        Some(vec![
+            (SignatureScheme::Ed25519, CandidateConfidence::Possible),
+            (SignatureScheme::EcdsaP256Raw, CandidateConfidence::Possible),
+        ])
+    );
+
+    // the last byte is too large for the S value of an Ed25519 signature
+    let mut signature = vec![0u8; 64];
+    signature[63] = 0xff;
+    assert_eq!(
+        signature_candidates(ctx, &signature).await?,
+        Some(vec![(
+            SignatureScheme::EcdsaP256Raw,
+            CandidateConfidence::Possible
+        )])
+    );
+
+    // a DER sequence of two 32-byte integers
+    let mut der = vec![0x30, 0x44, 0x02, 0x20];
+    der.extend([1u8; 32]);
+    der.extend([0x02, 0x20]);
+    der.extend([2u8; 32]);
+    assert_eq!(
+        signature_candidates(ctx, &der).await?,
+        Some(vec![(
+            SignatureScheme::EcdsaP256Der,
+            CandidateConfidence::Likely
+        )])
+    );
+
+    // a truncated DER sequence is not a candidate
+    assert_eq!(
+        signature_candidates(ctx, &der[..der.len() - 1]).await?,
+        Some(vec![])
+    );
+    assert_eq!(signature_candidates(ctx, &[0u8; 10]).await?, Some(vec![]));
+
+    // an empty signature is rejected
+    assert_eq!(signature_candidates(ctx, &[]).await?, None);
+
+    ctx.stop().await
+}
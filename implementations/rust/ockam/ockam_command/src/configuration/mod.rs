@@ -2,11 +2,13 @@ mod get;
 mod get_default_node;
 mod list;
 mod set_default_node;
+mod show;
 
 use get::GetCommand;
 use get_default_node::GetDefaultNodeCommand;
 use list::ListCommand;
 use set_default_node::SetDefaultNodeCommand;
+use show::ShowCommand;
 
 use crate::docs;
 use crate::CommandGlobalOpts;
@@ -27,6 +29,7 @@ pub enum ConfigurationSubcommand {
     GetDefaultNode(GetDefaultNodeCommand),
     List(ListCommand),
     SetDefaultNode(SetDefaultNodeCommand),
+    Show(ShowCommand),
 }
 
 impl ConfigurationCommand {
@@ -36,6 +39,7 @@ impl ConfigurationCommand {
             ConfigurationSubcommand::GetDefaultNode(c) => c.run(options),
             ConfigurationSubcommand::List(c) => c.run(options),
             ConfigurationSubcommand::SetDefaultNode(c) => c.run(options),
+            ConfigurationSubcommand::Show(c) => c.run(options),
         }
     }
 }
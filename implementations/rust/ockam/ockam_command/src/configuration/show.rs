@@ -0,0 +1,143 @@
+use crate::util::local_cmd;
+use crate::vault::{default_vault, OCKAM_DEFAULT_VAULT};
+use crate::{fmt_log, CommandGlobalOpts};
+use clap::Args;
+use miette::IntoDiagnostic;
+use ockam_api::cli_state::{StateDirTrait, StateItemTrait};
+use ockam_core::env::get_env;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Environment variables which change how the command resolves its configuration
+const ENVIRONMENT_OVERRIDES: &[&str] = &[
+    "OCKAM_HOME",
+    OCKAM_DEFAULT_VAULT,
+    "OCKAM_CONTROLLER_ADDR",
+    "OCKAM_LOG",
+    "OCKAM_DISABLE_UPGRADE_CHECK",
+];
+
+/// Show the configuration resolved by the command: state directory, defaults and
+/// environment overrides. Nothing is modified
+#[derive(Clone, Debug, Args)]
+pub struct ShowCommand {}
+
+impl ShowCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        local_cmd(run_impl(options));
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts) -> miette::Result<()> {
+    let configuration = EffectiveConfiguration::resolve(&opts);
+    opts.terminal
+        .stdout()
+        .plain(configuration.plain())
+        .machine(configuration.machine())
+        .json(serde_json::to_string_pretty(&configuration).into_diagnostic()?)
+        .write_line()?;
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct EffectiveConfiguration {
+    state_dir: String,
+    /// Where the state directory comes from: `OCKAM_HOME` or the home directory
+    state_dir_source: String,
+    default_vault: Option<String>,
+    /// Where the default vault comes from: `OCKAM_DEFAULT_VAULT` or the state directory
+    default_vault_source: String,
+    default_identity: Option<String>,
+    default_node: Option<String>,
+    /// The environment overrides which are set, with their values
+    environment: BTreeMap<String, String>,
+}
+
+impl EffectiveConfiguration {
+    fn resolve(opts: &CommandGlobalOpts) -> Self {
+        let environment: BTreeMap<String, String> = ENVIRONMENT_OVERRIDES
+            .iter()
+            .filter_map(|name| match get_env::<String>(name) {
+                Ok(Some(value)) if !value.is_empty() => Some((name.to_string(), value)),
+                _ => None,
+            })
+            .collect();
+        let source = |name: &str, default: &str| {
+            if environment.contains_key(name) {
+                name.to_string()
+            } else {
+                default.to_string()
+            }
+        };
+
+        let state = &opts.state;
+        Self {
+            state_dir: state.dir.display().to_string(),
+            state_dir_source: source("OCKAM_HOME", "home directory"),
+            default_vault: default_vault(state).ok().map(|v| v.name().to_string()),
+            default_vault_source: source(OCKAM_DEFAULT_VAULT, "state directory"),
+            default_identity: state
+                .identities
+                .default()
+                .ok()
+                .map(|i| i.name().to_string()),
+            default_node: state.nodes.default().ok().map(|n| n.name().to_string()),
+            environment,
+        }
+    }
+
+    fn plain(&self) -> String {
+        let none = || "none".to_string();
+        let mut lines = vec![
+            fmt_log!(
+                "State directory: {} (from {})",
+                self.state_dir,
+                self.state_dir_source
+            ),
+            fmt_log!(
+                "Default vault: {} (from {})",
+                self.default_vault.clone().unwrap_or_else(none),
+                self.default_vault_source
+            ),
+            fmt_log!(
+                "Default identity: {}",
+                self.default_identity.clone().unwrap_or_else(none)
+            ),
+            fmt_log!(
+                "Default node: {}",
+                self.default_node.clone().unwrap_or_else(none)
+            ),
+        ];
+        if self.environment.is_empty() {
+            lines.push(fmt_log!("Environment overrides: none"));
+        } else {
+            lines.push(fmt_log!("Environment overrides:"));
+            for (name, value) in self.environment.iter() {
+                lines.push(fmt_log!("  {name}={value}"));
+            }
+        }
+        lines.join("\n")
+    }
+
+    fn machine(&self) -> String {
+        let mut lines = vec![
+            format!("state_dir\t{}", self.state_dir),
+            format!(
+                "default_vault\t{}",
+                self.default_vault.as_deref().unwrap_or_default()
+            ),
+            format!(
+                "default_identity\t{}",
+                self.default_identity.as_deref().unwrap_or_default()
+            ),
+            format!(
+                "default_node\t{}",
+                self.default_node.as_deref().unwrap_or_default()
+            ),
+        ];
+        for (name, value) in self.environment.iter() {
+            lines.push(format!("{name}\t{value}"));
+        }
+        lines.join("\n")
+    }
+}
@@ -83,10 +83,10 @@ async fn run_impl(
         auth_identity_identifier.to_string(),
     );
 
-    let vault_name = cmd
-        .vault
-        .clone()
-        .unwrap_or_else(|| default_vault_name(&opts.state));
+    let vault_name = match cmd.vault.clone() {
+        Some(vault_name) => vault_name,
+        None => default_vault_name(&opts.state)?,
+    };
     let vault = opts.state.vaults.get(&vault_name)?.get().await?;
     let identities = opts.state.get_identities(vault).await?;
     let issuer = ident_state.identifier();
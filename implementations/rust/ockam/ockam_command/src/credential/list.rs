@@ -29,10 +29,10 @@ async fn run_impl(
     opts.terminal
         .write_line(&fmt_log!("Listing Credentials...\n"))?;
 
-    let vault_name = cmd
-        .vault
-        .clone()
-        .unwrap_or_else(|| default_vault_name(&opts.state));
+    let vault_name = match cmd.vault.clone() {
+        Some(vault_name) => vault_name,
+        None => default_vault_name(&opts.state)?,
+    };
     let mut credentials: Vec<CredentialOutput> = Vec::new();
 
     for cred_state in opts.state.credentials.list()? {
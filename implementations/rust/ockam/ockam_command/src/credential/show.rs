@@ -26,10 +26,10 @@ async fn run_impl(
     _ctx: Context,
     (opts, cmd): (CommandGlobalOpts, ShowCommand),
 ) -> miette::Result<()> {
-    let vault_name = cmd
-        .vault
-        .clone()
-        .unwrap_or_else(|| default_vault_name(&opts.state));
+    let vault_name = match cmd.vault.clone() {
+        Some(vault_name) => vault_name,
+        None => default_vault_name(&opts.state)?,
+    };
     display_credential(&opts, &cmd.credential_name, &vault_name).await
 }
 
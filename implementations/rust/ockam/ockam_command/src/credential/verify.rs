@@ -53,6 +53,11 @@ async fn run_impl(
     opts.terminal
         .write_line(&fmt_log!("Verifying credential...\n"))?;
 
+    let vault_name = match cmd.vault.clone() {
+        Some(vault_name) => vault_name,
+        None => default_vault_name(&opts.state)?,
+    };
+
     let is_finished: Mutex<bool> = Mutex::new(false);
 
     let send_req = async {
@@ -67,11 +72,6 @@ async fn run_impl(
             }
         };
 
-        let vault_name = cmd
-            .vault
-            .clone()
-            .unwrap_or_else(|| default_vault_name(&opts.state));
-
         let issuer = match &cmd.issuer().await {
             Ok(i) => i,
             Err(_) => {
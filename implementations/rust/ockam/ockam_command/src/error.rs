@@ -2,7 +2,12 @@ use crate::{exitcode, fmt_log, ExitCode};
 
 use miette::miette;
 use miette::Diagnostic;
+use ockam_api::cli_state::CliStateError;
 use std::fmt::Debug;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set when errors are reported as JSON objects, with `--output json`
+static JSON_ERRORS: AtomicBool = AtomicBool::new(false);
 
 pub type Result<T> = miette::Result<T, Error>;
 
@@ -120,3 +125,37 @@ gen_from_impl!(ockam_api::cli_state::CliStateError, SOFTWARE);
 gen_from_impl!(ockam_multiaddr::Error, SOFTWARE);
 gen_from_impl!(miette::ErrReport, SOFTWARE);
 gen_from_impl!(time::error::Parse, DATAERR);
+
+/// Report the errors of all the commands as JSON objects instead of human-readable messages
+pub(crate) fn enable_json_errors() {
+    JSON_ERRORS.store(true, Ordering::Relaxed);
+}
+
+/// Print the error of a failed command to stderr.
+///
+/// With `--output json` the error is printed as `{"error": {"code": ..., "message": ...}}`
+pub(crate) fn report_error(e: &miette::Report) {
+    if JSON_ERRORS.load(Ordering::Relaxed) {
+        let json = serde_json::json!({
+            "error": {
+                "code": error_code(e),
+                "message": e.to_string(),
+            }
+        });
+        eprintln!("{json}");
+    } else {
+        eprintln!("{:?}", e);
+    }
+}
+
+/// Return a stable code for an error: the kind of CLI state errors,
+/// otherwise the diagnostic code of the error, if any
+fn error_code(e: &miette::Report) -> String {
+    if let Some(e) = e.downcast_ref::<CliStateError>() {
+        return e.error_code().to_string();
+    }
+    match e.code() {
+        Some(code) => code.to_string(),
+        None => "unknown".to_string(),
+    }
+}
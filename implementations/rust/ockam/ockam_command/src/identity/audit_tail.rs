@@ -0,0 +1,320 @@
+use crate::identity::{get_identity_name, initialize_identity_if_default};
+use crate::node::util::{delete_embedded_node, start_embedded_node_with_vault_and_identity};
+use crate::util::api::{CloudOpts, TrustContextOpts};
+use crate::util::{clean_nodes_multiaddr, extract_address_value, node_rpc, RpcBuilder};
+use crate::{docs, fmt_log, CommandGlobalOpts};
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, Context as _, IntoDiagnostic};
+use minicbor::Decoder;
+use ockam::{Context, TcpTransport};
+use ockam_api::identity::models::{AuditEntry, AuditLogResponse};
+use ockam_api::identity::response_body;
+use ockam_api::nodes::models::secure_channel::CredentialExchangeMode;
+use ockam_api::nodes::service::message::SendMessage;
+use ockam_core::api::{Request, Response, Status};
+use ockam_multiaddr::MultiAddr;
+use serde::Serialize;
+use std::time::Duration;
+use tokio::time::sleep;
+
+const LONG_ABOUT: &str = include_str!("./static/audit_tail/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/audit_tail/after_long_help.txt");
+
+/// Path of the audit log of an identity service. The requests of this command are not shown
+const AUDIT_PATH: &str = "audit";
+
+/// Print the new entries of the audit log of an identity service as they are recorded
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct AuditTailCommand {
+    /// Route to the identity service
+    #[arg(long, value_name = "ROUTE")]
+    service: MultiAddr,
+
+    /// The node to send the requests from
+    #[arg(long, value_name = "NODE")]
+    from: Option<String>,
+
+    /// Only print the entries of this action, such as `create_signature`
+    #[arg(long, value_name = "ACTION")]
+    action: Option<String>,
+
+    /// Only print the entries of the requests sent by this identity
+    #[arg(long, value_name = "IDENTIFIER")]
+    sender: Option<String>,
+
+    /// Time to wait between two reads of the audit log (seconds)
+    #[arg(long, value_name = "SECONDS", default_value = "2", value_parser = clap::value_parser!(u64).range(1..))]
+    interval: u64,
+
+    /// Timeout of each read of the audit log (seconds)
+    #[arg(long, value_name = "SECONDS", default_value = "10")]
+    timeout: u64,
+
+    #[command(flatten)]
+    cloud_opts: CloudOpts,
+
+    #[command(flatten)]
+    trust_context_opts: TrustContextOpts,
+}
+
+impl AuditTailCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        initialize_identity_if_default(&opts, &self.cloud_opts.identity);
+        node_rpc(Self::run_impl, (opts, self))
+    }
+
+    async fn run_impl(
+        ctx: Context,
+        (opts, cmd): (CommandGlobalOpts, AuditTailCommand),
+    ) -> miette::Result<()> {
+        let (api_node, tcp) = if let Some(node) = &cmd.from {
+            let api_node = extract_address_value(node)?;
+            let tcp = TcpTransport::create(&ctx).await.into_diagnostic()?;
+            (api_node, Some(tcp))
+        } else {
+            let identity = get_identity_name(&opts.state, &cmd.cloud_opts.identity);
+            let api_node = start_embedded_node_with_vault_and_identity(
+                &ctx,
+                &opts,
+                None,
+                Some(identity),
+                Some(&cmd.trust_context_opts),
+            )
+            .await?;
+            (api_node, None)
+        };
+
+        let (to, meta) = clean_nodes_multiaddr(&cmd.service, &opts.state)
+            .context("Argument '--service' is invalid")?;
+        let projects_sc = crate::project::util::get_projects_secure_channels_from_config_lookup(
+            &ctx,
+            &opts,
+            &meta,
+            &api_node,
+            tcp.as_ref(),
+            CredentialExchangeMode::Oneway,
+        )
+        .await?;
+        let to = crate::project::util::clean_projects_multiaddr(to, projects_sc)?;
+
+        let result = tail(&ctx, &opts, &cmd, &api_node, tcp.as_ref(), &to).await;
+        if cmd.from.is_none() {
+            delete_embedded_node(&opts, &api_node).await;
+        }
+        result
+    }
+}
+
+/// Read the audit log until the command is interrupted, printing the entries recorded since
+/// the previous read. The first read only determines which entries already exist
+async fn tail(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    cmd: &AuditTailCommand,
+    api_node: &str,
+    tcp: Option<&TcpTransport>,
+    to: &MultiAddr,
+) -> miette::Result<()> {
+    let mut cursor = AuditCursor::default();
+    cursor.advance(read_audit_log(ctx, opts, cmd, api_node, tcp, to).await?);
+    opts.terminal.write_line(&fmt_log!(
+        "Tailing the audit log of {}, press Ctrl+C to stop",
+        cmd.service
+    ))?;
+
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+            _ = sleep(Duration::from_secs(cmd.interval)) => {}
+        }
+
+        let entries = read_audit_log(ctx, opts, cmd, api_node, tcp, to).await?;
+        for entry in cursor.advance(entries) {
+            if !is_selected(cmd, &entry) {
+                continue;
+            }
+            let output = AuditEntryOutput::new(&entry);
+            opts.terminal
+                .clone()
+                .stdout()
+                .plain(fmt_log!("{}", output.plain()))
+                .machine(&output.path)
+                .json(serde_json::to_string(&output).into_diagnostic()?)
+                .write_line()?;
+        }
+    }
+}
+
+async fn read_audit_log(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    cmd: &AuditTailCommand,
+    api_node: &str,
+    tcp: Option<&TcpTransport>,
+    to: &MultiAddr,
+) -> miette::Result<Vec<AuditEntry<'static>>> {
+    // The request is relayed to the service by the node, as a message
+    let request = Request::get(AUDIT_PATH).to_vec().into_diagnostic()?;
+    let mut rpc = RpcBuilder::new(ctx, opts, api_node).tcp(tcp)?.build();
+    rpc.request_with_timeout(
+        Request::post("v0/message").body(SendMessage::new(to, request.as_slice())),
+        Duration::from_secs(cmd.timeout),
+    )
+    .await?;
+    let buf = rpc.parse_response::<Vec<u8>>()?;
+
+    let mut dec = Decoder::new(&buf);
+    let header: Response = dec.decode().into_diagnostic()?;
+    if header.status() != Some(Status::Ok) {
+        return Err(miette!(
+            "The audit log of {} can't be read: {}",
+            cmd.service,
+            header
+                .status()
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| "no status".to_string())
+        ));
+    }
+    let body = response_body(&header, &mut dec).into_diagnostic()?;
+    let log: AuditLogResponse = minicbor::decode(&body).into_diagnostic()?;
+    Ok(log
+        .entries()
+        .iter()
+        .map(|entry| entry.clone().into_owned())
+        .collect())
+}
+
+fn is_selected(cmd: &AuditTailCommand, entry: &AuditEntry) -> bool {
+    if entry.path() == AUDIT_PATH {
+        return false;
+    }
+    if let Some(action) = &cmd.action {
+        let path = entry.path();
+        if path != action && path.strip_prefix("actions/") != Some(action.as_str()) {
+            return false;
+        }
+    }
+    match &cmd.sender {
+        Some(sender) => entry.sender() == Some(sender.as_str()),
+        None => true,
+    }
+}
+
+/// Position in the audit log of the last entry which was read.
+/// The entries don't have identifiers, so the position is the timestamp of the last entry with
+/// the number of entries read having this timestamp
+#[derive(Default)]
+struct AuditCursor {
+    timestamp: u64,
+    count: usize,
+}
+
+impl AuditCursor {
+    /// Return the entries which were not read yet and move the cursor after them
+    fn advance(&mut self, entries: Vec<AuditEntry<'static>>) -> Vec<AuditEntry<'static>> {
+        let mut new_entries = vec![];
+        let mut same_timestamp = 0;
+        for entry in entries {
+            let timestamp = entry.timestamp().unix_time();
+            if timestamp < self.timestamp {
+                continue;
+            }
+            if timestamp == self.timestamp {
+                same_timestamp += 1;
+                if same_timestamp <= self.count {
+                    continue;
+                }
+            }
+            new_entries.push(entry);
+        }
+        if let Some(last) = new_entries.last() {
+            let last = last.timestamp().unix_time();
+            if last != self.timestamp {
+                self.timestamp = last;
+                self.count = 0;
+            }
+            self.count += new_entries
+                .iter()
+                .filter(|entry| entry.timestamp().unix_time() == last)
+                .count();
+        }
+        new_entries
+    }
+}
+
+#[derive(Serialize)]
+struct AuditEntryOutput {
+    timestamp: u64,
+    method: Option<String>,
+    path: String,
+    sender: Option<String>,
+    status: Option<String>,
+}
+
+impl AuditEntryOutput {
+    fn new(entry: &AuditEntry) -> Self {
+        Self {
+            timestamp: entry.timestamp().unix_time(),
+            method: entry.method().map(|m| m.to_string()),
+            path: entry.path().to_string(),
+            sender: entry.sender().map(|s| s.to_string()),
+            status: entry.status().map(|s| s.to_string()),
+        }
+    }
+
+    fn plain(&self) -> String {
+        format!(
+            "{} {} {} from {} -> {}",
+            self.timestamp,
+            self.method.as_deref().unwrap_or("-"),
+            self.path,
+            self.sender.as_deref().unwrap_or("a local sender"),
+            self.status.as_deref().unwrap_or("no response")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam::identity::Timestamp;
+    use ockam_core::api::Method;
+
+    fn entries(now: Timestamp, offsets: &[u64]) -> Vec<AuditEntry<'static>> {
+        offsets
+            .iter()
+            .map(|offset| {
+                AuditEntry::new(
+                    now.add_seconds(*offset),
+                    Some(Method::Post),
+                    format!("actions/create_signature/{offset}"),
+                    None::<String>,
+                    Some(Status::Ok),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn audit_cursor_only_returns_new_entries() {
+        let now = Timestamp::now().unwrap();
+        let mut cursor = AuditCursor::default();
+        assert_eq!(cursor.advance(entries(now, &[0, 1, 1])).len(), 3);
+        assert!(cursor.advance(entries(now, &[0, 1, 1])).is_empty());
+
+        // an entry having the same timestamp as the last read entries is new
+        let new_entries = cursor.advance(entries(now, &[1, 1, 1, 2]));
+        assert_eq!(new_entries.len(), 2);
+        assert_eq!(new_entries[1].path(), "actions/create_signature/2");
+
+        // the entries dropped from the log are not a problem
+        assert_eq!(cursor.advance(entries(now, &[2, 3])).len(), 1);
+    }
+}
@@ -0,0 +1,204 @@
+use crate::util::node_rpc;
+use crate::util::parsers::duration_parser;
+use crate::vault::default_vault_name;
+use crate::{docs, fmt_log, fmt_ok, fmt_warn, CommandGlobalOpts};
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
+use ockam::identity::{
+    AttributesEntry, IdentitiesRepository, Identity, IdentityIdentifier, Timestamp,
+};
+use ockam_api::cli_state::traits::StateDirTrait;
+use ockam_api::identity::models::KeyCreation;
+use ockam_api::identity::KEY_CREATION_ATTRIBUTE;
+use ockam_node::Context;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+const LONG_ABOUT: &str = include_str!("./static/auto_rotate/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/auto_rotate/after_long_help.txt");
+
+/// Rotate the keys of a set of identities on a schedule
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct AutoRotateCommand {
+    /// Names of the identities to rotate, separated by commas
+    #[arg(long, value_name = "NAMES", value_delimiter = ',', required = true)]
+    names: Vec<String>,
+
+    /// Time to wait between two rotation cycles, such as `1d`
+    #[arg(long, value_name = "DURATION", value_parser = duration_parser)]
+    interval: Duration,
+
+    /// Only rotate the keys which are older than this duration, such as `90d`
+    #[arg(long, value_name = "DURATION", value_parser = duration_parser)]
+    max_age: Option<Duration>,
+
+    /// Name of the vault containing the keys of the identities
+    #[arg(long, value_name = "VAULT_NAME")]
+    vault: Option<String>,
+}
+
+impl AutoRotateCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(Self::run_impl, (opts, self))
+    }
+
+    async fn run_impl(
+        _ctx: Context,
+        (opts, cmd): (CommandGlobalOpts, AutoRotateCommand),
+    ) -> miette::Result<()> {
+        if cmd.interval.is_zero() {
+            return Err(miette!("The interval between two rotations can't be zero"));
+        }
+        // an unknown identity is an error in the command, not a failure to retry
+        let mut identities = vec![];
+        for name in &cmd.names {
+            identities.push((name.clone(), opts.state.identities.get(name)?.identifier()));
+        }
+        let vault_name = match cmd.vault.clone() {
+            Some(vault_name) => vault_name,
+            None => default_vault_name(&opts.state)?,
+        };
+        opts.terminal.write_line(&fmt_log!(
+            "Rotating the keys of the identities {}, press Ctrl+C to stop",
+            cmd.names.join(", ")
+        ))?;
+
+        loop {
+            for (name, identifier) in &identities {
+                // a failure, such as an unavailable vault, is retried on the next cycle
+                let rotated = rotate_key(&opts, &vault_name, identifier, cmd.max_age).await;
+                let rotated_at = match rotated {
+                    Ok(Some(rotated_at)) => rotated_at,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        opts.terminal.write_line(&fmt_warn!(
+                            "The key of the identity {name} was not rotated: {e}"
+                        ))?;
+                        continue;
+                    }
+                };
+                let event = KeyRotatedEvent {
+                    identity: name.clone(),
+                    identifier: identifier.to_string(),
+                    rotated_at: rotated_at.unix_time(),
+                };
+                opts.terminal
+                    .clone()
+                    .stdout()
+                    .plain(fmt_ok!("Rotated the key of the identity {name}"))
+                    .machine(&event.identity)
+                    .json(serde_json::to_string(&event).into_diagnostic()?)
+                    .write_line()?;
+            }
+
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => return Ok(()),
+                _ = sleep(cmd.interval) => {}
+            }
+        }
+    }
+}
+
+/// Rotate the root key of an identity, unless a maximum age is given and the key is younger.
+/// Return the time of the rotation, if the key was rotated
+async fn rotate_key(
+    opts: &CommandGlobalOpts,
+    vault_name: &str,
+    identifier: &IdentityIdentifier,
+    max_age: Option<Duration>,
+) -> miette::Result<Option<Timestamp>> {
+    let now = Timestamp::now().ok_or_else(|| miette!("unable to get the current time"))?;
+    let repository = opts.state.identities.identities_repository().await?;
+    let mut identity = repository
+        .get_identity(identifier)
+        .await
+        .into_diagnostic()?;
+    if let Some(max_age) = max_age {
+        // a key of unknown age is rotated, so that its successor has a known age
+        if let Some(created_at) = key_created_at(&repository, &identity).await? {
+            if now.elapsed(created_at).unwrap_or_default() <= max_age {
+                return Ok(None);
+            }
+        }
+    }
+
+    let vault = opts.state.vaults.get(vault_name)?.get().await?;
+    opts.state
+        .get_identities(vault)
+        .await?
+        .identities_keys()
+        .rotate_root_key(&mut identity)
+        .await
+        .into_diagnostic()?;
+    repository
+        .update_identity(&identity)
+        .await
+        .into_diagnostic()?;
+
+    // the creation time is recorded like the identity service does, so that both agree
+    // on the age of the key
+    let creation = KeyCreation::new(
+        identity.get_root_public_key().into_diagnostic()?.data(),
+        now,
+    );
+    let (mut attributes, attested_by) = match repository
+        .get_attributes(identifier)
+        .await
+        .into_diagnostic()?
+    {
+        Some(entry) => (entry.attrs().clone(), entry.attested_by()),
+        None => (BTreeMap::new(), None),
+    };
+    attributes.insert(
+        KEY_CREATION_ATTRIBUTE.to_string(),
+        minicbor::to_vec(creation).into_diagnostic()?,
+    );
+    repository
+        .put_attributes(
+            identifier,
+            AttributesEntry::new(attributes, now, None, attested_by),
+        )
+        .await
+        .into_diagnostic()?;
+    Ok(Some(now))
+}
+
+/// Return the creation time of the current root key of an identity, if it is known
+async fn key_created_at(
+    repository: &Arc<dyn IdentitiesRepository>,
+    identity: &Identity,
+) -> miette::Result<Option<Timestamp>> {
+    let stored = repository
+        .get_attributes(&identity.identifier())
+        .await
+        .into_diagnostic()?
+        .and_then(|entry| entry.attrs().get(KEY_CREATION_ATTRIBUTE).cloned());
+    let creation = match stored {
+        Some(stored) => minicbor::decode::<KeyCreation>(&stored).into_diagnostic()?,
+        None => return Ok(None),
+    };
+    // the creation time of a previous root key doesn't apply to the current one
+    if creation.public_key() != identity.get_root_public_key().into_diagnostic()?.data() {
+        return Ok(None);
+    }
+    Ok(Some(creation.created_at()))
+}
+
+/// Event emitted when the key of an identity is rotated
+#[derive(Serialize)]
+struct KeyRotatedEvent {
+    identity: String,
+    identifier: String,
+    rotated_at: u64,
+}
@@ -0,0 +1,122 @@
+use crate::util::node_rpc;
+use crate::{docs, fmt_ok, CommandGlobalOpts};
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
+use ockam::identity::{IdentityChangeHistory, IdentityHistoryComparison};
+use ockam_api::cli_state::traits::StateDirTrait;
+use ockam_api::cli_state::CliState;
+use ockam_node::Context;
+use serde::Serialize;
+use std::path::PathBuf;
+
+const LONG_ABOUT: &str = include_str!("./static/compare/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/compare/after_long_help.txt");
+
+/// Compare the change histories of two identities
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct CompareCommand {
+    /// Name of the identity whose change history is compared
+    #[arg()]
+    name: String,
+
+    /// Name of the identity whose change history is used as the known history
+    #[arg(
+        required_unless_present = "history_file",
+        conflicts_with = "history_file"
+    )]
+    other_name: Option<String>,
+
+    /// Path to a file containing a hex-encoded change history used as the known history
+    #[arg(long, value_name = "PATH")]
+    history_file: Option<PathBuf>,
+}
+
+impl CompareCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(Self::run_impl, (opts, self))
+    }
+
+    async fn run_impl(
+        _ctx: Context,
+        (opts, cmd): (CommandGlobalOpts, CompareCommand),
+    ) -> miette::Result<()> {
+        let current = load_change_history(&opts.state, &cmd.name).await?;
+        let (known_name, known) = match (&cmd.other_name, &cmd.history_file) {
+            (Some(other_name), _) => (
+                other_name.clone(),
+                load_change_history(&opts.state, other_name).await?,
+            ),
+            (None, Some(path)) => {
+                let hex = std::fs::read_to_string(path).into_diagnostic()?;
+                let history = IdentityChangeHistory::import_hex(hex.trim())
+                    .map_err(|e| miette!("invalid change history: {e}"))?;
+                (path.display().to_string(), history)
+            }
+            (None, None) => return Err(miette!("missing known change history")),
+        };
+
+        let output = IdentityComparisonOutput::new(&current, &known);
+        let plain = match output.divergence_index {
+            Some(index) => fmt_ok!(
+                "The change histories of {} and {known_name} diverge at change {index}",
+                cmd.name
+            ),
+            None => fmt_ok!(
+                "The change history of {} is {} compared to {known_name}",
+                cmd.name,
+                output.result.to_lowercase()
+            ),
+        };
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .machine(&output.result)
+            .json(serde_json::to_string_pretty(&output).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+async fn load_change_history(
+    state: &CliState,
+    name: &str,
+) -> miette::Result<IdentityChangeHistory> {
+    let identifier = state.identities.get(name)?.identifier();
+    let identity = state
+        .identities
+        .identities_repository()
+        .await?
+        .get_identity(&identifier)
+        .await
+        .into_diagnostic()?;
+    Ok(identity.change_history())
+}
+
+/// Result of the comparison of a change history with a known change history
+#[derive(Serialize)]
+struct IdentityComparisonOutput {
+    result: String,
+    divergence_index: Option<usize>,
+}
+
+impl IdentityComparisonOutput {
+    fn new(current: &IdentityChangeHistory, known: &IdentityChangeHistory) -> Self {
+        let result = match current.compare(known) {
+            IdentityHistoryComparison::Equal => "Equal",
+            IdentityHistoryComparison::Conflict => "Conflict",
+            IdentityHistoryComparison::Newer => "Newer",
+            IdentityHistoryComparison::Older => "Older",
+        };
+        Self {
+            result: result.to_string(),
+            divergence_index: current.divergence_index(known),
+        }
+    }
+}
@@ -1,18 +1,40 @@
 use crate::terminal::OckamColor;
 use crate::util::node_rpc;
+use crate::vault::default_vault_override;
 use crate::{docs, fmt_log, fmt_ok, CommandGlobalOpts};
-use clap::Args;
+use clap::{Args, ValueEnum};
 use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
 use ockam::Context;
 use ockam_api::cli_state::traits::StateDirTrait;
+use ockam_core::errcode::{Kind, Origin};
 use ockam_identity::IdentityIdentifier;
+use ockam_vault::SecretAttributes;
 use rand::prelude::random;
-use tokio::sync::Mutex;
+use rand::seq::SliceRandom;
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::{Mutex, Semaphore};
 use tokio::try_join;
 
 const LONG_ABOUT: &str = include_str!("./static/create/long_about.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/create/after_long_help.txt");
 
+/// Words used to generate identity names with `--auto-name`
+const ADJECTIVES: &[&str] = &[
+    "amber", "bold", "brave", "bright", "calm", "clever", "cosmic", "crisp", "eager", "gentle",
+    "golden", "happy", "jolly", "keen", "lucky", "mellow", "nimble", "proud", "quiet", "rapid",
+    "silent", "sunny", "swift", "witty",
+];
+const NOUNS: &[&str] = &[
+    "badger", "beacon", "comet", "falcon", "forest", "harbor", "heron", "island", "lantern",
+    "meadow", "otter", "panda", "pebble", "raven", "river", "rocket", "sparrow", "summit",
+    "thunder", "tiger", "valley", "walrus", "willow", "zephyr",
+];
+
+/// Number of adjective-noun names tried before adding a random suffix to the generated name
+const MAX_AUTO_NAME_ATTEMPTS: usize = 16;
+
 /// Create a new identity
 #[derive(Clone, Debug, Args)]
 #[command(
@@ -20,17 +42,62 @@ long_about = docs::about(LONG_ABOUT),
 after_long_help = docs::after_help(AFTER_LONG_HELP)
 )]
 pub struct CreateCommand {
-    #[arg(hide_default_value = true, default_value_t = hex::encode(& random::< [u8; 4] > ()))]
+    #[arg(hide_default_value = true, default_value_t = hex::encode(& random::< [u8; 4] > ()), value_parser = parse_identity_name)]
     name: String,
 
     /// Vault name to store the identity key
     #[arg(long, value_name = "VAULT_NAME", global = true)]
     vault: Option<String>,
+
+    /// Generate a memorable name, like 'swift-otter', which is not used by another identity
+    #[arg(long, conflicts_with = "name")]
+    auto_name: bool,
+
+    /// Type of the identity key generated in the vault
+    #[arg(long, value_enum, value_name = "KEY_TYPE", default_value_t = KeyType::Ed25519)]
+    key_type: KeyType,
+
+    /// Create this number of identities, named `<PREFIX>-0`, `<PREFIX>-1`, ...
+    #[arg(long, value_name = "N", requires = "name_prefix", conflicts_with_all = ["name", "auto_name"], value_parser = clap::value_parser!(u64).range(1..))]
+    count: Option<u64>,
+
+    /// Prefix of the names of the identities created with `--count`
+    #[arg(long, value_name = "PREFIX", requires = "count", value_parser = parse_identity_name)]
+    name_prefix: Option<String>,
+
+    /// Maximum number of identity keys generated at the same time with `--count`
+    #[arg(long, value_name = "N", default_value = "1", requires = "count", value_parser = clap::value_parser!(u64).range(1..))]
+    parallel: u64,
+}
+
+/// Type of an identity key
+#[derive(Clone, Copy, Debug, ValueEnum, PartialEq, Eq)]
+pub enum KeyType {
+    Ed25519,
+    #[value(alias = "p-256")]
+    P256,
+}
+
+impl KeyType {
+    fn secret_attributes(&self) -> SecretAttributes {
+        match self {
+            KeyType::Ed25519 => SecretAttributes::Ed25519,
+            KeyType::P256 => SecretAttributes::NistP256,
+        }
+    }
 }
 
 impl CreateCommand {
     pub fn new(name: String, vault: Option<String>) -> CreateCommand {
-        CreateCommand { name, vault }
+        CreateCommand {
+            name,
+            vault,
+            auto_name: false,
+            key_type: KeyType::Ed25519,
+            count: None,
+            name_prefix: None,
+            parallel: 1,
+        }
     }
 
     pub fn run(self, options: CommandGlobalOpts) {
@@ -41,27 +108,34 @@ impl CreateCommand {
         _ctx: Context,
         (options, cmd): (CommandGlobalOpts, CreateCommand),
     ) -> miette::Result<()> {
-        cmd.create_identity(options).await.map(|_| ())
+        match (cmd.count, &cmd.name_prefix) {
+            (Some(count), Some(name_prefix)) => {
+                cmd.create_identities(options, name_prefix, count).await
+            }
+            _ => cmd.create_identity(options).await.map(|_| ()),
+        }
     }
 
     pub async fn create_identity(
         &self,
         opts: CommandGlobalOpts,
     ) -> miette::Result<IdentityIdentifier> {
+        let name = if self.auto_name {
+            generate_identity_name(&opts)?
+        } else {
+            self.name.clone()
+        };
         opts.terminal.write_line(&fmt_log!(
             "Creating identity {}...\n",
-            &self
-                .name
-                .to_string()
-                .color(OckamColor::PrimaryResource.color())
+            &name.to_string().color(OckamColor::PrimaryResource.color())
         ))?;
 
         let is_finished: Mutex<bool> = Mutex::new(false);
 
         let send_req = async {
-            let default_vault_created =
-                self.vault.is_none() && opts.state.vaults.default().is_err();
-            let vault_state = opts.state.create_vault_state(self.vault.as_deref()).await?;
+            let vault = self.vault.clone().or_else(default_vault_override);
+            let default_vault_created = vault.is_none() && opts.state.vaults.default().is_err();
+            let vault_state = opts.state.create_vault_state(vault.as_deref()).await?;
             if default_vault_created {
                 opts.terminal.write_line(&fmt_log!(
                     "Default vault created: {}\n",
@@ -74,16 +148,30 @@ impl CreateCommand {
 
             let vault = vault_state.get().await?;
 
-            let identity = opts
+            let secret_attributes = self.key_type.secret_attributes();
+            let identity = match opts
                 .state
                 .get_identities(vault)
                 .await?
                 .identities_creation()
-                .create_identity()
-                .await?;
+                .create_identity_with_secret_attributes(secret_attributes)
+                .await
+            {
+                Ok(identity) => identity,
+                // the vault rejects key types it doesn't support
+                Err(e) if e.code().origin == Origin::Vault && e.code().kind == Kind::Misuse => {
+                    return Err(miette!(
+                        "The vault {} does not support {} keys",
+                        vault_state.name(),
+                        secret_attributes.secret_type()
+                    )
+                    .into());
+                }
+                Err(e) => return Err(e.into()),
+            };
 
             opts.state
-                .create_identity_state(&identity.identifier(), Some(&self.name))
+                .create_identity_state(&identity.identifier(), Some(&name))
                 .await?;
 
             let identifier = identity.identifier();
@@ -109,16 +197,135 @@ impl CreateCommand {
                         .to_string()
                         .color(OckamColor::PrimaryResource.color())
                 ) + &fmt_log!(
-                    "created successfully as {}",
-                    &self
-                        .name
-                        .to_string()
-                        .color(OckamColor::PrimaryResource.color())
+                    "created successfully as {} with a {} key",
+                    &name.to_string().color(OckamColor::PrimaryResource.color()),
+                    self.key_type.secret_attributes().secret_type()
                 ),
             )
             .machine(identifier.clone())
-            .json(serde_json::json!({ "identity": { "identifier": &identifier } }))
+            .json(serde_json::json!({
+                "identity": {
+                    "identifier": &identifier,
+                    "name": &name,
+                    "key_type": self.key_type.secret_attributes().secret_type().to_string()
+                }
+            }))
             .write_line()?;
         Ok(identifier)
     }
+
+    /// Create `count` identities named after a prefix, sharing the same vault.
+    /// Their keys are generated concurrently, then the identities are stored in order
+    async fn create_identities(
+        &self,
+        opts: CommandGlobalOpts,
+        name_prefix: &str,
+        count: u64,
+    ) -> miette::Result<()> {
+        let names: Vec<String> = (0..count).map(|i| format!("{name_prefix}-{i}")).collect();
+        if let Some(name) = names.iter().find(|name| opts.state.identities.exists(name)) {
+            return Err(miette!("An identity named {name} already exists"));
+        }
+
+        let vault = self.vault.clone().or_else(default_vault_override);
+        let vault_state = opts.state.create_vault_state(vault.as_deref()).await?;
+        let identities_creation = opts
+            .state
+            .get_identities(vault_state.get().await?)
+            .await?
+            .identities_creation();
+        let secret_attributes = self.key_type.secret_attributes();
+        let semaphore = Arc::new(Semaphore::new(self.parallel as usize));
+        let handles: Vec<_> = names
+            .iter()
+            .map(|_| {
+                let semaphore = semaphore.clone();
+                let identities_creation = identities_creation.clone();
+                tokio::spawn(async move {
+                    let _permit = semaphore.acquire_owned().await;
+                    identities_creation
+                        .create_identity_with_secret_attributes(secret_attributes)
+                        .await
+                })
+            })
+            .collect();
+
+        let mut created = vec![];
+        for (name, handle) in names.into_iter().zip(handles) {
+            let identity = handle.await.into_diagnostic()?.into_diagnostic()?;
+            let identifier = identity.identifier();
+            opts.state
+                .create_identity_state(&identifier, Some(&name))
+                .await?;
+            opts.terminal.write_line(&fmt_log!(
+                "Created identity {} as {}",
+                identifier
+                    .to_string()
+                    .color(OckamColor::PrimaryResource.color()),
+                name.to_string().color(OckamColor::PrimaryResource.color())
+            ))?;
+            created.push(CreatedIdentity {
+                name,
+                identifier: identifier.to_string(),
+            });
+        }
+
+        let machine = created
+            .iter()
+            .map(|identity| format!("{} {}", identity.name, identity.identifier))
+            .collect::<Vec<_>>()
+            .join("\n");
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "Created {} identities with a {} key",
+                created.len(),
+                secret_attributes.secret_type()
+            ))
+            .machine(machine)
+            .json(serde_json::json!({
+                "identities": created,
+                "key_type": secret_attributes.secret_type().to_string()
+            }))
+            .write_line()?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct CreatedIdentity {
+    name: String,
+    identifier: String,
+}
+
+/// Check that an identity name can be used as the name of its file in the CLI state
+fn parse_identity_name(name: &str) -> Result<String, String> {
+    if name.is_empty() {
+        return Err("the identity name must not be empty".to_string());
+    }
+    if name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(format!(
+            "invalid identity name '{name}': it must not start with '.' or contain '/' or '\\'"
+        ));
+    }
+    Ok(name.to_string())
+}
+
+/// Generate an adjective-noun name which is not used by any identity yet.
+/// A random suffix is added when the attempts to find a free name are exhausted
+fn generate_identity_name(opts: &CommandGlobalOpts) -> miette::Result<String> {
+    let mut rng = rand::thread_rng();
+    let mut name = String::new();
+    for _ in 0..MAX_AUTO_NAME_ATTEMPTS {
+        name = format!(
+            "{}-{}",
+            ADJECTIVES.choose(&mut rng).expect("no adjectives"),
+            NOUNS.choose(&mut rng).expect("no nouns")
+        );
+        if !opts.state.identities.exists(&name) {
+            return parse_identity_name(&name).map_err(|e| miette!(e));
+        }
+    }
+    name = format!("{name}-{}", hex::encode(random::<[u8; 2]>()));
+    parse_identity_name(&name).map_err(|e| miette!(e))
 }
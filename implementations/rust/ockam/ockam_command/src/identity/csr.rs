@@ -0,0 +1,372 @@
+use crate::identity::{get_identity_name, initialize_identity_if_default};
+use crate::util::node_rpc;
+use crate::vault::default_vault_name;
+use crate::{docs, fmt_ok, CommandGlobalOpts};
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic, WrapErr};
+use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
+use ockam_node::Context;
+use ockam_vault::{EcdsaSignatureEncoding, PublicKey, SecretType};
+use serde::Serialize;
+use std::net::IpAddr;
+use std::path::PathBuf;
+
+const LONG_ABOUT: &str = include_str!("./static/csr/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/csr/after_long_help.txt");
+
+/// Create a PKCS#10 certificate signing request for the key of an identity
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct CsrCommand {
+    /// Name of the identity whose key is certified
+    #[arg(long)]
+    identity: Option<String>,
+
+    /// Path to the file where the certificate signing request is written
+    #[arg(long = "out", value_name = "PATH")]
+    output: PathBuf,
+
+    /// Subject of the certificate, as comma-separated `CN`, `O`, `OU`, `L`, `ST` and `C`
+    /// attributes. Defaults to `CN=<identifier of the identity>`
+    #[arg(long, value_name = "DN")]
+    subject: Option<String>,
+
+    /// Subject alternative name, as `dns:<name>`, `ip:<address>`, `email:<address>` or
+    /// `uri:<uri>`. Can be repeated
+    #[arg(long = "san", value_name = "SAN")]
+    subject_alternative_names: Vec<String>,
+
+    /// Write the request as DER instead of PEM
+    #[arg(long)]
+    der: bool,
+
+    /// Name of the vault containing the key of the identity
+    #[arg(long, value_name = "VAULT_NAME")]
+    vault: Option<String>,
+}
+
+impl CsrCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        initialize_identity_if_default(&opts, &self.identity);
+        node_rpc(Self::run_impl, (opts, self))
+    }
+
+    async fn run_impl(
+        _ctx: Context,
+        (opts, cmd): (CommandGlobalOpts, CsrCommand),
+    ) -> miette::Result<()> {
+        let name = get_identity_name(&opts.state, &cmd.identity);
+        let identifier = opts.state.identities.get(&name)?.config().identifier();
+        let subject = cmd
+            .subject
+            .clone()
+            .unwrap_or_else(|| format!("CN={identifier}"));
+        let subject_name = distinguished_name(&subject)?;
+        let subject_alternative_names = cmd
+            .subject_alternative_names
+            .iter()
+            .map(|san| general_name(san))
+            .collect::<miette::Result<Vec<_>>>()?;
+
+        let identity = opts
+            .state
+            .identities
+            .identities_repository()
+            .await?
+            .get_identity(&identifier)
+            .await
+            .into_diagnostic()?;
+        let public_key = identity.get_root_public_key().into_diagnostic()?;
+        let (public_key_info, signature_algorithm) = key_algorithm(&public_key)?;
+        let request_info =
+            certification_request_info(subject_name, public_key_info, subject_alternative_names);
+
+        let vault_name = match cmd.vault.clone() {
+            Some(vault_name) => vault_name,
+            None => default_vault_name(&opts.state)?,
+        };
+        let vault = opts.state.vaults.get(&vault_name)?.get().await?;
+        let identities_keys = opts.state.get_identities(vault).await?.identities_keys();
+        let signature = identities_keys
+            .create_signature(&identity, &request_info, None)
+            .await
+            .into_diagnostic()?;
+        let signature = match public_key.stype() {
+            SecretType::NistP256 => signature
+                .to_ecdsa_encoding(EcdsaSignatureEncoding::Der)
+                .into_diagnostic()?,
+            _ => signature,
+        };
+
+        let request = der(
+            SEQUENCE,
+            &[
+                request_info,
+                signature_algorithm,
+                bit_string(signature.as_ref()),
+            ]
+            .concat(),
+        );
+        let format = if cmd.der { "der" } else { "pem" };
+        if cmd.der {
+            std::fs::write(&cmd.output, request)
+        } else {
+            let pem = pem_rfc7468::encode_string(
+                "CERTIFICATE REQUEST",
+                pem_rfc7468::LineEnding::LF,
+                &request,
+            )
+            .into_diagnostic()
+            .wrap_err("Unable to encode the certificate signing request to PEM")?;
+            std::fs::write(&cmd.output, pem)
+        }
+        .map_err(|e| miette!("Unable to write {}: {e}", cmd.output.display()))?;
+
+        let output = CsrOutput {
+            identity: identifier.to_string(),
+            subject,
+            format: format.to_string(),
+            csr: cmd.output.display().to_string(),
+        };
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "Created a certificate signing request for {name}, it was written to {}",
+                output.csr
+            ))
+            .machine(&output.csr)
+            .json(serde_json::to_string_pretty(&output).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct CsrOutput {
+    identity: String,
+    subject: String,
+    format: String,
+    csr: String,
+}
+
+const SEQUENCE: u8 = 0x30;
+const SET: u8 = 0x31;
+const INTEGER: u8 = 0x02;
+const BIT_STRING: u8 = 0x03;
+const OCTET_STRING: u8 = 0x04;
+const OBJECT_IDENTIFIER: u8 = 0x06;
+const UTF8_STRING: u8 = 0x0c;
+const PRINTABLE_STRING: u8 = 0x13;
+/// Context-specific constructed tag `[0]`, used for the attributes of the request
+const ATTRIBUTES: u8 = 0xa0;
+
+/// Encoded object identifiers
+const OID_ED25519: &[u8] = &[0x2b, 0x65, 0x70];
+const OID_ECDSA_WITH_SHA256: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02];
+const OID_EXTENSION_REQUEST: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x09, 0x0e];
+const OID_SUBJECT_ALT_NAME: &[u8] = &[0x55, 0x1d, 0x11];
+
+/// Encode a DER element with its tag and length
+fn der(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut encoded = vec![tag];
+    let length = content.len();
+    if length < 0x80 {
+        encoded.push(length as u8);
+    } else {
+        let bytes = length.to_be_bytes();
+        let skipped = bytes.iter().take_while(|b| **b == 0).count();
+        encoded.push(0x80 | (bytes.len() - skipped) as u8);
+        encoded.extend_from_slice(&bytes[skipped..]);
+    }
+    encoded.extend_from_slice(content);
+    encoded
+}
+
+fn bit_string(bytes: &[u8]) -> Vec<u8> {
+    der(BIT_STRING, &[&[0u8][..], bytes].concat())
+}
+
+/// Return the SubjectPublicKeyInfo of a key and the AlgorithmIdentifier of its signatures
+fn key_algorithm(public_key: &PublicKey) -> miette::Result<(Vec<u8>, Vec<u8>)> {
+    match public_key.stype() {
+        SecretType::Ed25519 => {
+            let algorithm = der(SEQUENCE, &der(OBJECT_IDENTIFIER, OID_ED25519));
+            let public_key_info = der(
+                SEQUENCE,
+                &[algorithm.clone(), bit_string(public_key.data())].concat(),
+            );
+            Ok((public_key_info, algorithm))
+        }
+        // P-256 public keys are already stored as a DER SubjectPublicKeyInfo
+        SecretType::NistP256 => Ok((
+            public_key.data().to_vec(),
+            der(SEQUENCE, &der(OBJECT_IDENTIFIER, OID_ECDSA_WITH_SHA256)),
+        )),
+        stype => Err(miette!(
+            "A certificate signing request can't be created for a {stype:?} key"
+        )),
+    }
+}
+
+/// Return the CertificationRequestInfo, which is the part of the request signed by the key
+fn certification_request_info(
+    subject: Vec<u8>,
+    public_key_info: Vec<u8>,
+    subject_alternative_names: Vec<Vec<u8>>,
+) -> Vec<u8> {
+    let attributes = if subject_alternative_names.is_empty() {
+        vec![]
+    } else {
+        let extension = der(
+            SEQUENCE,
+            &[
+                der(OBJECT_IDENTIFIER, OID_SUBJECT_ALT_NAME),
+                der(
+                    OCTET_STRING,
+                    &der(SEQUENCE, &subject_alternative_names.concat()),
+                ),
+            ]
+            .concat(),
+        );
+        der(
+            SEQUENCE,
+            &[
+                der(OBJECT_IDENTIFIER, OID_EXTENSION_REQUEST),
+                der(SET, &der(SEQUENCE, &extension)),
+            ]
+            .concat(),
+        )
+    };
+    der(
+        SEQUENCE,
+        &[
+            der(INTEGER, &[0]),
+            subject,
+            public_key_info,
+            der(ATTRIBUTES, &attributes),
+        ]
+        .concat(),
+    )
+}
+
+/// Encode a distinguished name such as `CN=node 1,O=Acme,C=US` as an X.501 Name.
+/// The attributes are kept in the order they are given
+fn distinguished_name(dn: &str) -> miette::Result<Vec<u8>> {
+    let mut names = vec![];
+    for attribute in dn.split(',') {
+        let (kind, value) = attribute
+            .split_once('=')
+            .map(|(kind, value)| (kind.trim(), value.trim()))
+            .ok_or_else(|| {
+                miette!("Invalid subject attribute '{attribute}', expected KEY=VALUE")
+            })?;
+        if value.is_empty() {
+            return Err(miette!("The subject attribute {kind} has no value"));
+        }
+        let (oid, string_type) = match kind.to_ascii_uppercase().as_str() {
+            "CN" => (3, UTF8_STRING),
+            "C" => {
+                if value.len() != 2 || !value.chars().all(|c| c.is_ascii_alphabetic()) {
+                    return Err(miette!(
+                        "The subject country '{value}' must be a two-letter code"
+                    ));
+                }
+                (6, PRINTABLE_STRING)
+            }
+            "L" => (7, UTF8_STRING),
+            "ST" => (8, UTF8_STRING),
+            "O" => (10, UTF8_STRING),
+            "OU" => (11, UTF8_STRING),
+            _ => {
+                return Err(miette!(
+                    "Unsupported subject attribute '{kind}', expected CN, O, OU, L, ST or C"
+                ))
+            }
+        };
+        let type_and_value = der(
+            SEQUENCE,
+            &[
+                der(OBJECT_IDENTIFIER, &[0x55, 0x04, oid]),
+                der(string_type, value.as_bytes()),
+            ]
+            .concat(),
+        );
+        names.push(der(SET, &type_and_value));
+    }
+    Ok(der(SEQUENCE, &names.concat()))
+}
+
+/// Encode a subject alternative name such as `dns:node1.example.com` as a GeneralName
+fn general_name(san: &str) -> miette::Result<Vec<u8>> {
+    let (kind, value) = san.split_once(':').ok_or_else(|| {
+        miette!("Invalid subject alternative name '{san}', expected dns:, ip:, email: or uri:")
+    })?;
+    if value.is_empty() {
+        return Err(miette!("The subject alternative name '{san}' has no value"));
+    }
+    if kind != "ip" && !value.is_ascii() {
+        return Err(miette!(
+            "The subject alternative name '{san}' must only contain ASCII characters"
+        ));
+    }
+    match kind {
+        "email" => Ok(der(0x81, value.as_bytes())),
+        "dns" => Ok(der(0x82, value.as_bytes())),
+        "uri" => Ok(der(0x86, value.as_bytes())),
+        "ip" => {
+            let address = value
+                .parse::<IpAddr>()
+                .map_err(|_| miette!("Invalid IP address '{value}'"))?;
+            let octets = match address {
+                IpAddr::V4(address) => address.octets().to_vec(),
+                IpAddr::V6(address) => address.octets().to_vec(),
+            };
+            Ok(der(0x87, &octets))
+        }
+        _ => Err(miette!(
+            "Unsupported subject alternative name '{kind}', expected dns, ip, email or uri"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn der_lengths() {
+        assert_eq!(der(OCTET_STRING, &[1, 2]), vec![0x04, 0x02, 1, 2]);
+        let long = der(OCTET_STRING, &[0; 200]);
+        assert_eq!(&long[..3], &[0x04, 0x81, 200]);
+        let longer = der(OCTET_STRING, &[0; 300]);
+        assert_eq!(&longer[..4], &[0x04, 0x82, 0x01, 0x2c]);
+    }
+
+    #[test]
+    fn subject_and_alternative_names() {
+        let name = distinguished_name("CN=a, C=US").unwrap();
+        assert_eq!(
+            name,
+            vec![
+                0x30, 0x19, 0x31, 0x0a, 0x30, 0x08, 0x06, 0x03, 0x55, 0x04, 0x03, 0x0c, 0x01, b'a',
+                0x31, 0x0b, 0x30, 0x09, 0x06, 0x03, 0x55, 0x04, 0x06, 0x13, 0x02, b'U', b'S',
+            ]
+        );
+        assert!(distinguished_name("CN=a,C=USA").is_err());
+        assert!(distinguished_name("X=a").is_err());
+
+        assert_eq!(
+            general_name("ip:10.0.0.1").unwrap(),
+            vec![0x87, 0x04, 10, 0, 0, 1]
+        );
+        assert_eq!(general_name("dns:a").unwrap(), vec![0x82, 0x01, b'a']);
+        assert!(general_name("dns:").is_err());
+        assert!(general_name("ftp:a").is_err());
+    }
+}
@@ -0,0 +1,195 @@
+use crate::identity::{get_identity_name, initialize_identity_if_default};
+use crate::node::util::{delete_embedded_node, start_embedded_node_with_vault_and_identity};
+use crate::util::api::{CloudOpts, TrustContextOpts};
+use crate::util::{clean_nodes_multiaddr, node_rpc, RpcBuilder};
+use crate::{docs, fmt_ok, CommandGlobalOpts};
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, Context as _, IntoDiagnostic};
+use minicbor::Decoder;
+use ockam::identity::{identities, Identity};
+use ockam::Context;
+use ockam_api::cli_state::traits::StateDirTrait;
+use ockam_api::identity::models::CreateResponse;
+use ockam_api::identity::response_body;
+use ockam_api::nodes::models::secure_channel::CredentialExchangeMode;
+use ockam_api::nodes::service::message::SendMessage;
+use ockam_core::api::{Request, Response, Status};
+use ockam_multiaddr::MultiAddr;
+use std::time::Duration;
+
+const LONG_ABOUT: &str = include_str!("./static/fetch/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/fetch/after_long_help.txt");
+
+/// Fetch an identity from a remote identity service and store it locally
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct FetchCommand {
+    /// Name of the identity on the remote identity service
+    remote_name: String,
+
+    /// Route to the remote identity service
+    #[arg(long, value_name = "ROUTE")]
+    from: MultiAddr,
+
+    /// Name under which the identity is stored locally
+    #[arg(long, value_name = "NAME")]
+    name: String,
+
+    /// Timeout of the request to the remote identity service (seconds)
+    #[arg(long, value_name = "SECONDS", default_value = "10")]
+    timeout: u64,
+
+    #[command(flatten)]
+    cloud_opts: CloudOpts,
+
+    #[command(flatten)]
+    trust_context_opts: TrustContextOpts,
+}
+
+impl FetchCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        initialize_identity_if_default(&opts, &self.cloud_opts.identity);
+        node_rpc(Self::run_impl, (opts, self))
+    }
+
+    async fn run_impl(
+        ctx: Context,
+        (opts, cmd): (CommandGlobalOpts, FetchCommand),
+    ) -> miette::Result<()> {
+        if opts.state.identities.exists(&cmd.name) {
+            return Err(miette!("An identity named '{}' already exists", cmd.name));
+        }
+
+        let identity = get_identity_name(&opts.state, &cmd.cloud_opts.identity);
+        let api_node = start_embedded_node_with_vault_and_identity(
+            &ctx,
+            &opts,
+            None,
+            Some(identity),
+            Some(&cmd.trust_context_opts),
+        )
+        .await?;
+        let result = fetch(&ctx, &opts, &cmd, &api_node).await;
+        delete_embedded_node(&opts, &api_node).await;
+        let identity = result?;
+
+        // the identity is only stored once its change history is verified
+        let identifier = identity.identifier();
+        opts.state
+            .identities
+            .identities_repository()
+            .await?
+            .update_identity(&identity)
+            .await
+            .into_diagnostic()?;
+        opts.state
+            .create_identity_state(&identifier, Some(&cmd.name))
+            .await?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The identity {identifier} was fetched from {} and stored as '{}'",
+                cmd.from,
+                cmd.name
+            ))
+            .machine(identifier.to_string())
+            .json(serde_json::json!({
+                "name": cmd.name,
+                "identifier": identifier.to_string(),
+            }))
+            .write_line()?;
+        Ok(())
+    }
+}
+
+/// Request the identity from the remote identity service and verify its change history
+async fn fetch(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    cmd: &FetchCommand,
+    api_node: &str,
+) -> miette::Result<Identity> {
+    let (to, meta) =
+        clean_nodes_multiaddr(&cmd.from, &opts.state).context("Argument '--from' is invalid")?;
+    let projects_sc = crate::project::util::get_projects_secure_channels_from_config_lookup(
+        ctx,
+        opts,
+        &meta,
+        api_node,
+        None,
+        CredentialExchangeMode::Oneway,
+    )
+    .await?;
+    let to = crate::project::util::clean_projects_multiaddr(to, projects_sc)?;
+
+    // The request is relayed to the service by the node, as a message.
+    // Any failure to get a response means that the service can't be reached
+    let request = Request::get(cmd.remote_name.as_str())
+        .to_vec()
+        .into_diagnostic()?;
+    let mut rpc = RpcBuilder::new(ctx, opts, api_node).build();
+    let buf = match rpc
+        .request_with_timeout(
+            Request::post("v0/message").body(SendMessage::new(&to, request.as_slice())),
+            Duration::from_secs(cmd.timeout),
+        )
+        .await
+    {
+        Ok(()) => rpc.parse_response::<Vec<u8>>().ok(),
+        Err(_) => None,
+    };
+    let buf = buf.ok_or_else(|| miette!("Unable to reach the identity service at {}", cmd.from))?;
+
+    let mut dec = Decoder::new(&buf);
+    let header: Response = dec.decode().into_diagnostic()?;
+    match header.status() {
+        Some(Status::Ok) => {}
+        // the identity service answers a request for an unknown identity with a bad request
+        Some(Status::BadRequest) => {
+            return Err(miette!(
+                "The identity service at {} has no identity named '{}'",
+                cmd.from,
+                cmd.remote_name
+            ))
+        }
+        status => {
+            return Err(miette!(
+                "The identity '{}' can't be fetched from {}: {}",
+                cmd.remote_name,
+                cmd.from,
+                status
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| "no status".to_string())
+            ))
+        }
+    }
+    let body = response_body(&header, &mut dec).into_diagnostic()?;
+    let response: CreateResponse = minicbor::decode(&body).into_diagnostic()?;
+
+    let identity = identities()
+        .identities_creation()
+        .decode_identity(response.identity())
+        .await
+        .map_err(|e| {
+            miette!(
+                "The change history of the identity '{}' is not valid: {e}",
+                cmd.remote_name
+            )
+        })?;
+    if identity.identifier().to_string() != response.identity_id() {
+        return Err(miette!(
+            "The identity service at {} returned an identity which doesn't match its identifier {}",
+            cmd.from,
+            response.identity_id()
+        ));
+    }
+    Ok(identity)
+}
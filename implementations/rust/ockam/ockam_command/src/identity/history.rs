@@ -0,0 +1,118 @@
+use crate::identity::{get_identity_name, initialize_identity_if_default};
+use crate::util::node_rpc;
+use crate::{docs, CommandGlobalOpts, Result};
+use clap::Args;
+use cli_table::{Cell, Style, Table};
+use miette::IntoDiagnostic;
+use ockam::identity::{IdentityChange, IdentityChangeHistory};
+use ockam_api::cli_state::traits::StateDirTrait;
+use ockam_node::Context;
+use serde::Serialize;
+
+const LONG_ABOUT: &str = include_str!("./static/history/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/history/after_long_help.txt");
+
+/// Show the change history of an identity
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct HistoryCommand {
+    #[arg()]
+    name: Option<String>,
+}
+
+impl HistoryCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        initialize_identity_if_default(&opts, &self.name);
+        node_rpc(Self::run_impl, (opts, self))
+    }
+
+    async fn run_impl(
+        _ctx: Context,
+        (opts, cmd): (CommandGlobalOpts, HistoryCommand),
+    ) -> miette::Result<()> {
+        let name = get_identity_name(&opts.state, &cmd.name);
+        let state = opts.state.identities.get(&name)?;
+        let identity = opts
+            .state
+            .identities
+            .identities_repository()
+            .await?
+            .get_identity(&state.identifier())
+            .await
+            .into_diagnostic()?;
+
+        let changes = IdentityChangeOutput::from_history(&identity.change_history())?;
+        opts.terminal
+            .stdout()
+            .plain(IdentityChangeOutput::table(&changes)?)
+            .json(serde_json::to_string_pretty(&changes).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+/// Human and machine readable description of a change in an identity change history
+#[derive(Serialize)]
+pub struct IdentityChangeOutput {
+    pub index: usize,
+    pub identifier: String,
+    pub change_type: String,
+    pub label: String,
+    pub key_type: String,
+    pub public_key: String,
+}
+
+impl IdentityChangeOutput {
+    pub fn from_history(history: &IdentityChangeHistory) -> Result<Vec<IdentityChangeOutput>> {
+        let mut changes = vec![];
+        for (index, signed_change) in history.as_ref().iter().enumerate() {
+            let change = signed_change.change();
+            let public_key = change.public_key()?;
+            let change_type = match change {
+                IdentityChange::CreateKey(_) => "CreateKey",
+                IdentityChange::RotateKey(_) => "RotateKey",
+            };
+            changes.push(IdentityChangeOutput {
+                index,
+                identifier: signed_change.identifier().to_string(),
+                change_type: change_type.to_string(),
+                label: change.label().to_string(),
+                key_type: public_key.stype().to_string(),
+                public_key: hex::encode(public_key.data()),
+            })
+        }
+        Ok(changes)
+    }
+
+    pub fn table(changes: &[IdentityChangeOutput]) -> Result<String> {
+        let rows: Vec<_> = changes
+            .iter()
+            .map(|c| {
+                [
+                    c.index.cell(),
+                    c.identifier.as_str().cell(),
+                    c.change_type.as_str().cell(),
+                    c.label.as_str().cell(),
+                    c.key_type.as_str().cell(),
+                ]
+            })
+            .collect();
+        let table = rows
+            .table()
+            .title([
+                "Index".cell().bold(true),
+                "Change Identifier".cell().bold(true),
+                "Type".cell().bold(true),
+                "Key Label".cell().bold(true),
+                "Key Type".cell().bold(true),
+            ])
+            .display()?
+            .to_string();
+        Ok(table)
+    }
+}
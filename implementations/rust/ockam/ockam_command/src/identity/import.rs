@@ -0,0 +1,53 @@
+use crate::util::local_cmd;
+use crate::{docs, fmt_log, fmt_ok, CommandGlobalOpts};
+use clap::Args;
+use colorful::Colorful;
+use miette::miette;
+use ockam_api::identity::parse_public_identity_uri;
+
+const LONG_ABOUT: &str = include_str!("./static/import/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/import/after_long_help.txt");
+
+/// Decode a public identity shared as an `ockam://identity/` URI
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ImportCommand {
+    /// URI of the public identity, as shown by `ockam identity show --uri`
+    uri: String,
+}
+
+impl ImportCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        local_cmd(run_impl(opts, self));
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: ImportCommand) -> miette::Result<()> {
+    let public_identity = parse_public_identity_uri(&cmd.uri)
+        .map_err(|e| miette!("Invalid public identity URI: {e}"))?;
+    let identifier = public_identity.identity_id();
+    let key_type = public_identity.key_type().to_string();
+    let public_key = hex::encode(public_identity.public_key());
+    opts.terminal
+        .stdout()
+        .plain(format!(
+            "{}\n{}\n{}",
+            fmt_ok!("The URI contains the public identity {identifier}"),
+            fmt_log!("Key type: {key_type}"),
+            fmt_log!("Public key: {public_key}")
+        ))
+        .machine(identifier)
+        .json(serde_json::json!({
+            "identifier": identifier,
+            "key_type": key_type,
+            "public_key": public_key,
+        }))
+        .write_line()?;
+    Ok(())
+}
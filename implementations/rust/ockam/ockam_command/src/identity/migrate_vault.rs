@@ -0,0 +1,157 @@
+use crate::util::node_rpc;
+use crate::{docs, fmt_log, fmt_ok, fmt_warn, CommandGlobalOpts};
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
+use ockam::identity::Identity;
+use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
+use ockam_node::{Context, KeyValueStorage};
+use ockam_vault::storage::PersistentStorage;
+use ockam_vault::{KeyId, SecretsStoreReader, Vault};
+use serde::Serialize;
+
+const LONG_ABOUT: &str = include_str!("./static/migrate_vault/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/migrate_vault/after_long_help.txt");
+
+/// Copy the keys of the identities backed by a vault to another vault
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct MigrateVaultCommand {
+    /// Name of the vault holding the keys of the identities
+    #[arg(long, value_name = "VAULT_NAME")]
+    from: String,
+
+    /// Name of the vault receiving the keys
+    #[arg(long, value_name = "VAULT_NAME")]
+    to: String,
+}
+
+impl MigrateVaultCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(Self::run_impl, (opts, self))
+    }
+
+    async fn run_impl(
+        _ctx: Context,
+        (opts, cmd): (CommandGlobalOpts, MigrateVaultCommand),
+    ) -> miette::Result<()> {
+        if cmd.from == cmd.to {
+            return Err(miette!("The source and target vaults must be different"));
+        }
+        let from = opts.state.vaults.get(&cmd.from)?;
+        let to = opts.state.vaults.get(&cmd.to)?;
+        if to.config().is_aws() {
+            return Err(miette!(
+                "The keys can't be imported into the vault '{}' since it is backed by AWS KMS",
+                cmd.to
+            ));
+        }
+        // AWS KMS keys never leave the KMS
+        let exportable = !from.config().is_aws();
+        let from_vault = from.get().await?;
+        let from_storage = PersistentStorage::create(from.vault_file_path())
+            .await
+            .into_diagnostic()?;
+        let to_storage = PersistentStorage::create(to.vault_file_path())
+            .await
+            .into_diagnostic()?;
+
+        let repository = opts.state.identities.identities_repository().await?;
+        let mut output = MigrateVaultOutput {
+            from: cmd.from.clone(),
+            to: cmd.to.clone(),
+            migrated: vec![],
+            refused: vec![],
+        };
+        for state in opts.state.identities.list()? {
+            let identity = match repository
+                .retrieve_identity(&state.identifier())
+                .await
+                .into_diagnostic()?
+            {
+                Some(identity) => identity,
+                None => continue,
+            };
+            let key_ids = held_key_ids(&from_vault, &identity).await?;
+            if key_ids.is_empty() {
+                continue;
+            }
+            let name = state.name().to_string();
+            if !exportable {
+                opts.terminal.write_line(&fmt_warn!(
+                    "The keys of the identity {name} can't be exported from the vault '{}'",
+                    cmd.from
+                ))?;
+                output.refused.push(name);
+                continue;
+            }
+            for key_id in key_ids.iter() {
+                if let Some(secret) = from_storage.get(key_id).await.into_diagnostic()? {
+                    to_storage
+                        .put(key_id.clone(), secret)
+                        .await
+                        .into_diagnostic()?;
+                }
+            }
+            opts.terminal.write_line(&fmt_log!(
+                "Migrated the identity {name} ({} keys)",
+                key_ids.len()
+            ))?;
+            output.migrated.push(name);
+        }
+
+        let mut plain = fmt_ok!(
+            "Migrated {} identities from the vault '{}' to the vault '{}'",
+            output.migrated.len(),
+            output.from,
+            output.to
+        );
+        if !output.refused.is_empty() {
+            plain.push('\n');
+            plain.push_str(&fmt_warn!(
+                "These identities have keys which can't be exported and were not migrated: {}",
+                output.refused.join(", ")
+            ));
+        }
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .machine(output.migrated.len().to_string())
+            .json(serde_json::to_string_pretty(&output).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct MigrateVaultOutput {
+    from: String,
+    to: String,
+    migrated: Vec<String>,
+    refused: Vec<String>,
+}
+
+/// Return the identifiers of the keys of an identity, across its change history,
+/// which are held by a vault
+async fn held_key_ids(vault: &Vault, identity: &Identity) -> miette::Result<Vec<KeyId>> {
+    let mut key_ids = vec![];
+    let change_history = identity.change_history();
+    for change in change_history.as_ref() {
+        let public_key = change.change().public_key().into_diagnostic()?;
+        let key_id = match vault.get_key_id(&public_key).await {
+            Ok(key_id) => key_id,
+            Err(_) => continue,
+        };
+        // the software vault derives key identifiers from the public keys,
+        // so the key must also be found in the vault
+        if vault.get_secret_attributes(&key_id).await.is_ok() && !key_ids.contains(&key_id) {
+            key_ids.push(key_id);
+        }
+    }
+    Ok(key_ids)
+}
@@ -1,14 +1,44 @@
+mod audit_tail;
+mod auto_rotate;
+mod compare;
 mod create;
+mod csr;
 mod default;
 mod delete;
+mod fetch;
+mod history;
+mod import;
 mod list;
+mod migrate_vault;
+mod prune;
 mod show;
-
+mod sign;
+mod usage;
+mod validate;
+mod verify;
+mod verify_manifest;
+mod watch;
+
+pub(crate) use audit_tail::AuditTailCommand;
+pub(crate) use auto_rotate::AutoRotateCommand;
 use colorful::Colorful;
+pub(crate) use compare::CompareCommand;
 pub(crate) use create::CreateCommand;
+pub(crate) use csr::CsrCommand;
 pub(crate) use delete::DeleteCommand;
+pub(crate) use fetch::FetchCommand;
+pub(crate) use history::HistoryCommand;
+pub(crate) use import::ImportCommand;
 pub(crate) use list::ListCommand;
+pub(crate) use migrate_vault::MigrateVaultCommand;
+pub(crate) use prune::PruneCommand;
 pub(crate) use show::ShowCommand;
+pub(crate) use sign::SignCommand;
+pub(crate) use usage::UsageCommand;
+pub(crate) use validate::ValidateCommand;
+pub(crate) use verify::VerifyCommand;
+pub(crate) use verify_manifest::VerifyManifestCommand;
+pub(crate) use watch::WatchCommand;
 
 use crate::identity::default::DefaultCommand;
 use crate::terminal::OckamColor;
@@ -38,6 +68,21 @@ pub enum IdentitySubcommand {
     List(ListCommand),
     Default(DefaultCommand),
     Delete(DeleteCommand),
+    History(HistoryCommand),
+    Compare(CompareCommand),
+    Sign(SignCommand),
+    Verify(VerifyCommand),
+    VerifyManifest(VerifyManifestCommand),
+    Watch(WatchCommand),
+    MigrateVault(MigrateVaultCommand),
+    Import(ImportCommand),
+    Fetch(FetchCommand),
+    Csr(CsrCommand),
+    AuditTail(AuditTailCommand),
+    Prune(PruneCommand),
+    Validate(ValidateCommand),
+    AutoRotate(AutoRotateCommand),
+    Usage(UsageCommand),
 }
 
 impl IdentityCommand {
@@ -48,6 +93,21 @@ impl IdentityCommand {
             IdentitySubcommand::List(c) => c.run(options),
             IdentitySubcommand::Delete(c) => c.run(options),
             IdentitySubcommand::Default(c) => c.run(options),
+            IdentitySubcommand::History(c) => c.run(options),
+            IdentitySubcommand::Compare(c) => c.run(options),
+            IdentitySubcommand::Sign(c) => c.run(options),
+            IdentitySubcommand::Verify(c) => c.run(options),
+            IdentitySubcommand::VerifyManifest(c) => c.run(options),
+            IdentitySubcommand::Watch(c) => c.run(options),
+            IdentitySubcommand::MigrateVault(c) => c.run(options),
+            IdentitySubcommand::Import(c) => c.run(options),
+            IdentitySubcommand::Fetch(c) => c.run(options),
+            IdentitySubcommand::Csr(c) => c.run(options),
+            IdentitySubcommand::AuditTail(c) => c.run(options),
+            IdentitySubcommand::Prune(c) => c.run(options),
+            IdentitySubcommand::Validate(c) => c.run(options),
+            IdentitySubcommand::AutoRotate(c) => c.run(options),
+            IdentitySubcommand::Usage(c) => c.run(options),
         }
     }
 }
@@ -0,0 +1,166 @@
+use crate::terminal::ConfirmResult;
+use crate::util::parsers::duration_parser;
+use crate::util::{api, node_rpc, Rpc};
+use crate::{docs, fmt_log, fmt_ok, fmt_warn, CommandGlobalOpts};
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, Context as _, IntoDiagnostic};
+use ockam::identity::{IdentityIdentifier, Timestamp};
+use ockam::Context;
+use ockam_api::cli_state::traits::StateDirTrait;
+use ockam_api::identity::models::{Revocation, RevocationData};
+use ockam_api::identity::REVOCATION_ATTRIBUTE;
+use ockam_api::nodes::models::secure_channel::SecureChannelListenersList;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+const LONG_ABOUT: &str = include_str!("./static/prune/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/prune/after_long_help.txt");
+
+/// Delete the revoked identities
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct PruneCommand {
+    /// Delete the identities which were revoked
+    #[arg(long, required = true)]
+    revoked: bool,
+
+    /// Only delete the identities revoked longer ago than this duration, such as `30d`
+    #[arg(long, value_name = "DURATION", value_parser = duration_parser)]
+    older_than: Option<Duration>,
+
+    /// Confirm the deletion without prompting
+    #[arg(display_order = 901, long, short)]
+    yes: bool,
+}
+
+impl PruneCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(Self::run_impl, (opts, self))
+    }
+
+    async fn run_impl(
+        ctx: Context,
+        (opts, cmd): (CommandGlobalOpts, PruneCommand),
+    ) -> miette::Result<()> {
+        let now = Timestamp::now().ok_or_else(|| miette!("unable to get the current time"))?;
+        let repository = opts.state.identities.identities_repository().await?;
+        let mut revoked = vec![];
+        for state in opts.state.identities.list()? {
+            let revocation = match repository
+                .get_attributes(&state.identifier())
+                .await
+                .into_diagnostic()?
+                .and_then(|entry| entry.attrs().get(REVOCATION_ATTRIBUTE).cloned())
+            {
+                Some(revocation) => revocation,
+                None => continue,
+            };
+            let revoked_at = revoked_at(&revocation)
+                .with_context(|| format!("Invalid revocation of the identity {}", state.name()))?;
+            let revoked_for = now.elapsed(revoked_at).unwrap_or_default();
+            if cmd
+                .older_than
+                .map_or(true, |older_than| revoked_for > older_than)
+            {
+                revoked.push(state);
+            }
+        }
+
+        let mut output = PruneOutput {
+            pruned: vec![],
+            skipped: vec![],
+        };
+        if !revoked.is_empty() && !cmd.yes {
+            let names: Vec<&str> = revoked.iter().map(|state| state.name()).collect();
+            match opts.terminal.confirm(&fmt_warn!(
+                "This will delete the revoked identities {}. Do you wish to proceed?",
+                names.join(", ")
+            ))? {
+                ConfirmResult::Yes => {}
+                ConfirmResult::No => return Ok(()),
+                ConfirmResult::NonTTY => return Err(miette!("Use --yes to confirm")),
+            }
+        }
+
+        let listening = listening_identities(&ctx, &opts).await?;
+        for state in revoked {
+            let name = state.name().to_string();
+            if listening.contains(&state.identifier()) {
+                opts.terminal.write_line(&fmt_warn!(
+                    "The identity {name} is still accepting secure channels and was not deleted"
+                ))?;
+                output.skipped.push(name);
+                continue;
+            }
+            if let Err(e) = opts.state.delete_identity(state) {
+                opts.terminal
+                    .write_line(&fmt_warn!("The identity {name} was not deleted: {e}"))?;
+                output.skipped.push(name);
+                continue;
+            }
+            opts.terminal
+                .write_line(&fmt_log!("Deleted the identity {name}"))?;
+            output.pruned.push(name);
+        }
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "Deleted {} revoked identities",
+                output.pruned.len()
+            ))
+            .machine(output.pruned.len().to_string())
+            .json(serde_json::to_string_pretty(&output).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct PruneOutput {
+    pruned: Vec<String>,
+    skipped: Vec<String>,
+}
+
+/// Return the time at which an identity was revoked, from its encoded revocation record
+fn revoked_at(revocation: &[u8]) -> miette::Result<Timestamp> {
+    let revocation: Revocation = minicbor::decode(revocation).into_diagnostic()?;
+    let data: RevocationData = minicbor::decode(revocation.data()).into_diagnostic()?;
+    Ok(data.revoked_at())
+}
+
+/// Return the identifiers of the identities accepting secure channels on the running nodes
+async fn listening_identities(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+) -> miette::Result<BTreeSet<IdentityIdentifier>> {
+    let mut identifiers = BTreeSet::new();
+    for node in opts.state.nodes.list()? {
+        if !node.is_running() {
+            continue;
+        }
+        let mut rpc = Rpc::background(ctx, opts, node.name())?;
+        rpc.request(api::list_secure_channel_listener())
+            .await
+            .with_context(|| {
+                format!(
+                    "The secure channel listeners of the node {} can't be listed",
+                    node.name()
+                )
+            })?;
+        let listeners = rpc.parse_response::<SecureChannelListenersList>()?;
+        for identifier in listeners.list.iter().filter_map(|l| l.identifier.as_ref()) {
+            identifiers
+                .insert(IdentityIdentifier::try_from(identifier.as_str()).into_diagnostic()?);
+        }
+    }
+    Ok(identifiers)
+}
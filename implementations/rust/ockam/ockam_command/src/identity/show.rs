@@ -4,11 +4,17 @@ use crate::util::{node_rpc, println_output};
 use crate::{docs, CommandGlobalOpts, EncodeFormat, Result};
 use clap::Args;
 use core::fmt::Write;
-use miette::IntoDiagnostic;
+use miette::{miette, IntoDiagnostic};
 use ockam::identity::identity::IdentityChangeHistory;
+use ockam::identity::IdentityIdentifier;
 use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
+use ockam_api::identity::models::PublicIdentityResponse;
+use ockam_api::identity::public_identity_uri;
 use ockam_api::nodes::models::identity::{LongIdentityResponse, ShortIdentityResponse};
 use ockam_node::Context;
+use qrcode::render::unicode::Dense1x2;
+use qrcode::types::QrError;
+use qrcode::QrCode;
 
 const LONG_ABOUT: &str = include_str!("./static/show/long_about.txt");
 const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
@@ -34,6 +40,14 @@ pub struct ShowCommand {
     //      for `full` (change history) identity.
     #[arg(long, value_enum, requires = "full")]
     encoding: Option<EncodeFormat>,
+
+    /// Render the public identity as a QR code, to share it with a peer
+    #[arg(long, conflicts_with = "full")]
+    qr: bool,
+
+    /// Show the public identity as an `ockam://identity/` URI, to share it with a peer
+    #[arg(long, conflicts_with_all = ["full", "qr"])]
+    uri: bool,
 }
 
 impl ShowCommand {
@@ -49,7 +63,47 @@ impl ShowCommand {
         let (opts, cmd) = options;
         let name = get_identity_name(&opts.state, &cmd.name);
         let state = opts.state.identities.get(&name)?;
-        if cmd.full {
+        if cmd.uri {
+            let identifier = state.config().identifier();
+            let public_identity = public_identity(&opts, &identifier).await?;
+            let uri = public_identity_uri(&public_identity).into_diagnostic()?;
+            opts.terminal
+                .stdout()
+                .plain(&uri)
+                .machine(&uri)
+                .json(serde_json::json!({
+                    "identifier": identifier.to_string(),
+                    "uri": &uri,
+                }))
+                .write_line()?;
+        } else if cmd.qr {
+            let identifier = state.config().identifier();
+            let public_identity = hex::encode(
+                minicbor::to_vec(public_identity(&opts, &identifier).await?).into_diagnostic()?,
+            );
+            let code = match QrCode::new(public_identity.as_bytes()) {
+                Ok(code) => code,
+                Err(QrError::DataTooLong) => {
+                    return Err(miette!(
+                        "The identity {} is too large to be encoded in a QR code. \
+                        Export it with `ockam identity show {} --full --encoding hex` instead",
+                        name,
+                        name
+                    ))
+                }
+                Err(e) => return Err(miette!("Unable to create a QR code: {e}")),
+            };
+            let qr = code.render::<Dense1x2>().quiet_zone(true).build();
+            opts.terminal
+                .stdout()
+                .plain(qr)
+                .machine(&public_identity)
+                .json(serde_json::json!({
+                    "identifier": identifier.to_string(),
+                    "public_identity": &public_identity,
+                }))
+                .write_line()?;
+        } else if cmd.full {
             let identifier = state.config().identifier();
             let identity = opts
                 .state
@@ -76,6 +130,27 @@ impl ShowCommand {
     }
 }
 
+/// Return the same minimal blob as the one returned by the identity service at `<name>/public`
+async fn public_identity(
+    opts: &CommandGlobalOpts,
+    identifier: &IdentityIdentifier,
+) -> miette::Result<PublicIdentityResponse<'static>> {
+    let identity = opts
+        .state
+        .identities
+        .identities_repository()
+        .await?
+        .get_identity(identifier)
+        .await
+        .into_diagnostic()?;
+    let public_key = identity.get_root_public_key().into_diagnostic()?;
+    Ok(PublicIdentityResponse::new(
+        identifier.to_string(),
+        public_key.stype(),
+        public_key.data().to_vec(),
+    ))
+}
+
 impl Output for LongIdentityResponse<'_> {
     fn output(&self) -> Result<String> {
         let mut w = String::new();
@@ -0,0 +1,166 @@
+use crate::identity::{get_identity_name, initialize_identity_if_default};
+use crate::util::node_rpc;
+use crate::vault::default_vault_name;
+use crate::{docs, fmt_ok, CommandGlobalOpts};
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
+use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
+use ockam_node::Context;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{BufReader, ErrorKind, Read};
+use std::path::{Path, PathBuf};
+
+const LONG_ABOUT: &str = include_str!("./static/sign/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/sign/after_long_help.txt");
+
+/// Sign a file and write a detached signature
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct SignCommand {
+    /// Name of the identity signing the file
+    #[arg(long)]
+    identity: Option<String>,
+
+    /// Path to the file to sign, or `-` to read the data from the standard input.
+    /// Without `--prehash` the whole input is held in memory: use `--prehash` to sign large
+    /// streamed inputs
+    #[arg(long = "in", value_name = "PATH")]
+    input: PathBuf,
+
+    /// Path to the file where the signature is written
+    #[arg(long = "out", value_name = "PATH")]
+    output: PathBuf,
+
+    /// Sign the SHA-256 digest of the file instead of its contents
+    #[arg(long)]
+    prehash: bool,
+
+    /// Name of the vault containing the key of the identity
+    #[arg(long, value_name = "VAULT_NAME")]
+    vault: Option<String>,
+}
+
+impl SignCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        initialize_identity_if_default(&opts, &self.identity);
+        node_rpc(Self::run_impl, (opts, self))
+    }
+
+    async fn run_impl(
+        _ctx: Context,
+        (opts, cmd): (CommandGlobalOpts, SignCommand),
+    ) -> miette::Result<()> {
+        let name = get_identity_name(&opts.state, &cmd.identity);
+        let identifier = opts.state.identities.get(&name)?.config().identifier();
+        let identity = opts
+            .state
+            .identities
+            .identities_repository()
+            .await?
+            .get_identity(&identifier)
+            .await
+            .into_diagnostic()?;
+
+        let vault_name = match cmd.vault.clone() {
+            Some(vault_name) => vault_name,
+            None => default_vault_name(&opts.state)?,
+        };
+        let vault = opts.state.vaults.get(&vault_name)?.get().await?;
+        let identities_keys = opts.state.get_identities(vault).await?.identities_keys();
+
+        let payload = signed_payload(&cmd.input, cmd.prehash)?;
+        let signature = identities_keys
+            .create_signature(&identity, &payload, None)
+            .await
+            .into_diagnostic()?;
+        std::fs::write(&cmd.output, signature.as_ref())
+            .map_err(|e| miette!("Unable to write {}: {e}", cmd.output.display()))?;
+
+        let output = SignatureOutput {
+            identity: identifier.to_string(),
+            file: input_name(&cmd.input),
+            signature: cmd.output.display().to_string(),
+        };
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "Signed {} with {name}, the signature was written to {}",
+                output.file,
+                output.signature
+            ))
+            .machine(&output.signature)
+            .json(serde_json::to_string_pretty(&output).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+/// Path given to `--in` to read the data from the standard input
+const STDIN: &str = "-";
+
+/// Return the data which is signed for a file: either its contents or their SHA-256 digest.
+/// The digest is computed while the file is read, so that large files are not held in memory.
+/// If the path is `-` the data is read from the standard input
+pub(super) fn signed_payload(path: &Path, prehash: bool) -> miette::Result<Vec<u8>> {
+    let source = input_name(path);
+    let mut reader: Box<dyn Read> = if path == Path::new(STDIN) {
+        Box::new(std::io::stdin().lock())
+    } else {
+        let file = File::open(path).map_err(|e| miette!("Unable to read {source}: {e}"))?;
+        Box::new(BufReader::new(file))
+    };
+    if !prehash {
+        let mut contents = vec![];
+        reader
+            .read_to_end(&mut contents)
+            .map_err(|e| read_error(&source, e))?;
+        return Ok(contents);
+    }
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 8192];
+    loop {
+        let read = reader
+            .read(&mut buffer)
+            .map_err(|e| read_error(&source, e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher.finalize().to_vec())
+}
+
+/// Return a description of the input to use in messages
+pub(super) fn input_name(path: &Path) -> String {
+    if path == Path::new(STDIN) {
+        "the standard input".to_string()
+    } else {
+        path.display().to_string()
+    }
+}
+
+fn read_error(source: &str, e: std::io::Error) -> miette::Report {
+    match e.kind() {
+        ErrorKind::BrokenPipe | ErrorKind::UnexpectedEof => {
+            miette!(
+                "Unable to read {source}: the input was closed before all the data was received"
+            )
+        }
+        _ => miette!("Unable to read {source}: {e}"),
+    }
+}
+
+#[derive(Serialize)]
+struct SignatureOutput {
+    identity: String,
+    file: String,
+    signature: String,
+}
@@ -0,0 +1,132 @@
+use crate::util::{disk_usage, node_rpc};
+use crate::{docs, CommandGlobalOpts};
+use clap::Args;
+use miette::IntoDiagnostic;
+use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
+use ockam_node::Context;
+use serde::Serialize;
+use std::fmt::Write;
+
+const LONG_ABOUT: &str = include_str!("./static/usage/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/usage/after_long_help.txt");
+
+/// Show the disk space used by each identity
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct UsageCommand {}
+
+impl UsageCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(Self::run_impl, (opts, self))
+    }
+
+    async fn run_impl(
+        _ctx: Context,
+        (opts, _cmd): (CommandGlobalOpts, UsageCommand),
+    ) -> miette::Result<()> {
+        let repository = opts.state.identities.identities_repository().await?;
+        let mut identities = vec![];
+        for state in opts.state.identities.list()? {
+            // the change history is stored in the identities store, with the other identities
+            let (changes, history_bytes) = match repository
+                .retrieve_identity(&state.identifier())
+                .await
+                .into_diagnostic()?
+            {
+                Some(identity) => (
+                    identity.change_history().as_ref().len(),
+                    identity.export().into_diagnostic()?.len() as u64,
+                ),
+                None => (0, 0),
+            };
+            let state_bytes = disk_usage(state.path());
+            identities.push(IdentityUsage {
+                name: state.name().to_string(),
+                identifier: state.identifier().to_string(),
+                changes,
+                state_bytes,
+                history_bytes,
+                total_bytes: state_bytes + history_bytes,
+            });
+        }
+        // the largest identities first, then by name so that the order is stable
+        identities.sort_by(|a, b| {
+            b.total_bytes
+                .cmp(&a.total_bytes)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        let store_bytes = disk_usage(&opts.state.identities.identities_repository_path()?);
+        let output = IdentitiesUsageOutput {
+            total_bytes: identities
+                .iter()
+                .map(|identity| identity.state_bytes)
+                .sum::<u64>()
+                + store_bytes,
+            store_bytes,
+            identities,
+        };
+
+        opts.terminal
+            .stdout()
+            .plain(output.plain())
+            .machine(output.machine())
+            .json(serde_json::to_string_pretty(&output).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct IdentitiesUsageOutput {
+    identities: Vec<IdentityUsage>,
+    /// Size of the store holding the change histories and attributes of all the identities
+    store_bytes: u64,
+    total_bytes: u64,
+}
+
+#[derive(Serialize)]
+struct IdentityUsage {
+    name: String,
+    identifier: String,
+    changes: usize,
+    state_bytes: u64,
+    history_bytes: u64,
+    total_bytes: u64,
+}
+
+impl IdentitiesUsageOutput {
+    fn plain(&self) -> String {
+        let mut plain = String::from("Disk usage of the identities\n");
+        if self.identities.is_empty() {
+            plain.push_str("  none\n");
+        }
+        for identity in &self.identities {
+            let _ = writeln!(
+                plain,
+                "  {}: {} bytes ({} bytes of configuration, {} bytes of change history with {} changes)",
+                identity.name,
+                identity.total_bytes,
+                identity.state_bytes,
+                identity.history_bytes,
+                identity.changes
+            );
+        }
+        let _ = writeln!(plain, "Identities store: {} bytes", self.store_bytes);
+        let _ = write!(plain, "Total: {} bytes", self.total_bytes);
+        plain
+    }
+
+    /// One `<name> <bytes>` line per identity, the largest identities first
+    fn machine(&self) -> String {
+        self.identities
+            .iter()
+            .map(|identity| format!("{} {}", identity.name, identity.total_bytes))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
@@ -0,0 +1,128 @@
+use crate::util::node_rpc;
+use crate::{docs, fmt_err, fmt_ok, CommandGlobalOpts};
+use clap::{Args, ValueEnum};
+use colorful::Colorful;
+use data_encoding::BASE64;
+use miette::{miette, IntoDiagnostic};
+use ockam::identity::{identities, IdentityChangeHistory, IdentityIdentifier};
+use ockam_node::Context;
+use serde::Serialize;
+use std::path::PathBuf;
+
+const LONG_ABOUT: &str = include_str!("./static/validate/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/validate/after_long_help.txt");
+
+/// Validate the change history of an identity stored in a file
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ValidateCommand {
+    /// Path to the file containing the change history
+    #[arg(long, value_name = "PATH")]
+    history_file: PathBuf,
+
+    /// Encoding of the change history in the file
+    #[arg(long, value_enum, default_value = "hex")]
+    encoding: HistoryEncoding,
+}
+
+/// Encoding of a change history stored in a file
+#[derive(Clone, Copy, Debug, ValueEnum, PartialEq, Eq)]
+pub enum HistoryEncoding {
+    Hex,
+    Base64,
+    Raw,
+}
+
+impl HistoryEncoding {
+    fn decode(&self, contents: &[u8]) -> miette::Result<Vec<u8>> {
+        match self {
+            HistoryEncoding::Raw => Ok(contents.to_vec()),
+            HistoryEncoding::Hex => {
+                let text = std::str::from_utf8(contents).into_diagnostic()?;
+                hex::decode(text.trim()).map_err(|e| miette!("Invalid hex: {e}"))
+            }
+            HistoryEncoding::Base64 => {
+                let text = std::str::from_utf8(contents).into_diagnostic()?;
+                BASE64
+                    .decode(text.trim().as_bytes())
+                    .map_err(|e| miette!("Invalid base64: {e}"))
+            }
+        }
+    }
+}
+
+impl ValidateCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(Self::run_impl, (opts, self))
+    }
+
+    async fn run_impl(
+        _ctx: Context,
+        (opts, cmd): (CommandGlobalOpts, ValidateCommand),
+    ) -> miette::Result<()> {
+        let contents = std::fs::read(&cmd.history_file)
+            .map_err(|e| miette!("Unable to read {}: {e}", cmd.history_file.display()))?;
+        let history = cmd.encoding.decode(&contents)?;
+
+        // The identifier is derived from the first root key, even when a later change is
+        // not valid, so that the identity can be named in the verdict
+        let identifier = IdentityChangeHistory::import(&history)
+            .and_then(|history| history.get_first_root_public_key())
+            .map(|public_key| IdentityIdentifier::from_public_key(&public_key))
+            .ok();
+        let error = identities()
+            .identities_creation()
+            .decode_identity(&history)
+            .await
+            .err()
+            .map(|e| e.to_string());
+        let output = ValidateOutput {
+            identifier: identifier.map(|identifier| identifier.to_string()),
+            is_valid: error.is_none(),
+            error,
+        };
+
+        opts.terminal
+            .stdout()
+            .plain(output.plain())
+            .machine(output.is_valid.to_string())
+            .json(serde_json::to_string_pretty(&output).into_diagnostic()?)
+            .write_line()?;
+
+        if !output.is_valid {
+            return Err(miette!(
+                "The change history in {} is not valid",
+                cmd.history_file.display()
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct ValidateOutput {
+    identifier: Option<String>,
+    is_valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl ValidateOutput {
+    fn plain(&self) -> String {
+        let identity = self
+            .identifier
+            .as_deref()
+            .map(|identifier| format!("the identity {identifier}"))
+            .unwrap_or_else(|| "an unknown identity".to_string());
+        match &self.error {
+            None => fmt_ok!("The change history of {identity} is valid"),
+            Some(error) => fmt_err!("The change history of {identity} is not valid: {error}"),
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use crate::identity::sign::{input_name, signed_payload};
+use crate::util::node_rpc;
+use crate::{docs, fmt_err, fmt_ok, CommandGlobalOpts};
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
+use ockam::identity::{identities, Identity};
+use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
+use ockam_api::cli_state::CliState;
+use ockam_node::Context;
+use ockam_vault::Signature;
+use std::path::PathBuf;
+
+const LONG_ABOUT: &str = include_str!("./static/verify/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/verify/after_long_help.txt");
+
+/// Verify the detached signature of a file
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct VerifyCommand {
+    /// Name of a local identity, or path to a file containing a hex-encoded identity
+    #[arg(long)]
+    signer: String,
+
+    /// Path to the signed file, or `-` to read the data from the standard input.
+    /// Without `--prehash` the whole input is held in memory
+    #[arg(long = "in", value_name = "PATH")]
+    input: PathBuf,
+
+    /// Path to the signature file
+    #[arg(long = "sig", value_name = "PATH")]
+    signature: PathBuf,
+
+    /// Verify a signature of the SHA-256 digest of the file
+    #[arg(long)]
+    prehash: bool,
+}
+
+impl VerifyCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(Self::run_impl, (opts, self))
+    }
+
+    async fn run_impl(
+        _ctx: Context,
+        (opts, cmd): (CommandGlobalOpts, VerifyCommand),
+    ) -> miette::Result<()> {
+        let signer = load_signer(&opts.state, &cmd.signer).await?;
+        let payload = signed_payload(&cmd.input, cmd.prehash)?;
+        let signature = std::fs::read(&cmd.signature)
+            .map_err(|e| miette!("Unable to read {}: {e}", cmd.signature.display()))?;
+
+        let is_valid = identities()
+            .identities_keys()
+            .verify_signature(&signer, &Signature::new(signature), &payload, None)
+            .await
+            .unwrap_or(false);
+
+        let file = input_name(&cmd.input);
+        let plain = if is_valid {
+            fmt_ok!("The signature of {file} is valid")
+        } else {
+            fmt_err!("The signature of {file} is not valid")
+        };
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .machine(is_valid.to_string())
+            .json(serde_json::json!({ "is_valid": is_valid }))
+            .write_line()?;
+
+        // scripts rely on the exit code to detect an invalid signature
+        if !is_valid {
+            return Err(miette!("The signature of {file} is not valid"));
+        }
+        Ok(())
+    }
+}
+
+/// Load a local identity by name, or decode a hex-encoded identity stored in a file
+pub(super) async fn load_signer(state: &CliState, signer: &str) -> miette::Result<Identity> {
+    if let Ok(identity_state) = state.identities.get(signer) {
+        return state
+            .identities
+            .identities_repository()
+            .await?
+            .get_identity(&identity_state.config().identifier())
+            .await
+            .into_diagnostic();
+    }
+    let hex = std::fs::read_to_string(signer)
+        .map_err(|e| miette!("'{signer}' is neither an identity name nor a readable file: {e}"))?;
+    identities()
+        .identities_creation()
+        .decode_identity_hex(hex.trim())
+        .await
+        .map_err(|e| miette!("invalid identity in {signer}: {e}"))
+}
@@ -0,0 +1,214 @@
+use crate::identity::sign::signed_payload;
+use crate::identity::verify::load_signer;
+use crate::util::node_rpc;
+use crate::{docs, fmt_err, fmt_ok, CommandGlobalOpts};
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
+use ockam::identity::{identities, Identity};
+use ockam_api::cli_state::traits::StateDirTrait;
+use ockam_node::Context;
+use ockam_vault::Signature;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+const LONG_ABOUT: &str = include_str!("./static/verify_manifest/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/verify_manifest/after_long_help.txt");
+
+/// Verify the detached signatures of all the files listed in a manifest
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct VerifyManifestCommand {
+    /// Path to the JSON manifest listing the files, their signers and their signatures
+    #[arg(value_name = "PATH")]
+    manifest: PathBuf,
+
+    /// Maximum number of entries verified at the same time
+    #[arg(long, value_name = "N", default_value = "4", value_parser = clap::value_parser!(u64).range(1..))]
+    concurrency: u64,
+}
+
+/// Entry of a manifest. Relative paths are relative to the directory of the manifest
+#[derive(Clone, Debug, Deserialize)]
+struct ManifestEntry {
+    path: PathBuf,
+    /// Name of a local identity, or path to a file containing a hex-encoded identity
+    signer: String,
+    signature: PathBuf,
+    #[serde(default)]
+    prehash: bool,
+}
+
+impl VerifyManifestCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(Self::run_impl, (opts, self))
+    }
+
+    async fn run_impl(
+        _ctx: Context,
+        (opts, cmd): (CommandGlobalOpts, VerifyManifestCommand),
+    ) -> miette::Result<()> {
+        let contents = std::fs::read_to_string(&cmd.manifest)
+            .map_err(|e| miette!("Unable to read {}: {e}", cmd.manifest.display()))?;
+        let entries: Vec<ManifestEntry> = serde_json::from_str(&contents)
+            .map_err(|e| miette!("Invalid manifest {}: {e}", cmd.manifest.display()))?;
+        let base_dir = cmd.manifest.parent().unwrap_or_else(|| Path::new(""));
+
+        // Each signer is loaded once, even if it signed several files
+        let mut signers = BTreeMap::new();
+        for entry in entries.iter() {
+            if !signers.contains_key(&entry.signer) {
+                let signer = match opts.state.identities.get(&entry.signer) {
+                    Ok(_) => entry.signer.clone(),
+                    Err(_) => base_dir.join(&entry.signer).display().to_string(),
+                };
+                let identity = load_signer(&opts.state, &signer)
+                    .await
+                    .map_err(|e| e.to_string());
+                signers.insert(entry.signer.clone(), identity);
+            }
+        }
+
+        let semaphore = Arc::new(Semaphore::new(cmd.concurrency as usize));
+        let handles: Vec<_> = entries
+            .into_iter()
+            .map(|entry| {
+                let semaphore = semaphore.clone();
+                let signer = signers[&entry.signer].clone();
+                let base_dir = base_dir.to_path_buf();
+                tokio::spawn(async move {
+                    let _permit = semaphore.acquire_owned().await;
+                    verify_entry(&base_dir, entry, signer).await
+                })
+            })
+            .collect();
+        let mut results = vec![];
+        for handle in handles {
+            results.push(handle.await.into_diagnostic()?);
+        }
+
+        let report = ManifestReport::new(results);
+        opts.terminal
+            .stdout()
+            .plain(report.plain())
+            .machine(report.machine())
+            .json(serde_json::to_string_pretty(&report).into_diagnostic()?)
+            .write_line()?;
+
+        if report.failed > 0 {
+            return Err(miette!(
+                "{} of the {} entries of the manifest failed verification",
+                report.failed,
+                report.passed + report.failed
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Verify the signature of a manifest entry with its signer, or with the error which
+/// happened when the signer was loaded
+async fn verify_entry(
+    base_dir: &Path,
+    entry: ManifestEntry,
+    signer: Result<Identity, String>,
+) -> EntryResult {
+    let mut result = EntryResult {
+        path: entry.path.display().to_string(),
+        signer: entry.signer.clone(),
+        is_valid: false,
+        error: None,
+    };
+    let signer = match signer {
+        Ok(signer) => signer,
+        Err(e) => {
+            result.error = Some(e);
+            return result;
+        }
+    };
+
+    let path = base_dir.join(&entry.path);
+    let signature_path = base_dir.join(&entry.signature);
+    let files = tokio::task::spawn_blocking(move || {
+        let payload = signed_payload(&path, entry.prehash)?;
+        let signature = std::fs::read(&signature_path)
+            .map_err(|e| miette!("Unable to read {}: {e}", signature_path.display()))?;
+        Ok::<_, miette::Report>((payload, signature))
+    })
+    .await
+    .into_diagnostic()
+    .and_then(|files| files);
+    let (payload, signature) = match files {
+        Ok(files) => files,
+        Err(e) => {
+            result.error = Some(e.to_string());
+            return result;
+        }
+    };
+
+    result.is_valid = identities()
+        .identities_keys()
+        .verify_signature(&signer, &Signature::new(signature), &payload, None)
+        .await
+        .unwrap_or(false);
+    if !result.is_valid {
+        result.error = Some("the signature is not valid".to_string());
+    }
+    result
+}
+
+#[derive(Serialize)]
+struct EntryResult {
+    path: String,
+    signer: String,
+    is_valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ManifestReport {
+    passed: usize,
+    failed: usize,
+    entries: Vec<EntryResult>,
+}
+
+impl ManifestReport {
+    fn new(entries: Vec<EntryResult>) -> Self {
+        let passed = entries.iter().filter(|e| e.is_valid).count();
+        Self {
+            passed,
+            failed: entries.len() - passed,
+            entries,
+        }
+    }
+
+    fn plain(&self) -> String {
+        let mut lines: Vec<String> = self
+            .entries
+            .iter()
+            .map(|e| match &e.error {
+                None => fmt_ok!("{} is signed by {}", e.path, e.signer),
+                Some(error) => fmt_err!("{}: {error}", e.path),
+            })
+            .collect();
+        lines.push(format!("\n{} passed, {} failed", self.passed, self.failed));
+        lines.join("\n")
+    }
+
+    fn machine(&self) -> String {
+        self.entries
+            .iter()
+            .map(|e| format!("{}\t{}", e.path, if e.is_valid { "pass" } else { "fail" }))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
@@ -0,0 +1,114 @@
+use crate::identity::{get_identity_name, initialize_identity_if_default};
+use crate::util::node_rpc;
+use crate::{docs, fmt_log, fmt_ok, fmt_warn, CommandGlobalOpts};
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+use ockam_api::cli_state::traits::StateDirTrait;
+use ockam_api::identity::history_digest;
+use ockam_node::Context;
+use serde::Serialize;
+use std::time::Duration;
+use tokio::time::sleep;
+
+const LONG_ABOUT: &str = include_str!("./static/watch/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/watch/after_long_help.txt");
+
+/// Watch an identity and print an event when its change history is updated
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct WatchCommand {
+    #[arg()]
+    name: Option<String>,
+
+    /// Time to wait between two checks of the change history (seconds)
+    #[arg(long, value_name = "SECONDS", default_value = "2", value_parser = clap::value_parser!(u64).range(1..))]
+    interval: u64,
+}
+
+impl WatchCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        initialize_identity_if_default(&opts, &self.name);
+        node_rpc(Self::run_impl, (opts, self))
+    }
+
+    async fn run_impl(
+        _ctx: Context,
+        (opts, cmd): (CommandGlobalOpts, WatchCommand),
+    ) -> miette::Result<()> {
+        let name = get_identity_name(&opts.state, &cmd.name);
+        let (mut digest, changes) = history_summary(&opts, &name).await?;
+        opts.terminal.write_line(&fmt_log!(
+            "Watching identity {name} with {changes} changes, press Ctrl+C to stop"
+        ))?;
+
+        loop {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => return Ok(()),
+                _ = sleep(Duration::from_secs(cmd.interval)) => {}
+            }
+
+            let (new_digest, changes) = match history_summary(&opts, &name).await {
+                Ok(summary) => summary,
+                // the identity may be read while another process is updating it
+                Err(e) => {
+                    opts.terminal.write_line(&fmt_warn!(
+                        "Unable to read the change history of {name}, retrying: {e}"
+                    ))?;
+                    continue;
+                }
+            };
+            if new_digest == digest {
+                continue;
+            }
+            digest = new_digest;
+
+            let event = HistoryChangedEvent {
+                identity: name.clone(),
+                changes,
+                digest: digest.clone(),
+            };
+            opts.terminal
+                .clone()
+                .stdout()
+                .plain(fmt_ok!(
+                    "The change history of {name} was updated, it now has {changes} changes"
+                ))
+                .machine(&event.digest)
+                .json(serde_json::to_string(&event).into_diagnostic()?)
+                .write_line()?;
+        }
+    }
+}
+
+/// Return the hex-encoded digest of the exported change history of an identity, as computed by
+/// the identity service, with the number of changes in the history
+async fn history_summary(opts: &CommandGlobalOpts, name: &str) -> miette::Result<(String, usize)> {
+    let identifier = opts.state.identities.get(name)?.identifier();
+    let identity = opts
+        .state
+        .identities
+        .identities_repository()
+        .await?
+        .get_identity(&identifier)
+        .await
+        .into_diagnostic()?;
+    let history = identity.export().into_diagnostic()?;
+    Ok((
+        hex::encode(history_digest(&history)),
+        identity.change_history().as_ref().len(),
+    ))
+}
+
+/// Event emitted when the change history of a watched identity is updated
+#[derive(Serialize)]
+struct HistoryChangedEvent {
+    identity: String,
+    changes: usize,
+    digest: String,
+}
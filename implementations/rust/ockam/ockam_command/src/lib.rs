@@ -33,6 +33,7 @@ mod run;
 mod secure_channel;
 mod service;
 mod space;
+mod state;
 mod status;
 mod subscription;
 mod tcp;
@@ -82,6 +83,7 @@ use reset::ResetCommand;
 use secure_channel::{listener::SecureChannelListenerCommand, SecureChannelCommand};
 use service::ServiceCommand;
 use space::SpaceCommand;
+use state::StateCommand;
 use status::StatusCommand;
 use std::{path::PathBuf, sync::Mutex};
 use tcp::{
@@ -292,7 +294,9 @@ pub enum OckamSubcommand {
     Run(RunCommand),
     Status(StatusCommand),
     Reset(ResetCommand),
+    State(StateCommand),
     Authenticated(AuthenticatedCommand),
+    #[command(alias = "config")]
     Configuration(ConfigurationCommand),
 
     Completion(CompletionCommand),
@@ -342,6 +346,9 @@ impl OckamCommand {
             )
         }));
         let options = CommandGlobalOpts::new(self.global_args.clone());
+        if options.global_args.output_format == OutputFormat::Json {
+            error::enable_json_errors();
+        }
 
         let _tracing_guard = if !options.global_args.quiet {
             let log_path = self.log_path(&options);
@@ -412,6 +419,7 @@ impl OckamCommand {
             OckamSubcommand::Run(c) => c.run(options),
             OckamSubcommand::Status(c) => c.run(options),
             OckamSubcommand::Reset(c) => c.run(options),
+            OckamSubcommand::State(c) => c.run(options),
             OckamSubcommand::Authenticated(c) => c.run(),
             OckamSubcommand::Configuration(c) => c.run(options),
 
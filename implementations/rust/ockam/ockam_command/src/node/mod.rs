@@ -10,6 +10,7 @@ use ockam_api::cli_state::{CliState, StateDirTrait};
 use show::ShowCommand;
 use start::StartCommand;
 use stop::StopCommand;
+use trust::TrustCommand;
 
 use crate::{docs, fmt_log, terminal::OckamColor, CommandGlobalOpts, PARSER_LOGS};
 
@@ -21,6 +22,7 @@ mod logs;
 mod show;
 mod start;
 mod stop;
+mod trust;
 pub mod util;
 pub use create::*;
 
@@ -56,6 +58,8 @@ pub enum NodeSubcommand {
     #[command(display_order = 800)]
     Stop(StopCommand),
     #[command(display_order = 800)]
+    Trust(TrustCommand),
+    #[command(display_order = 800)]
     Default(DefaultCommand),
 }
 
@@ -68,6 +72,7 @@ impl NodeCommand {
             NodeSubcommand::Show(c) => c.run(options),
             NodeSubcommand::Start(c) => c.run(options),
             NodeSubcommand::Stop(c) => c.run(options),
+            NodeSubcommand::Trust(c) => c.run(options),
             NodeSubcommand::Logs(c) => c.run(options),
             NodeSubcommand::Default(c) => c.run(options),
         }
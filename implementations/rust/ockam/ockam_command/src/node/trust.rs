@@ -0,0 +1,174 @@
+use crate::node::{get_node_name, initialize_node_if_default};
+use crate::util::{api, node_rpc, Rpc};
+use crate::{docs, CommandGlobalOpts};
+use clap::Args;
+use miette::IntoDiagnostic;
+use ockam_api::nodes::models::trust::NodeTrust;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+const LONG_ABOUT: &str = include_str!("./static/trust/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/trust/after_long_help.txt");
+
+/// Show the trust configuration of a node
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct TrustCommand {
+    /// Name of the node.
+    #[arg()]
+    node_name: Option<String>,
+}
+
+impl TrustCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        initialize_node_if_default(&opts, &self.node_name);
+        node_rpc(run_impl, (opts, self))
+    }
+}
+
+async fn run_impl(
+    ctx: ockam::Context,
+    (opts, cmd): (CommandGlobalOpts, TrustCommand),
+) -> miette::Result<()> {
+    let node_name = get_node_name(&opts.state, &cmd.node_name);
+    let mut rpc = Rpc::background(&ctx, &opts, &node_name)?;
+    rpc.request(api::show_node_trust()).await?;
+    let trust = rpc.parse_response::<NodeTrust>()?;
+
+    let output = NodeTrustOutput::new(&node_name, &trust);
+    opts.terminal
+        .stdout()
+        .plain(output.plain())
+        .machine(
+            output
+                .trusted_identities
+                .iter()
+                .map(|identity| identity.identifier.clone())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+        .json(serde_json::to_string_pretty(&output).into_diagnostic()?)
+        .write_line()?;
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct NodeTrustOutput {
+    node: String,
+    trusted_identities: Vec<TrustedIdentityOutput>,
+    trust_context: Option<TrustContextOutput>,
+    listeners: Vec<ListenerTrustOutput>,
+}
+
+#[derive(Serialize)]
+struct TrustedIdentityOutput {
+    identifier: String,
+    attributes: BTreeMap<String, String>,
+}
+
+#[derive(Serialize)]
+struct TrustContextOutput {
+    id: String,
+    authorities: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct ListenerTrustOutput {
+    address: String,
+    identifier: Option<String>,
+    /// `None` when any identity can open a secure channel with the listener
+    authorized_identifiers: Option<Vec<String>>,
+}
+
+impl NodeTrustOutput {
+    fn new(node_name: &str, trust: &NodeTrust) -> Self {
+        Self {
+            node: node_name.to_string(),
+            trusted_identities: trust
+                .trusted_identities
+                .iter()
+                .map(|identity| TrustedIdentityOutput {
+                    identifier: identity.identifier.clone(),
+                    attributes: identity.attributes.clone(),
+                })
+                .collect(),
+            trust_context: trust
+                .trust_context
+                .as_ref()
+                .map(|trust_context| TrustContextOutput {
+                    id: trust_context.id.clone(),
+                    authorities: trust_context.authorities.clone(),
+                }),
+            listeners: trust
+                .listeners
+                .iter()
+                .map(|listener| ListenerTrustOutput {
+                    address: listener.addr.to_string(),
+                    identifier: listener.identifier.clone(),
+                    authorized_identifiers: listener.authorized_identifiers.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    fn plain(&self) -> String {
+        let mut plain = format!("Trust configuration of the node {}\n", self.node);
+
+        plain.push_str("\nTrusted identities:\n");
+        if self.trusted_identities.is_empty() {
+            plain.push_str("  none\n");
+        }
+        for identity in &self.trusted_identities {
+            let attributes: Vec<String> = identity
+                .attributes
+                .iter()
+                .map(|(name, value)| format!("{name}={value}"))
+                .collect();
+            let _ = writeln!(plain, "  {} {}", identity.identifier, attributes.join(" "));
+        }
+
+        plain.push_str("\nTrust context:\n");
+        match &self.trust_context {
+            Some(trust_context) => {
+                let _ = writeln!(plain, "  Id: {}", trust_context.id);
+                let _ = writeln!(
+                    plain,
+                    "  Authorities: {}",
+                    if trust_context.authorities.is_empty() {
+                        "none".to_string()
+                    } else {
+                        trust_context.authorities.join(", ")
+                    }
+                );
+            }
+            None => plain.push_str("  none\n"),
+        }
+
+        plain.push_str("\nSecure channel listeners:\n");
+        if self.listeners.is_empty() {
+            plain.push_str("  none\n");
+        }
+        for listener in &self.listeners {
+            let identity = listener
+                .identifier
+                .as_deref()
+                .unwrap_or("an unknown identity");
+            let allowed = match &listener.authorized_identifiers {
+                Some(identifiers) => format!("only {}", identifiers.join(", ")),
+                None => "any identity".to_string(),
+            };
+            let _ = writeln!(
+                plain,
+                "  {} listening as {identity}, accepts {allowed}",
+                listener.address
+            );
+        }
+        plain
+    }
+}
@@ -0,0 +1,32 @@
+mod repair;
+
+use crate::state::repair::RepairCommand;
+use crate::{docs, CommandGlobalOpts};
+use clap::{Args, Subcommand};
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+
+/// Manage the local state directory
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    subcommand_required = true,
+    long_about = docs::about(LONG_ABOUT),
+)]
+pub struct StateCommand {
+    #[command(subcommand)]
+    subcommand: StateSubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum StateSubcommand {
+    Repair(RepairCommand),
+}
+
+impl StateCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        match self.subcommand {
+            StateSubcommand::Repair(cmd) => cmd.run(opts),
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use crate::util::local_cmd;
+use crate::{docs, fmt_log, fmt_ok, fmt_warn, CommandGlobalOpts};
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+use ockam_api::cli_state::traits::StateDirTrait;
+use serde::Serialize;
+use std::path::Path;
+
+const LONG_ABOUT: &str = include_str!("./static/repair/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/repair/after_long_help.txt");
+
+/// Detect and fix the inconsistent default markers of the state directory
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct RepairCommand {
+    /// Fix the detected issues, instead of only reporting them
+    #[arg(long)]
+    apply: bool,
+}
+
+impl RepairCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        local_cmd(run_impl(opts, self));
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: RepairCommand) -> miette::Result<()> {
+    let mut issues = vec![];
+    issues.extend(repair_default(&opts.state.vaults, "vault", cmd.apply)?);
+    issues.extend(repair_default(
+        &opts.state.identities,
+        "identity",
+        cmd.apply,
+    )?);
+    let output = RepairOutput {
+        applied: cmd.apply,
+        issues,
+    };
+
+    opts.terminal
+        .stdout()
+        .plain(output.plain())
+        .machine(output.issues.len().to_string())
+        .json(serde_json::to_string_pretty(&output).into_diagnostic()?)
+        .write_line()?;
+    Ok(())
+}
+
+/// Check the default marker of a state directory, which must be a link to one of its items,
+/// and fix it if `apply` is set
+fn repair_default<S: StateDirTrait>(
+    state: &S,
+    kind: &str,
+    apply: bool,
+) -> miette::Result<Option<Issue>> {
+    let marker = state.default_path()?;
+    let metadata = match std::fs::symlink_metadata(&marker) {
+        Ok(metadata) => metadata,
+        // having no default is consistent
+        Err(_) => return Ok(None),
+    };
+
+    if metadata.file_type().is_symlink() {
+        if linked_item(state, &marker).is_some() {
+            return Ok(None);
+        }
+        let target = std::fs::read_link(&marker).into_diagnostic()?;
+        if apply {
+            std::fs::remove_file(&marker).into_diagnostic()?;
+        }
+        return Ok(Some(Issue {
+            state: kind.to_string(),
+            problem: Problem::DanglingDefault,
+            description: format!(
+                "The default {kind} marker points to {}, which is not a {kind}",
+                target.display()
+            ),
+            fix: format!("clearing the default {kind} marker"),
+            fixed: apply,
+        }));
+    }
+
+    // a marker which was copied instead of linked duplicates the items it was copied from
+    let contents = std::fs::read(&marker).into_diagnostic()?;
+    let mut names = state.list_items_names()?;
+    names.sort();
+    let copied_from: Vec<String> = names
+        .into_iter()
+        .filter(|name| std::fs::read(state.path(name)).ok().as_ref() == Some(&contents))
+        .collect();
+    let issue = match copied_from.first() {
+        Some(first) => {
+            if apply {
+                std::fs::remove_file(&marker).into_diagnostic()?;
+                state.set_default(first)?;
+            }
+            Issue {
+                state: kind.to_string(),
+                problem: Problem::DuplicateDefault,
+                description: format!(
+                    "The default {kind} marker is a copy of {} instead of a link",
+                    copied_from.join(", ")
+                ),
+                fix: format!("making {first} the default {kind}"),
+                fixed: apply,
+            }
+        }
+        None => {
+            if apply {
+                std::fs::remove_file(&marker).into_diagnostic()?;
+            }
+            Issue {
+                state: kind.to_string(),
+                problem: Problem::DanglingDefault,
+                description: format!("The default {kind} marker is not a link to a {kind}"),
+                fix: format!("clearing the default {kind} marker"),
+                fixed: apply,
+            }
+        }
+    };
+    Ok(Some(issue))
+}
+
+/// Return the name of the item a default marker links to, if it is an item of the state
+/// directory which can be loaded
+fn linked_item<S: StateDirTrait>(state: &S, marker: &Path) -> Option<String> {
+    let target = std::fs::canonicalize(marker).ok()?;
+    let name = target.file_stem()?.to_str()?.to_string();
+    let item = std::fs::canonicalize(state.path(&name)).ok()?;
+    if item != target || state.get(&name).is_err() {
+        return None;
+    }
+    Some(name)
+}
+
+#[derive(Serialize)]
+struct RepairOutput {
+    applied: bool,
+    issues: Vec<Issue>,
+}
+
+#[derive(Serialize)]
+struct Issue {
+    /// Type of the state holding the issue, such as `vault`
+    state: String,
+    problem: Problem,
+    description: String,
+    fix: String,
+    fixed: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Problem {
+    /// The default marker doesn't point to an existing item
+    DanglingDefault,
+    /// The default marker is a copy of one or more items instead of a link to one of them
+    DuplicateDefault,
+}
+
+impl RepairOutput {
+    fn plain(&self) -> String {
+        if self.issues.is_empty() {
+            return fmt_ok!("The default markers of the state directory are consistent");
+        }
+        let mut lines = vec![];
+        for issue in &self.issues {
+            lines.push(fmt_warn!("{}", issue.description));
+            if issue.fixed {
+                lines.push(fmt_ok!("Fixed by {}", issue.fix));
+            } else {
+                lines.push(fmt_log!("Run with --apply to fix it by {}", issue.fix));
+            }
+        }
+        lines.join("\n")
+    }
+}
@@ -0,0 +1,104 @@
+use crate::util::local_cmd;
+use crate::{docs, fmt_ok, fmt_warn, CommandGlobalOpts};
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
+use ockam::identity::IdentityIdentifier;
+use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
+use serde::Serialize;
+use std::path::PathBuf;
+
+const LONG_ABOUT: &str = include_str!("./static/import/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/import/after_long_help.txt");
+
+/// Add the identifiers listed in a file to the trusted identifiers of a trust context
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ImportCommand {
+    /// Name of the trust context
+    name: String,
+
+    /// Path to a file containing one identifier per line
+    #[arg(long, value_name = "PATH")]
+    file: PathBuf,
+}
+
+impl ImportCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        local_cmd(run_impl(opts, self));
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: ImportCommand) -> miette::Result<()> {
+    let contents = std::fs::read_to_string(&cmd.file)
+        .map_err(|e| miette!("Unable to read {}: {e}", cmd.file.display()))?;
+    let state = opts.state.trust_contexts.get(&cmd.name)?;
+    let mut config = state.config().clone();
+
+    let mut output = ImportOutput::default();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match IdentityIdentifier::try_from(line) {
+            Ok(identifier) => {
+                if config.add_trusted_identifier(identifier) {
+                    output.added += 1;
+                } else {
+                    output.skipped += 1;
+                }
+            }
+            Err(e) => output.rejected.push(RejectedIdentifier {
+                line: index + 1,
+                value: line.to_string(),
+                error: e.to_string(),
+            }),
+        }
+    }
+    opts.state.trust_contexts.overwrite(&cmd.name, config)?;
+
+    let mut plain = fmt_ok!(
+        "Imported identifiers into the trust context '{}': {} added, {} skipped, {} rejected\n",
+        cmd.name,
+        output.added,
+        output.skipped,
+        output.rejected.len()
+    );
+    for rejected in &output.rejected {
+        plain.push_str(&fmt_warn!(
+            "Line {}: invalid identifier '{}': {}\n",
+            rejected.line,
+            rejected.value,
+            rejected.error
+        ));
+    }
+    opts.terminal
+        .stdout()
+        .plain(plain)
+        .machine(output.added.to_string())
+        .json(serde_json::to_string_pretty(&output).into_diagnostic()?)
+        .write_line()?;
+    Ok(())
+}
+
+/// Result of the import of a file of identifiers into a trust context
+#[derive(Serialize, Default)]
+struct ImportOutput {
+    added: usize,
+    skipped: usize,
+    rejected: Vec<RejectedIdentifier>,
+}
+
+/// A line of the imported file which is not a valid identifier
+#[derive(Serialize)]
+struct RejectedIdentifier {
+    line: usize,
+    value: String,
+    error: String,
+}
@@ -1,6 +1,7 @@
 mod create;
 mod default;
 mod delete;
+mod import;
 mod list;
 mod show;
 
@@ -10,6 +11,7 @@ use crate::{docs, CommandGlobalOpts};
 
 use crate::trust_context::default::DefaultCommand;
 use crate::trust_context::delete::DeleteCommand;
+use crate::trust_context::import::ImportCommand;
 use crate::trust_context::list::ListCommand;
 use crate::trust_context::show::ShowCommand;
 pub use create::CreateCommand;
@@ -35,6 +37,7 @@ pub enum TrustContextSubcommand {
     Delete(DeleteCommand),
     List(ListCommand),
     Default(DefaultCommand),
+    Import(ImportCommand),
 }
 
 impl TrustContextCommand {
@@ -45,6 +48,7 @@ impl TrustContextCommand {
             TrustContextSubcommand::List(cmd) => cmd.run(opts),
             TrustContextSubcommand::Delete(cmd) => cmd.run(opts),
             TrustContextSubcommand::Default(cmd) => cmd.run(opts),
+            TrustContextSubcommand::Import(cmd) => cmd.run(opts),
         }
     }
 }
@@ -120,6 +120,11 @@ pub(crate) fn list_secure_channel_listener() -> RequestBuilder<'static, ()> {
     Request::get("/node/secure_channel_listener")
 }
 
+/// Construct a request to show the trust configuration of a node
+pub(crate) fn show_node_trust() -> RequestBuilder<'static, ()> {
+    Request::get("/node/trust")
+}
+
 pub(crate) fn delete_secure_channel_listener(
     addr: &Address,
 ) -> RequestBuilder<'static, models::secure_channel::DeleteSecureChannelListenerRequest<'static>> {
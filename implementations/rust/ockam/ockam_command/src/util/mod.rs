@@ -372,7 +372,7 @@ pub async fn stop_node(mut ctx: Context) {
 pub fn local_cmd(res: miette::Result<()>) {
     if let Err(e) = res {
         error!(%e, "Failed to run command");
-        eprintln!("{:?}", e);
+        crate::error::report_error(&e);
         std::process::exit(exitcode::SOFTWARE);
     }
 }
@@ -388,7 +388,7 @@ where
             let res = f(ctx, a).await;
             if let Err(e) = res {
                 error!(%e, "Failed to run command");
-                eprintln!("{:?}", e);
+                crate::error::report_error(&e);
                 std::process::exit(exitcode::SOFTWARE);
             }
             Ok(())
@@ -635,6 +635,26 @@ pub fn random_name() -> String {
     hex::encode(rand::random::<[u8; 4]>())
 }
 
+/// Return the number of bytes used on disk by a file, or by all the files of a directory.
+/// A path which doesn't exist doesn't use any space
+pub fn disk_usage(path: &Path) -> u64 {
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(_) => return 0,
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| disk_usage(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -783,4 +803,16 @@ mod tests {
             Err(miette!("boom"))
         }
     }
+
+    #[test]
+    fn test_disk_usage() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a"), [0u8; 10]).unwrap();
+        std::fs::create_dir(dir.path().join("nested")).unwrap();
+        std::fs::write(dir.path().join("nested").join("b"), [0u8; 5]).unwrap();
+
+        assert_eq!(disk_usage(&dir.path().join("a")), 10);
+        assert_eq!(disk_usage(dir.path()), 15);
+        assert_eq!(disk_usage(&dir.path().join("missing")), 0);
+    }
 }
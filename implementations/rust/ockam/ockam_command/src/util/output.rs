@@ -357,6 +357,19 @@ impl Output for VaultState {
                 false => "OCKAM",
             }
         )?;
+        let tags = self.config().tags();
+        if !tags.is_empty() {
+            writeln!(
+                output,
+                "Tags: {}",
+                comma_separated(
+                    &tags
+                        .iter()
+                        .map(|(key, value)| format!("{key}={value}"))
+                        .collect::<Vec<_>>()
+                )
+            )?;
+        }
         Ok(output)
     }
 
@@ -1,6 +1,7 @@
 use crate::Result;
 use miette::miette;
 use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
 
 /// Helper fn for parsing ip and port from user input
 /// It can parse a string containing either an `ip:port` pair or just a `port`
@@ -30,11 +31,36 @@ pub(crate) fn socket_addr_parser(input: &str) -> Result<SocketAddr> {
     }
 }
 
+/// Helper fn for parsing a duration from user input, given as a number of
+/// seconds (`60s`), minutes (`15m`), hours (`12h`) or days (`30d`)
+pub(crate) fn duration_parser(input: &str) -> Result<Duration> {
+    let invalid =
+        || miette!("Invalid duration {input}, expected a duration such as 30d, 12h, 15m or 60s");
+    let unit = match input.chars().last() {
+        Some('s') => 1,
+        Some('m') => 60,
+        Some('h') => 60 * 60,
+        Some('d') => 24 * 60 * 60,
+        _ => return Err(invalid().into()),
+    };
+    let count = &input[..input.len() - 1];
+    if count.is_empty() || !count.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid().into());
+    }
+    let seconds = count
+        .parse::<u64>()
+        .ok()
+        .and_then(|count| count.checked_mul(unit))
+        .ok_or_else(invalid)?;
+    Ok(Duration::from_secs(seconds))
+}
+
 #[cfg(test)]
 mod tests {
     use ockam_core::compat::net::{IpAddr, Ipv4Addr, SocketAddr};
+    use std::time::Duration;
 
-    use crate::util::parsers::socket_addr_parser;
+    use crate::util::parsers::{duration_parser, socket_addr_parser};
 
     #[test]
     fn test_parse_bootstrap_server() {
@@ -72,4 +98,25 @@ mod tests {
         let invalid_input = "192,166,0.1:9999";
         assert!(socket_addr_parser(invalid_input).is_err());
     }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(duration_parser("60s").unwrap(), Duration::from_secs(60));
+        assert_eq!(
+            duration_parser("15m").unwrap(),
+            Duration::from_secs(15 * 60)
+        );
+        assert_eq!(
+            duration_parser("12h").unwrap(),
+            Duration::from_secs(12 * 60 * 60)
+        );
+        assert_eq!(
+            duration_parser("30d").unwrap(),
+            Duration::from_secs(30 * 24 * 60 * 60)
+        );
+
+        for invalid_input in ["", "d", "30", "30w", "-1d", "1.5h", "99999999999999999999d"] {
+            assert!(duration_parser(invalid_input).is_err());
+        }
+    }
 }
@@ -9,7 +9,7 @@ use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
 use ockam_identity::{IdentityChangeConstants, KeyAttributes};
 use ockam_vault::SecretAttributes;
 
-use crate::util::node_rpc;
+use crate::vault::vault_rpc;
 use crate::CommandGlobalOpts;
 
 /// Attach a key to a vault
@@ -26,7 +26,7 @@ pub struct AttachKeyCommand {
 
 impl AttachKeyCommand {
     pub fn run(self, opts: CommandGlobalOpts) {
-        node_rpc(rpc, (opts, self));
+        vault_rpc(rpc, (opts, self));
     }
 }
 
@@ -0,0 +1,217 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use colorful::Colorful;
+use miette::{Context as _, IntoDiagnostic};
+use ockam::Context;
+use ockam_api::cli_state::traits::StateDirTrait;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::vault::create::create_vault;
+use crate::vault::default::set_default_vault;
+use crate::vault::delete::delete_vault;
+use crate::vault::tag::tag_vault;
+use crate::vault::vault_rpc;
+use crate::{docs, fmt_ok, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/batch/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/batch/after_long_help.txt");
+
+/// Apply a list of vault operations, or none of them
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct BatchCommand {
+    /// Path to a JSON file containing the list of operations
+    #[arg(long, value_name = "PATH")]
+    file: PathBuf,
+}
+
+impl BatchCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        vault_rpc(rpc, (opts, self));
+    }
+}
+
+async fn rpc(
+    mut _ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, BatchCommand),
+) -> miette::Result<()> {
+    run_impl(opts, cmd).await
+}
+
+/// An operation of a batch file
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "kebab-case", deny_unknown_fields)]
+enum VaultOperation {
+    Create {
+        name: String,
+        #[serde(default)]
+        aws_kms: bool,
+    },
+    SetDefault {
+        name: String,
+    },
+    Tag {
+        name: String,
+        tags: BTreeMap<String, String>,
+    },
+    Delete {
+        name: String,
+    },
+}
+
+impl Display for VaultOperation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VaultOperation::Create { name, .. } => write!(f, "create '{name}'"),
+            VaultOperation::SetDefault { name } => write!(f, "set-default '{name}'"),
+            VaultOperation::Tag { name, .. } => write!(f, "tag '{name}'"),
+            VaultOperation::Delete { name } => write!(f, "delete '{name}'"),
+        }
+    }
+}
+
+impl VaultOperation {
+    async fn apply(self, opts: &CommandGlobalOpts) -> miette::Result<()> {
+        match self {
+            VaultOperation::Create { name, aws_kms } => create_vault(opts, &name, aws_kms).await,
+            VaultOperation::SetDefault { name } => set_default_vault(opts, &name),
+            VaultOperation::Tag { name, tags } => tag_vault(opts, &name, tags).map(|_| ()),
+            VaultOperation::Delete { name } => delete_vault(opts, &name),
+        }
+    }
+}
+
+async fn run_impl(opts: CommandGlobalOpts, cmd: BatchCommand) -> miette::Result<()> {
+    let contents = std::fs::read_to_string(&cmd.file)
+        .into_diagnostic()
+        .wrap_err(format!("Unable to read {}", cmd.file.display()))?;
+    let operations: Vec<VaultOperation> = serde_json::from_str(&contents)
+        .into_diagnostic()
+        .wrap_err(format!("Invalid batch file {}", cmd.file.display()))?;
+
+    let snapshot = VaultsSnapshot::take(&opts)?;
+    for (index, operation) in operations.iter().enumerate() {
+        debug!(index, %operation, "Applying vault operation");
+        if let Err(e) = operation.clone().apply(&opts).await {
+            snapshot.restore()?;
+            return Err(e.wrap_err(format!(
+                "Operation #{} ({operation}) failed. All the operations were rolled back",
+                index + 1
+            )));
+        }
+    }
+
+    let mut plain = fmt_ok!("Applied {} vault operations", operations.len());
+    for operation in operations.iter() {
+        plain.push_str(&format!("\n{:2}{operation}", ""));
+    }
+    opts.terminal
+        .stdout()
+        .plain(plain)
+        .machine(operations.len().to_string())
+        .json(serde_json::json!({ "applied": &operations }))
+        .write_line()?;
+    Ok(())
+}
+
+/// Content of the vaults state directory, and target of the default vault link,
+/// before a batch is applied
+struct VaultsSnapshot {
+    dir: PathBuf,
+    files: BTreeMap<PathBuf, Vec<u8>>,
+    default_path: PathBuf,
+    default_target: Option<PathBuf>,
+}
+
+impl VaultsSnapshot {
+    fn take(opts: &CommandGlobalOpts) -> miette::Result<Self> {
+        let dir = opts.state.vaults.dir().clone();
+        let mut files = BTreeMap::new();
+        for path in list_files(&dir)? {
+            let contents = std::fs::read(&path).into_diagnostic()?;
+            files.insert(path, contents);
+        }
+        let default_path = opts.state.vaults.default_path()?;
+        let default_target = std::fs::read_link(&default_path).ok();
+        Ok(Self {
+            dir,
+            files,
+            default_path,
+            default_target,
+        })
+    }
+
+    /// Remove the files created since the snapshot was taken, and write back the others
+    fn restore(&self) -> miette::Result<()> {
+        for path in list_files(&self.dir)? {
+            if !self.files.contains_key(&path) {
+                std::fs::remove_file(&path).into_diagnostic()?;
+            }
+        }
+        for (path, contents) in self.files.iter() {
+            std::fs::write(path, contents).into_diagnostic()?;
+        }
+        let _ = std::fs::remove_file(&self.default_path);
+        if let Some(target) = &self.default_target {
+            std::os::unix::fs::symlink(target, &self.default_path).into_diagnostic()?;
+        }
+        warn!("The vault operations were rolled back");
+        Ok(())
+    }
+}
+
+/// Return the paths of the files of a directory and of its subdirectories
+fn list_files(dir: &Path) -> miette::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(files),
+        Err(e) => return Err(e).into_diagnostic(),
+    };
+    for entry in entries {
+        let path = entry.into_diagnostic()?.path();
+        if path.is_dir() {
+            files.extend(list_files(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_operations() {
+        let operations: Vec<VaultOperation> = serde_json::from_str(
+            r#"[
+                {"op": "create", "name": "v1"},
+                {"op": "set-default", "name": "v1"},
+                {"op": "tag", "name": "v1", "tags": {"owner": "ops"}},
+                {"op": "delete", "name": "v2"}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(operations.len(), 4);
+        assert!(matches!(
+            &operations[0],
+            VaultOperation::Create { name, aws_kms: false } if name == "v1"
+        ));
+        assert_eq!(operations[3].to_string(), "delete 'v2'");
+
+        let unknown: Result<Vec<VaultOperation>, _> =
+            serde_json::from_str(r#"[{"op": "rename", "name": "v1"}]"#);
+        assert!(unknown.is_err());
+    }
+}
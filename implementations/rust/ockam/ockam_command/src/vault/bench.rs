@@ -0,0 +1,207 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use clap::Args;
+use miette::{miette, IntoDiagnostic};
+use ockam::Context;
+use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
+use ockam_vault::{
+    EphemeralSecretsStore, KeyId, PersistentSecretsStore, SecretAttributes, Signer, Vault,
+};
+use serde::Serialize;
+
+use crate::vault::{default_vault, vault_rpc};
+use crate::{docs, fmt_ok, fmt_warn, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/bench/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/bench/after_long_help.txt");
+
+/// Benchmark the signing throughput of a vault
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct BenchCommand {
+    /// Name of the vault
+    name: Option<String>,
+
+    /// Number of payloads to sign
+    #[arg(long, default_value_t = 1000, value_parser = clap::value_parser!(u32).range(1..))]
+    count: u32,
+
+    /// Size of each payload, in bytes
+    #[arg(long, default_value_t = 256)]
+    size: usize,
+
+    /// Number of signatures created concurrently
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    concurrency: u32,
+}
+
+impl BenchCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        vault_rpc(rpc, (opts, self));
+    }
+}
+
+async fn rpc(
+    mut _ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, BenchCommand),
+) -> miette::Result<()> {
+    run_impl(opts, cmd).await
+}
+
+/// Results of a signing benchmark
+#[derive(Serialize)]
+struct BenchOutput {
+    vault: String,
+    count: u32,
+    size: usize,
+    concurrency: u32,
+    elapsed_ms: f64,
+    signatures_per_second: f64,
+    p50_ms: f64,
+    p95_ms: f64,
+    p99_ms: f64,
+}
+
+async fn run_impl(opts: CommandGlobalOpts, cmd: BenchCommand) -> miette::Result<()> {
+    let name = match cmd.name {
+        Some(name) => name,
+        None => default_vault(&opts.state)?.name().to_string(),
+    };
+    let state = opts.state.vaults.get(&name)?;
+    let is_aws = state.config().is_aws();
+    let vault = state.get().await?;
+
+    // AWS KMS keys are persistent, and only support NIST P-256
+    let key_id = if is_aws {
+        vault
+            .create_persistent_secret(SecretAttributes::NistP256)
+            .await
+    } else {
+        vault
+            .create_ephemeral_secret(SecretAttributes::Ed25519)
+            .await
+    }
+    .into_diagnostic()?;
+
+    let result = bench(vault.clone(), &key_id, &cmd).await;
+
+    // the benchmark key is deleted even when the benchmark failed
+    let deleted = if is_aws {
+        vault.delete_persistent_secret(key_id.clone()).await
+    } else {
+        vault.delete_ephemeral_secret(key_id.clone()).await
+    };
+    if let Err(e) = deleted {
+        opts.terminal.write_line(&fmt_warn!(
+            "Unable to delete the benchmark key {key_id}: {e}"
+        ))?;
+    }
+    let (elapsed, mut latencies) = result?;
+
+    latencies.sort();
+    let signatures_per_second = cmd.count as f64 / elapsed.as_secs_f64();
+    let output = BenchOutput {
+        vault: name,
+        count: cmd.count,
+        size: cmd.size,
+        concurrency: cmd.concurrency,
+        elapsed_ms: as_millis(elapsed),
+        signatures_per_second,
+        p50_ms: as_millis(percentile(&latencies, 50)),
+        p95_ms: as_millis(percentile(&latencies, 95)),
+        p99_ms: as_millis(percentile(&latencies, 99)),
+    };
+
+    let plain = fmt_ok!(
+        "Signed {} payloads of {} bytes with the vault '{}' in {:.1}ms\n",
+        output.count,
+        output.size,
+        output.vault,
+        output.elapsed_ms
+    ) + &format!(
+        "{:2}Throughput: {:.1} signatures/s\n{:2}Latency: p50 {:.3}ms, p95 {:.3}ms, p99 {:.3}ms",
+        "", output.signatures_per_second, "", output.p50_ms, output.p95_ms, output.p99_ms
+    );
+    opts.terminal
+        .stdout()
+        .plain(plain)
+        .machine(format!("{signatures_per_second:.1}"))
+        .json(serde_json::to_string_pretty(&output).into_diagnostic()?)
+        .write_line()?;
+    Ok(())
+}
+
+/// Sign `cmd.count` payloads with `cmd.concurrency` concurrent signers.
+/// Return the total duration of the benchmark and the latency of each signature
+async fn bench(
+    vault: Arc<Vault>,
+    key_id: &KeyId,
+    cmd: &BenchCommand,
+) -> miette::Result<(Duration, Vec<Duration>)> {
+    let payload: Arc<Vec<u8>> = Arc::new((0..cmd.size).map(|_| rand::random()).collect());
+    let started_at = Instant::now();
+    let handles: Vec<_> = (0..cmd.concurrency)
+        .map(|i| {
+            // spread the remainder of the payloads over the first signers
+            let count = cmd.count / cmd.concurrency + u32::from(i < cmd.count % cmd.concurrency);
+            let vault = vault.clone();
+            let key_id = key_id.clone();
+            let payload = payload.clone();
+            tokio::spawn(async move {
+                let mut latencies = Vec::with_capacity(count as usize);
+                for _ in 0..count {
+                    let signed_at = Instant::now();
+                    vault.sign(&key_id, &payload).await?;
+                    latencies.push(signed_at.elapsed());
+                }
+                Ok::<_, ockam_core::Error>(latencies)
+            })
+        })
+        .collect();
+
+    let mut latencies = Vec::with_capacity(cmd.count as usize);
+    for handle in handles {
+        let signer_latencies = handle
+            .await
+            .into_diagnostic()?
+            .map_err(|e| miette!("Unable to sign with the vault: {e}"))?;
+        latencies.extend(signer_latencies);
+    }
+    Ok((started_at.elapsed(), latencies))
+}
+
+/// Return the latency below which `p` percent of the sorted latencies are
+fn percentile(sorted: &[Duration], p: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = ((sorted.len() * p + 99) / 100).max(1);
+    sorted[rank - 1]
+}
+
+fn as_millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles() {
+        let latencies: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&latencies, 50), Duration::from_millis(50));
+        assert_eq!(percentile(&latencies, 95), Duration::from_millis(95));
+        assert_eq!(percentile(&latencies, 99), Duration::from_millis(99));
+
+        let latencies = [Duration::from_millis(7)];
+        assert_eq!(percentile(&latencies, 50), Duration::from_millis(7));
+        assert_eq!(percentile(&[], 99), Duration::ZERO);
+    }
+}
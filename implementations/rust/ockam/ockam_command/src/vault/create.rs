@@ -6,7 +6,7 @@ use ockam::Context;
 use ockam_api::cli_state;
 use ockam_api::cli_state::traits::StateDirTrait;
 
-use crate::util::node_rpc;
+use crate::vault::vault_rpc;
 use crate::{docs, fmt_info, fmt_ok, CommandGlobalOpts};
 
 const LONG_ABOUT: &str = include_str!("./static/create/long_about.txt");
@@ -32,7 +32,7 @@ pub struct CreateCommand {
 
 impl CreateCommand {
     pub fn run(self, opts: CommandGlobalOpts) {
-        node_rpc(rpc, (opts, self));
+        vault_rpc(rpc, (opts, self));
     }
 }
 
@@ -49,16 +49,7 @@ async fn run_impl(
     cmd: CreateCommand,
 ) -> miette::Result<()> {
     let CreateCommand { name, aws_kms, .. } = cmd;
-    let config = cli_state::VaultConfig::new(aws_kms)?;
-    if opts.state.vaults.is_empty()? {
-        opts.terminal.write_line(&fmt_info!(
-            "This is the first vault to be created in this environment. It will be set as the default vault"
-        ))?;
-    }
-    opts.state
-        .vaults
-        .create_async(&name, config.clone())
-        .await?;
+    create_vault(&opts, &name, aws_kms).await?;
 
     opts.terminal
         .stdout()
@@ -68,3 +59,19 @@ async fn run_impl(
         .write_line()?;
     Ok(())
 }
+
+/// Create a vault. The first vault created in an environment is the default vault
+pub(crate) async fn create_vault(
+    opts: &CommandGlobalOpts,
+    name: &str,
+    aws_kms: bool,
+) -> miette::Result<()> {
+    let config = cli_state::VaultConfig::new(aws_kms)?;
+    if opts.state.vaults.is_empty()? {
+        opts.terminal.write_line(&fmt_info!(
+            "This is the first vault to be created in this environment. It will be set as the default vault"
+        ))?;
+    }
+    opts.state.vaults.create_async(name, config).await?;
+    Ok(())
+}
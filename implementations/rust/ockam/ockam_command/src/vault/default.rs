@@ -1,9 +1,10 @@
-use crate::util::local_cmd;
+use crate::vault::vault_cmd;
 use crate::{docs, fmt_ok, CommandGlobalOpts};
 use clap::Args;
 use colorful::Colorful;
 use miette::miette;
 use ockam_api::cli_state::traits::StateDirTrait;
+use tracing::debug;
 
 const LONG_ABOUT: &str = include_str!("./static/default/long_about.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/default/after_long_help.txt");
@@ -17,18 +18,38 @@ const AFTER_LONG_HELP: &str = include_str!("./static/default/after_long_help.txt
 pub struct DefaultCommand {
     /// Name of the vault to be set as default
     name: String,
+
+    /// Reset the default vault even if the current default can't be read,
+    /// for example when it points to a deleted vault
+    #[arg(long)]
+    force: bool,
 }
 
 impl DefaultCommand {
     pub fn run(self, opts: CommandGlobalOpts) {
-        local_cmd(run_impl(opts, self));
+        vault_cmd(run_impl(opts, self));
     }
 }
 
 fn run_impl(opts: CommandGlobalOpts, cmd: DefaultCommand) -> miette::Result<()> {
-    let DefaultCommand { name } = cmd;
-    let state = opts.state.vaults;
-    let v = state.get(&name)?;
+    let DefaultCommand { name, force } = cmd;
+    if force {
+        return force_default(opts, &name);
+    }
+    set_default_vault(&opts, &name)?;
+    opts.terminal
+        .stdout()
+        .plain(fmt_ok!("The vault '{name}' is now the default"))
+        .machine(&name)
+        .json(serde_json::json!({ "vault": {"name": name} }))
+        .write_line()?;
+    Ok(())
+}
+
+/// Set a vault as the default vault, failing if it is already the default
+pub(crate) fn set_default_vault(opts: &CommandGlobalOpts, name: &str) -> miette::Result<()> {
+    let state = &opts.state.vaults;
+    let v = state.get(name)?;
     // If it exists, warn the user and exit
     if state.is_default(v.name())? {
         Err(miette!("The vault '{}' is already the default", name))
@@ -36,12 +57,30 @@ fn run_impl(opts: CommandGlobalOpts, cmd: DefaultCommand) -> miette::Result<()>
     // Otherwise, set it as default
     else {
         state.set_default(v.name())?;
-        opts.terminal
-            .stdout()
-            .plain(fmt_ok!("The vault '{name}' is now the default"))
-            .machine(&name)
-            .json(serde_json::json!({ "vault": {"name": name} }))
-            .write_line()?;
         Ok(())
     }
 }
+
+/// Remove the current default vault marker, without checking what it points to,
+/// and set the given vault as the default
+fn force_default(opts: CommandGlobalOpts, name: &str) -> miette::Result<()> {
+    let state = &opts.state.vaults;
+    state.get(name)?;
+    let default_path = state.default_path()?;
+    match std::fs::read_link(&default_path) {
+        Ok(previous) => debug!(previous = %previous.display(), "Resetting the default vault"),
+        Err(e) => debug!(%e, "The default vault marker can't be read"),
+    }
+    // The marker may not exist, or be a dangling link
+    let _ = std::fs::remove_file(&default_path);
+    state.set_default(name)?;
+    opts.terminal
+        .stdout()
+        .plain(fmt_ok!(
+            "The default vault was reset, the vault '{name}' is now the default"
+        ))
+        .machine(name)
+        .json(serde_json::json!({ "vault": { "name": name }, "force_reset": true }))
+        .write_line()?;
+    Ok(())
+}
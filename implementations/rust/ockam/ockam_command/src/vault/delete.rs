@@ -5,7 +5,7 @@ use ockam::Context;
 use ockam_api::cli_state::traits::StateDirTrait;
 
 use crate::terminal::ConfirmResult;
-use crate::util::node_rpc;
+use crate::vault::vault_rpc;
 use crate::{docs, fmt_ok, fmt_warn, CommandGlobalOpts};
 
 const LONG_ABOUT: &str = include_str!("./static/delete/long_about.txt");
@@ -24,7 +24,7 @@ pub struct DeleteCommand {
 
 impl DeleteCommand {
     pub fn run(self, opts: CommandGlobalOpts) {
-        node_rpc(rpc, (opts, self));
+        vault_rpc(rpc, (opts, self));
     }
 }
 
@@ -41,15 +41,14 @@ async fn run_impl(
     cmd: DeleteCommand,
 ) -> miette::Result<()> {
     let DeleteCommand { name } = cmd;
-    let state = opts.state.vaults;
-    state.get(&name)?;
+    opts.state.vaults.get(&name)?;
     if let ConfirmResult::No = opts.terminal.confirm(&fmt_warn!(
         "This will delete the vault named '{name}'. Do you wish to proceed?"
     ))? {
         // If the user has not confirmed, exit
         return Ok(());
     }
-    state.delete(&name)?;
+    delete_vault(&opts, &name)?;
     opts.terminal
         .stdout()
         .plain(fmt_ok!("The vault named '{name}' has been deleted"))
@@ -58,3 +57,11 @@ async fn run_impl(
         .write_line()?;
     Ok(())
 }
+
+/// Delete an existing vault
+pub(crate) fn delete_vault(opts: &CommandGlobalOpts, name: &str) -> miette::Result<()> {
+    let state = &opts.state.vaults;
+    state.get(name)?;
+    state.delete(name)?;
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use crate::util::node_rpc;
+use ockam::Context;
+use ockam_api::cli_state::CliStateError;
+use ockam_core::errcode::Kind;
+use std::io::ErrorKind;
+use tracing::error;
+
+/// Exit codes of the vault commands.
+///
+/// These codes are stable, so that scripts can find out why a vault command failed:
+///  - 1: any error which doesn't have a more specific code
+///  - 2: the vault, or another resource, doesn't exist
+///  - 3: the vault, or another resource, already exists
+///  - 4: the operation is not permitted, for example the state directory can't be written
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VaultExitCode {
+    General = 1,
+    NotFound = 2,
+    AlreadyExists = 3,
+    Permission = 4,
+}
+
+impl From<CliStateError> for VaultExitCode {
+    fn from(e: CliStateError) -> Self {
+        match e {
+            CliStateError::ResourceNotFound { .. } => VaultExitCode::NotFound,
+            CliStateError::AlreadyExists { .. } => VaultExitCode::AlreadyExists,
+            CliStateError::Io(e) => match e.kind() {
+                ErrorKind::NotFound => VaultExitCode::NotFound,
+                ErrorKind::AlreadyExists => VaultExitCode::AlreadyExists,
+                ErrorKind::PermissionDenied => VaultExitCode::Permission,
+                _ => VaultExitCode::General,
+            },
+            CliStateError::Ockam(e) => match e.code().kind {
+                Kind::NotFound => VaultExitCode::NotFound,
+                Kind::AlreadyExists => VaultExitCode::AlreadyExists,
+                _ => VaultExitCode::General,
+            },
+            _ => VaultExitCode::General,
+        }
+    }
+}
+
+impl From<miette::Report> for VaultExitCode {
+    fn from(e: miette::Report) -> Self {
+        match e.downcast::<CliStateError>() {
+            Ok(e) => e.into(),
+            Err(e) => match e.downcast_ref::<std::io::Error>().map(|e| e.kind()) {
+                Some(ErrorKind::PermissionDenied) => VaultExitCode::Permission,
+                _ => VaultExitCode::General,
+            },
+        }
+    }
+}
+
+/// Report the error of a failed vault command and exit with the matching [`VaultExitCode`]
+fn exit_with_error(e: miette::Report) -> ! {
+    error!(%e, "Failed to run command");
+    crate::error::report_error(&e);
+    std::process::exit(VaultExitCode::from(e) as i32);
+}
+
+/// Run a vault command which doesn't need a node
+pub(crate) fn vault_cmd(res: miette::Result<()>) {
+    if let Err(e) = res {
+        exit_with_error(e);
+    }
+}
+
+/// Run a vault command in an embedded node
+pub(crate) fn vault_rpc<A, F, Fut>(f: F, a: A)
+where
+    A: Send + Sync + 'static,
+    F: FnOnce(Context, A) -> Fut + Send + Sync + 'static,
+    Fut: core::future::Future<Output = miette::Result<()>> + Send + 'static,
+{
+    node_rpc(
+        |ctx, a| async move {
+            if let Err(e) = f(ctx, a).await {
+                exit_with_error(e);
+            }
+            Ok(())
+        },
+        a,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exit_codes_of_cli_state_errors() {
+        let not_found = CliStateError::ResourceNotFound {
+            resource: "vault".to_string(),
+            name: "v".to_string(),
+        };
+        assert_eq!(VaultExitCode::from(not_found), VaultExitCode::NotFound);
+
+        let already_exists = CliStateError::AlreadyExists {
+            resource: "vault".to_string(),
+            name: "v".to_string(),
+        };
+        assert_eq!(
+            VaultExitCode::from(miette::Report::new(already_exists)),
+            VaultExitCode::AlreadyExists
+        );
+
+        let permission = CliStateError::Io(std::io::Error::from(ErrorKind::PermissionDenied));
+        assert_eq!(VaultExitCode::from(permission), VaultExitCode::Permission);
+
+        let general = miette::miette!("The vault 'v' is already the default");
+        assert_eq!(VaultExitCode::from(general), VaultExitCode::General);
+    }
+}
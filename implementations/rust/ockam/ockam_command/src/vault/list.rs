@@ -1,9 +1,17 @@
 use clap::Args;
+use colorful::Colorful;
+use core::fmt::Write;
+use miette::{miette, IntoDiagnostic};
+use std::path::PathBuf;
 
 use ockam_api::cli_state::traits::StateDirTrait;
+use ockam_api::cli_state::{VaultState, VaultsState};
 
-use crate::util::local_cmd;
-use crate::{docs, CommandGlobalOpts};
+use crate::terminal::OckamColor;
+use crate::util::output::Output;
+use crate::vault::vault_cmd;
+use crate::vault::VaultOutput;
+use crate::{docs, fmt_list, fmt_warn, CommandGlobalOpts, OutputFormat};
 
 const LONG_ABOUT: &str = include_str!("./static/list/long_about.txt");
 const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
@@ -16,19 +24,136 @@ const AFTER_LONG_HELP: &str = include_str!("./static/list/after_long_help.txt");
     before_help = docs::before_help(PREVIEW_TAG),
     after_long_help = docs::after_help(AFTER_LONG_HELP)
 )]
-pub struct ListCommand;
+pub struct ListCommand {
+    /// Also list the vaults of another Ockam state directory. Can be repeated
+    #[arg(long = "state-root", value_name = "PATH")]
+    state_roots: Vec<PathBuf>,
+
+    /// Print each vault as soon as it is read, instead of reading all the vaults first.
+    /// The JSON output is not streamed
+    #[arg(long, conflicts_with = "state_roots")]
+    stream: bool,
+}
 
 impl ListCommand {
     pub fn run(self, opts: CommandGlobalOpts) {
-        local_cmd(run_impl(opts));
+        vault_cmd(run_impl(opts, self));
     }
 }
 
-fn run_impl(opts: CommandGlobalOpts) -> miette::Result<()> {
+fn run_impl(opts: CommandGlobalOpts, cmd: ListCommand) -> miette::Result<()> {
+    if !cmd.state_roots.is_empty() {
+        return list_across_state_roots(opts, cmd.state_roots);
+    }
+    if cmd.stream && opts.global_args.output_format != OutputFormat::Json {
+        return stream_vaults(opts);
+    }
     let vaults = opts.state.vaults.list()?;
-    let list = opts
-        .terminal
-        .build_list(&vaults, "Vaults", "No vaults found on this system.")?;
-    opts.terminal.stdout().plain(list).write_line()?;
+    let list = opts.terminal.build_list(
+        &vaults,
+        "Vaults",
+        "No vaults found on this system. Run `ockam vault create` to create one.",
+    )?;
+    let json: Vec<VaultOutput> = vaults.iter().map(VaultOutput::new).collect();
+    opts.terminal
+        .stdout()
+        .plain(list)
+        .json(serde_json::to_string_pretty(&json).into_diagnostic()?)
+        .write_line()?;
+    Ok(())
+}
+
+/// Print the vaults one by one while they are read from the state directory
+fn stream_vaults(opts: CommandGlobalOpts) -> miette::Result<()> {
+    let mut empty = true;
+    for vault in opts.state.vaults.iter()? {
+        empty = false;
+        let mut plain = String::new();
+        for line in vault.list_output()?.split('\n') {
+            writeln!(plain, "{}", fmt_list!("{line}")).into_diagnostic()?;
+        }
+        opts.terminal
+            .clone()
+            .stdout()
+            .plain(plain)
+            .machine(vault.name())
+            .write_line()?;
+    }
+    if empty {
+        opts.terminal
+            .stdout()
+            .plain(fmt_warn!(
+                "No vaults found on this system. Run `ockam vault create` to create one."
+            ))
+            .machine("")
+            .write_line()?;
+    }
+    Ok(())
+}
+
+/// List the vaults of the current state directory and of the given state directories,
+/// annotating each vault with the state directory it belongs to.
+/// Vaults having the same name in different directories are all listed
+fn list_across_state_roots(
+    opts: CommandGlobalOpts,
+    state_roots: Vec<PathBuf>,
+) -> miette::Result<()> {
+    let mut vaults = vec![];
+    for root in [opts.state.dir.clone()].into_iter().chain(state_roots) {
+        if !root.is_dir() {
+            return Err(miette!(
+                "The state root {} is not a directory",
+                root.display()
+            ));
+        }
+        // The vaults directory is not created if it doesn't exist
+        let state = VaultsState::new(VaultsState::build_dir(&root));
+        for vault in state.list()? {
+            vaults.push(RootedVault {
+                root: root.display().to_string(),
+                vault,
+            });
+        }
+    }
+    let list = opts.terminal.build_list(
+        &vaults,
+        "Vaults",
+        "No vaults found in these state roots. Run `ockam vault create` to create one.",
+    )?;
+    let json: Vec<_> = vaults
+        .iter()
+        .map(|v| VaultOutput::new(&v.vault).with_state_root(&v.root))
+        .collect();
+    opts.terminal
+        .stdout()
+        .plain(list)
+        .json(serde_json::to_string_pretty(&json).into_diagnostic()?)
+        .write_line()?;
     Ok(())
 }
+
+/// A vault with the state directory containing it
+struct RootedVault {
+    root: String,
+    vault: VaultState,
+}
+
+impl Output for RootedVault {
+    fn output(&self) -> crate::Result<String> {
+        let mut output = self.vault.output()?;
+        writeln!(output, "State root: {}", self.root)?;
+        Ok(output)
+    }
+
+    fn list_output(&self) -> crate::Result<String> {
+        let mut output = self.vault.list_output()?;
+        write!(
+            output,
+            "\nState root {}",
+            self.root
+                .as_str()
+                .color(OckamColor::PrimaryResource.color())
+        )?;
+        Ok(output)
+    }
+}
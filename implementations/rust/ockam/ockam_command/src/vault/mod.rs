@@ -1,24 +1,43 @@
 mod attach_key;
+mod batch;
+mod bench;
 mod create;
 mod default;
 mod delete;
+mod exit_code;
 mod list;
+mod policy;
 mod show;
+mod tag;
+mod usage;
 
 use crate::vault::attach_key::AttachKeyCommand;
+use crate::vault::batch::BatchCommand;
+use crate::vault::bench::BenchCommand;
 use crate::vault::create::CreateCommand;
 use crate::vault::default::DefaultCommand;
 use crate::vault::delete::DeleteCommand;
+pub(crate) use crate::vault::exit_code::{vault_cmd, vault_rpc};
 use crate::vault::list::ListCommand;
+use crate::vault::policy::PolicyCommand;
 use crate::vault::show::ShowCommand;
+use crate::vault::tag::TagCommand;
+use crate::vault::usage::UsageCommand;
 use crate::{docs, CommandGlobalOpts};
 
 use clap::{Args, Subcommand};
-use ockam_api::cli_state::traits::StateDirTrait;
-use ockam_api::cli_state::CliState;
+use miette::miette;
+use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
+use ockam_api::cli_state::{CliState, CliStateError, VaultPolicy, VaultState};
+use ockam_core::env::get_env;
+use serde::Serialize;
+use std::collections::BTreeMap;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
 
+/// Name of the environment variable which overrides the default vault for a single command
+pub const OCKAM_DEFAULT_VAULT: &str = "OCKAM_DEFAULT_VAULT";
+
 /// Manage vaults
 #[derive(Clone, Debug, Args)]
 #[command(
@@ -39,6 +58,11 @@ pub enum VaultSubcommand {
     Delete(DeleteCommand),
     List(ListCommand),
     Default(DefaultCommand),
+    Tag(TagCommand),
+    Policy(PolicyCommand),
+    Bench(BenchCommand),
+    Batch(BatchCommand),
+    Usage(UsageCommand),
 }
 
 impl VaultCommand {
@@ -50,13 +74,83 @@ impl VaultCommand {
             VaultSubcommand::List(cmd) => cmd.run(opts),
             VaultSubcommand::Delete(cmd) => cmd.run(opts),
             VaultSubcommand::Default(cmd) => cmd.run(opts),
+            VaultSubcommand::Tag(cmd) => cmd.run(opts),
+            VaultSubcommand::Policy(cmd) => cmd.run(opts),
+            VaultSubcommand::Bench(cmd) => cmd.run(opts),
+            VaultSubcommand::Batch(cmd) => cmd.run(opts),
+            VaultSubcommand::Usage(cmd) => cmd.run(opts),
         }
     }
 }
 
-pub fn default_vault_name(cli_state: &CliState) -> String {
-    cli_state
-        .vaults
-        .default()
-        .map_or("default".to_string(), |v| v.name().to_string())
+/// Return the name of the vault used by a command when no vault is given with `--vault`.
+/// The vault set with `OCKAM_DEFAULT_VAULT` must exist, while a state directory without
+/// a default vault falls back to the vault named "default"
+pub fn default_vault_name(cli_state: &CliState) -> miette::Result<String> {
+    match default_vault_override() {
+        Some(name) => match cli_state.vaults.get(&name) {
+            Ok(vault) => Ok(vault.name().to_string()),
+            Err(e) => Err(miette!(
+                "The vault {name} set with {OCKAM_DEFAULT_VAULT} can't be used: {e}"
+            )),
+        },
+        None => Ok(cli_state
+            .vaults
+            .default()
+            .map_or("default".to_string(), |v| v.name().to_string())),
+    }
+}
+
+/// Return the name of the vault set with the `OCKAM_DEFAULT_VAULT` environment variable, if any
+pub fn default_vault_override() -> Option<String> {
+    match get_env::<String>(OCKAM_DEFAULT_VAULT) {
+        Ok(Some(name)) if !name.is_empty() => Some(name),
+        _ => None,
+    }
+}
+
+/// Return the vault used by a command when no vault is given with `--vault`: the vault set with
+/// `OCKAM_DEFAULT_VAULT`, or else the default vault of the state directory.
+/// The override is not persisted: `ockam vault default` and the nodes only use the default vault
+/// of the state directory
+pub fn default_vault(cli_state: &CliState) -> Result<VaultState, CliStateError> {
+    match default_vault_override() {
+        Some(name) => cli_state.vaults.get(name),
+        None => cli_state.vaults.default(),
+    }
+}
+
+/// JSON representation of a vault
+#[derive(Serialize)]
+pub(crate) struct VaultOutput<'a> {
+    name: &'a str,
+    #[serde(rename = "type")]
+    vault_type: &'a str,
+    tags: &'a BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    policy: Option<&'a VaultPolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state_root: Option<&'a str>,
+}
+
+impl<'a> VaultOutput<'a> {
+    pub(crate) fn new(state: &'a VaultState) -> Self {
+        Self {
+            name: state.name(),
+            vault_type: if state.config().is_aws() {
+                "AWS KMS"
+            } else {
+                "OCKAM"
+            },
+            tags: state.config().tags(),
+            policy: state.config().policy(),
+            state_root: None,
+        }
+    }
+
+    /// Add the state directory containing the vault
+    pub(crate) fn with_state_root(mut self, state_root: &'a str) -> Self {
+        self.state_root = Some(state_root);
+        self
+    }
 }
@@ -0,0 +1,120 @@
+use clap::{Args, Subcommand};
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
+use ockam::identity::IdentityIdentifier;
+use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
+use ockam_api::cli_state::VaultPolicy;
+
+use crate::vault::vault_cmd;
+use crate::vault::VaultOutput;
+use crate::{docs, fmt_ok, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/policy/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/policy/after_long_help.txt");
+
+/// Set or show the access policy of a vault
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    subcommand_required = true,
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct PolicyCommand {
+    /// Name of the vault
+    name: String,
+
+    #[command(subcommand)]
+    subcommand: PolicySubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum PolicySubcommand {
+    /// Set the policy of the vault. Without identifiers, the policy is removed
+    Set {
+        /// Identifier of an identity allowed to use the vault
+        #[arg(long, value_name = "IDENTIFIER", value_parser = parse_identifier)]
+        allow: Vec<String>,
+
+        /// Identifier of an identity which is not allowed to use the vault
+        #[arg(long, value_name = "IDENTIFIER", value_parser = parse_identifier)]
+        deny: Vec<String>,
+    },
+    /// Show the policy of the vault
+    Show,
+}
+
+impl PolicyCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        vault_cmd(run_impl(opts, self));
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: PolicyCommand) -> miette::Result<()> {
+    match cmd.subcommand {
+        PolicySubcommand::Set { allow, deny } => {
+            let state = opts.state.vaults.get(&cmd.name)?;
+            let mut config = state.config().clone();
+            if allow.is_empty() && deny.is_empty() {
+                config.set_policy(None);
+            } else {
+                config.set_policy(Some(VaultPolicy::new(allow, deny)));
+            }
+            let state = opts.state.vaults.overwrite(&cmd.name, config)?;
+
+            opts.terminal
+                .stdout()
+                .plain(fmt_ok!(
+                    "The policy of the vault '{}' was updated",
+                    cmd.name
+                ))
+                .machine(&cmd.name)
+                .json(serde_json::to_string_pretty(&VaultOutput::new(&state)).into_diagnostic()?)
+                .write_line()?;
+        }
+        PolicySubcommand::Show => {
+            let state = opts.state.vaults.get(&cmd.name)?;
+            let policy = state.config().policy().cloned().unwrap_or_default();
+
+            let mut plain = format!("Policy of the vault '{}':\n", cmd.name);
+            let mut machine = String::new();
+            if policy.allow().is_empty() && policy.deny().is_empty() {
+                plain.push_str("  Any identity can use the vault\n");
+            }
+            if !policy.allow().is_empty() {
+                plain.push_str(&format!("  Allow: {}\n", policy.allow().join(", ")));
+            }
+            if !policy.deny().is_empty() {
+                plain.push_str(&format!("  Deny: {}\n", policy.deny().join(", ")));
+            }
+            for identifier in policy.allow() {
+                machine.push_str(&format!("allow {identifier}\n"));
+            }
+            for identifier in policy.deny() {
+                machine.push_str(&format!("deny {identifier}\n"));
+            }
+
+            opts.terminal
+                .stdout()
+                .plain(plain.trim_end())
+                .machine(machine.trim_end())
+                .json(
+                    serde_json::to_string_pretty(&serde_json::json!({
+                        "allow": policy.allow(),
+                        "deny": policy.deny(),
+                    }))
+                    .into_diagnostic()?,
+                )
+                .write_line()?;
+        }
+    }
+    Ok(())
+}
+
+fn parse_identifier(input: &str) -> miette::Result<String> {
+    IdentityIdentifier::try_from(input)
+        .map(|identifier| identifier.to_string())
+        .map_err(|_| miette!("Invalid identifier: {input}"))
+}
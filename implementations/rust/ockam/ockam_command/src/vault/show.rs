@@ -1,7 +1,9 @@
 use clap::Args;
+use miette::IntoDiagnostic;
 use ockam_api::cli_state::traits::StateDirTrait;
 
-use crate::util::local_cmd;
+use crate::vault::VaultOutput;
+use crate::vault::{default_vault, vault_cmd};
 use crate::{docs, CommandGlobalOpts};
 
 const LONG_ABOUT: &str = include_str!("./static/show/long_about.txt");
@@ -22,18 +24,24 @@ pub struct ShowCommand {
 
 impl ShowCommand {
     pub fn run(self, opts: CommandGlobalOpts) {
-        local_cmd(run_impl(opts, self));
+        vault_cmd(run_impl(opts, self));
     }
 }
 
 fn run_impl(opts: CommandGlobalOpts, cmd: ShowCommand) -> miette::Result<()> {
-    let name = cmd
-        .name
-        .unwrap_or(opts.state.vaults.default()?.name().to_string());
+    let name = match cmd.name {
+        Some(name) => name,
+        None => default_vault(&opts.state)?.name().to_string(),
+    };
     let state = opts.state.vaults.get(name)?;
-    println!("Vault:");
+    let mut plain = "Vault:\n".to_string();
     for line in state.to_string().lines() {
-        println!("{:2}{}", "", line)
+        plain.push_str(&format!("{:2}{}\n", "", line));
     }
+    opts.terminal
+        .stdout()
+        .plain(plain.trim_end())
+        .json(serde_json::to_string_pretty(&VaultOutput::new(&state)).into_diagnostic()?)
+        .write_line()?;
     Ok(())
 }
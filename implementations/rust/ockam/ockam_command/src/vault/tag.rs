@@ -0,0 +1,68 @@
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
+use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
+use ockam_api::cli_state::VaultState;
+
+use crate::vault::vault_cmd;
+use crate::vault::VaultOutput;
+use crate::{docs, fmt_ok, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/tag/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/tag/after_long_help.txt");
+
+/// Set or remove the tags of a vault
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct TagCommand {
+    /// Name of the vault
+    name: String,
+
+    /// Tags to set, as `key=value`. An empty value removes the tag
+    #[arg(required = true, value_name = "KEY=VALUE", value_parser = parse_tag)]
+    tags: Vec<(String, String)>,
+}
+
+impl TagCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        vault_cmd(run_impl(opts, self));
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: TagCommand) -> miette::Result<()> {
+    let state = tag_vault(&opts, &cmd.name, cmd.tags)?;
+
+    opts.terminal
+        .stdout()
+        .plain(fmt_ok!("The tags of the vault '{}' were updated", cmd.name))
+        .machine(&cmd.name)
+        .json(serde_json::to_string_pretty(&VaultOutput::new(&state)).into_diagnostic()?)
+        .write_line()?;
+    Ok(())
+}
+
+/// Set the tags of a vault. An empty value removes the tag
+pub(crate) fn tag_vault(
+    opts: &CommandGlobalOpts,
+    name: &str,
+    tags: impl IntoIterator<Item = (String, String)>,
+) -> miette::Result<VaultState> {
+    let state = opts.state.vaults.get(name)?;
+    let mut config = state.config().clone();
+    for (key, value) in tags {
+        config.set_tag(key, value);
+    }
+    Ok(opts.state.vaults.overwrite(name, config)?)
+}
+
+fn parse_tag(input: &str) -> miette::Result<(String, String)> {
+    match input.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(miette!("Tags must be given as key=value")),
+    }
+}
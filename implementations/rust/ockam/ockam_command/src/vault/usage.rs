@@ -0,0 +1,106 @@
+use clap::Args;
+use miette::IntoDiagnostic;
+use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
+use serde::Serialize;
+use std::fmt::Write;
+
+use crate::util::disk_usage;
+use crate::vault::vault_cmd;
+use crate::{docs, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/usage/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/usage/after_long_help.txt");
+
+/// Show the disk space used by each vault
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct UsageCommand {}
+
+impl UsageCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        vault_cmd(run_impl(opts));
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts) -> miette::Result<()> {
+    let mut vaults: Vec<VaultUsage> = opts
+        .state
+        .vaults
+        .list()?
+        .iter()
+        .map(|vault| {
+            let config_bytes = disk_usage(vault.path());
+            let data_bytes = disk_usage(vault.vault_file_path());
+            VaultUsage {
+                name: vault.name().to_string(),
+                config_bytes,
+                data_bytes,
+                total_bytes: config_bytes + data_bytes,
+            }
+        })
+        .collect();
+    // the largest vaults first, then by name so that the order is stable
+    vaults.sort_by(|a, b| {
+        b.total_bytes
+            .cmp(&a.total_bytes)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    let output = VaultsUsageOutput {
+        total_bytes: vaults.iter().map(|vault| vault.total_bytes).sum(),
+        vaults,
+    };
+
+    opts.terminal
+        .stdout()
+        .plain(output.plain())
+        .machine(output.machine())
+        .json(serde_json::to_string_pretty(&output).into_diagnostic()?)
+        .write_line()?;
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct VaultsUsageOutput {
+    vaults: Vec<VaultUsage>,
+    total_bytes: u64,
+}
+
+#[derive(Serialize)]
+struct VaultUsage {
+    name: String,
+    config_bytes: u64,
+    data_bytes: u64,
+    total_bytes: u64,
+}
+
+impl VaultsUsageOutput {
+    fn plain(&self) -> String {
+        let mut plain = String::from("Disk usage of the vaults\n");
+        if self.vaults.is_empty() {
+            plain.push_str("  none\n");
+        }
+        for vault in &self.vaults {
+            let _ = writeln!(
+                plain,
+                "  {}: {} bytes ({} bytes of configuration, {} bytes of secrets)",
+                vault.name, vault.total_bytes, vault.config_bytes, vault.data_bytes
+            );
+        }
+        let _ = write!(plain, "Total: {} bytes", self.total_bytes);
+        plain
+    }
+
+    /// One `<name> <bytes>` line per vault, the largest vaults first
+    fn machine(&self) -> String {
+        self.vaults
+            .iter()
+            .map(|vault| format!("{} {}", vault.name, vault.total_bytes))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
@@ -47,6 +47,35 @@ teardown() {
   assert_failure
 }
 
+@test "trust context - import trusted identifiers from a file" {
+  mkdir -p "$OCKAM_HOME/trust_contexts"
+  echo '{"id": "tc"}' >"$OCKAM_HOME/trust_contexts/tc.json"
+
+  run "$OCKAM" identity create i1
+  assert_success
+  run "$OCKAM" identity create i2
+  assert_success
+  i1=$($OCKAM identity show i1)
+  i2=$($OCKAM identity show i2)
+
+  printf "# trusted identifiers\n%s\n%s\n%s\nnot-an-identifier\n" "$i1" "$i2" "$i1" >"$OCKAM_HOME/trusted.txt"
+  run "$OCKAM" trust-context import tc --file "$OCKAM_HOME/trusted.txt" --output json
+  assert_success
+  assert_output --partial "\"added\": 2"
+  assert_output --partial "\"skipped\": 1"
+  assert_output --partial "\"line\": 5"
+
+  # Importing the same file again doesn't add any identifier
+  run "$OCKAM" trust-context import tc --file "$OCKAM_HOME/trusted.txt" --output json
+  assert_success
+  assert_output --partial "\"added\": 0"
+
+  run "$OCKAM" trust-context show tc
+  assert_success
+  assert_output --partial "$i1"
+  assert_output --partial "$i2"
+}
+
 @test "trust context - no trust context; everything is accepted" {
   run "$OCKAM" identity create m1
   run "$OCKAM" node create n1 --identity m1
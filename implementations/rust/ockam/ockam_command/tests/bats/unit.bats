@@ -32,6 +32,18 @@ teardown() {
   assert_output --partial "/service/uppercase"
 }
 
+@test "node - show the trust configuration" {
+  n="$(random_str)"
+  run "$OCKAM" node create "$n"
+  assert_success
+
+  run "$OCKAM" node trust "$n" --output json
+  assert_success
+  assert_output --partial "\"trusted_identities\": []"
+  assert_output --partial "\"address\": \"0#api\""
+  assert_output --partial "\"authorized_identifiers\": null"
+}
+
 @test "node - start services" {
   run "$OCKAM" node create n1
   assert_success
@@ -102,6 +114,286 @@ teardown() {
   assert_output --partial "Type OCKAM"
   assert_output --partial "Vault ${v2}"
   assert_output --partial "Type AWS KMS"
+
+  run "$OCKAM" vault list --stream
+  assert_success
+  assert_output --partial "${v1}"
+  assert_output --partial "${v2}"
+
+  run "$OCKAM" vault list --stream --output json
+  assert_success
+  assert_output --partial "\"name\": \"${v1}\""
+}
+
+@test "vault - override the default vault with an environment variable" {
+  v1=$(random_str)
+  run "$OCKAM" vault create "${v1}"
+  assert_success
+  v2=$(random_str)
+  run "$OCKAM" vault create "${v2}"
+  assert_success
+  run "$OCKAM" vault default "${v1}"
+  assert_success
+
+  OCKAM_DEFAULT_VAULT="${v2}" run "$OCKAM" vault show
+  assert_success
+  assert_output --partial "Name: ${v2}"
+
+  # The override is not persisted
+  run "$OCKAM" vault show
+  assert_success
+  assert_output --partial "Name: ${v1}"
+
+  # The override doesn't change the default vault of the state directory
+  OCKAM_DEFAULT_VAULT="${v2}" run "$OCKAM" vault default "${v2}"
+  assert_success
+  run "$OCKAM" vault show
+  assert_success
+  assert_output --partial "Name: ${v2}"
+
+  # A vault which doesn't exist is reported instead of falling back to another vault
+  i=$(random_str)
+  run "$OCKAM" identity create "${i}"
+  assert_success
+  echo "some data" >"$OCKAM_HOME/data.txt"
+  OCKAM_DEFAULT_VAULT="missing" run "$OCKAM" identity sign --identity "${i}" --in "$OCKAM_HOME/data.txt" --out "$OCKAM_HOME/data.sig"
+  assert_failure
+  assert_output --partial "OCKAM_DEFAULT_VAULT"
+}
+
+@test "state - repair the default markers" {
+  v1=$(random_str)
+  run "$OCKAM" vault create "${v1}"
+  assert_success
+  run "$OCKAM" vault default "${v1}"
+
+  run "$OCKAM" state repair --output json
+  assert_success
+  assert_output --partial "\"issues\": []"
+
+  # the default vault was deleted by hand, the marker is only cleared with --apply
+  rm "$OCKAM_HOME/vaults/${v1}.json"
+  run "$OCKAM" state repair --output json
+  assert_success
+  assert_output --partial "\"problem\": \"dangling_default\""
+  assert_output --partial "\"fixed\": false"
+  run "$OCKAM" state repair --apply --output json
+  assert_success
+  assert_output --partial "\"fixed\": true"
+  run "$OCKAM" state repair --output json
+  assert_success
+  assert_output --partial "\"issues\": []"
+
+  # the marker was copied instead of linked
+  v2=$(random_str)
+  run "$OCKAM" vault create "${v2}"
+  assert_success
+  cp --remove-destination "$OCKAM_HOME/vaults/${v2}.json" "$OCKAM_HOME/defaults/vault"
+  run "$OCKAM" state repair --apply --output json
+  assert_success
+  assert_output --partial "\"problem\": \"duplicate_default\""
+  assert_output --partial "\"fixed\": true"
+  run "$OCKAM" state repair --output json
+  assert_success
+  assert_output --partial "\"issues\": []"
+}
+
+@test "configuration - show the effective configuration" {
+  v1=$(random_str)
+  run "$OCKAM" vault create "${v1}"
+  assert_success
+  v2=$(random_str)
+  run "$OCKAM" vault create "${v2}"
+  assert_success
+  run "$OCKAM" vault default "${v1}"
+  assert_success
+
+  run "$OCKAM" config show --output json
+  assert_success
+  assert_output --partial "\"state_dir\": \"$OCKAM_HOME\""
+  assert_output --partial "\"default_vault\": \"${v1}\""
+
+  OCKAM_DEFAULT_VAULT="${v2}" run "$OCKAM" config show --output json
+  assert_success
+  assert_output --partial "\"default_vault\": \"${v2}\""
+  assert_output --partial "\"default_vault_source\": \"OCKAM_DEFAULT_VAULT\""
+}
+
+@test "vault - set and remove tags" {
+  v1=$(random_str)
+  run "$OCKAM" vault create "${v1}"
+  assert_success
+
+  run "$OCKAM" vault tag "${v1}" environment=production owner=ops
+  assert_success
+
+  run "$OCKAM" vault show "${v1}" --output json
+  assert_success
+  assert_output --partial "\"environment\": \"production\""
+  assert_output --partial "\"owner\": \"ops\""
+
+  # An empty value removes the tag
+  run "$OCKAM" vault tag "${v1}" owner=
+  assert_success
+  run "$OCKAM" vault list --output json
+  assert_success
+  assert_output --partial "\"environment\": \"production\""
+  refute_output --partial "\"owner\""
+
+  run "$OCKAM" vault tag "${v1}" missing-separator
+  assert_failure
+}
+
+@test "vault - set and show the access policy of a vault" {
+  v1=$(random_str)
+  run "$OCKAM" vault create "${v1}"
+  assert_success
+
+  allowed=P6c20e814b56579306f55c64e8747e6c1b4a53d9a3f4ca83c252cc2fbfc72fa94
+  denied=P8b604a07640ecd944f379b5a1a5da0748f36f76327b00193067d1d8c6092dfae
+  run "$OCKAM" vault policy "${v1}" set --allow "${allowed}" --deny "${denied}"
+  assert_success
+
+  run "$OCKAM" vault policy "${v1}" show --output json
+  assert_success
+  assert_output --partial "\"${allowed}\""
+  assert_output --partial "\"${denied}\""
+
+  run "$OCKAM" vault show "${v1}" --output json
+  assert_success
+  assert_output --partial "\"policy\""
+
+  # Without identifiers, the policy is removed
+  run "$OCKAM" vault policy "${v1}" set
+  assert_success
+  run "$OCKAM" vault show "${v1}" --output json
+  assert_success
+  refute_output --partial "\"policy\""
+
+  run "$OCKAM" vault policy "${v1}" set --allow not-an-identifier
+  assert_failure
+}
+
+@test "vault - show the disk usage of the vaults" {
+  v1=$(random_str)
+  run "$OCKAM" vault create "${v1}"
+  assert_success
+
+  run "$OCKAM" vault usage --output json
+  assert_success
+  assert_output --partial "\"name\": \"${v1}\""
+  assert_output --partial "\"total_bytes\""
+
+  run "$OCKAM" vault usage
+  assert_success
+  assert_output --partial "${v1}"
+  assert_output --partial "Total:"
+}
+
+@test "vault - benchmark the signing throughput" {
+  v1=$(random_str)
+  run "$OCKAM" vault create "${v1}"
+  assert_success
+
+  run "$OCKAM" vault bench "${v1}" --count 20 --size 64 --concurrency 4 --output json
+  assert_success
+  assert_output --partial "\"count\": 20"
+  assert_output --partial "\"signatures_per_second\""
+  assert_output --partial "\"p99_ms\""
+
+  run "$OCKAM" vault bench "${v1}" --count 0
+  assert_failure
+}
+
+@test "vault - apply a batch of operations" {
+  v1=$(random_str)
+  v2=$(random_str)
+  ops="$(mktemp)"
+  cat >"${ops}" <<EOF
+[
+  { "op": "create", "name": "${v1}" },
+  { "op": "create", "name": "${v2}" },
+  { "op": "set-default", "name": "${v2}" },
+  { "op": "tag", "name": "${v1}", "tags": { "owner": "ops" } }
+]
+EOF
+  run "$OCKAM" vault batch --file "${ops}"
+  assert_success
+  run "$OCKAM" vault show --output json
+  assert_success
+  assert_output --partial "\"name\": \"${v2}\""
+
+  # the batch fails on its last operation, so the vault it created is removed
+  # and the vault it deleted is restored
+  v3=$(random_str)
+  cat >"${ops}" <<EOF
+[
+  { "op": "create", "name": "${v3}" },
+  { "op": "delete", "name": "${v1}" },
+  { "op": "set-default", "name": "missing-vault" }
+]
+EOF
+  run "$OCKAM" vault batch --file "${ops}"
+  assert_failure
+  assert_output --partial "rolled back"
+  run "$OCKAM" vault show "${v3}"
+  assert_failure
+  run "$OCKAM" vault show "${v1}" --output json
+  assert_success
+  assert_output --partial "\"owner\": \"ops\""
+}
+
+@test "vault - report errors as json" {
+  run "$OCKAM" vault default missing-vault --output json
+  assert_failure
+  assert_output --partial "\"code\":\"not_found\""
+
+  run "$OCKAM" vault default missing-vault
+  assert_failure
+  refute_output --partial "\"code\""
+}
+
+@test "vault - list across state roots" {
+  v1=$(random_str)
+  run "$OCKAM" vault create "${v1}"
+  assert_success
+
+  other_root="$(mktemp -d)"
+  v2=$(random_str)
+  OCKAM_HOME="${other_root}" run "$OCKAM" vault create "${v2}"
+  assert_success
+
+  run "$OCKAM" vault list --state-root "${other_root}" --output json
+  assert_success
+  assert_output --partial "\"name\": \"${v1}\""
+  assert_output --partial "\"name\": \"${v2}\""
+  assert_output --partial "\"state_root\": \"${other_root}\""
+}
+
+@test "vault - force the default vault" {
+  v1=$(random_str)
+  run "$OCKAM" vault create "${v1}"
+  assert_success
+  run "$OCKAM" vault default "${v1}"
+
+  run "$OCKAM" vault default "${v1}" --force --output json
+  assert_success
+  assert_output --partial "\"force_reset\":true"
+}
+
+@test "vault - exit codes" {
+  run "$OCKAM" vault show missing-vault
+  assert_failure 2
+
+  v=$(random_str)
+  run "$OCKAM" vault create "${v}"
+  assert_success
+  run "$OCKAM" vault create "${v}"
+  assert_failure 3
+
+  run "$OCKAM" vault default "${v}"
+  run "$OCKAM" vault default "${v}"
+  assert_failure 1
 }
 
 @test "vault - CRUD" {
@@ -153,6 +445,364 @@ teardown() {
   assert_output --partial "signatures"
 }
 
+@test "identity - create with a generated name" {
+  run "$OCKAM" identity create --auto-name --output json
+  assert_success
+  assert_output --regexp '"name":"[a-z]+-[a-z]+(-[0-9a-f]{4})?"'
+
+  run "$OCKAM" identity create --auto-name i
+  assert_failure
+}
+
+@test "identity - create identities in bulk" {
+  p=$(random_str)
+  run "$OCKAM" identity create --count 3 --name-prefix "${p}" --parallel 2 --output json
+  assert_success
+  assert_output --partial "\"name\":\"${p}-0\""
+  assert_output --partial "\"name\":\"${p}-2\""
+
+  run "$OCKAM" identity show "${p}-1"
+  assert_success
+
+  # The names must not be used yet
+  run "$OCKAM" identity create --count 2 --name-prefix "${p}"
+  assert_failure
+  run "$OCKAM" identity create --count 2
+  assert_failure
+}
+
+@test "identity - show the public identity as a QR code" {
+  i=$(random_str)
+  run "$OCKAM" identity create "${i}"
+  assert_success
+
+  run "$OCKAM" identity show "${i}" --qr --output json
+  assert_success
+  assert_output --partial "\"public_identity\""
+
+  run "$OCKAM" identity show "${i}" --qr --full
+  assert_failure
+}
+
+@test "identity - share the public identity as a URI" {
+  i=$(random_str)
+  run "$OCKAM" identity create "${i}"
+  assert_success
+  identifier=$($OCKAM identity show "${i}")
+
+  run "$OCKAM" identity show "${i}" --uri
+  assert_success
+  assert_output --partial "ockam://identity/"
+  uri=$($OCKAM identity show "${i}" --uri)
+
+  run "$OCKAM" identity import "${uri}" --output json
+  assert_success
+  assert_output --partial "\"identifier\":\"${identifier}\""
+
+  run "$OCKAM" identity import "https://identity/${uri:17}"
+  assert_failure
+  run "$OCKAM" identity import "ockam://identity/not-a-public-identity"
+  assert_failure
+}
+
+@test "identity - validate a change history stored in a file" {
+  i=$(random_str)
+  run "$OCKAM" identity create "${i}"
+  assert_success
+  identifier=$($OCKAM identity show "${i}")
+
+  "$OCKAM" identity show "${i}" --full --encoding hex >"$OCKAM_HOME/${i}.identity"
+  run "$OCKAM" identity validate --history-file "$OCKAM_HOME/${i}.identity" --output json
+  assert_success
+  assert_output --partial "\"identifier\": \"${identifier}\""
+  assert_output --partial "\"is_valid\": true"
+
+  # The file is read with the given encoding
+  run "$OCKAM" identity validate --history-file "$OCKAM_HOME/${i}.identity" --encoding raw
+  assert_failure
+
+  echo "not a change history" >"$OCKAM_HOME/invalid.identity"
+  run "$OCKAM" identity validate --history-file "$OCKAM_HOME/invalid.identity"
+  assert_failure
+}
+
+@test "identity - create a certificate signing request" {
+  i=$(random_str)
+  run "$OCKAM" identity create "${i}"
+  assert_success
+
+  run "$OCKAM" identity csr --identity "${i}" --out "$OCKAM_HOME/${i}.csr" --subject "CN=node1,O=Acme,C=US" --san dns:node1.example.com --san ip:10.0.0.1
+  assert_success
+  run head -n 1 "$OCKAM_HOME/${i}.csr"
+  assert_output "-----BEGIN CERTIFICATE REQUEST-----"
+
+  run "$OCKAM" identity csr --identity "${i}" --out "$OCKAM_HOME/${i}.csr" --subject "C=USA"
+  assert_failure
+  run "$OCKAM" identity csr --identity "${i}" --out "$OCKAM_HOME/${i}.csr" --san ftp:node1
+  assert_failure
+}
+
+@test "identity - prune the revoked identities" {
+  i=$(random_str)
+  run "$OCKAM" identity create "${i}"
+  assert_success
+
+  # identities which were not revoked are kept
+  run "$OCKAM" identity prune --revoked --older-than 30d --yes --output json
+  assert_success
+  assert_output --partial "\"pruned\": []"
+  run "$OCKAM" identity show "${i}"
+  assert_success
+
+  run "$OCKAM" identity prune --revoked --older-than 30w --yes
+  assert_failure
+  run "$OCKAM" identity prune --yes
+  assert_failure
+}
+
+@test "identity - rotate keys on a schedule" {
+  i=$(random_str)
+  run "$OCKAM" identity create "${i}"
+  assert_success
+
+  # the identities must exist and the interval can't be zero
+  run "$OCKAM" identity auto-rotate --names "${i},$(random_str)" --interval 1d
+  assert_failure
+  run "$OCKAM" identity auto-rotate --names "${i}" --interval 0s
+  assert_failure
+  run "$OCKAM" identity auto-rotate --names "${i}" --interval 1w
+  assert_failure
+}
+
+@test "identity - watch the change history of an identity" {
+  i=$(random_str)
+  run "$OCKAM" identity create "${i}"
+  assert_success
+
+  # The watch is stopped by the timeout, after the identity was rotated by another process
+  timeout --signal=INT --kill-after=2 10 "$OCKAM" identity watch "${i}" --interval 1 --output json >"$OCKAM_HOME/watch.log" 2>&1 &
+  watch_pid=$!
+  sleep 3
+  run timeout --signal=INT --kill-after=2 4 "$OCKAM" identity auto-rotate --names "${i}" --interval 1d
+  wait "${watch_pid}" || true
+
+  run cat "$OCKAM_HOME/watch.log"
+  assert_output --partial "\"identity\":\"${i}\""
+  assert_output --partial "\"changes\":2"
+}
+
+@test "identity - show the disk usage of the identities" {
+  i=$(random_str)
+  run "$OCKAM" identity create "${i}"
+  assert_success
+
+  run "$OCKAM" identity usage --output json
+  assert_success
+  assert_output --partial "\"name\": \"${i}\""
+  assert_output --partial "\"changes\": 1"
+  assert_output --partial "\"store_bytes\""
+}
+
+@test "identity - show change history" {
+  i=$(random_str)
+  run "$OCKAM" identity create "${i}"
+  assert_success
+
+  run "$OCKAM" identity history "${i}"
+  assert_success
+  assert_output --partial "CreateKey"
+  assert_output --partial "OCKAM_RK"
+
+  run "$OCKAM" identity history "${i}" --output json
+  assert_success
+  assert_output --partial "\"change_type\": \"CreateKey\""
+}
+
+@test "identity - compare change histories" {
+  i=$(random_str)
+  j=$(random_str)
+  run "$OCKAM" identity create "${i}"
+  assert_success
+  run "$OCKAM" identity create "${j}"
+  assert_success
+
+  run "$OCKAM" identity compare "${i}" "${i}" --output json
+  assert_success
+  assert_output --partial "\"result\": \"Equal\""
+
+  run "$OCKAM" identity compare "${i}" "${j}" --output json
+  assert_success
+  assert_output --partial "\"result\": \"Conflict\""
+  assert_output --partial "\"divergence_index\": 0"
+
+  "$OCKAM" identity show "${i}" --full --encoding hex >"$OCKAM_HOME/history.hex"
+  run "$OCKAM" identity compare "${j}" --history-file "$OCKAM_HOME/history.hex" --output json
+  assert_success
+  assert_output --partial "\"result\": \"Conflict\""
+}
+
+@test "identity - sign and verify a file" {
+  i=$(random_str)
+  j=$(random_str)
+  run "$OCKAM" identity create "${i}"
+  assert_success
+  run "$OCKAM" identity create "${j}"
+  assert_success
+
+  echo "some data" >"$OCKAM_HOME/data.txt"
+  run "$OCKAM" identity sign --identity "${i}" --in "$OCKAM_HOME/data.txt" --out "$OCKAM_HOME/data.sig"
+  assert_success
+
+  run "$OCKAM" identity verify --signer "${i}" --in "$OCKAM_HOME/data.txt" --sig "$OCKAM_HOME/data.sig" --output json
+  assert_success
+  assert_output --partial "\"is_valid\":true"
+
+  # The signer can be loaded from an exported identity
+  "$OCKAM" identity show "${i}" --full --encoding hex >"$OCKAM_HOME/signer.hex"
+  run "$OCKAM" identity verify --signer "$OCKAM_HOME/signer.hex" --in "$OCKAM_HOME/data.txt" --sig "$OCKAM_HOME/data.sig" --output json
+  assert_success
+  assert_output --partial "\"is_valid\":true"
+
+  run "$OCKAM" identity verify --signer "${j}" --in "$OCKAM_HOME/data.txt" --sig "$OCKAM_HOME/data.sig" --output json
+  assert_failure
+  assert_output --partial "\"is_valid\":false"
+
+  # A signature of the digest of the file is only valid with --prehash
+  run "$OCKAM" identity sign --identity "${i}" --in "$OCKAM_HOME/data.txt" --out "$OCKAM_HOME/digest.sig" --prehash
+  assert_success
+  run "$OCKAM" identity verify --signer "${i}" --in "$OCKAM_HOME/data.txt" --sig "$OCKAM_HOME/digest.sig" --prehash --output json
+  assert_success
+  assert_output --partial "\"is_valid\":true"
+  run "$OCKAM" identity verify --signer "${i}" --in "$OCKAM_HOME/data.txt" --sig "$OCKAM_HOME/digest.sig" --output json
+  assert_failure
+  assert_output --partial "\"is_valid\":false"
+
+  # The data can be piped to the command, with or without --prehash
+  run bash -c "cat \"$OCKAM_HOME/data.txt\" | \"$OCKAM\" identity sign --identity \"${i}\" --in - --out \"$OCKAM_HOME/stdin.sig\""
+  assert_success
+  run "$OCKAM" identity verify --signer "${i}" --in "$OCKAM_HOME/data.txt" --sig "$OCKAM_HOME/stdin.sig" --output json
+  assert_success
+  assert_output --partial "\"is_valid\":true"
+  run bash -c "cat \"$OCKAM_HOME/data.txt\" | \"$OCKAM\" identity sign --identity \"${i}\" --in - --out \"$OCKAM_HOME/stdin-digest.sig\" --prehash"
+  assert_success
+  run "$OCKAM" identity verify --signer "${i}" --in "$OCKAM_HOME/data.txt" --sig "$OCKAM_HOME/stdin-digest.sig" --prehash --output json
+  assert_success
+  assert_output --partial "\"is_valid\":true"
+}
+
+@test "identity - migrate the keys of identities to another vault" {
+  v1=$(random_str)
+  v2=$(random_str)
+  i=$(random_str)
+  run "$OCKAM" vault create "${v1}"
+  assert_success
+  run "$OCKAM" vault create "${v2}"
+  assert_success
+  run "$OCKAM" identity create "${i}" --vault "${v1}"
+  assert_success
+
+  echo "some data" >"$OCKAM_HOME/data.txt"
+  run "$OCKAM" identity sign --identity "${i}" --vault "${v2}" --in "$OCKAM_HOME/data.txt" --out "$OCKAM_HOME/data.sig"
+  assert_failure
+
+  run "$OCKAM" identity migrate-vault --from "${v1}" --to "${v2}" --output json
+  assert_success
+  assert_output --partial "\"migrated\": ["
+  assert_output --partial "\"${i}\""
+
+  run "$OCKAM" identity sign --identity "${i}" --vault "${v2}" --in "$OCKAM_HOME/data.txt" --out "$OCKAM_HOME/data.sig"
+  assert_success
+
+  run "$OCKAM" identity migrate-vault --from "${v1}" --to "${v1}"
+  assert_failure
+}
+
+@test "identity - verify the signatures of a manifest" {
+  i=$(random_str)
+  j=$(random_str)
+  run "$OCKAM" identity create "${i}"
+  assert_success
+  run "$OCKAM" identity create "${j}"
+  assert_success
+
+  mkdir -p "$OCKAM_HOME/release"
+  echo "first" >"$OCKAM_HOME/release/a.txt"
+  echo "second" >"$OCKAM_HOME/release/b.txt"
+  run "$OCKAM" identity sign --identity "${i}" --in "$OCKAM_HOME/release/a.txt" --out "$OCKAM_HOME/release/a.sig"
+  assert_success
+  run "$OCKAM" identity sign --identity "${i}" --in "$OCKAM_HOME/release/b.txt" --out "$OCKAM_HOME/release/b.sig" --prehash
+  assert_success
+
+  cat >"$OCKAM_HOME/release/manifest.json" <<EOF
+[
+  { "path": "a.txt", "signer": "${i}", "signature": "a.sig" },
+  { "path": "b.txt", "signer": "${i}", "signature": "b.sig", "prehash": true }
+]
+EOF
+  run "$OCKAM" identity verify-manifest "$OCKAM_HOME/release/manifest.json" --concurrency 1 --output json
+  assert_success
+  assert_output --partial "\"passed\": 2"
+  assert_output --partial "\"failed\": 0"
+
+  # An entry with the wrong signer fails the verification
+  cat >"$OCKAM_HOME/release/manifest.json" <<EOF
+[
+  { "path": "a.txt", "signer": "${i}", "signature": "a.sig" },
+  { "path": "b.txt", "signer": "${j}", "signature": "b.sig", "prehash": true }
+]
+EOF
+  run "$OCKAM" identity verify-manifest "$OCKAM_HOME/release/manifest.json" --output json
+  assert_failure
+  assert_output --partial "\"passed\": 1"
+  assert_output --partial "\"failed\": 1"
+}
+
+@test "identity - create with a key type" {
+  i=$(random_str)
+  run "$OCKAM" identity create "${i}" --key-type p256 --output json
+  assert_success
+  assert_output --partial "\"key_type\":\"NistP256\""
+
+  i=$(random_str)
+  run "$OCKAM" identity create "${i}" --output json
+  assert_success
+  assert_output --partial "\"key_type\":\"Ed25519\""
+
+  run "$OCKAM" identity create "$(random_str)" --key-type rsa
+  assert_failure
+}
+
+@test "identity - fetch an identity from a remote identity service" {
+  run "$OCKAM" node create n1
+  assert_success
+  run "$OCKAM" service start identity --addr my_identity --at n1
+  assert_success
+
+  i=$(random_str)
+  run "$OCKAM" identity create "${i}"
+  assert_success
+
+  run "$OCKAM" identity fetch "${i}" --from /node/n1/service/my_identity --name "${i}-copy" --output json
+  assert_success
+  assert_output --partial "\"name\":\"${i}-copy\""
+
+  run "$OCKAM" identity list
+  assert_success
+  assert_output --partial "${i}-copy"
+
+  # The local name is already used
+  run "$OCKAM" identity fetch "${i}" --from /node/n1/service/my_identity --name "${i}-copy"
+  assert_failure
+
+  run "$OCKAM" identity fetch unknown --from /node/n1/service/my_identity --name "$(random_str)"
+  assert_failure
+  assert_output --partial "has no identity named"
+
+  run "$OCKAM" identity fetch "${i}" --from /node/n1/service/missing --name "$(random_str)" --timeout 2
+  assert_failure
+  assert_output --partial "Unable to reach"
+}
+
 @test "identity - CRUD" {
   # Create with random name
   run "$OCKAM" identity create
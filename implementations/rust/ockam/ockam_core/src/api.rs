@@ -96,6 +96,13 @@ pub struct Request<'a> {
     #[n(3)] method: Option<Method>,
     /// Indicator if a request body is expected after this header.
     #[n(4)] has_body: bool,
+    /// Indicator that the client accepts a compressed response body.
+    #[n(5)] accept_compression: Option<bool>,
+    /// Token authenticating the client, for services which require one.
+    #[b(6)] auth_token: Option<Cow<'a, str>>,
+    /// Media type of the response body expected by the client, for services
+    /// which can represent a resource in several ways.
+    #[b(7)] accept: Option<Cow<'a, str>>,
 }
 
 /// The response header.
@@ -123,6 +130,11 @@ pub struct Response {
     #[n(3)] status: Option<Status>,
     /// Indicator if a response body is expected after this header.
     #[n(4)] has_body: bool,
+    /// Indicator that the response body is compressed.
+    ///
+    /// A compressed body is encoded as a byte string containing the
+    /// DEFLATE-compressed encoding of the original body.
+    #[n(5)] compressed: Option<bool>,
 }
 
 /// Create an error response because the request path was unknown.
@@ -213,8 +225,11 @@ pub enum Status {
     #[n(404)] NotFound,
     #[n(409)] Conflict,
     #[n(405)] MethodNotAllowed,
+    #[n(429)] TooManyRequests,
     #[n(500)] InternalServerError,
     #[n(501)] NotImplemented,
+    #[n(503)] ServiceUnavailable,
+    #[n(504)] GatewayTimeout,
 }
 
 impl Display for Status {
@@ -227,8 +242,11 @@ impl Display for Status {
             Status::NotFound => "404 NotFound",
             Status::Conflict => "409 Conflict",
             Status::MethodNotAllowed => "405 MethodNotAllowed",
+            Status::TooManyRequests => "429 TooManyRequests",
             Status::InternalServerError => "500 InternalServerError",
             Status::NotImplemented => "501 NotImplemented",
+            Status::ServiceUnavailable => "503 ServiceUnavailable",
+            Status::GatewayTimeout => "504 GatewayTimeout",
         })
     }
 }
@@ -261,6 +279,9 @@ impl<'a> Request<'a> {
             method: Some(method),
             path: path.into(),
             has_body,
+            accept_compression: None,
+            auth_token: None,
+            accept: None,
         }
     }
 
@@ -310,6 +331,18 @@ impl<'a> Request<'a> {
     pub fn has_body(&self) -> bool {
         self.has_body
     }
+
+    pub fn accepts_compression(&self) -> bool {
+        self.accept_compression.unwrap_or(false)
+    }
+
+    pub fn auth_token(&self) -> Option<&str> {
+        self.auth_token.as_deref()
+    }
+
+    pub fn accept(&self) -> Option<&str> {
+        self.accept.as_deref()
+    }
 }
 
 impl Response {
@@ -321,6 +354,7 @@ impl Response {
             re,
             status: Some(status),
             has_body,
+            compressed: None,
         }
     }
 
@@ -359,6 +393,18 @@ impl Response {
         Response::builder(re, Status::InternalServerError)
     }
 
+    pub fn too_many_requests(re: Id) -> ResponseBuilder {
+        Response::builder(re, Status::TooManyRequests)
+    }
+
+    pub fn service_unavailable(re: Id) -> ResponseBuilder {
+        Response::builder(re, Status::ServiceUnavailable)
+    }
+
+    pub fn gateway_timeout(re: Id) -> ResponseBuilder {
+        Response::builder(re, Status::GatewayTimeout)
+    }
+
     pub fn id(&self) -> Id {
         self.id
     }
@@ -374,6 +420,10 @@ impl Response {
     pub fn has_body(&self) -> bool {
         self.has_body
     }
+
+    pub fn is_compressed(&self) -> bool {
+        self.compressed.unwrap_or(false)
+    }
 }
 
 /// An error type used in response bodies.
@@ -520,6 +570,21 @@ impl<'a, T> RequestBuilder<'a, T> {
         self
     }
 
+    pub fn accept_compression(mut self) -> Self {
+        self.header.accept_compression = Some(true);
+        self
+    }
+
+    pub fn auth_token<S: Into<Cow<'a, str>>>(mut self, token: S) -> Self {
+        self.header.auth_token = Some(token.into());
+        self
+    }
+
+    pub fn accept<S: Into<Cow<'a, str>>>(mut self, media_type: S) -> Self {
+        self.header.accept = Some(media_type.into());
+        self
+    }
+
     pub fn header(&self) -> &Request<'a> {
         &self.header
     }
@@ -583,6 +648,11 @@ impl<T> ResponseBuilder<T> {
         self
     }
 
+    pub fn compressed(mut self) -> Self {
+        self.header.compressed = Some(true);
+        self
+    }
+
     pub fn header(&self) -> &Response {
         &self.header
     }
@@ -867,8 +937,11 @@ mod schema_test {
         Status::BadRequest,
         Status::NotFound,
         Status::MethodNotAllowed,
+        Status::TooManyRequests,
         Status::InternalServerError,
         Status::NotImplemented,
+        Status::ServiceUnavailable,
+        Status::GatewayTimeout,
     ];
 
     #[derive(Debug, Clone)]
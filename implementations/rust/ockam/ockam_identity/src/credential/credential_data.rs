@@ -332,7 +332,8 @@ impl Timestamp {
             .map(|d| Timestamp(d.as_secs()))
     }
 
-    pub(crate) fn add_seconds(&self, seconds: u64) -> Self {
+    /// Return a timestamp which is a given number of seconds after this one
+    pub fn add_seconds(&self, seconds: u64) -> Self {
         Timestamp(self.0.saturating_add(seconds))
     }
 
@@ -91,9 +91,18 @@ impl IdentitiesCreation {
 
     /// Create an Identity
     pub async fn create_identity(&self) -> Result<Identity> {
+        self.create_identity_with_secret_attributes(SecretAttributes::Ed25519)
+            .await
+    }
+
+    /// Create an Identity with a root key having specific attributes
+    pub async fn create_identity_with_secret_attributes(
+        &self,
+        secret_attributes: SecretAttributes,
+    ) -> Result<Identity> {
         let attrs = KeyAttributes::new(
             IdentityChangeConstants::ROOT_LABEL.to_string(),
-            SecretAttributes::Ed25519,
+            secret_attributes,
         );
         self.make_and_persist_identity(None, attrs).await
     }
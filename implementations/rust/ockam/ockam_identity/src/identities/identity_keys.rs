@@ -125,6 +125,26 @@ impl IdentitiesKeys {
         Ok(())
     }
 
+    /// Return the index of the first change of an `IdentityChangeHistory` which is not
+    /// properly signed, or None if all the changes are verified
+    pub async fn find_invalid_change(
+        &self,
+        identity_changes: &IdentityChangeHistory,
+    ) -> Option<usize> {
+        for i in 0..identity_changes.as_ref().len() {
+            let existing_changes = &identity_changes.as_ref()[..i];
+            let new_change = &identity_changes.as_ref()[i];
+            if self
+                .verify_change(existing_changes, new_change)
+                .await
+                .is_err()
+            {
+                return Some(i);
+            }
+        }
+        None
+    }
+
     /// Return the secret key of an identity
     pub async fn get_secret_key(
         &self,
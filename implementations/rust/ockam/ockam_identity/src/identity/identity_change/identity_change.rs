@@ -30,14 +30,16 @@ impl IdentityChange {
         self.label() == label
     }
 
-    pub(crate) fn label(&self) -> &str {
+    /// Label of the key created or rotated by this change
+    pub fn label(&self) -> &str {
         match self {
             IdentityChange::CreateKey(data) => data.key_attributes().label(),
             IdentityChange::RotateKey(data) => data.key_attributes().label(),
         }
     }
 
-    pub(crate) fn public_key(&self) -> Result<PublicKey> {
+    /// Public key created or rotated by this change
+    pub fn public_key(&self) -> Result<PublicKey> {
         Ok(match self {
             IdentityChange::CreateKey(data) => data.public_key(),
             IdentityChange::RotateKey(data) => data.public_key(),
@@ -45,7 +47,8 @@ impl IdentityChange {
         .clone())
     }
 
-    pub(crate) fn previous_change_identifier(&self) -> &ChangeIdentifier {
+    /// Identifier of the change preceding this one
+    pub fn previous_change_identifier(&self) -> &ChangeIdentifier {
         match self {
             IdentityChange::CreateKey(data) => data.prev_change_id(),
             IdentityChange::RotateKey(data) => data.prev_change_id(),
@@ -86,6 +86,25 @@ impl IdentityChangeHistory {
                 .as_slice(),
         )
     }
+
+    /// Export the changes which follow the first `known_length` changes to the binary format,
+    /// so that they can be appended to a prefix of this `IdentityChangeHistory`
+    pub fn export_delta(&self, known_length: usize) -> Result<Vec<u8>> {
+        let delta = self.0.get(known_length..).unwrap_or_default();
+        serde_bare::to_vec(&delta).map_err(|_| IdentityError::ConsistencyError.into())
+    }
+
+    /// Append changes exported with [`IdentityChangeHistory::export_delta`], after checking
+    /// that they extend this `IdentityChangeHistory`
+    pub fn import_delta(&mut self, data: &[u8]) -> Result<()> {
+        let delta: Vec<IdentitySignedChange> =
+            serde_bare::from_slice(data).map_err(|_| IdentityError::ConsistencyError)?;
+        if !Self::check_consistency(self.as_ref(), &delta) {
+            return Err(IdentityError::ConsistencyError.into());
+        }
+        self.0.extend(delta);
+        Ok(())
+    }
 }
 
 impl IdentityChangeHistory {
@@ -131,6 +150,15 @@ impl IdentityChangeHistory {
         }
     }
 
+    /// Return the index of the first change which doesn't match between the current
+    /// `IdentityChangeHistory` and a known one, if the two histories are in conflict
+    pub fn divergence_index(&self, known: &Self) -> Option<usize> {
+        self.0
+            .iter()
+            .zip(known.0.iter())
+            .position(|(current, known)| current.identifier() != known.identifier())
+    }
+
     /// Get public key with the given label (name)
     pub fn get_public_key(&self, label: &str) -> Result<PublicKey> {
         Self::get_public_key_static(self.as_ref(), label)
@@ -1,4 +1,6 @@
-use crate::SignatureVec;
+use crate::{SignatureVec, VaultError};
+use minicbor::{Decode, Encode};
+use ockam_core::Result;
 use p256::elliptic_curve::subtle;
 use serde::{Deserialize, Serialize};
 use zeroize::Zeroize;
@@ -14,6 +16,31 @@ impl Signature {
     }
 }
 
+impl Signature {
+    /// Convert a NIST P-256 ECDSA signature to the given encoding.
+    /// The signature can either be DER-encoded or raw
+    pub fn to_ecdsa_encoding(&self, encoding: EcdsaSignatureEncoding) -> Result<Signature> {
+        let signature = p256::ecdsa::Signature::from_der(&self.0)
+            .or_else(|_| p256::ecdsa::Signature::from_slice(&self.0))
+            .map_err(|_| VaultError::InvalidSignature)?;
+        Ok(match encoding {
+            EcdsaSignatureEncoding::Raw => Signature::new(signature.to_bytes().to_vec()),
+            EcdsaSignatureEncoding::Der => Signature::new(signature.to_der().as_bytes().to_vec()),
+        })
+    }
+}
+
+/// Encoding of an ECDSA signature
+#[derive(Encode, Decode, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum EcdsaSignatureEncoding {
+    /// Fixed-width concatenation of the r and s scalars
+    #[n(0)] Raw,
+    /// ASN.1 DER encoding, as produced and expected by OpenSSL
+    #[n(1)] Der,
+}
+
 impl AsRef<[u8]> for Signature {
     fn as_ref(&self) -> &[u8] {
         &self.0
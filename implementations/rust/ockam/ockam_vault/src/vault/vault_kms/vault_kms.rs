@@ -185,6 +185,8 @@ impl VaultSecurityModule {
                 use p256::pkcs8::DecodePrivateKey;
                 let sec = p256::ecdsa::SigningKey::from_pkcs8_der(key).map_err(Self::from_pkcs8)?;
 
+                // the nonce is derived from the key and the data as specified by RFC 6979,
+                // so that signing the same data twice returns the same signature
                 let sig: p256::ecdsa::Signature = sec.sign(data);
                 Ok(Signature::new(sig.to_der().as_bytes().to_vec()))
             }